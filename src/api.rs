@@ -0,0 +1,233 @@
+//! # Library API
+//!
+//! This module runs solpm commands from Rust code instead of the command line.
+//! Each function takes the same options struct as its CLI command and returns
+//! the outcome the command returns, which `--json` prints as its `result`.
+//!
+//! The functions never prompt. While one runs, the process is in embedded
+//! mode (see `output::embed`), so text output goes to stderr, spinners are
+//! hidden, and a command that would need an answer fails with
+//! `SolanaPmError::InputRequired` instead; pass tokens, passwords, and choices
+//! in the options. Status lines can be silenced with
+//! `output::set_verbosity(true, 0)`.
+//!
+//! Like the CLI, every function works on the project in the current directory
+//! and reads its `.solpm.toml` and `[solpm]` settings from there.
+//! `AddOptions::registry` and `InstallOptions::registry` pick the registry
+//! for a single call instead of the process-wide `--registry` setting.
+
+use crate::commands::add::{self, AddOptions};
+use crate::commands::codegen::{self, CodegenOptions};
+use crate::commands::config::load_project_settings;
+use crate::commands::install::{self, InstallOptions};
+use crate::error::Result;
+use crate::output::{Embedded, embed};
+
+pub use crate::commands::add::{AddOutcome, AddedProgram};
+pub use crate::commands::codegen::{CodegenOutcome, GeneratedClient};
+pub use crate::commands::install::{FailedProgram, InstallOutcome, InstalledProgram};
+
+/// Adds a program dependency to the project in the current directory.
+/// 
+/// Runs `solpm add` without prompting; see `commands::add::add_program`.
+/// 
+/// # Arguments
+/// 
+/// * `package_spec` - The package name, optionally with a version (e.g. `token-vesting@1.0.0`)
+/// * `options` - The `add` options
+/// 
+/// # Returns
+/// 
+/// Returns what the command did, or the error it failed with.
+/// 
+/// # Examples
+/// 
+/// ```no_run
+/// use solpm::api::{self, AddOutcome};
+/// use solpm::commands::add::AddOptions;
+/// 
+/// # async fn example() -> solpm::error::Result<()> {
+/// let options = AddOptions { dev: true, ..Default::default() };
+/// if let AddOutcome::Added(program) = api::add("token-vesting@1.0.0", &options).await? {
+///     println!("{} is at {:?}", program.program_id, program.idl_path);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub async fn add(package_spec: &str, options: &AddOptions) -> Result<AddOutcome> {
+    let _embedded = prepare()?;
+    add::add_program(package_spec, options).await
+}
+
+/// Installs every dependency in the project's SolanaPrograms.json.
+/// 
+/// Runs `solpm install` without prompting; see
+/// `commands::install::install_dependencies`. Programs that fail to download
+/// are listed in [`InstallOutcome::failed`] rather than failing the call.
+/// 
+/// # Arguments
+/// 
+/// * `options` - The `install` options
+/// 
+/// # Returns
+/// 
+/// Returns what the command did, or the error it failed with.
+pub async fn install(options: &InstallOptions) -> Result<InstallOutcome> {
+    let _embedded = prepare()?;
+    install::install_dependencies(options).await
+}
+
+/// Generates TypeScript clients for the project's installed programs.
+/// 
+/// Runs `solpm codegen`; see `commands::codegen::generate_typescript_client`.
+/// 
+/// # Arguments
+/// 
+/// * `options` - The codegen options
+/// 
+/// # Returns
+/// 
+/// Returns the generated clients, or the error generation failed with.
+/// 
+/// # Examples
+/// 
+/// ```no_run
+/// use solpm::api;
+/// use solpm::commands::codegen::CodegenOptions;
+/// 
+/// # fn example() -> solpm::error::Result<()> {
+/// let options = CodegenOptions { ensure_helpers: true, ..Default::default() };
+/// for client in api::codegen(&options)?.clients {
+///     println!("{} -> {}", client.name, client.path);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn codegen(options: &CodegenOptions) -> Result<CodegenOutcome> {
+    let _embedded = prepare()?;
+    codegen::generate_typescript_client(options)
+}
+
+/// Enters embedded mode and loads the project settings.
+/// 
+/// # Returns
+/// 
+/// Returns the guard that keeps the call embedded, or an error if a settings
+/// file is invalid.
+fn prepare() -> Result<Embedded> {
+    let embedded = embed();
+    load_project_settings()?;
+    Ok(embedded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::mock_server::{MockResponse, serve};
+    use crate::commands::test_project;
+    use crate::error::SolanaPmError;
+    use crate::output::is_embedded;
+    use crate::utils::default_idl_path;
+    use std::fs;
+    
+    const PROGRAM_ID: &str = "Vest1ngZbdCMbGVTwfM8fBNBfWqBqTBBHwCbUHmBxjr";
+    
+    const INSTALL_RESPONSE: &str = r#"{
+        "version": "1.0.0",
+        "program_id": "Vest1ngZbdCMbGVTwfM8fBNBfWqBqTBBHwCbUHmBxjr",
+        "idl": { "metadata": { "name": "vesting", "version": "1.0.0" }, "instructions": [] }
+    }"#;
+    
+    /// Writes a SolanaPrograms.json with the given devnet dependencies.
+    fn write_manifest(programs: &[&str]) {
+        let programs: serde_json::Map<String, serde_json::Value> = programs.iter()
+            .map(|name| (name.to_string(), serde_json::json!({ "version": "1.0.0", "program_id": PROGRAM_ID, "network": "devnet" })))
+            .collect();
+        let manifest = serde_json::json!({ "programs": programs, "devPrograms": {} });
+        fs::write("SolanaPrograms.json", manifest.to_string()).unwrap();
+    }
+    
+    #[tokio::test]
+    async fn add_returns_the_added_program() {
+        let _project = test_project::enter();
+        let (base_url, requests) = serve(|_| MockResponse::json("200 OK", INSTALL_RESPONSE)).await;
+        let options = AddOptions { registry: Some(base_url.clone()), ..Default::default() };
+        
+        let AddOutcome::Added(program) = add("vesting", &options).await.unwrap() else {
+            panic!("expected the program to be added");
+        };
+        assert_eq!(program.name, "vesting");
+        assert_eq!(program.version, "1.0.0");
+        assert_eq!(program.program_id, PROGRAM_ID);
+        assert_eq!(program.network, "devnet");
+        assert_eq!(program.idl_path, Some(default_idl_path("vesting")));
+        assert!(!program.dev && !program.cached);
+        assert!(fs::read_to_string(default_idl_path("vesting")).unwrap().contains("\"vesting\""));
+        assert_eq!(requests.lock().unwrap()[0].path, "/programs/vesting/latest/install");
+        
+        let skipped = add("vesting", &options).await.unwrap();
+        assert!(matches!(skipped, AddOutcome::Skipped { ref name, dev: false } if name == "vesting"), "{:?}", skipped);
+        assert!(!is_embedded());
+    }
+    
+    #[tokio::test]
+    async fn add_check_only_returns_the_available_version() {
+        let project = test_project::enter();
+        let (base_url, _) = serve(|_| MockResponse::json("200 OK", INSTALL_RESPONSE)).await;
+        let options = AddOptions { registry: Some(base_url), check_only: true, ..Default::default() };
+        
+        let outcome = add("vesting@1.0.0", &options).await.unwrap();
+        assert!(matches!(
+            outcome,
+            AddOutcome::Available { ref version, ref program_id, .. } if version == "1.0.0" && program_id == PROGRAM_ID
+        ), "{:?}", outcome);
+        assert!(!project.path().join("SolanaPrograms.json").exists());
+    }
+    
+    #[tokio::test]
+    async fn install_returns_installed_and_failed_programs() {
+        let _project = test_project::enter();
+        write_manifest(&["vesting", "missing"]);
+        let (base_url, _) = serve(|request| if request.path.starts_with("/programs/missing/") {
+            MockResponse::json("404 Not Found", r#"{"error": "not found"}"#)
+        } else {
+            MockResponse::json("200 OK", INSTALL_RESPONSE)
+        }).await;
+        
+        let outcome = install(&InstallOptions { registry: Some(base_url), ..Default::default() }).await.unwrap();
+        assert_eq!(outcome.total, 2);
+        assert!(!outcome.codegen_failed);
+        assert_eq!(outcome.installed.len(), 1);
+        assert_eq!(outcome.installed[0].name, "vesting");
+        assert_eq!(outcome.installed[0].version, "1.0.0");
+        assert_eq!(outcome.installed[0].idl_path, Some(default_idl_path("vesting")));
+        assert_eq!(outcome.failed.len(), 1);
+        assert_eq!(outcome.failed[0].name, "missing");
+        assert!(!outcome.failed[0].dev);
+        assert!(!is_embedded());
+    }
+    
+    #[test]
+    fn codegen_returns_the_generated_clients() {
+        let project = test_project::enter();
+        write_manifest(&["vesting"]);
+        fs::create_dir_all(project.path().join("program/idl")).unwrap();
+        fs::write(default_idl_path("vesting"), r#"{ "instructions": [] }"#).unwrap();
+        
+        let outcome = codegen(&CodegenOptions::default()).unwrap();
+        assert_eq!(outcome.clients.len(), 1);
+        assert_eq!(outcome.clients[0].name, "vesting");
+        assert!(outcome.clients[0].path.ends_with("VestingClient.ts"), "{}", outcome.clients[0].path);
+        assert!(fs::read_to_string(&outcome.clients[0].path).unwrap().contains("const PROGRAM_ID"));
+    }
+    
+    #[test]
+    fn embedded_mode_ends_when_a_call_fails() {
+        let _project = test_project::enter();
+        
+        let Err(SolanaPmError::ConfigNotFound(_)) = codegen(&CodegenOptions::default()) else {
+            panic!("expected codegen without SolanaPrograms.json to fail");
+        };
+        assert!(!is_embedded());
+    }
+}
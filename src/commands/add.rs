@@ -6,21 +6,140 @@
 //! - Adding programs by name (latest version) or name@version (specific version)
 //! - Installing as regular or development dependencies
 //! - Custom IDL file paths
-//! - Network selection (mainnet/devnet)
+//! - Network selection (mainnet/devnet/testnet/localnet)
 //! - Optional TypeScript client code generation
+//! - Interactive registry search when no package is given
+//! - Warnings for deprecated versions and refusal of yanked versions
+//! - Checking that a version is available without adding it (`--check-only`)
+//! - Naming the generated client with an alias (`--alias`)
 //!
 //! The command fetches program metadata and IDL files from the registry,
 //! saves them locally, and updates the project's SolanaPrograms.json configuration.
 
-use crate::commands::constants::{GET_PROGRAM_URL, PROGRAM_IDL_DIR, SOLANA_PROGRAMS_FILE};
-use crate::commands::types::{Program, ProgramResponse, SolanaPrograms};
+use crate::commands::config::resolve_network;
+use crate::commands::constants::SOLANA_PROGRAMS_FILE;
+use crate::commands::registry::{InstallRequest, RegistryApi, RegistryClient};
+use crate::commands::types::{Program, ProgramResponse, SearchResult, SolanaPrograms};
 use crate::commands::codegen;
+use crate::commands::download::{resolve_max_idl_size, temp_path_for, version_status_warning, write_idl_file, write_validators};
 use crate::cli::Network;
-use crate::error::{Result, SolanaPmError};
-use crate::utils::{CliProgress, CliStyle, generate_project_hash, parse_package_spec};
+use crate::error::{Context, Result, SolanaPmError};
+use crate::commands::hooks::load_solpmrc;
+use crate::outln;
+use crate::statusln;
+use crate::output::require_interactive;
+use crate::utils::{CliProgress, CliStyle, current_timestamp, default_idl_path, generate_project_hash, network_idl_path, parse_package_spec, prompt_input};
 use std::collections::HashMap;
 use std::fs;
-use serde_json::json;
+use std::io::IsTerminal;
+use serde::Serialize;
+
+/// Options for the `add` command.
+#[derive(Default)]
+pub struct AddOptions {
+    /// Add as a development dependency
+    pub dev: bool,
+    /// Custom path for the IDL file
+    pub path: Option<String>,
+    /// The network (mainnet, devnet, testnet, or localnet) to fetch from, or
+    /// `None` for the global `network` (devnet by default)
+    pub network: Option<Network>,
+    /// Generate TypeScript client code after adding the program
+    pub codegen: bool,
+    /// The `--max-idl-size` limit in bytes, if given
+    pub max_idl_size: Option<u64>,
+    /// Add a version the registry has yanked
+    pub allow_yanked: bool,
+    /// Store the IDL under `./program/idl/{network}/` (also enabled by
+    /// `per_network_idl` in `.solpmrc`)
+    pub per_network_idl: bool,
+    /// Only check that the version exists, without writing any files
+    pub check_only: bool,
+    /// Name codegen uses for the client file instead of the program name
+    pub alias: Option<String>,
+    /// Tags `install --tag` selects the program by
+    pub tags: Vec<String>,
+    /// Reuse an existing IDL file at the target path instead of downloading it
+    pub prefer_cached: bool,
+    /// API token sent with this invocation's registry requests, never stored
+    pub registry_token: Option<String>,
+    /// Registry base URL to use instead of the active registry and its
+    /// mirrors; the CLI sets the registry with `--registry` instead
+    pub registry: Option<String>,
+}
+
+/// What [`add_program`] did, by the `status` reported with `--json`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum AddOutcome {
+    /// The program was downloaded (or reused from disk) and added
+    Added(AddedProgram),
+    /// With `check_only`, the requested version exists and can be added
+    Available {
+        /// The program name
+        name: String,
+        /// The version that would be added
+        version: String,
+        /// The program ID on the network
+        program_id: String,
+        /// The network that was checked
+        network: String,
+        /// Warnings about the version, such as a yanked release
+        warnings: Vec<String>,
+    },
+    /// The program was already a dependency and nothing changed
+    Skipped {
+        /// The program name
+        name: String,
+        /// Whether it is a dev dependency
+        dev: bool,
+    },
+    /// The program was already a dependency and its client alias was changed
+    AliasUpdated {
+        /// The program name
+        name: String,
+        /// Whether it is a dev dependency
+        dev: bool,
+        /// The new client alias
+        alias: String,
+    },
+    /// The program was already a dependency and its tags were changed
+    Updated {
+        /// The program name
+        name: String,
+        /// Whether it is a dev dependency
+        dev: bool,
+        /// The client alias, if one was set as well
+        alias: Option<String>,
+        /// The new tags
+        tags: Vec<String>,
+    },
+}
+
+/// A program [`add_program`] wrote to SolanaPrograms.json.
+#[derive(Debug, Clone, Serialize)]
+pub struct AddedProgram {
+    /// The program name
+    pub name: String,
+    /// Whether it was added as a dev dependency
+    pub dev: bool,
+    /// The installed version
+    pub version: String,
+    /// The program ID on the network
+    pub program_id: String,
+    /// The network the program was added from
+    pub network: String,
+    /// Where the IDL was written
+    pub idl_path: Option<String>,
+    /// The client alias, if any
+    pub alias: Option<String>,
+    /// The tags `install --tag` selects the program by
+    pub tags: Vec<String>,
+    /// Whether an IDL already on disk was reused instead of downloaded
+    pub cached: bool,
+    /// Warnings about the version, such as a yanked release
+    pub warnings: Vec<String>,
+}
 
 /// Adds a Solana program dependency to the current project.
 /// 
@@ -32,40 +151,98 @@ use serde_json::json;
 /// # Arguments
 /// 
 /// * `package_spec` - The package specification (name or name@version) to add
-/// * `is_dev` - Whether to add as a development dependency
-/// * `custom_path` - Optional custom path for the IDL file
-/// * `network` - The target network (mainnet or devnet) to fetch from
-/// * `codegen` - Whether to generate TypeScript client code after adding the program
+/// * `options` - The dependency type, IDL location, network, and download flags
+/// 
+/// Without a custom path, the IDL is written to `./program/idl/{name}.json`,
+/// or to `./program/idl/{network}/{name}.json` with per-network IDLs.
+/// 
+/// With `check_only`, the version is only looked up and nothing is written
+/// (see [`check_availability`]).
+/// 
+/// With `alias`, the entry records the alias codegen names the client after
+/// (see [`codegen::client_identifier`]). Passing `--alias` for a program that
+/// is already added only updates its alias.
+/// 
+/// With `tags`, the entry records the tags `install --tag` selects it by.
+/// Passing `--tag` for a program that is already added replaces its tags.
+/// 
+/// With `prefer_cached`, an IDL file already at the target path is reused
+/// and the entry is filled from its own metadata (see [`read_cached_idl`]);
+/// nothing is downloaded unless the file is missing, lacks a version or
+/// address, or holds a different version than requested.
+/// 
+/// With `registry`, the program is downloaded from that registry instead of
+/// the active one, without mirrors.
 /// 
 /// # Returns
 /// 
-/// Returns `Ok(())` on success, or an error if the program is not found, network request fails,
-/// or file operations fail.
+/// Returns what was done, or an error if the program is not found, network request fails,
+/// the response exceeds the IDL size limit, the version is yanked, or file operations fail.
 /// 
 /// # Examples
 /// 
-/// ```rust
+/// ```no_run
+/// use solpm::Network;
+/// use solpm::commands::add::{AddOptions, add_program};
+/// 
+/// # async fn example() -> solpm::error::Result<()> {
 /// // Add a regular dependency (latest version) from devnet
-/// add_program("my-program", false, None, &Network::Dev, false).await?;
+/// let options = AddOptions {
+///     dev: false,
+///     path: None,
+///     network: Some(Network::Dev),
+///     codegen: false,
+///     max_idl_size: None,
+///     allow_yanked: false,
+///     per_network_idl: false,
+///     check_only: false,
+///     alias: None,
+///     tags: vec![],
+///     prefer_cached: false,
+///     registry_token: None,
+///     registry: None,
+/// };
+/// add_program("my-program", &options).await?;
 /// 
-/// // Add a specific version as dev dependency with custom IDL path and generate client code
-/// add_program("my-program@1.0.0", true, Some("./custom/path.json"), &Network::Main, true).await?;
+/// // Add a specific version from mainnet next to the devnet IDL and generate client code
+/// add_program("my-program@1.0.0", &AddOptions { network: Some(Network::Main), codegen: true, per_network_idl: true, ..Default::default() }).await?;
+/// 
+/// // Only check that the version is available on mainnet
+/// add_program("my-program@1.0.0", &AddOptions { network: Some(Network::Main), check_only: true, ..Default::default() }).await?;
+/// # Ok(())
+/// # }
 /// ```
-pub async fn add_program(package_spec: &str, is_dev: bool, custom_path: Option<&str>, network: &Network, codegen: bool) -> Result<()> {
+pub async fn add_program(package_spec: &str, options: &AddOptions) -> Result<AddOutcome> {
+    let max_idl_size = resolve_max_idl_size(options.max_idl_size)?;
+    let is_dev = options.dev;
+    
     // Parse package specification
     let parsed_spec = parse_package_spec(package_spec);
     let package_name = &parsed_spec.name;
     
     // Convert network enum to string
-    let network_str = match network {
-        Network::Main => "mainnet",
-        Network::Dev => "devnet",
-    };
+    let network_str = resolve_network(options.network.as_ref())?.as_str();
+    
+    let registry = RegistryClient::for_registry(options.registry.as_deref())?.with_optional_token(options.registry_token.as_deref());
+    
+    if options.check_only {
+        return check_availability(&registry, package_name, parsed_spec.version.as_deref(), network_str, options.allow_yanked).await;
+    }
+    
+    // Reject aliases that cannot name a client before downloading anything
+    if let Some(alias) = &options.alias {
+        codegen::program_identifier(alias).map_err(|_| SolanaPmError::DataMissing(format!(
+            "Alias '{}' cannot be converted to a TypeScript identifier; use letters and digits, not starting with a digit",
+            alias
+        )))?;
+    }
+    
+    let tags = validate_tags(&options.tags)?;
     
     // Read existing SolanaPrograms.json or create new one
     let mut solana_programs = if fs::metadata(SOLANA_PROGRAMS_FILE).is_ok() {
-        let content = fs::read_to_string(SOLANA_PROGRAMS_FILE)?;
-        serde_json::from_str(&content)?
+        let content = fs::read_to_string(SOLANA_PROGRAMS_FILE).with_context(|| format!("Failed to read {}", SOLANA_PROGRAMS_FILE))?;
+        serde_json::from_str(&content).with_context(|| format!("Failed to parse {}", SOLANA_PROGRAMS_FILE))?
     } else {
         SolanaPrograms {
             programs: HashMap::new(),
@@ -80,93 +257,100 @@ pub async fn add_program(package_spec: &str, is_dev: bool, custom_path: Option<&
         solana_programs.programs.contains_key(package_name)
     };
     
+    if already_exists && (options.alias.is_some() || !tags.is_empty()) {
+        let programs = if is_dev { &mut solana_programs.dev_programs } else { &mut solana_programs.programs };
+        if let Some(program_info) = programs.get_mut(package_name) {
+            if let Some(alias) = &options.alias {
+                program_info.alias = Some(alias.clone());
+            }
+            if !tags.is_empty() {
+                program_info.tags = tags.clone();
+            }
+        }
+        fs::write(SOLANA_PROGRAMS_FILE, serde_json::to_string_pretty(&solana_programs)?).with_context(|| format!("Failed to write {}", SOLANA_PROGRAMS_FILE))?;
+        if let Some(alias) = &options.alias {
+            outln!("{}", CliStyle::success(&format!(
+                "Set the client alias of {} to {}; run {} to regenerate its client",
+                CliStyle::package(package_name),
+                CliStyle::package(alias),
+                CliStyle::command("solpm codegen")
+            )));
+        }
+        if !tags.is_empty() {
+            outln!("{}", CliStyle::success(&format!(
+                "Set the tags of {} to {}",
+                CliStyle::package(package_name),
+                tags.join(", ")
+            )));
+        }
+        return Ok(match (&options.alias, tags.is_empty()) {
+            (Some(alias), true) => AddOutcome::AliasUpdated { name: package_name.clone(), dev: is_dev, alias: alias.clone() },
+            _ => AddOutcome::Updated { name: package_name.clone(), dev: is_dev, alias: options.alias.clone(), tags },
+        });
+    }
+    
     if already_exists {
         let dependency_type = if is_dev { "dev dependency" } else { "dependency" };
-        println!("{}", CliStyle::warning(&format!(
+        statusln!("{}", CliStyle::warning(&format!(
             "Program {} already exists as {}. Skipping.",
             CliStyle::package(package_name),
             dependency_type
         )));
-        return Ok(());
-    }
-    
-    // Only fetch from API if program doesn't exist locally
-    let spinner = CliProgress::new_spinner(&format!("Installing {} from {}...", CliStyle::package(package_name), CliStyle::highlight(network_str)));
-
-    let client = reqwest::Client::new();
-    let project_hash = generate_project_hash();
-    
-    // Build URL based on whether a specific version was requested
-    let url = if let Some(version) = &parsed_spec.version {
-        format!("{}/{}/{}/install", GET_PROGRAM_URL, package_name, version)
-    } else {
-        format!("{}/{}/latest/install", GET_PROGRAM_URL, package_name)
-    };
-    
-    // Create request body with network and project hash for download tracking
-    let request_body = json!({
-        "network": network_str,
-        "project_hash": project_hash
-    });
-    
-    let response = client
-        .post(&url)
-        .json(&request_body)
-        .send()
-        .await?;
-
-    spinner.finish_and_clear();
-    
-    if !response.status().is_success() {
-        if response.status().as_u16() == 404 {
-            return Err(SolanaPmError::ProgramNotFound(package_name.to_string()));
-        } else {
-            let error_text = response.text().await?;
-            return Err(SolanaPmError::UploadFailed(error_text));
-        }
+        return Ok(AddOutcome::Skipped { name: package_name.clone(), dev: is_dev });
     }
     
-    let program_response: ProgramResponse = response.json().await?;
-    
     // Determine IDL file path
-    let idl_file_path = if let Some(path) = custom_path {
+    let idl_file_path = if let Some(path) = &options.path {
         path.to_string()
+    } else if options.per_network_idl || load_solpmrc()?.per_network_idl {
+        network_idl_path(package_name, network_str)
     } else {
-        format!("{}/{}.json", PROGRAM_IDL_DIR, package_name)
+        default_idl_path(package_name)
     };
     
-    // Convert API response to our Program struct  
-    let program_info = Program {
-        version: program_response.version,
-        program_id: program_response.program_id,
-        network: network_str.to_string(),
-        idl_path: Some(idl_file_path.clone()),
+    let cached = if options.prefer_cached {
+        read_cached_idl(&idl_file_path, parsed_spec.version.as_deref())
+    } else {
+        None
     };
+    let used_cache = cached.is_some();
     
-    // Create directory for IDL file
-    if let Some(parent) = std::path::Path::new(&idl_file_path).parent() {
-        std::fs::create_dir_all(parent).map_err(|e| {
-            SolanaPmError::InvalidPath(format!("Failed to create directory {}: {}", parent.display(), e))
-        })?;
-    }
-    
-    // Save IDL file
-    let idl_content = serde_json::to_string_pretty(&program_response.idl)?;
-    fs::write(&idl_file_path, idl_content).map_err(|e| {
-        SolanaPmError::InvalidPath(format!("Failed to write IDL file {}: {}", idl_file_path, e))
-    })?;
+    // Only fetch from API if the IDL is not reused from disk
+    let (mut program_info, warnings) = match cached {
+        Some((version, program_id)) => {
+            statusln!("{}", CliStyle::info(&format!(
+                "Using the cached IDL at {} instead of downloading {}",
+                CliStyle::path(&idl_file_path),
+                CliStyle::package(package_name)
+            )));
+            let program_info = Program {
+                version,
+                program_id,
+                network: network_str.to_string(),
+                idl_path: Some(idl_file_path.clone()),
+                installed_at: None,
+                source: None,
+                alias: None,
+                tags: Vec::new(),
+            };
+            (program_info, Vec::new())
+        }
+        None => download_program(&registry, package_name, parsed_spec.version.as_deref(), network_str, &idl_file_path, max_idl_size, options.allow_yanked).await?,
+    };
+    program_info.alias = options.alias.clone();
+    program_info.tags = tags;
     
     // Add program to appropriate section
     if is_dev {
         solana_programs.dev_programs.insert(package_name.to_string(), program_info.clone());
-        println!("{}", CliStyle::success(&format!(
+        outln!("{}", CliStyle::success(&format!(
             "Added {} {} as dev dependency",
             CliStyle::package(package_name),
             CliStyle::version(&program_info.version)
         )));
     } else {
         solana_programs.programs.insert(package_name.to_string(), program_info.clone());
-        println!("{}", CliStyle::success(&format!(
+        outln!("{}", CliStyle::success(&format!(
             "Added {} {} as dependency",
             CliStyle::package(package_name),
             CliStyle::version(&program_info.version)
@@ -175,18 +359,498 @@ pub async fn add_program(package_spec: &str, is_dev: bool, custom_path: Option<&
     
     // Write back to SolanaPrograms.json
     let json = serde_json::to_string_pretty(&solana_programs)?;
-    fs::write(SOLANA_PROGRAMS_FILE, json)?;
+    fs::write(SOLANA_PROGRAMS_FILE, json).with_context(|| format!("Failed to write {}", SOLANA_PROGRAMS_FILE))?;
+    tracing::debug!("Wrote {}", SOLANA_PROGRAMS_FILE);
     
     // Generate TypeScript client code if requested
-    if codegen {
-        println!("\n{}", CliStyle::info("Generating TypeScript client code..."));
-        if let Err(e) = codegen::generate_typescript_client() {
-            println!("{}", CliStyle::warning(&format!(
+    if options.codegen {
+        statusln!("\n{}", CliStyle::info("Generating TypeScript client code..."));
+        if let Err(e) = codegen::generate_typescript_client(&codegen::CodegenOptions::default()) {
+            statusln!("{}", CliStyle::warning(&format!(
                 "Failed to generate TypeScript client: {}",
                 e
             )));
         }
     }
     
-    Ok(())
-}
\ No newline at end of file
+    Ok(AddOutcome::Added(AddedProgram {
+        name: package_name.clone(),
+        dev: is_dev,
+        version: program_info.version,
+        program_id: program_info.program_id,
+        network: program_info.network,
+        idl_path: program_info.idl_path,
+        alias: program_info.alias,
+        tags: program_info.tags,
+        cached: used_cache,
+        warnings,
+    }))
+}
+
+/// Downloads a program's IDL to `idl_file_path` for [`add_program`].
+/// 
+/// # Arguments
+/// 
+/// * `registry` - The registry to download from
+/// * `package_name` - The program name
+/// * `version` - The requested version, or `None` for the latest
+/// * `network` - The network to download from
+/// * `idl_file_path` - Where to write the IDL
+/// * `max_idl_size` - The maximum response size in bytes
+/// * `allow_yanked` - Whether `--allow-yanked` was passed
+/// 
+/// # Returns
+/// 
+/// Returns the manifest entry, without alias or tags, and the warnings to
+/// print, or an error if the download fails or the version is refused.
+async fn download_program(
+    registry: &impl RegistryApi,
+    package_name: &str,
+    version: Option<&str>,
+    network: &str,
+    idl_file_path: &str,
+    max_idl_size: u64,
+    allow_yanked: bool,
+) -> Result<(Program, Vec<String>)> {
+    let spinner = CliProgress::new_spinner(&format!("Installing {} from {}...", CliStyle::package(package_name), CliStyle::highlight(network)));
+
+    let project_hash = generate_project_hash();
+    
+    // Create directory for IDL file
+    if let Some(parent) = std::path::Path::new(idl_file_path).parent() {
+        std::fs::create_dir_all(parent).map_err(|e| {
+            SolanaPmError::InvalidPath(format!("Failed to create directory {}: {}", parent.display(), e))
+        })?;
+    }
+    
+    // Send the network and project hash for download tracking
+    let program_response = registry.install_program(&InstallRequest {
+        name: package_name,
+        version,
+        network,
+        project_hash: &project_hash,
+        idl_file_path,
+        max_idl_size,
+        progress: Some(&spinner),
+        validators: None,
+    }).await;
+
+    spinner.finish_and_clear();
+    
+    if let Err(SolanaPmError::ProgramNotFound(_)) = &program_response
+        && version.is_some()
+        && let Ok(versions) = registry.versions(package_name, network).await
+    {
+        statusln!("{}", CliStyle::info(&format!(
+            "Published versions of {} on {}: {}", package_name, network, versions.join(", ")
+        )));
+    }
+    // Only a conditional request can be answered with Not Modified
+    let program_response = program_response?.ok_or_else(|| SolanaPmError::DataMissing(format!(
+        "The registry sent no install response for {}",
+        package_name
+    )))?;
+    
+    let warnings = match check_installable(package_name, version, &program_response, allow_yanked) {
+        Ok(warnings) => warnings,
+        Err(e) => {
+            let _ = fs::remove_file(temp_path_for(idl_file_path));
+            return Err(e);
+        }
+    };
+    for warning in &warnings {
+        statusln!("{}", CliStyle::warning(warning));
+    }
+    
+    // Convert API response to our Program struct  
+    let program_info = Program {
+        version: program_response.version,
+        program_id: program_response.program_id,
+        network: network.to_string(),
+        idl_path: Some(idl_file_path.to_string()),
+        installed_at: Some(current_timestamp()),
+        source: Some(registry.base_url().to_string()),
+        alias: None,
+        tags: Vec::new(),
+    };
+    
+    // Save IDL file and the validators the next install revalidates it with
+    write_idl_file(idl_file_path, &program_response.idl)?;
+    write_validators(idl_file_path, &program_response.validators)?;
+    
+    Ok((program_info, warnings))
+}
+
+/// Reads the version and program ID of an existing IDL for `--prefer-cached`.
+/// 
+/// The version comes from `metadata.version` (or the top-level `version` of
+/// Shank IDLs) and the program ID from `address` (or `metadata.address`).
+/// 
+/// # Arguments
+/// 
+/// * `idl_file_path` - The IDL file `add` would write
+/// * `requested_version` - The version from the package specification, if any
+/// 
+/// # Returns
+/// 
+/// Returns the version and program ID, or `None` if the file does not exist,
+/// does not declare both, or holds a different version than was requested.
+fn read_cached_idl(idl_file_path: &str, requested_version: Option<&str>) -> Option<(String, String)> {
+    let content = fs::read_to_string(idl_file_path).ok()?;
+    let Ok(idl) = serde_json::from_str::<serde_json::Value>(&content) else {
+        tracing::debug!("{} is not valid JSON; downloading instead", idl_file_path);
+        return None;
+    };
+    
+    let version = idl["metadata"]["version"].as_str().or_else(|| idl["version"].as_str());
+    let program_id = idl["address"].as_str().or_else(|| idl["metadata"]["address"].as_str());
+    let (Some(version), Some(program_id)) = (version, program_id) else {
+        tracing::debug!("{} does not declare its version and address; downloading instead", idl_file_path);
+        return None;
+    };
+    
+    if let Some(requested_version) = requested_version
+        && requested_version != "latest"
+        && requested_version != version
+    {
+        tracing::debug!("{} holds version {}, not {}; downloading instead", idl_file_path, version, requested_version);
+        return None;
+    }
+    
+    Some((version.to_string(), program_id.to_string()))
+}
+
+/// Checks `--tag` values and removes duplicates.
+/// 
+/// # Arguments
+/// 
+/// * `tags` - The tags as passed on the command line
+/// 
+/// # Returns
+/// 
+/// Returns the trimmed tags in their original order, or an error if a tag is
+/// empty or contains whitespace.
+fn validate_tags(tags: &[String]) -> Result<Vec<String>> {
+    let mut validated: Vec<String> = Vec::new();
+    for tag in tags.iter().map(|tag| tag.trim()) {
+        if tag.is_empty() || tag.contains(char::is_whitespace) {
+            return Err(SolanaPmError::DataMissing(format!(
+                "Invalid tag '{}'; tags must be non-empty and contain no spaces",
+                tag
+            )));
+        }
+        if !validated.iter().any(|existing| existing == tag) {
+            validated.push(tag.to_string());
+        }
+    }
+    Ok(validated)
+}
+
+/// Looks a program version up in the registry without adding it.
+/// 
+/// Prints the resolved version and program ID. Nothing is downloaded to disk,
+/// no download is counted, and SolanaPrograms.json is left unchanged.
+/// 
+/// # Arguments
+/// 
+/// * `registry` - The registry to look the version up in
+/// * `package_name` - The program name
+/// * `version` - The requested version, or `None` for the latest
+/// * `network` - The network to look the version up on
+/// * `allow_yanked` - Whether a yanked version counts as available
+/// 
+/// # Returns
+/// 
+/// Returns `AddOutcome::Available` if `add` would install the version, or an
+/// error if it does not exist or would be refused.
+async fn check_availability(registry: &impl RegistryApi, package_name: &str, version: Option<&str>, network: &str, allow_yanked: bool) -> Result<AddOutcome> {
+    let spinner = CliProgress::new_spinner(&format!("Checking {} on {}...", CliStyle::package(package_name), CliStyle::highlight(network)));
+    let program_response = registry.get_program(package_name, version.unwrap_or("latest"), network).await;
+    spinner.finish_and_clear();
+    let program_response = program_response?;
+    
+    let warnings = check_installable(package_name, version, &program_response, allow_yanked)?;
+    for warning in &warnings {
+        statusln!("{}", CliStyle::warning(warning));
+    }
+    
+    outln!("{}", CliStyle::success(&format!(
+        "{} {} is available on {}",
+        CliStyle::package(package_name),
+        CliStyle::version(&program_response.version),
+        CliStyle::highlight(network)
+    )));
+    outln!("  Program ID: {}", program_response.program_id);
+    
+    Ok(AddOutcome::Available {
+        name: package_name.to_string(),
+        version: program_response.version,
+        program_id: program_response.program_id,
+        network: network.to_string(),
+        warnings,
+    })
+}
+
+/// Checks whether `add` may install the version the registry resolved.
+/// 
+/// Unlisted and draft versions are only installable by exact version, and
+/// yanked versions are refused unless explicitly allowed; deprecations only
+/// warn.
+/// 
+/// # Arguments
+/// 
+/// * `package_name` - The program name
+/// * `requested_version` - The version from the package specification, if any
+/// * `program_response` - The registry's response for the resolved version
+/// * `allow_yanked` - Whether `--allow-yanked` was passed
+/// 
+/// # Returns
+/// 
+/// Returns the warnings to print, or an error if the version is refused.
+fn check_installable(package_name: &str, requested_version: Option<&str>, program_response: &ProgramResponse, allow_yanked: bool) -> Result<Vec<String>> {
+    let mut warnings = Vec::new();
+    
+    let visibility = program_response.visibility.as_deref().unwrap_or("public");
+    if visibility != "public" {
+        let exact_version = requested_version.is_some_and(|version| version != "latest");
+        if !exact_version {
+            return Err(SolanaPmError::DataMissing(format!(
+                "The registry resolved the latest {} to {} version {}. Unlisted versions must be requested explicitly: {}",
+                package_name,
+                visibility,
+                program_response.version,
+                CliStyle::command(&format!("solpm add {}@{}", package_name, program_response.version))
+            )));
+        }
+        warnings.push(format!(
+            "{} {} is {} and not yet listed in the registry",
+            CliStyle::package(package_name),
+            CliStyle::version(&program_response.version),
+            visibility
+        ));
+    }
+    
+    if let Some(warning) = version_status_warning(package_name, program_response) {
+        if program_response.yanked && !allow_yanked {
+            return Err(SolanaPmError::DataMissing(format!("{}. Pass --allow-yanked to add it anyway.", warning)));
+        }
+        warnings.push(warning);
+    }
+    
+    Ok(warnings)
+}
+
+/// Adds a program chosen interactively from registry search results.
+/// 
+/// Prompts for a search term, queries the registry, and lets the user pick a
+/// program with a fuzzy-filtered list and then a version. The selection is
+/// added with the normal [`add_program`] flow. Requires an interactive
+/// terminal; piped invocations must pass a package name.
+/// 
+/// # Arguments
+/// 
+/// * `options` - The dependency type, IDL location, network (also searched),
+///   and download flags
+/// 
+/// # Returns
+/// 
+/// Returns what [`add_program`] did, `None` if the user cancels, or an error
+/// if not running in a terminal, the search request fails, or adding fails.
+/// 
+/// # Examples
+/// 
+/// ```no_run
+/// use solpm::Network;
+/// use solpm::commands::add::{AddOptions, add_program_interactive};
+/// 
+/// # async fn example() -> solpm::error::Result<()> {
+/// // Search devnet programs and add the selection
+/// let options = AddOptions { network: Some(Network::Dev), ..Default::default() };
+/// add_program_interactive(&options).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn add_program_interactive(options: &AddOptions) -> Result<Option<AddOutcome>> {
+    require_interactive("A package name")?;
+    if !std::io::stdin().is_terminal() || !std::io::stdout().is_terminal() {
+        return Err(SolanaPmError::DataMissing(
+            "A package name is required in non-interactive mode. Usage: solpm add <package>[@version]".to_string()
+        ));
+    }
+    
+    let network_str = resolve_network(options.network.as_ref())?.as_str();
+    
+    let registry = RegistryClient::for_registry(options.registry.as_deref())?.with_optional_token(options.registry_token.as_deref());
+    
+    loop {
+        let query = match prompt_input("Search the registry (leave empty to cancel)", None)? {
+            Some(query) if !query.trim().is_empty() => query.trim().to_string(),
+            _ => {
+                statusln!("{}", CliStyle::info("No program selected."));
+                return Ok(None);
+            }
+        };
+        
+        let spinner = CliProgress::new_spinner(&format!("Searching {} for '{}'...", CliStyle::highlight(network_str), query));
+        let results = registry.search(&query, network_str).await;
+        spinner.finish_and_clear();
+        let results = results?;
+        
+        if results.is_empty() {
+            statusln!("{}", CliStyle::warning(&format!("No programs match '{}'. Try another search.", query)));
+            continue;
+        }
+        
+        let Some(program) = select_search_result(&results)? else {
+            continue;
+        };
+        
+        let version = select_version(program)?;
+        let package_spec = format!("{}@{}", program.name, version);
+        return add_program(&package_spec, options).await.map(Some);
+    }
+}
+
+/// Lets the user pick one of the search results with a fuzzy filter.
+/// 
+/// # Arguments
+/// 
+/// * `results` - The search results to choose from
+/// 
+/// # Returns
+/// 
+/// Returns the selected program, `None` to search again, or an error if the
+/// prompt fails.
+fn select_search_result(results: &[SearchResult]) -> Result<Option<&SearchResult>> {
+    use dialoguer::FuzzySelect;
+    
+    let mut items: Vec<String> = results
+        .iter()
+        .map(|result| {
+            if result.description.is_empty() {
+                format!("{} v{}", result.name, result.version)
+            } else {
+                format!("{} v{} - {}", result.name, result.version, result.description)
+            }
+        })
+        .collect();
+    items.push("(search again)".to_string());
+    
+    let selection = FuzzySelect::new()
+        .with_prompt("Select a program (type to filter)")
+        .items(&items)
+        .default(0)
+        .interact_opt()
+        .map_err(|e| SolanaPmError::DataMissing(format!("Failed to read selection: {}", e)))?;
+    
+    Ok(selection.and_then(|index| results.get(index)))
+}
+
+/// Lets the user pick a version of the selected program.
+/// 
+/// The latest version is preselected. Programs without a version list use
+/// their latest version without prompting.
+/// 
+/// # Arguments
+/// 
+/// * `program` - The selected search result
+/// 
+/// # Returns
+/// 
+/// Returns the chosen version, or an error if the prompt fails.
+fn select_version(program: &SearchResult) -> Result<String> {
+    use dialoguer::Select;
+    
+    if program.versions.len() <= 1 {
+        return Ok(program.version.clone());
+    }
+    
+    let default_index = program.versions
+        .iter()
+        .position(|version| *version == program.version)
+        .unwrap_or(0);
+    
+    let selection = Select::new()
+        .with_prompt(format!("Select a version of {}", program.name))
+        .items(&program.versions)
+        .default(default_index)
+        .interact()
+        .map_err(|e| SolanaPmError::DataMissing(format!("Failed to read selection: {}", e)))?;
+    
+    Ok(program.versions[selection].clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::mock_server::{MockResponse, serve};
+    use crate::commands::download::read_validators;
+    
+    const INSTALL_RESPONSE: &str = r#"{
+        "version": "1.0.0",
+        "program_id": "Vest1ngZbdCMbGVTwfM8fBNBfWqBqTBBHwCbUHmBxjr",
+        "idl": { "metadata": { "name": "vesting", "version": "1.0.0" }, "instructions": [] }
+    }"#;
+    
+    const MAX_IDL_SIZE: u64 = 1024 * 1024;
+    
+    #[tokio::test]
+    async fn download_program_writes_the_idl_and_its_validators() {
+        let (base_url, requests) = serve(|_| {
+            MockResponse::json("200 OK", INSTALL_RESPONSE).with_header("ETag", "\"v1\"")
+        }).await;
+        let registry = RegistryClient::with_base_url(reqwest::Client::new(), base_url.clone());
+        let dir = tempfile::tempdir().unwrap();
+        let idl_file_path = dir.path().join("idls/vesting.json").to_string_lossy().to_string();
+        
+        let (program, warnings) = download_program(&registry, "vesting", Some("1.0.0"), "devnet", &idl_file_path, MAX_IDL_SIZE, false).await.unwrap();
+        assert_eq!(program.version, "1.0.0");
+        assert_eq!(program.program_id, "Vest1ngZbdCMbGVTwfM8fBNBfWqBqTBBHwCbUHmBxjr");
+        assert_eq!(program.network, "devnet");
+        assert_eq!(program.idl_path.as_deref(), Some(idl_file_path.as_str()));
+        assert_eq!(program.source.as_deref(), Some(base_url.as_str()));
+        assert!(warnings.is_empty());
+        
+        let idl: serde_json::Value = serde_json::from_str(&fs::read_to_string(&idl_file_path).unwrap()).unwrap();
+        assert_eq!(idl["metadata"]["name"], "vesting");
+        assert_eq!(read_validators(&idl_file_path).unwrap().etag.as_deref(), Some("\"v1\""));
+        
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, "POST");
+        assert_eq!(requests[0].path, "/programs/vesting/1.0.0/install");
+        let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+        assert_eq!(body["network"], "devnet");
+    }
+    
+    #[tokio::test]
+    async fn download_program_reports_a_missing_program() {
+        let (base_url, _) = serve(|_| MockResponse::json("404 Not Found", r#"{"error": "not found"}"#)).await;
+        let registry = RegistryClient::with_base_url(reqwest::Client::new(), base_url);
+        let dir = tempfile::tempdir().unwrap();
+        let idl_file_path = dir.path().join("vesting.json").to_string_lossy().to_string();
+        
+        let Err(error) = download_program(&registry, "vesting", None, "devnet", &idl_file_path, MAX_IDL_SIZE, false).await else {
+            panic!("the download succeeded");
+        };
+        assert!(matches!(error, SolanaPmError::ProgramNotFound(ref name) if name == "vesting"), "{}", error);
+        assert_eq!(error.exit_code(), 3);
+        assert!(!std::path::Path::new(&idl_file_path).exists());
+    }
+    
+    #[tokio::test]
+    async fn download_program_rejects_an_oversized_response() {
+        let (base_url, _) = serve(|_| MockResponse::json("200 OK", INSTALL_RESPONSE)).await;
+        let registry = RegistryClient::with_base_url(reqwest::Client::new(), base_url);
+        let dir = tempfile::tempdir().unwrap();
+        let idl_file_path = dir.path().join("vesting.json").to_string_lossy().to_string();
+        
+        let Err(error) = download_program(&registry, "vesting", None, "devnet", &idl_file_path, 64, false).await else {
+            panic!("the download succeeded");
+        };
+        assert!(matches!(error, SolanaPmError::InvalidIdl(_)), "{}", error);
+        assert!(error.to_string().contains("exceeds the maximum IDL size of 64 bytes"), "{}", error);
+        assert!(!std::path::Path::new(&idl_file_path).exists());
+        assert!(!std::path::Path::new(&temp_path_for(&idl_file_path)).exists());
+    }
+}
@@ -8,18 +8,32 @@
 //! - Secure token storage with password-based encryption
 //! - Token verification with the registry API
 //! - Login/logout functionality
+//! - Browser sign-in with the OAuth device-code flow (`login --sso`)
+//! - Re-encrypting stored credentials with a new password (`login --refresh`)
+//! - Exporting the stored token as a shell snippet for CI (`login --export`)
+//! - Checking that the registry is reachable before authenticating
+//!   (`login --test-connection`)
+//! - One-off tokens for a single `add` or `install` (`--registry-token`),
+//!   which are never stored
 //! - Credential persistence across sessions
+//! - Telling a corrupt credentials file apart from an incorrect password
 //! - Safe handling of sensitive authentication data
 //!
 //! All credentials are stored encrypted in the user's configuration directory
 //! (~/.solpm) and require password verification for access.
 
-use crate::commands::constants::AUTH_VERIFY_URL;
+use crate::commands::constants::{AUTH_DEVICE_CODE_PATH, AUTH_DEVICE_TOKEN_PATH, TOKEN_ENV_VAR};
+use crate::commands::http::{SendLogged, describe_request_error, http_client, request_error};
+use crate::commands::registry::{RegistryApi, RegistryClient, registry_base_url, registry_error, registry_url};
 use crate::error::{Result, SolanaPmError};
+use crate::outln;
+use crate::statusln;
+use crate::output::{is_quiet, require_interactive, set_result};
 use crate::utils::{CliStyle, prompt_input};
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 use aes_gcm::{Aes256Gcm, Key, Nonce, KeyInit};
 use aes_gcm::aead::{Aead, OsRng};
 use pbkdf2::pbkdf2_hmac;
@@ -27,6 +41,30 @@ use sha2::Sha256;
 use rand::RngCore;
 use base64::{Engine as _, engine::general_purpose};
 
+/// Client identifier sent with device-code requests.
+const DEVICE_CLIENT_ID: &str = "solpm-cli";
+
+/// Grant type for polling the device token endpoint (RFC 8628).
+const DEVICE_GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:device_code";
+
+/// Polling interval used when the registry does not send one.
+const DEFAULT_POLL_INTERVAL_SECS: u64 = 5;
+
+/// Seconds added to the polling interval on each `slow_down` response.
+const SLOW_DOWN_INCREMENT_SECS: u64 = 5;
+
+/// Response headers that may carry the registry's version, in order.
+const SERVER_VERSION_HEADERS: &[&str] = &["X-Registry-Version", "Server"];
+
+/// Length of the PBKDF2 salt in bytes.
+const SALT_LEN: usize = 16;
+
+/// Length of the AES-GCM nonce in bytes.
+const NONCE_LEN: usize = 12;
+
+/// Length of the AES-GCM authentication tag appended to the ciphertext.
+const TAG_LEN: usize = 16;
+
 #[derive(Serialize, Deserialize, Debug)]
 struct Credentials {
     encrypted_token: String,
@@ -34,10 +72,42 @@ struct Credentials {
     nonce: String,
 }
 
+/// The decoded parts of stored credentials.
+struct EncryptedToken {
+    /// The AES-256-GCM ciphertext, including its authentication tag
+    ciphertext: Vec<u8>,
+    /// The PBKDF2 salt
+    salt: Vec<u8>,
+    /// The AES-GCM nonce
+    nonce: Vec<u8>,
+}
+
+/// Response of the device authorization endpoint.
+#[derive(Deserialize)]
+struct DeviceCodeResponse {
+    /// Code the CLI polls the token endpoint with
+    device_code: String,
+    /// Short code the user enters in the browser
+    user_code: String,
+    /// Page where the user enters the code
+    verification_uri: String,
+    /// Page with the code already filled in, if the registry provides one
+    verification_uri_complete: Option<String>,
+    /// Seconds until the device code expires
+    expires_in: u64,
+    /// Minimum seconds between polls
+    interval: Option<u64>,
+}
+
+/// Response of the device token endpoint.
+/// 
+/// Holds `access_token` once the user has approved the request, or an
+/// `error` such as `authorization_pending` while polling should continue.
 #[derive(Deserialize)]
-struct AuthVerifyResponse {
-    valid: bool,
-    permissions: Vec<String>,
+struct DeviceTokenResponse {
+    access_token: Option<String>,
+    error: Option<String>,
+    error_description: Option<String>,
 }
 
 /// Gets the file path for storing encrypted credentials.
@@ -97,8 +167,8 @@ fn derive_key_from_password(password: &str, salt: &[u8]) -> [u8; 32] {
 /// or an error if encryption fails.
 fn encrypt_token(token: &str, password: &str) -> Result<(String, String, String)> {
     // Generate random salt and nonce
-    let mut salt = [0u8; 16];
-    let mut nonce_bytes = [0u8; 12];
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce_bytes = [0u8; NONCE_LEN];
     OsRng.fill_bytes(&mut salt);
     OsRng.fill_bytes(&mut nonce_bytes);
     
@@ -114,48 +184,83 @@ fn encrypt_token(token: &str, password: &str) -> Result<(String, String, String)
     
     // Encode to base64
     let encrypted_b64 = general_purpose::STANDARD.encode(&encrypted);
-    let salt_b64 = general_purpose::STANDARD.encode(&salt);
-    let nonce_b64 = general_purpose::STANDARD.encode(&nonce_bytes);
+    let salt_b64 = general_purpose::STANDARD.encode(salt);
+    let nonce_b64 = general_purpose::STANDARD.encode(nonce_bytes);
     
     Ok((encrypted_b64, salt_b64, nonce_b64))
 }
 
-/// Decrypts an API token using AES-256-GCM with a password-derived key.
+/// Reads and decodes the stored credentials without decrypting them.
 /// 
-/// Takes base64 encoded encrypted data and decrypts it back to the original token.
+/// Validating the file before prompting for the password means a truncated
+/// or hand-edited file is reported as corrupt instead of as a wrong password.
 /// 
 /// # Arguments
 /// 
-/// * `encrypted_token` - Base64 encoded encrypted token
-/// * `salt` - Base64 encoded salt used for key derivation
-/// * `nonce` - Base64 encoded nonce used for encryption
-/// * `password` - The password to derive the decryption key from
+/// * `credentials_path` - The path of the credentials file
 /// 
 /// # Returns
 /// 
-/// Returns the decrypted token string, or an error if decryption fails
-/// (usually indicating an incorrect password).
-fn decrypt_token(encrypted_token: &str, salt: &str, nonce: &str, password: &str) -> Result<String> {
-    // Decode from base64
-    let encrypted = general_purpose::STANDARD.decode(encrypted_token)
-        .map_err(|e| SolanaPmError::InvalidPath(format!("Invalid encrypted token: {}", e)))?;
-    let salt_bytes = general_purpose::STANDARD.decode(salt)
-        .map_err(|e| SolanaPmError::InvalidPath(format!("Invalid salt: {}", e)))?;
-    let nonce_bytes = general_purpose::STANDARD.decode(nonce)
-        .map_err(|e| SolanaPmError::InvalidPath(format!("Invalid nonce: {}", e)))?;
+/// Returns the decoded token parts, or `SolanaPmError::CorruptCredentials`
+/// if the file is not valid JSON, a field is not valid base64, or the salt,
+/// nonce, or ciphertext has the wrong length.
+fn read_credentials(credentials_path: &Path) -> Result<EncryptedToken> {
+    let credentials_content = fs::read(credentials_path)?;
+    let credentials: Credentials = serde_json::from_slice(&credentials_content)
+        .map_err(|e| SolanaPmError::CorruptCredentials(format!("invalid JSON: {}", e)))?;
     
+    let decode = |field: &str, value: &str| general_purpose::STANDARD.decode(value)
+        .map_err(|e| SolanaPmError::CorruptCredentials(format!("{} is not valid base64: {}", field, e)));
+    let token = EncryptedToken {
+        ciphertext: decode("encrypted_token", &credentials.encrypted_token)?,
+        salt: decode("salt", &credentials.salt)?,
+        nonce: decode("nonce", &credentials.nonce)?,
+    };
+    
+    let lengths = [
+        ("salt", token.salt.len(), SALT_LEN),
+        ("nonce", token.nonce.len(), NONCE_LEN),
+    ];
+    for (field, actual, expected) in lengths {
+        if actual != expected {
+            return Err(SolanaPmError::CorruptCredentials(format!(
+                "{} is {} bytes, expected {}",
+                field, actual, expected
+            )));
+        }
+    }
+    if token.ciphertext.len() <= TAG_LEN {
+        return Err(SolanaPmError::CorruptCredentials("encrypted_token is truncated".to_string()));
+    }
+    
+    Ok(token)
+}
+
+/// Decrypts an API token using AES-256-GCM with a password-derived key.
+/// 
+/// # Arguments
+/// 
+/// * `encrypted` - The decoded token parts from [`read_credentials`]
+/// * `password` - The password to derive the decryption key from
+/// 
+/// # Returns
+/// 
+/// Returns the decrypted token string, `SolanaPmError::IncorrectPassword` if
+/// decryption fails, or `SolanaPmError::CorruptCredentials` if the decrypted
+/// token is not valid UTF-8.
+fn decrypt_token(encrypted: &EncryptedToken, password: &str) -> Result<String> {
     // Derive key from password
-    let key_bytes = derive_key_from_password(password, &salt_bytes);
+    let key_bytes = derive_key_from_password(password, &encrypted.salt);
     let key = Key::<Aes256Gcm>::from_slice(&key_bytes);
     let cipher = Aes256Gcm::new(key);
     
     // Decrypt token
-    let nonce = Nonce::from_slice(&nonce_bytes);
-    let decrypted = cipher.decrypt(nonce, encrypted.as_slice())
-        .map_err(|_| SolanaPmError::InvalidPath("Decryption failed. Incorrect password?".to_string()))?;
+    let nonce = Nonce::from_slice(&encrypted.nonce);
+    let decrypted = cipher.decrypt(nonce, encrypted.ciphertext.as_slice())
+        .map_err(|_| SolanaPmError::IncorrectPassword)?;
     
     String::from_utf8(decrypted)
-        .map_err(|e| SolanaPmError::InvalidPath(format!("Invalid token data: {}", e)))
+        .map_err(|e| SolanaPmError::CorruptCredentials(format!("invalid token data: {}", e)))
 }
 
 /// Authenticates with the registry API and stores encrypted credentials.
@@ -177,60 +282,258 @@ fn decrypt_token(encrypted_token: &str, salt: &str, nonce: &str, password: &str)
 /// 
 /// # Examples
 /// 
-/// ```rust
+/// ```no_run
+/// use solpm::commands::auth::login;
+/// 
+/// # async fn example() -> solpm::error::Result<()> {
 /// // Login with prompt for token
 /// login(None).await?;
 /// 
 /// // Login with provided token
 /// login(Some("spr_your_token_here")).await?;
+/// # Ok(())
+/// # }
 /// ```
 pub async fn login(token_arg: Option<&str>) -> Result<()> {
-    println!("\n{}", CliStyle::header("Registry API Token Required"));
-    println!("To use Solana Program Manager to publish programs, you need an API token from the registry.");
-    println!("Follow these steps to get an API token:");
-    println!("1. Go to: {}", CliStyle::highlight("https://solpm.dev/auth/github"));
-    println!("2. Sign in with GitHub");
-    println!("3. Go to: {}", CliStyle::highlight("https://solpm.dev/api-tokens"));
-    println!("4. Create a new token with {} permissions", CliStyle::package("publish:programs"));
-    println!("5. Copy the generated token (starts with 'spr_')\n");
+    outln!("\n{}", CliStyle::header("Registry API Token Required"));
+    outln!("To use Solana Program Manager to publish programs, you need an API token from the registry.");
+    outln!("Follow these steps to get an API token:");
+    outln!("1. Go to: {}", CliStyle::highlight("https://solpm.dev/auth/github"));
+    outln!("2. Sign in with GitHub");
+    outln!("3. Go to: {}", CliStyle::highlight("https://solpm.dev/api-tokens"));
+    outln!("4. Create a new token with {} permissions", CliStyle::package("publish:programs"));
+    outln!("5. Copy the generated token (starts with 'spr_')\n");
     
     // Get token from argument or prompt
     let token = if let Some(t) = token_arg {
         t.trim().to_string()
     } else {
-        match prompt_input("Enter your Registry API Token", None) {
+        match prompt_input("Enter your Registry API Token", None)? {
             Some(t) if !t.trim().is_empty() => t.trim().to_string(),
             _ => return Err(SolanaPmError::InvalidPath("Token is required".to_string())),
         }
     };
     
-    // Validate token format (should start with 'spr_')
-    if !token.starts_with("spr_") {
-        return Err(SolanaPmError::UploadFailed(
-            "Invalid API token format. Registry API tokens should start with 'spr_'.".to_string()
-        ));
-    }
+    verify_and_store_token(&token).await
+}
+
+/// Checks that the registry is reachable without authenticating.
+/// 
+/// Sends a GET to the registry base URL and reports the status, the round
+/// trip latency, and the server version header when the registry sends one.
+/// Any HTTP response counts as reachable, so a failure here points to the
+/// network, proxy, or registry URL rather than the token.
+/// 
+/// # Returns
+/// 
+/// Returns `Ok(())` if the registry answered, or an error if the request
+/// could not be sent or timed out.
+/// 
+/// # Examples
+/// 
+/// ```no_run
+/// use solpm::commands::auth::test_connection;
+/// 
+/// # async fn example() -> solpm::error::Result<()> {
+/// test_connection().await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn test_connection() -> Result<()> {
+    let client = http_client()?;
+    let url = registry_base_url()?;
     
-    // Validate token by making a test request to the auth/verify endpoint
-    let client = reqwest::Client::new();
+    statusln!("{}", CliStyle::progress(&format!("Connecting to {}...", CliStyle::highlight(&url))));
+    let started = Instant::now();
+    let response = client
+        .get(&url)
+        .send_logged()
+        .await
+        .map_err(|e| if e.is_timeout() {
+            request_error("Connecting to the registry", e)
+        } else {
+            SolanaPmError::UploadFailed(format!("Registry is not reachable at {}: {}", url, describe_request_error(&e)))
+        })?;
+    let latency = started.elapsed();
+    
+    let status = response.status();
+    let server_version = SERVER_VERSION_HEADERS.iter()
+        .find_map(|header| response.headers().get(*header))
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_string());
+    
+    outln!("{}", CliStyle::success(&format!("Registry is reachable at {}", CliStyle::highlight(&url))));
+    outln!("  Status:  {}", status);
+    outln!("  Latency: {} ms", latency.as_millis());
+    match &server_version {
+        Some(version) => outln!("  Server:  {}", CliStyle::version(version)),
+        None => outln!("  Server:  {}", CliStyle::highlight("no version header")),
+    }
+    if status.is_server_error() {
+        statusln!("{}", CliStyle::warning("The registry answered with a server error; it may be down or misconfigured"));
+    }
+    set_result(serde_json::json!({
+        "registry": url,
+        "status": status.as_u16(),
+        "latency_ms": latency.as_millis() as u64,
+        "server_version": server_version,
+    }));
     
-    println!("{}", CliStyle::progress("Validating token..."));
+    Ok(())
+}
+
+/// Logs in through the registry's OAuth device-code flow.
+/// 
+/// This function:
+/// 1. Requests a device code from `/auth/device/code`
+/// 2. Prints the verification URL and user code to enter in the browser
+/// 3. Polls `/auth/device/token` every `interval` seconds until the request
+///    is approved, denied, or expires (`slow_down` lengthens the interval)
+/// 4. Verifies the issued token and stores it encrypted like `login`
+/// 
+/// Registries that do not support the device flow (the code endpoint answers
+/// 404, 405, or 501) fall back to the manual token prompt.
+/// 
+/// # Returns
+/// 
+/// Returns `Ok(())` once the token is stored, or an error if the request is
+/// denied, expires, or the token cannot be verified or saved.
+/// 
+/// # Examples
+/// 
+/// ```no_run
+/// use solpm::commands::auth::login_with_sso;
+/// 
+/// # async fn example() -> solpm::error::Result<()> {
+/// login_with_sso().await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn login_with_sso() -> Result<()> {
+    let client = http_client()?;
+    let url = registry_url(AUTH_DEVICE_CODE_PATH)?;
     
     let response = client
-        .get(AUTH_VERIFY_URL)
-        .header("Authorization", format!("Bearer {}", token))
-        .send()
+        .post(&url)
+        .json(&serde_json::json!({ "client_id": DEVICE_CLIENT_ID, "scope": "publish:programs" }))
+        .send_logged()
         .await
-        .map_err(|e| SolanaPmError::UploadFailed(format!("Failed to connect to registry server: {}. Make sure the server is running at {}", e, AUTH_VERIFY_URL)))?;
+        .map_err(|e| SolanaPmError::UploadFailed(format!("Failed to connect to registry server at {}: {}", url, describe_request_error(&e))))?;
     
-    if !response.status().is_success() {
+    let status = response.status();
+    if matches!(status.as_u16(), 404 | 405 | 501) {
+        statusln!("{}", CliStyle::warning("The registry does not support browser sign-in yet; falling back to an API token"));
+        return login(None).await;
+    }
+    if !status.is_success() {
+        return Err(registry_error(response, format!("Starting browser sign-in at {}", url)).await);
+    }
+    
+    let device: DeviceCodeResponse = response.json().await
+        .map_err(|e| SolanaPmError::UploadFailed(format!("Failed to parse server response: {}", e)))?;
+    
+    outln!("\n{}", CliStyle::header("Browser Sign-In"));
+    outln!("1. Open: {}", CliStyle::highlight(device.verification_uri_complete.as_deref().unwrap_or(&device.verification_uri)));
+    outln!("2. Enter the code: {}", CliStyle::package(&device.user_code));
+    outln!("3. Approve the request with {} permissions\n", CliStyle::package("publish:programs"));
+    statusln!("{}", CliStyle::progress("Waiting for authorization..."));
+    
+    let token = poll_device_token(&client, &device).await?;
+    
+    verify_and_store_token(&token).await
+}
+
+/// Polls the device token endpoint until the user finishes authorization.
+/// 
+/// # Arguments
+/// 
+/// * `client` - HTTP client used for polling
+/// * `device` - The device code response that started the flow
+/// 
+/// # Returns
+/// 
+/// Returns the issued API token, or an error if the user denies the request,
+/// the device code expires, or the registry returns an unexpected error.
+async fn poll_device_token(client: &reqwest::Client, device: &DeviceCodeResponse) -> Result<String> {
+    let deadline = Instant::now() + Duration::from_secs(device.expires_in);
+    let mut interval = device.interval.unwrap_or(DEFAULT_POLL_INTERVAL_SECS).max(1);
+    
+    loop {
+        tokio::time::sleep(Duration::from_secs(interval)).await;
+        if Instant::now() >= deadline {
+            return Err(SolanaPmError::UploadFailed(
+                "Browser sign-in expired before it was approved. Run 'solpm login --sso' again.".to_string()
+            ));
+        }
+        
+        let url = registry_url(AUTH_DEVICE_TOKEN_PATH)?;
+        let response = client
+            .post(&url)
+            .json(&serde_json::json!({
+                "grant_type": DEVICE_GRANT_TYPE,
+                "device_code": device.device_code,
+                "client_id": DEVICE_CLIENT_ID,
+            }))
+            .send_logged()
+            .await
+            .map_err(|e| SolanaPmError::UploadFailed(format!("Failed to connect to registry server at {}: {}", url, describe_request_error(&e))))?;
+        
         let status = response.status();
-        let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-        return Err(SolanaPmError::UploadFailed(format!("API token validation failed ({}): {}. Make sure your token is correct and the server is running.", status, error_text)));
+        let body: DeviceTokenResponse = response.json().await
+            .map_err(|e| SolanaPmError::UploadFailed(format!("Failed to parse server response ({}): {}", status, e)))?;
+        
+        if let Some(token) = body.access_token {
+            return Ok(token);
+        }
+        
+        match body.error.as_deref() {
+            Some("authorization_pending") => {}
+            Some("slow_down") => interval += SLOW_DOWN_INCREMENT_SECS,
+            Some("access_denied") => {
+                return Err(SolanaPmError::UploadFailed("Browser sign-in was denied".to_string()));
+            }
+            Some("expired_token") => {
+                return Err(SolanaPmError::UploadFailed(
+                    "Browser sign-in expired before it was approved. Run 'solpm login --sso' again.".to_string()
+                ));
+            }
+            error => {
+                let detail = body.error_description.as_deref().or(error).unwrap_or("no token in response");
+                return Err(SolanaPmError::Registry {
+                    status: status.as_u16(),
+                    code: body.error.clone(),
+                    message: detail.to_string(),
+                    operation: "Browser sign-in".to_string(),
+                });
+            }
+        }
+    }
+}
+
+/// Verifies an API token with the registry and stores it encrypted.
+/// 
+/// Checks the token format and its `publish:programs` permission, then
+/// prompts for an encryption password and writes the credentials file.
+/// 
+/// # Arguments
+/// 
+/// * `token` - The API token to verify and store
+/// 
+/// # Returns
+/// 
+/// Returns `Ok(())` once the credentials are saved, or an error if the token
+/// is invalid or the credentials cannot be written.
+async fn verify_and_store_token(token: &str) -> Result<()> {
+    // Validate token format (should start with 'spr_')
+    if !token.starts_with("spr_") {
+        return Err(SolanaPmError::UploadFailed(
+            "Invalid API token format. Registry API tokens should start with 'spr_'.".to_string()
+        ));
     }
     
-    // Parse the verification response
-    let auth_response: AuthVerifyResponse = response.json().await?;
+    // Validate token by making a test request to the auth/verify endpoint
+    statusln!("{}", CliStyle::progress("Validating token..."));
+    let auth_response = RegistryClient::new()?.verify_token(token).await?;
     
     if !auth_response.valid {
         return Err(SolanaPmError::UploadFailed("Token verification failed. Please check your token and try again.".to_string()));
@@ -242,11 +545,211 @@ pub async fn login(token_arg: Option<&str>) -> Result<()> {
     }
     
     // Prompt for encryption password
-    println!("\n{}", CliStyle::header("Encryption Password Setup"));
-    println!("To secure your API token, please create an encryption password.");
-    println!("You will need this password when publishing programs (not for other operations).");
+    outln!("\n{}", CliStyle::header("Encryption Password Setup"));
+    outln!("To secure your API token, please create an encryption password.");
+    outln!("You will need this password when publishing programs (not for other operations).");
+    
+    let password = prompt_new_password("Enter encryption password: ")?;
+    
+    // Encrypt and save credentials
+    let credentials_path = save_credentials(token, &password)?;
+    
+    let permissions_str = auth_response.permissions.join(", ");
+    outln!("\n{}", CliStyle::success("Successfully authenticated with API token"));
+    outln!("Token permissions: {}", CliStyle::package(&permissions_str));
+    outln!("Encrypted credentials saved to: {}", CliStyle::path(&credentials_path.display().to_string()));
+    statusln!("{}", CliStyle::info("Remember your encryption password - you'll need it when publishing programs!"));
     
-    let password = rpassword::prompt_password("Enter encryption password: ")
+    Ok(())
+}
+
+/// Re-encrypts the stored API token with a new password.
+/// 
+/// This function:
+/// 1. Decrypts the stored token with the current password
+/// 2. Verifies the token is still valid with the registry
+/// 3. Prompts for a new encryption password
+/// 4. Re-encrypts and replaces ~/.solpm/credentials.json
+/// 
+/// The stored credentials are left unchanged if any step fails.
+/// 
+/// # Returns
+/// 
+/// Returns `Ok(())` once the credentials are re-encrypted, or an error if
+/// no credentials are stored, the current password is wrong, or the token
+/// is no longer valid.
+/// 
+/// # Examples
+/// 
+/// ```no_run
+/// use solpm::commands::auth::refresh_credentials;
+/// 
+/// # async fn example() -> solpm::error::Result<()> {
+/// refresh_credentials().await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn refresh_credentials() -> Result<()> {
+    let credentials_path = get_credentials_path()?;
+    
+    if !credentials_path.exists() {
+        return Err(SolanaPmError::AuthRequired(
+            "Not logged in. Please run 'solpm login' first.".to_string()
+        ));
+    }
+    
+    let encrypted = read_credentials(&credentials_path)?;
+    
+    require_interactive("The current encryption password")?;
+    let current_password = rpassword::prompt_password("Enter current encryption password: ")
+        .map_err(|e| SolanaPmError::InvalidPath(format!("Failed to read password: {}", e)))?;
+    
+    let token = decrypt_token(&encrypted, &current_password)?;
+    
+    statusln!("{}", CliStyle::progress("Validating token..."));
+    
+    if !verify_token(&token).await? {
+        return Err(SolanaPmError::AuthRequired(
+            "Stored token is invalid or expired. Please run 'solpm login' with a new token.".to_string()
+        ));
+    }
+    
+    outln!("\n{}", CliStyle::header("New Encryption Password"));
+    
+    let password = prompt_new_password("Enter new encryption password: ")?;
+    let credentials_path = save_credentials(&token, &password)?;
+    
+    outln!("\n{}", CliStyle::success("Credentials re-encrypted with the new password"));
+    outln!("Encrypted credentials saved to: {}", CliStyle::path(&credentials_path.display().to_string()));
+    
+    Ok(())
+}
+
+/// Prints or writes the stored API token as an `export` statement for CI.
+/// 
+/// This function:
+/// 1. Refuses to run unless `i_understand` confirms that the token will be
+///    exposed in plain text
+/// 2. Decrypts the stored token with the encryption password
+/// 3. Verifies the token is still valid with the registry
+/// 4. Prints `export SOLPM_TOKEN='...'` to stdout, or writes it to `env_file`
+///    (created with owner-only permissions on Unix)
+/// 
+/// Status messages go to stderr so stdout can be captured with `eval`.
+/// 
+/// # Arguments
+/// 
+/// * `env_file` - File to write the export statement to instead of stdout
+/// * `i_understand` - Whether the user acknowledged that the token is exposed
+/// 
+/// # Returns
+/// 
+/// Returns `Ok(())` once the statement is printed or written, or an error if
+/// the acknowledgement is missing, no credentials are stored, the password is
+/// wrong, the token is no longer valid, or the file cannot be written.
+/// 
+/// # Examples
+/// 
+/// ```no_run
+/// use solpm::commands::auth::export_token;
+/// 
+/// # async fn example() -> solpm::error::Result<()> {
+/// // eval "$(solpm login --export --i-understand)"
+/// export_token(None, true).await?;
+/// 
+/// // solpm login --export --env-file .ci.env --i-understand
+/// export_token(Some(".ci.env"), true).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn export_token(env_file: Option<&str>, i_understand: bool) -> Result<()> {
+    if !i_understand {
+        return Err(SolanaPmError::DataMissing(format!(
+            "--export prints your API token in plain text, where shell history, logs, and CI output can capture it. \
+             Store it only in a secret store (for example as the {} CI secret) and pass --i-understand to continue.",
+            TOKEN_ENV_VAR
+        )));
+    }
+    
+    let credentials_path = get_credentials_path()?;
+    if !credentials_path.exists() {
+        return Err(SolanaPmError::AuthRequired(
+            "Not logged in. Please run 'solpm login' first.".to_string()
+        ));
+    }
+    
+    let encrypted = read_credentials(&credentials_path)?;
+    require_interactive("The encryption password")?;
+    let password = rpassword::prompt_password("Enter your encryption password: ")
+        .map_err(|e| SolanaPmError::InvalidPath(format!("Failed to read password: {}", e)))?;
+    let token = zeroize::Zeroizing::new(decrypt_token(&encrypted, &password)?);
+    
+    if !is_quiet() {
+        eprintln!("{}", CliStyle::progress("Validating token..."));
+    }
+    if !verify_token(&token).await? {
+        return Err(SolanaPmError::AuthRequired(
+            "Stored token is invalid or expired. Please run 'solpm login' with a new token.".to_string()
+        ));
+    }
+    
+    let statement = zeroize::Zeroizing::new(format!("export {}='{}'\n", TOKEN_ENV_VAR, token.as_str()));
+    match env_file {
+        Some(env_file) => {
+            write_private_file(Path::new(env_file), statement.as_bytes())?;
+            eprintln!("{}", CliStyle::success(&format!("Wrote {} to {}", TOKEN_ENV_VAR, CliStyle::path(env_file))));
+            eprintln!("{}", CliStyle::warning("The file holds your token in plain text; delete it once your CI secret is set."));
+        }
+        None => {
+            print!("{}", statement.as_str());
+            eprintln!("{}", CliStyle::warning("Your token was printed in plain text; clear it from your terminal and history."));
+        }
+    }
+    
+    Ok(())
+}
+
+/// Writes a file that only its owner can read.
+/// 
+/// On Unix the file is created with mode 0600 (an existing file is
+/// truncated and restricted); elsewhere it is written normally.
+fn write_private_file(path: &Path, contents: &[u8]) -> Result<()> {
+    use std::io::Write;
+    
+    let mut open_options = fs::OpenOptions::new();
+    open_options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        open_options.mode(0o600);
+    }
+    
+    let mut file = open_options.open(path)
+        .map_err(|e| SolanaPmError::InvalidPath(format!("Failed to write {}: {}", path.display(), e)))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    }
+    file.write_all(contents)?;
+    tracing::debug!("Wrote {}", path.display());
+    
+    Ok(())
+}
+
+/// Prompts for a new encryption password and its confirmation.
+/// 
+/// # Arguments
+/// 
+/// * `prompt` - The prompt shown for the first entry
+/// 
+/// # Returns
+/// 
+/// Returns the password, or an error if it is empty or the confirmation
+/// does not match.
+fn prompt_new_password(prompt: &str) -> Result<String> {
+    require_interactive("A new encryption password")?;
+    let password = rpassword::prompt_password(prompt)
         .map_err(|e| SolanaPmError::InvalidPath(format!("Failed to read password: {}", e)))?;
     
     if password.trim().is_empty() {
@@ -260,8 +763,22 @@ pub async fn login(token_arg: Option<&str>) -> Result<()> {
         return Err(SolanaPmError::InvalidPath("Passwords do not match".to_string()));
     }
     
-    // Encrypt and save credentials
-    let (encrypted_token, salt, nonce) = encrypt_token(&token, &password)?;
+    Ok(password)
+}
+
+/// Encrypts an API token and writes it to the credentials file.
+/// 
+/// # Arguments
+/// 
+/// * `token` - The API token to store
+/// * `password` - The encryption password
+/// 
+/// # Returns
+/// 
+/// Returns the path of the credentials file, or an error if encryption or
+/// writing fails.
+fn save_credentials(token: &str, password: &str) -> Result<PathBuf> {
+    let (encrypted_token, salt, nonce) = encrypt_token(token, password)?;
     let credentials = Credentials {
         encrypted_token,
         salt,
@@ -271,14 +788,9 @@ pub async fn login(token_arg: Option<&str>) -> Result<()> {
     let credentials_path = get_credentials_path()?;
     let credentials_json = serde_json::to_string_pretty(&credentials)?;
     fs::write(&credentials_path, credentials_json)?;
+    tracing::debug!("Wrote {}", credentials_path.display());
     
-    let permissions_str = auth_response.permissions.join(", ");
-    println!("\n{}", CliStyle::success("Successfully authenticated with API token"));
-    println!("Token permissions: {}", CliStyle::package(&permissions_str));
-    println!("Encrypted credentials saved to: {}", CliStyle::path(&credentials_path.display().to_string()));
-    println!("{}", CliStyle::info("Remember your encryption password - you'll need it when publishing programs!"));
-    
-    Ok(())
+    Ok(credentials_path)
 }
 
 /// Verifies an API token with the registry server.
@@ -293,24 +805,15 @@ pub async fn login(token_arg: Option<&str>) -> Result<()> {
 /// # Returns
 /// 
 /// Returns `Ok(true)` if the token is valid and has required permissions,
-/// `Ok(false)` if invalid, or an error if the request fails.
+/// `Ok(false)` if the registry rejects it, or an error if the request fails
+/// or the registry has a server error.
 pub async fn verify_token(token: &str) -> Result<bool> {
-    let client = reqwest::Client::new();
-    
-    let response = client
-        .get(AUTH_VERIFY_URL)
-        .header("Authorization", format!("Bearer {}", token))
-        .send()
-        .await
-        .map_err(|e| SolanaPmError::UploadFailed(format!("Failed to connect to registry server: {}", e)))?;
-    
-    if !response.status().is_success() {
-        return Ok(false);
+    match RegistryClient::new()?.verify_token(token).await {
+        Ok(auth_response) => Ok(auth_response.valid && auth_response.permissions.contains(&"publish:programs".to_string())),
+        // A rejected token is invalid; a registry failure says nothing about it
+        Err(e @ SolanaPmError::Registry { .. }) if !e.is_registry_fault() => Ok(false),
+        Err(e) => Err(e),
     }
-    
-    let auth_response: AuthVerifyResponse = response.json().await
-        .map_err(|e| SolanaPmError::UploadFailed(format!("Failed to parse server response: {}", e)))?;
-    Ok(auth_response.valid && auth_response.permissions.contains(&"publish:programs".to_string()))
 }
 
 /// Logs out by removing stored credentials from the local system.
@@ -326,10 +829,10 @@ pub fn logout() -> Result<()> {
     
     if credentials_path.exists() {
         fs::remove_file(&credentials_path)?;
-        println!("{}", CliStyle::success("Successfully logged out"));
-        println!("Credentials removed from: {}", credentials_path.display());
+        outln!("{}", CliStyle::success("Successfully logged out"));
+        outln!("Credentials removed from: {}", credentials_path.display());
     } else {
-        println!("{}", CliStyle::info("Already logged out"));
+        statusln!("{}", CliStyle::info("Already logged out"));
     }
     
     Ok(())
@@ -352,20 +855,15 @@ pub fn get_stored_token() -> Result<Option<String>> {
         return Ok(None);
     }
     
-    let credentials_content = fs::read_to_string(&credentials_path)?;
-    let credentials: Credentials = serde_json::from_str(&credentials_content)?;
+    let encrypted = read_credentials(&credentials_path)?;
     
     // Prompt for password to decrypt token only when needed
-    println!("{}", CliStyle::progress("Authentication required"));
+    require_interactive(&format!("The encryption password (set {} instead)", TOKEN_ENV_VAR))?;
+    statusln!("{}", CliStyle::progress("Authentication required"));
     let password = rpassword::prompt_password("Enter your encryption password: ")
         .map_err(|e| SolanaPmError::InvalidPath(format!("Failed to read password: {}", e)))?;
     
-    let decrypted_token = decrypt_token(
-        &credentials.encrypted_token,
-        &credentials.salt,
-        &credentials.nonce,
-        &password
-    )?;
+    let decrypted_token = decrypt_token(&encrypted, &password)?;
     
     Ok(Some(decrypted_token))
 }
@@ -384,13 +882,63 @@ pub fn has_stored_credentials() -> Result<bool> {
     Ok(credentials_path.exists())
 }
 
+/// Reads an API token from the `SOLPM_TOKEN` environment variable.
+/// 
+/// This allows non-interactive environments such as CI to authenticate
+/// without stored credentials or a password prompt.
+/// 
+/// # Returns
+/// 
+/// Returns `Some(token)` if the variable is set and non-empty, `None` otherwise.
+pub fn get_env_token() -> Option<String> {
+    std::env::var(TOKEN_ENV_VAR)
+        .ok()
+        .map(|token| token.trim().to_string())
+        .filter(|token| !token.is_empty())
+}
+
+/// Resolves the one-off token from `--registry-token` or
+/// `--registry-token-stdin`.
+/// 
+/// The token is only held for the current invocation; nothing is written to
+/// the credentials file.
+/// 
+/// # Arguments
+/// 
+/// * `token` - The `--registry-token` value, if given
+/// * `from_stdin` - Whether `--registry-token-stdin` was passed
+/// 
+/// # Returns
+/// 
+/// Returns the trimmed token, `None` if neither flag was given, or
+/// `SolanaPmError::AuthRequired` if the given token is empty.
+pub fn read_registry_token(token: Option<String>, from_stdin: bool) -> Result<Option<String>> {
+    let token = if from_stdin {
+        let mut line = String::new();
+        std::io::stdin().read_line(&mut line)?;
+        line
+    } else {
+        match token {
+            Some(token) => token,
+            None => return Ok(None),
+        }
+    };
+    
+    let token = token.trim();
+    if token.is_empty() {
+        return Err(SolanaPmError::AuthRequired("The registry token is empty".to_string()));
+    }
+    Ok(Some(token.to_string()))
+}
+
 /// Ensures the user is authenticated and returns a valid API token.
 /// 
 /// This function:
-/// 1. Checks if credentials exist locally
-/// 2. Prompts for decryption password if needed
-/// 3. Verifies the token is still valid with the registry
-/// 4. Returns the token if everything is valid
+/// 1. Uses the `SOLPM_TOKEN` environment variable if set
+/// 2. Otherwise checks if credentials exist locally
+/// 3. Prompts for decryption password if needed
+/// 4. Verifies the token is still valid with the registry
+/// 5. Returns the token if everything is valid
 /// 
 /// # Returns
 /// 
@@ -399,14 +947,30 @@ pub fn has_stored_credentials() -> Result<bool> {
 /// 
 /// # Examples
 /// 
-/// ```rust
+/// ```no_run
+/// use solpm::commands::auth::ensure_authenticated;
+/// 
+/// # async fn example() -> solpm::error::Result<()> {
 /// let token = ensure_authenticated().await?;
 /// // Use token for API calls
+/// # let _ = token;
+/// # Ok(())
+/// # }
 /// ```
 pub async fn ensure_authenticated() -> Result<String> {
+    // Prefer a token from the environment for non-interactive use
+    if let Some(token) = get_env_token() {
+        if verify_token(&token).await? {
+            return Ok(token);
+        }
+        return Err(SolanaPmError::AuthRequired(format!(
+            "Token in {} is invalid, expired, or lacks the 'publish:programs' permission.", TOKEN_ENV_VAR
+        )));
+    }
+    
     // First check if credentials exist without prompting for password
     if !has_stored_credentials()? {
-        return Err(SolanaPmError::ConfigNotFound(
+        return Err(SolanaPmError::AuthRequired(
             "Not logged in. Please run 'solpm login' first.".to_string()
         ));
     }
@@ -418,13 +982,76 @@ pub async fn ensure_authenticated() -> Result<String> {
             if verify_token(&token).await? {
                 Ok(token)
             } else {
-                Err(SolanaPmError::ConfigNotFound(
+                Err(SolanaPmError::AuthRequired(
                     "Token is invalid or expired. Please run 'solpm login' again.".to_string()
                 ))
             }
         },
-        None => Err(SolanaPmError::ConfigNotFound(
+        None => Err(SolanaPmError::AuthRequired(
             "Failed to decrypt stored token. Please run 'solpm login' again.".to_string()
         ))
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    /// Writes `contents` to a credentials file in a new temporary directory.
+    fn credentials_file(contents: &[u8]) -> (tempfile::TempDir, PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("credentials.json");
+        fs::write(&path, contents).unwrap();
+        (dir, path)
+    }
+    
+    #[test]
+    fn garbage_credentials_are_corrupt() {
+        let (_dir, path) = credentials_file(b"\x00\xffnot json at all");
+        let error = read_credentials(&path).err().unwrap();
+        assert!(matches!(error, SolanaPmError::CorruptCredentials(_)), "{:?}", error);
+        assert_eq!(error.exit_code(), 4);
+        assert_eq!(error.kind(), "auth");
+    }
+    
+    #[test]
+    fn invalid_fields_are_corrupt() {
+        let bad_base64 = r#"{"encrypted_token": "!!!", "salt": "AAAA", "nonce": "AAAA"}"#;
+        let (_dir, path) = credentials_file(bad_base64.as_bytes());
+        let error = read_credentials(&path).err().unwrap();
+        assert!(matches!(&error, SolanaPmError::CorruptCredentials(msg) if msg.contains("encrypted_token")), "{:?}", error);
+        
+        let (encrypted, _, nonce) = encrypt_token("spr_test", "password").unwrap();
+        let short_salt = serde_json::json!({ "encrypted_token": encrypted, "salt": "AAAA", "nonce": nonce }).to_string();
+        let (_dir, path) = credentials_file(short_salt.as_bytes());
+        let error = read_credentials(&path).err().unwrap();
+        assert!(matches!(&error, SolanaPmError::CorruptCredentials(msg) if msg.contains("salt")), "{:?}", error);
+    }
+    
+    #[test]
+    fn truncated_fields_are_corrupt() {
+        let (encrypted, salt, nonce) = encrypt_token("spr_test", "password").unwrap();
+        let short_nonce = general_purpose::STANDARD.encode(&general_purpose::STANDARD.decode(&nonce).unwrap()[..NONCE_LEN / 2]);
+        let contents = serde_json::json!({ "encrypted_token": encrypted, "salt": salt, "nonce": short_nonce }).to_string();
+        let (_dir, path) = credentials_file(contents.as_bytes());
+        let error = read_credentials(&path).err().unwrap();
+        assert!(matches!(&error, SolanaPmError::CorruptCredentials(msg) if msg.contains("nonce")), "{:?}", error);
+        
+        let short_ciphertext = general_purpose::STANDARD.encode(&general_purpose::STANDARD.decode(&encrypted).unwrap()[..TAG_LEN]);
+        let contents = serde_json::json!({ "encrypted_token": short_ciphertext, "salt": salt, "nonce": nonce }).to_string();
+        let (_dir, path) = credentials_file(contents.as_bytes());
+        let error = read_credentials(&path).err().unwrap();
+        assert!(matches!(&error, SolanaPmError::CorruptCredentials(msg) if msg.contains("truncated")), "{:?}", error);
+    }
+    
+    #[test]
+    fn valid_credentials_decrypt_only_with_the_password() {
+        let (encrypted, salt, nonce) = encrypt_token("spr_test", "password").unwrap();
+        let contents = serde_json::json!({ "encrypted_token": encrypted, "salt": salt, "nonce": nonce }).to_string();
+        let (_dir, path) = credentials_file(contents.as_bytes());
+        
+        let token = read_credentials(&path).unwrap();
+        assert_eq!(decrypt_token(&token, "password").unwrap(), "spr_test");
+        assert!(matches!(decrypt_token(&token, "wrong"), Err(SolanaPmError::IncorrectPassword)));
+    }
+}
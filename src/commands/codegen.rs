@@ -1,9 +1,66 @@
-use crate::commands::constants::{DEVNET_RPC_URL, MAINNET_RPC_URL, PROGRAM_CLIENT_DIR, PROGRAM_IDL_DIR, SOLANA_PROGRAMS_FILE, SYSTEM_PROGRAM_ID};
-use crate::commands::types::{Idl, IdlInstruction, IdlSeed, Program, SolanaPrograms};
-use crate::error::{Result, SolanaPmError};
-use crate::utils::CliStyle;
-use std::collections::HashSet;
+use crate::commands::config::{client_dir, rpc_url};
+use crate::commands::constants::{SOLANA_PROGRAMS_FILE, SYSTEM_PROGRAM_ID};
+use crate::commands::shank_client::generate_shank_ts_code;
+use crate::cli::{AnchorVersion, IdlFormat, Network};
+use crate::commands::types::{Idl, IdlAccount, IdlConstant, IdlInstruction, IdlPda, IdlSeed, Program, ShankIdl, SolanaPrograms, idl_type_string};
+use crate::error::{Context, Result, SolanaPmError};
+use crate::outln;
+use crate::statusln;
+use crate::output::phase;
+use crate::utils::{CliProgress, CliStyle, installed_idl_path, is_placeholder_program_id, parse_network};
+use indicatif::ProgressBar;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
 use std::fs;
+use std::path::{Component, Path};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+/// Marker in instruction docs that opts an instruction into remaining accounts.
+const REMAINING_ACCOUNTS_MARKER: &str = "@remaining-accounts";
+
+/// Rust numeric type suffixes that may appear in IDL constant values.
+const NUMERIC_SUFFIXES: [&str; 14] = ["u8", "i8", "u16", "i16", "u32", "i32", "u64", "i64", "u128", "i128", "usize", "isize", "f32", "f64"];
+
+/// Options controlling TypeScript client generation.
+#[derive(Default)]
+pub struct CodegenOptions {
+    /// Format of the installed IDL files
+    pub idl_format: IdlFormat,
+    /// Instructions (snake_case or camelCase) whose wrappers accept a
+    /// dynamic `remainingAccounts` list
+    pub accounts_as_remaining: Vec<String>,
+    /// Whether to emit `ensureX` helpers for instructions that initialize a PDA
+    pub ensure_helpers: bool,
+    /// Maximum number of clients generated at once; defaults to the number of
+    /// available cores
+    pub jobs: Option<usize>,
+    /// Whether unmappable IDL types are errors instead of `TODO` fallbacks
+    pub strict_types: bool,
+    /// Anchor client version the `Program` construction is emitted for
+    pub anchor_version: AnchorVersion,
+    /// Whether to write a `{Name}.ts` IDL type and construct `Program<{Name}>`
+    pub emit_idl_types: bool,
+    /// Only generate clients for programs with one of these tags; empty
+    /// generates every client
+    pub tags: Vec<String>,
+}
+
+/// What [`generate_typescript_client`] generated.
+#[derive(Debug, Clone, Serialize)]
+pub struct CodegenOutcome {
+    /// The generated clients, in program name order
+    pub clients: Vec<GeneratedClient>,
+}
+
+/// A TypeScript client [`generate_typescript_client`] wrote.
+#[derive(Debug, Clone, Serialize)]
+pub struct GeneratedClient {
+    /// The program the client is for
+    pub name: String,
+    /// Where the client file was written
+    pub path: String,
+}
 
 /// Generates TypeScript client code for all installed Solana programs.
 /// 
@@ -14,12 +71,59 @@ use std::fs;
 /// 3. Creating PDA (Program Derived Address) helper functions
 /// 4. Setting up proper imports and network connections
 /// 
-/// The generated client files are saved in the `program/client/` directory with
-/// the naming convention `{ProgramName}Client.ts`.
+/// Shank IDLs are converted into the Anchor-shaped internal representation,
+/// keeping each instruction's 1-byte discriminant. Native programs do not
+/// accept Anchor's 8-byte sighashes, so their clients get plain
+/// `TransactionInstruction` builders instead of `anchor.Program` wrappers
+/// (see the `shank_client` module), and `anchor_version` and
+/// `ensure_helpers` do not apply to them.
+/// 
+/// Standard IDLs do not describe dynamic account lists, so remaining accounts
+/// are opt-in: an instruction gets a `remainingAccounts` parameter when it is
+/// named in `accounts_as_remaining` or its IDL `docs` contain `@remaining-accounts`.
+/// 
+/// PDA helpers derive against `PROGRAM_ID` unless the IDL gives the PDA a
+/// `program` (a cross-program PDA), in which case they derive against that
+/// program's ID (see `pda_program`).
+/// 
+/// With `ensure_helpers`, each instruction that appears to initialize a PDA
+/// (see `find_initialized_pda`) also gets an `ensureX` helper that only sends
+/// the instruction when the account does not exist yet.
+/// 
+/// The generated client files are saved in the `program/client/` directory
+/// (or `client_dir` from ~/.solpm/config.toml) with
+/// the naming convention `{ProgramName}Client.ts`, or `{Alias}Client.ts` for
+/// programs added with `--alias` (see [`client_identifier`]). No barrel index
+/// is written, so the alias is the name applications import the client by.
+/// Two programs whose clients would share a file name are an error.
+/// 
+/// Programs are generated in parallel on up to `jobs` threads (all available
+/// cores by default). Each program reads its own IDL and writes its own client
+/// file, so workers share nothing but the work queue and the progress bar. After
+/// the first failure no new programs are started, and the error of the first
+/// failed program (in name order) is returned.
+/// 
+/// With `strict_types`, every IDL is checked before anything is written, and
+/// generation fails with a report of each instruction arg or PDA seed whose
+/// type cannot be mapped (see `find_unmappable_types`).
+/// 
+/// The `getProgram` helper constructs `anchor.Program` for `anchor_version`:
+/// `new Program(idl, PROGRAM_ID, provider)` for 0.29, or
+/// `new Program({ ...idl, address }, provider)` for 0.30 and later, so the
+/// installed program ID is used even when the IDL has no `address` field.
+/// 
+/// With `emit_idl_types`, each client also gets a `{Name}.ts` next to it that
+/// exports the IDL as a type literal (see `generate_idl_type`), and
+/// `getProgram` constructs `anchor.Program<{Name}>` so accounts, instruction
+/// arguments, and types are checked end to end. Only Anchor IDLs are supported.
+/// 
+/// # Arguments
+/// 
+/// * `options` - Codegen options such as the IDL format and remaining-accounts instructions
 /// 
 /// # Returns
 /// 
-/// Returns `Ok(())` on success, or an error if configuration files are missing,
+/// Returns the generated clients, or an error if configuration files are missing,
 /// IDL files cannot be read, or file generation fails.
 /// 
 /// # Errors
@@ -27,133 +131,453 @@ use std::fs;
 /// * `SolanaPmError::ConfigNotFound` - If SolanaPrograms.json doesn't exist
 /// * `SolanaPmError::InvalidPath` - If required IDL files are missing
 /// * File I/O errors during client file generation
-pub fn generate_typescript_client() -> Result<()> {
+pub fn generate_typescript_client(options: &CodegenOptions) -> Result<CodegenOutcome> {
+    let _phase = phase("Generate clients");
+    
     // Check if SolanaPrograms.json exists
     if !std::path::Path::new(SOLANA_PROGRAMS_FILE).exists() {
         return Err(SolanaPmError::ConfigNotFound(format!("{} not found. Run 'solpm add <program>' first.", SOLANA_PROGRAMS_FILE)));
     }
     
+    // anchor.Program<T> is typed by the Anchor IDL layout, which Shank IDLs do not follow
+    if options.emit_idl_types && matches!(options.idl_format, IdlFormat::Shank) {
+        return Err(SolanaPmError::ConfigNotFound("--emit-idl-types requires Anchor IDLs; it cannot be used with --idl-format shank".to_string()));
+    }
+    
     // Read SolanaPrograms.json
-    let solana_programs_content = fs::read_to_string(SOLANA_PROGRAMS_FILE)?;
-    let solana_programs: SolanaPrograms = serde_json::from_str(&solana_programs_content)?;
+    let solana_programs_content = fs::read_to_string(SOLANA_PROGRAMS_FILE).with_context(|| format!("Failed to read {}", SOLANA_PROGRAMS_FILE))?;
+    let solana_programs: SolanaPrograms = serde_json::from_str(&solana_programs_content).with_context(|| format!("Failed to parse {}", SOLANA_PROGRAMS_FILE))?;
     
     // Create client directory
-    std::fs::create_dir_all(PROGRAM_CLIENT_DIR)?;
+    let client_dir = client_dir()?;
+    std::fs::create_dir_all(&client_dir)
+        .with_context(|| format!("Failed to create directory {}", client_dir))?;
+    
+    statusln!("{}", CliStyle::header("TypeScript Client Generation"));
+    statusln!();
+    
+    // Process all programs (regular and dev) in a stable order
+    let mut all_programs: Vec<(&String, &Program)> = solana_programs.programs.iter()
+        .chain(solana_programs.dev_programs.iter())
+        .filter(|(_, program_info)| program_info.has_any_tag(&options.tags))
+        .collect();
+    all_programs.sort_by(|a, b| a.0.cmp(b.0));
     
-    println!("{}", CliStyle::header("TypeScript Client Generation"));
-    println!();
+    // An alias may collide with another program's name or alias
+    let mut client_owners: HashMap<String, &String> = HashMap::new();
+    for (program_name, program_info) in &all_programs {
+        let Ok(identifier) = client_identifier(program_name, program_info) else {
+            continue;
+        };
+        if let Some(owner) = client_owners.insert(identifier.clone(), program_name) {
+            return Err(SolanaPmError::InvalidIdl(format!(
+                "{} and {} would both generate {}Client.ts; set a different alias with 'solpm add <name> --alias <alias>'",
+                owner, program_name, identifier
+            )));
+        }
+    }
+    
+    if options.strict_types {
+        let mut report = Vec::new();
+        for (program_name, program_info) in &all_programs {
+            let idl = load_idl(program_name, program_info, options)?;
+            for problem in find_unmappable_types(&idl) {
+                report.push(format!("{}: {}", program_name, problem));
+            }
+        }
+        if !report.is_empty() {
+            return Err(SolanaPmError::InvalidIdl(format!(
+                "{} unmappable type{} (--strict-types):\n  - {}",
+                report.len(),
+                if report.len() == 1 { "" } else { "s" },
+                report.join("\n  - ")
+            )));
+        }
+    }
     
-    let mut generated_count = 0;
+    let jobs = options.jobs
+        .or_else(|| std::thread::available_parallelism().ok().map(usize::from))
+        .unwrap_or(1)
+        .clamp(1, all_programs.len().max(1));
     
-    // Process all programs (regular and dev)
-    let all_programs = solana_programs.programs.iter()
-        .chain(solana_programs.dev_programs.iter());
+    let progress = CliProgress::new_progress_bar(all_programs.len() as u64, "Generating clients");
+    let next_index = AtomicUsize::new(0);
+    let failed = AtomicBool::new(false);
+    let results: Mutex<Vec<(usize, Result<String>)>> = Mutex::new(Vec::new());
     
-    for (program_name, program_info) in all_programs {
-        // Determine IDL file path
-        let default_idl_path = format!("{}/{}.json", PROGRAM_IDL_DIR, program_name);
-        let idl_file_path = program_info.idl_path.as_deref().unwrap_or(&default_idl_path);
-        
-        // Check if IDL file exists
-        if !std::path::Path::new(idl_file_path).exists() {
-            return Err(SolanaPmError::InvalidPath(
-                format!("IDL file not found for '{}': {}\nRun {} to fetch missing IDL files.", 
-                program_name, idl_file_path, CliStyle::command("solpm install"))
-            ));
+    std::thread::scope(|scope| {
+        for _ in 0..jobs {
+            scope.spawn(|| {
+                while !failed.load(Ordering::Relaxed) {
+                    let index = next_index.fetch_add(1, Ordering::Relaxed);
+                    let Some((program_name, program_info)) = all_programs.get(index) else {
+                        break;
+                    };
+                    
+                    let result = generate_client(program_name, program_info, options, &client_dir, &progress);
+                    match &result {
+                        Ok(client_file_path) => progress.suspend(|| outln!("{}", CliStyle::success(&format!(
+                            "Generated {}", 
+                            CliStyle::path(client_file_path)
+                        )))),
+                        Err(_) => failed.store(true, Ordering::Relaxed),
+                    }
+                    CliProgress::inc(&progress, 1);
+                    results.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).push((index, result));
+                }
+            });
         }
-        
-        println!("{}", CliStyle::codegen(&format!(
-            "Generating client for {} ({}) from {}...", 
-            CliStyle::package(program_name),
-            CliStyle::highlight(&program_info.network),
-            CliStyle::path(idl_file_path)
-        )));
-        
-        // Read and parse IDL
-        let idl_content = fs::read_to_string(idl_file_path)?;
-        let idl: Idl = serde_json::from_str(&idl_content)?;
-        
-        // Generate TypeScript code
-        let ts_code = generate_ts_code(&idl, program_name, program_info)?;
-        
-        // Write client file
-        let client_file_name = format!("{}Client.ts", snake_to_pascal(program_name));
-        let client_file_path = format!("{}/{}", PROGRAM_CLIENT_DIR, client_file_name);
-        fs::write(&client_file_path, ts_code)?;
-        
-        generated_count += 1;
-        println!("{}", CliStyle::success(&format!(
-            "Generated {}", 
-            CliStyle::path(&client_file_path)
-        )));
+    });
+    
+    progress.finish_and_clear();
+    
+    let mut results = results.into_inner().unwrap_or_else(|poisoned| poisoned.into_inner());
+    results.sort_by_key(|(index, _)| *index);
+    let mut clients = Vec::new();
+    for (index, result) in results {
+        clients.push(GeneratedClient { name: all_programs[index].0.clone(), path: result? });
     }
+    let generated_count = clients.len();
     
     if generated_count == 0 {
-        println!("{}", CliStyle::warning("No client files generated. Make sure IDL files are available."));
+        statusln!("{}", CliStyle::warning("No client files generated. Make sure IDL files are available."));
     } else {
-        println!("\n{}", CliStyle::success(&format!(
+        outln!("\n{}", CliStyle::success(&format!(
             "🎉 Generated {} client{}!", 
             generated_count, 
             if generated_count == 1 { "" } else { "s" }
         )));
     }
     
-    Ok(())
+    Ok(CodegenOutcome { clients })
+}
+
+
+/// Generates and writes the TypeScript client for a single program.
+/// 
+/// # Arguments
+/// 
+/// * `program_name` - The name of the program
+/// * `program_info` - Program metadata including network and IDL path
+/// * `options` - Codegen options
+/// * `client_dir` - The directory client files are written to
+/// * `progress` - The aggregate progress bar, suspended while printing
+/// 
+/// # Returns
+/// 
+/// Returns the path of the written client file, or an error if the program
+/// name is not a valid identifier, the program ID is a placeholder, or the
+/// IDL is missing or invalid.
+fn generate_client(program_name: &str, program_info: &Program, options: &CodegenOptions, client_dir: &str, progress: &ProgressBar) -> Result<String> {
+    let _phase = phase(format!("Generate the {} client", program_name));
+    
+    // Ensure the program name (or alias) can be used to build TypeScript identifiers
+    let client_identifier = client_identifier(program_name, program_info)?;
+    
+    // A placeholder would produce a client that talks to no deployed program
+    if is_placeholder_program_id(&program_info.program_id) {
+        return Err(SolanaPmError::DataMissing(format!(
+            "{} has the placeholder program ID {}; its author must set the deployed ID with 'solpm init --program-id <ID>' and publish again",
+            program_name, program_info.program_id
+        )));
+    }
+    
+    let idl_file_path = installed_idl_path(program_name, program_info);
+    progress.suspend(|| statusln!("{}", CliStyle::codegen(&format!(
+        "Generating client for {} ({}) from {}...", 
+        CliStyle::package(program_name),
+        CliStyle::highlight(&program_info.network),
+        CliStyle::path(&idl_file_path)
+    ))));
+    
+    let idl = load_idl(program_name, program_info, options)?;
+    if idl.instructions.is_empty() {
+        progress.suspend(|| statusln!("{}", CliStyle::warning(&format!(
+            "{} has no instructions to generate; writing a client without instruction wrappers",
+            program_name
+        ))));
+    }
+    
+    // Write the IDL type next to the client, which imports it
+    if options.emit_idl_types {
+        let idl_content = fs::read_to_string(&idl_file_path)
+            .with_context(|| format!("Failed to read {}", idl_file_path))?;
+        let idl_json: serde_json::Value = serde_json::from_str(&idl_content)
+            .with_context(|| format!("Failed to parse {}", idl_file_path))?;
+        let idl_type_path = format!("{}/{}.ts", client_dir, client_identifier);
+        fs::write(&idl_type_path, generate_idl_type(&client_identifier, &idl_json)?)
+            .with_context(|| format!("Failed to write {}", idl_type_path))?;
+        tracing::debug!("Wrote {}", idl_type_path);
+    }
+    
+    // Generate TypeScript code
+    let ts_code = generate_ts_code(&idl, program_name, program_info, options, client_dir)?;
+    
+    // Write client file
+    let client_file_name = format!("{}Client.ts", client_identifier);
+    let client_file_path = format!("{}/{}", client_dir, client_file_name);
+    fs::write(&client_file_path, ts_code)
+        .with_context(|| format!("Failed to write {}", client_file_path))?;
+    tracing::debug!("Wrote {}", client_file_path);
+    
+    Ok(client_file_path)
+}
+
+/// Generates a TypeScript type mirroring an Anchor IDL.
+/// 
+/// JSON is valid TypeScript type syntax: strings, numbers, and booleans
+/// become literal types, arrays become tuples, and objects become object
+/// types. The result matches the `target/types` files `anchor build` writes,
+/// so it can be passed to `anchor.Program<T>`.
+/// 
+/// # Arguments
+/// 
+/// * `type_name` - The exported type name, e.g. `Vault`
+/// * `idl` - The installed IDL
+/// 
+/// # Returns
+/// 
+/// Returns the contents of the type file, or an error if the IDL cannot be
+/// serialized.
+/// 
+/// # Examples
+/// 
+/// ```text
+/// let code = generate_idl_type("Vault", &serde_json::json!({ "instructions": [] }))?;
+/// assert!(code.starts_with("export type Vault = {"));
+/// ```
+fn generate_idl_type(type_name: &str, idl: &serde_json::Value) -> Result<String> {
+    Ok(format!("export type {} = {};\n", type_name, serde_json::to_string_pretty(idl)?))
 }
 
+/// Reads and parses the installed IDL of a program.
+/// 
+/// # Arguments
+/// 
+/// * `program_name` - The name of the program
+/// * `program_info` - Program metadata including the IDL path
+/// * `options` - Codegen options selecting the IDL format
+/// 
+/// # Returns
+/// 
+/// Returns the parsed IDL, or an error if the file is missing or invalid.
+fn load_idl(program_name: &str, program_info: &Program, options: &CodegenOptions) -> Result<Idl> {
+    // Determine IDL file path, preferring the program's network directory
+    let idl_file_path = installed_idl_path(program_name, program_info);
+    let idl_file_path = idl_file_path.as_str();
+    
+    // Check if IDL file exists
+    if !std::path::Path::new(idl_file_path).exists() {
+        return Err(SolanaPmError::InvalidPath(
+            format!("IDL file not found for '{}': {}\nRun {} to fetch missing IDL files.", 
+            program_name, idl_file_path, CliStyle::command("solpm install"))
+        ));
+    }
+    
+    // Read and parse IDL
+    let idl_content = fs::read_to_string(idl_file_path)
+        .with_context(|| format!("Failed to read {}", idl_file_path))?;
+    let idl = match options.idl_format {
+        IdlFormat::Anchor => serde_json::from_str(&idl_content),
+        IdlFormat::Shank => serde_json::from_str::<ShankIdl>(&idl_content).map(Idl::from),
+    }.with_context(|| format!("Failed to parse {}", idl_file_path))?;
+    Ok(idl)
+}
+
+/// Lists the instruction args and PDA seeds whose types cannot be mapped.
+/// 
+/// An arg is unmappable when its IDL type has an unrecognized shape. An arg
+/// seed is unmappable when it names no instruction arg or its type has no
+/// seed byte conversion, i.e. when the lenient generator would guess a
+/// number width or emit a `TODO: Verify type handling` comment.
+/// 
+/// # Arguments
+/// 
+/// * `idl` - The parsed IDL
+/// 
+/// # Returns
+/// 
+/// Returns one `instruction ...: problem` entry per unmappable type.
+pub fn find_unmappable_types(idl: &Idl) -> Vec<String> {
+    let mut problems = Vec::new();
+    
+    for instruction in &idl.instructions {
+        for arg in &instruction.args {
+            let arg_type = arg.get_type_string();
+            if arg_type.split('_').any(|part| part == "unknown") {
+                problems.push(format!(
+                    "instruction `{}` arg `{}`: unrecognized type {}",
+                    instruction.name, arg.name, arg.arg_type
+                ));
+            }
+        }
+        
+        for account in &instruction.accounts {
+            let Some(pda) = &account.pda else {
+                continue;
+            };
+            for seed in pda.seeds.iter().filter(|seed| seed.kind == "arg") {
+                let Some(path) = &seed.path else {
+                    continue;
+                };
+                match instruction.args.iter().find(|arg| &arg.name == path) {
+                    None => problems.push(format!(
+                        "instruction `{}` PDA `{}` seed `{}`: no instruction arg with this name",
+                        instruction.name, account.name, path
+                    )),
+                    Some(arg) if seed_arg_buffer(path, &arg.get_type_string()).is_none() => problems.push(format!(
+                        "instruction `{}` PDA `{}` seed `{}`: type {} has no seed byte conversion",
+                        instruction.name, account.name, path, idl_type_string(&arg.arg_type)
+                    )),
+                    Some(_) => {}
+                }
+            }
+        }
+    }
+    
+    problems
+}
+
+/// Returns the import path of a project file as seen from the client directory.
+/// 
+/// Both paths are resolved against the current directory and normalized
+/// (`.` and `..` segments applied, Windows `\` separators read as `/`)
+/// before they are compared, so the same layout always yields the same
+/// import however the paths are spelled. Nothing is read from disk.
+/// 
+/// # Arguments
+/// 
+/// * `client_dir` - The directory the client is written to, relative to the project
+/// * `path` - The imported file, relative to the project or absolute
+/// 
+/// # Returns
+/// 
+/// Returns a `./` or `../` relative path, or the normalized absolute path
+/// when the two are on different Windows drives.
+/// 
+/// # Examples
+/// 
+/// ```text
+/// assert_eq!(relative_import_path("./program/client", "./program/idl/vault.json"), "../idl/vault.json");
+/// assert_eq!(relative_import_path("web\\src\\clients", "program/../idl/vault.json"), "../../../idl/vault.json");
+/// ```
+fn relative_import_path(client_dir: &str, path: &str) -> String {
+    let base = std::env::current_dir().unwrap_or_default();
+    let (from_root, from) = resolve_path(&base, client_dir);
+    let (to_root, to) = resolve_path(&base, path);
+    
+    if from_root != to_root {
+        return format!("{}{}", to_root, to.join("/"));
+    }
+    
+    let common = from.iter().zip(&to).take_while(|(from, to)| from == to).count();
+    let mut parts = vec![".."; from.len() - common];
+    parts.extend(to[common..].iter().map(String::as_str));
+    let relative = parts.join("/");
+    if relative.starts_with("..") {
+        relative
+    } else {
+        format!("./{}", relative)
+    }
+}
+
+/// Resolves a path against `base` without touching the file system.
+/// 
+/// # Arguments
+/// 
+/// * `base` - The directory relative paths are resolved against
+/// * `path` - The path to resolve, with `/` or `\` separators
+/// 
+/// # Returns
+/// 
+/// Returns the root (`/`, or e.g. `C:/` on Windows) and the remaining
+/// segments with `.` removed and `..` applied.
+fn resolve_path(base: &Path, path: &str) -> (String, Vec<String>) {
+    let path = path.replace('\\', "/");
+    let path = base.join(&path);
+    
+    let mut root = String::new();
+    let mut parts: Vec<String> = Vec::new();
+    for component in path.components() {
+        match component {
+            Component::Prefix(prefix) => root.push_str(&prefix.as_os_str().to_string_lossy().replace('\\', "/")),
+            Component::RootDir => root.push('/'),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                parts.pop();
+            }
+            Component::Normal(part) => parts.push(part.to_string_lossy().into_owned()),
+        }
+    }
+    
+    (root, parts)
+}
 
 /// Generates the complete TypeScript client code for a single Solana program.
 /// 
 /// This function creates a comprehensive TypeScript client by:
 /// 1. Setting up imports for Anchor and Solana Web3.js
 /// 2. Adding program ID and network configuration
-/// 3. Creating connection and program instance helpers
-/// 4. Generating PDA (Program Derived Address) functions
-/// 5. Creating wrapper functions for each program instruction
+/// 3. Exporting the constants declared in the IDL
+/// 4. Creating connection and program instance helpers
+/// 5. Generating PDA (Program Derived Address) functions
+/// 6. Creating wrapper functions for each program instruction
 /// 
 /// # Arguments
 /// 
 /// * `idl` - The parsed IDL (Interface Definition Language) for the program
 /// * `program_name` - The name of the program
 /// * `program_info` - Program metadata including network and program ID
+/// * `options` - Codegen options controlling instruction wrappers
+/// * `client_dir` - The directory the client is written to
 /// 
 /// # Returns
 /// 
 /// Returns the complete TypeScript code as a string, or an error if code
 /// generation fails.
-fn generate_ts_code(idl: &Idl, program_name: &str, program_info: &Program) -> Result<String> {
+fn generate_ts_code(idl: &Idl, program_name: &str, program_info: &Program, options: &CodegenOptions, client_dir: &str) -> Result<String> {
+    // Native programs dispatch on Shank discriminants, not Anchor sighashes
+    if matches!(options.idl_format, IdlFormat::Shank) {
+        return generate_shank_ts_code(idl, program_name, program_info, |instruction| uses_remaining_accounts(instruction, options));
+    }
+    
     let mut code = String::new();
     
     // Imports
     code.push_str("import * as anchor from '@coral-xyz/anchor';\n");
     code.push_str("import { Connection, PublicKey } from '@solana/web3.js';\n");
-    
-    // Generate correct IDL import path relative to the client file location
-    let default_idl_path = format!("../idl/{}.json", program_name);
-    let idl_path = if let Some(custom_path) = &program_info.idl_path {
-        // Convert absolute/relative custom path to relative from program/client/
-        if custom_path.starts_with("./") {
-            format!("../../{}", &custom_path[2..])
-        } else if custom_path.starts_with("/") {
-            custom_path.clone()
-        } else {
-            format!("../../{}", custom_path)
-        }
+    let idl_type = if options.emit_idl_types {
+        let type_name = client_identifier(program_name, program_info)?;
+        code.push_str(&format!("import type {{ {} }} from './{}';\n", type_name, type_name));
+        Some(type_name)
     } else {
-        default_idl_path
+        None
     };
+    
+    // Generate correct IDL import path relative to the client file location,
+    // including per-network IDL directories
+    let idl_path = relative_import_path(client_dir, &installed_idl_path(program_name, program_info));
     code.push_str(&format!("import idl from '{}';\n\n", idl_path));
     
     // Constants
-    code.push_str(&format!("// Your deployed program ID\n"));
+    code.push_str("// Your deployed program ID\n");
     code.push_str(&format!("const PROGRAM_ID = new PublicKey('{}');\n\n", program_info.program_id));
     
+    // Program constants declared in the IDL
+    generate_constants(&mut code, idl);
+    
     // Connection and getProgram
-    let (network_comment, rpc_url) = match program_info.network.as_str() {
-        "mainnet" => ("// Mainnet connection", MAINNET_RPC_URL),
-        "devnet" => ("// Devnet connection", DEVNET_RPC_URL),
-        _ => ("// Unknown network, defaulting to devnet", DEVNET_RPC_URL),
+    let network = parse_network(&program_info.network).map_err(|_| SolanaPmError::DataMissing(format!(
+        "{} is installed from unknown network '{}'; expected mainnet, devnet, testnet, or localnet",
+        program_name, program_info.network
+    )))?;
+    let network_comment = match network {
+        Network::Main => "// Mainnet connection",
+        Network::Dev => "// Devnet connection",
+        Network::Test => "// Testnet connection",
+        Network::Local => "// Local validator connection",
     };
+    let rpc_url = rpc_url(&network)?;
     code.push_str(&format!("{}\n", network_comment));
     code.push_str(&format!("const connection = new Connection('{}', 'confirmed');\n\n", rpc_url));
     code.push_str("// Get program instance\n");
@@ -162,20 +586,145 @@ fn generate_ts_code(idl: &Idl, program_name: &str, program_info: &Program) -> Re
     code.push_str("    commitment: 'confirmed',\n");
     code.push_str("  });\n");
     code.push_str("  \n");
-    code.push_str("  return new anchor.Program(idl, provider);\n");
+    match (options.anchor_version, &idl_type) {
+        (AnchorVersion::V029, None) => code.push_str("  return new anchor.Program(idl, PROGRAM_ID, provider);\n"),
+        (AnchorVersion::V030, None) => code.push_str("  return new anchor.Program({ ...idl, address: PROGRAM_ID.toBase58() }, provider);\n"),
+        (AnchorVersion::V029, Some(type_name)) => code.push_str(&format!(
+            "  return new anchor.Program<{0}>(idl as {0}, PROGRAM_ID, provider);\n", type_name
+        )),
+        (AnchorVersion::V030, Some(type_name)) => code.push_str(&format!(
+            "  return new anchor.Program<{0}>({{ ...idl, address: PROGRAM_ID.toBase58() }} as {0}, provider);\n", type_name
+        )),
+    }
     code.push_str("};\n\n");
     
     // Generate PDA helper functions
     generate_pda_functions(&mut code, idl)?;
     
     // Generate instruction wrapper functions
+    let mut ensured_accounts = HashSet::new();
     for instruction in &idl.instructions {
-        generate_instruction_function(&mut code, instruction, idl)?;
+        let remaining_accounts = uses_remaining_accounts(instruction, options);
+        generate_instruction_function(&mut code, instruction, idl, remaining_accounts)?;
+        
+        if options.ensure_helpers
+            && let Some(account) = find_initialized_pda(instruction)
+            && ensured_accounts.insert(account.name.clone())
+        {
+            generate_ensure_function(&mut code, instruction, account, idl, remaining_accounts)?;
+        }
     }
     
     Ok(code)
 }
 
+/// Generates exported TypeScript constants from the IDL's `constants` section.
+/// 
+/// Each constant is emitted as `export const NAME = value;` with a best-effort
+/// TypeScript value for its IDL type:
+/// - 8 to 32-bit integers and floats become numbers
+/// - 64 and 128-bit integers become `anchor.BN` values
+/// - `bool` becomes a boolean and `string` a string literal
+/// - `bytes` and `[u8; N]` arrays become `Buffer`s
+/// - `pubkey` becomes a `PublicKey`
+/// 
+/// Constants whose value cannot be converted are exported as the raw Rust
+/// expression string. Nothing is emitted when the section is absent.
+/// 
+/// # Arguments
+/// 
+/// * `code` - Mutable string to append the generated constants to
+/// * `idl` - The IDL containing the constants
+fn generate_constants(code: &mut String, idl: &Idl) {
+    let constants = match &idl.constants {
+        Some(constants) if !constants.is_empty() => constants,
+        _ => return,
+    };
+    
+    code.push_str("// Program constants from the IDL\n");
+    for constant in constants {
+        let is_identifier = constant.name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+            && constant.name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !is_identifier {
+            code.push_str(&format!("// Skipped constant '{}': not a valid identifier\n", constant.name));
+            continue;
+        }
+        
+        match constant_ts_value(constant) {
+            Some(value) => code.push_str(&format!("export const {} = {};\n", constant.name, value)),
+            None => code.push_str(&format!(
+                "export const {} = {}; // {} (raw value)\n",
+                constant.name,
+                serde_json::Value::String(constant.value.clone()),
+                constant.constant_type
+            )),
+        }
+    }
+    code.push('\n');
+}
+
+/// Converts an IDL constant's Rust expression to a TypeScript expression.
+/// 
+/// # Arguments
+/// 
+/// * `constant` - The IDL constant
+/// 
+/// # Returns
+/// 
+/// Returns the TypeScript expression, or `None` if the type is not supported
+/// or the value cannot be parsed.
+fn constant_ts_value(constant: &IdlConstant) -> Option<String> {
+    let value = constant.value.trim();
+    
+    // Numbers may use digit separators or a type suffix, e.g. 1_000u64
+    let digits = value.replace('_', "");
+    let digits = NUMERIC_SUFFIXES.iter()
+        .find_map(|suffix| digits.strip_suffix(suffix))
+        .unwrap_or(&digits)
+        .to_string();
+    let integer = || digits.parse::<i128>().is_ok().then(|| digits.clone());
+    let float = || digits.parse::<f64>().is_ok().then(|| digits.clone());
+    
+    // Byte strings (b"seed") and byte arrays ([1, 2, 3])
+    let bytes = || {
+        if let Some(literal) = value.strip_prefix('b') {
+            let text: String = serde_json::from_str(literal).ok()?;
+            Some(format!("Buffer.from({})", serde_json::Value::String(text)))
+        } else {
+            let bytes: Vec<u8> = serde_json::from_str(value).ok()?;
+            Some(format!("Buffer.from([{}])", bytes.iter().map(u8::to_string).collect::<Vec<_>>().join(", ")))
+        }
+    };
+    
+    match &constant.constant_type {
+        serde_json::Value::String(constant_type) => match constant_type.as_str() {
+            "u8" | "i8" | "u16" | "i16" | "u32" | "i32" => integer(),
+            "f32" | "f64" => float(),
+            "u64" | "i64" | "u128" | "i128" => integer().map(|digits| format!("new anchor.BN('{}')", digits)),
+            "bool" => matches!(value, "true" | "false").then(|| value.to_string()),
+            "string" => serde_json::from_str::<String>(value)
+                .ok()
+                .map(|text| serde_json::Value::String(text).to_string()),
+            "bytes" => bytes(),
+            "pubkey" | "publicKey" => {
+                let address = value
+                    .trim_start_matches("pubkey!(")
+                    .trim_end_matches(')')
+                    .trim_matches('"');
+                bs58::decode(address).into_vec().ok()
+                    .filter(|decoded| decoded.len() == 32)
+                    .map(|_| format!("new PublicKey('{}')", address))
+            }
+            _ => None,
+        },
+        serde_json::Value::Object(obj) => {
+            let element = obj.get("array")?.as_array()?.first()?;
+            (element == "u8").then(bytes).flatten()
+        }
+        _ => None,
+    }
+}
+
 /// Generates TypeScript functions for deriving Program Derived Addresses (PDAs).
 /// 
 /// This function analyzes all instructions in the IDL to find accounts that use PDAs
@@ -206,7 +755,18 @@ fn generate_pda_functions(code: &mut String, idl: &Idl) -> Result<()> {
                 let function_name = format!("get{}PDA", snake_to_pascal(&account.name));
                 
                 // Parse seeds to determine function parameters
-                let (params, seed_buffers) = parse_pda_seeds(&pda.seeds, &instruction.args)?;
+                let (params, seed_buffers) = parse_pda(pda, instruction)?;
+                
+                // Cross-program PDAs derive against another program's ID
+                let program_id = match pda_program(&account.name, pda, instruction)? {
+                    PdaProgram::Own => "PROGRAM_ID".to_string(),
+                    PdaProgram::Constant { name, address } => {
+                        code.push_str(&format!("// Program that owns the {} PDA\n", account.name));
+                        code.push_str(&format!("const {} = new PublicKey('{}');\n\n", name, address));
+                        name
+                    }
+                    PdaProgram::Param(param) => param,
+                };
                 
                 code.push_str(&format!("// Get {} PDA\n", account.name));
                 code.push_str(&format!("export const {} = ({}) => {{\n", function_name, params.join(", ")));
@@ -218,7 +778,7 @@ fn generate_pda_functions(code: &mut String, idl: &Idl) -> Result<()> {
                 }
                 
                 code.push_str("    ],\n");
-                code.push_str("    PROGRAM_ID\n");
+                code.push_str(&format!("    {}\n", program_id));
                 code.push_str("  );\n");
                 code.push_str("};\n\n");
             }
@@ -241,41 +801,30 @@ fn generate_pda_functions(code: &mut String, idl: &Idl) -> Result<()> {
 /// * `code` - Mutable string to append the generated function to
 /// * `instruction` - The IDL instruction definition to generate code for
 /// * `_idl` - The complete IDL (unused but available for future enhancements)
+/// * `remaining_accounts` - Whether the wrapper accepts a `remainingAccounts` array
 /// 
 /// # Returns
 /// 
 /// Returns `Ok(())` on success, or an error if function generation fails.
-fn generate_instruction_function(code: &mut String, instruction: &IdlInstruction, _idl: &Idl) -> Result<()> {
+fn generate_instruction_function(code: &mut String, instruction: &IdlInstruction, _idl: &Idl, remaining_accounts: bool) -> Result<()> {
     let function_name = snake_to_camel(&instruction.name);
     
     code.push_str(&format!("// {} on-chain\n", function_name));
-    code.push_str(&format!("export const {} = async (wallet", function_name));
-    
-    // Collect all parameters needed for this instruction
-    let mut all_params = Vec::new();
-    
-    // Add instruction args as parameters
-    for arg in &instruction.args {
-        all_params.push(arg.name.clone());
-    }
-    
-    // Add PDA-derived parameters
-    for account in &instruction.accounts {
-        if let Some(pda) = &account.pda {
-            let (pda_params, _) = parse_pda_seeds(&pda.seeds, &instruction.args)?;
-            for param in pda_params {
-                if !all_params.contains(&param) && param != "creator" {
-                    all_params.push(param);
-                }
-            }
-        }
+    if remaining_accounts {
+        code.push_str("// remainingAccounts: Array<{ pubkey: PublicKey, isSigner: boolean, isWritable: boolean }>\n");
     }
+    code.push_str(&format!("export const {} = async (wallet", function_name));
     
     // Add all parameters to function signature
+    let all_params = instruction_params(instruction)?;
     for param in &all_params {
         code.push_str(&format!(", {}", param));
     }
     
+    if remaining_accounts {
+        code.push_str(", remainingAccounts = []");
+    }
+    
     code.push_str(") => {\n");
     code.push_str("  const program = getProgram(wallet);\n");
     
@@ -286,16 +835,7 @@ fn generate_instruction_function(code: &mut String, instruction: &IdlInstruction
             let pda_function_name = format!("get{}PDA", snake_to_pascal(&account.name));
             let pda_var_name = format!("{}Pda", snake_to_camel(&account.name));
             
-            let (pda_params, _) = parse_pda_seeds(&pda.seeds, &instruction.args)?;
-            
-            let mut call_params = Vec::new();
-            for param in pda_params {
-                if param == "creator" {
-                    call_params.push("wallet.publicKey".to_string());
-                } else {
-                    call_params.push(param);
-                }
-            }
+            let call_params = pda_call_params(pda, instruction)?;
             
             code.push_str(&format!("  const [{}] = {}({});\n", 
                 pda_var_name, pda_function_name, call_params.join(", ")));
@@ -347,6 +887,9 @@ fn generate_instruction_function(code: &mut String, instruction: &IdlInstruction
     }
     
     code.push_str("    })\n");
+    if remaining_accounts {
+        code.push_str("    .remainingAccounts(remainingAccounts)\n");
+    }
     code.push_str("    .rpc();\n");
     code.push_str("    \n");
     
@@ -363,6 +906,195 @@ fn generate_instruction_function(code: &mut String, instruction: &IdlInstruction
     Ok(())
 }
 
+/// Collects the parameters of an instruction wrapper after `wallet`.
+/// 
+/// # Arguments
+/// 
+/// * `instruction` - The IDL instruction definition
+/// 
+/// # Returns
+/// 
+/// Returns the instruction args followed by any additional PDA seed inputs.
+/// The `creator` seed is filled from the wallet and is never a parameter.
+fn instruction_params(instruction: &IdlInstruction) -> Result<Vec<String>> {
+    let mut all_params: Vec<String> = instruction.args.iter().map(|arg| arg.name.clone()).collect();
+    
+    for account in &instruction.accounts {
+        if let Some(pda) = &account.pda {
+            let (pda_params, _) = parse_pda(pda, instruction)?;
+            for param in pda_params {
+                if !all_params.contains(&param) && param != "creator" {
+                    all_params.push(param);
+                }
+            }
+        }
+    }
+    
+    Ok(all_params)
+}
+
+/// Builds the arguments passed to a PDA helper inside an instruction wrapper.
+/// 
+/// # Arguments
+/// 
+/// * `pda` - The PDA definition of the account
+/// * `instruction` - The instruction the account belongs to
+/// 
+/// # Returns
+/// 
+/// Returns the call arguments, with `creator` replaced by `wallet.publicKey`.
+fn pda_call_params(pda: &IdlPda, instruction: &IdlInstruction) -> Result<Vec<String>> {
+    let (pda_params, _) = parse_pda(pda, instruction)?;
+    
+    Ok(pda_params
+        .into_iter()
+        .map(|param| if param == "creator" { "wallet.publicKey".to_string() } else { param })
+        .collect())
+}
+
+/// Finds the PDA account an instruction appears to initialize.
+/// 
+/// IDLs do not mark `init` accounts, so a heuristic is used. An instruction
+/// is treated as initializing an account when all of the following hold:
+/// 1. The account has PDA seeds and is writable (the account being created)
+/// 2. Another account is a writable signer (the rent payer)
+/// 3. The System Program is passed, either by its address or as an account
+///    named `system_program`, since creating an account goes through it
+/// 
+/// When several PDAs qualify, the first one in the accounts list is used.
+/// 
+/// # Arguments
+/// 
+/// * `instruction` - The IDL instruction definition
+/// 
+/// # Returns
+/// 
+/// Returns the initialized account, or `None` if the instruction does not
+/// match the heuristic.
+fn find_initialized_pda(instruction: &IdlInstruction) -> Option<&IdlAccount> {
+    let has_payer = instruction.accounts.iter()
+        .any(|account| account.is_signer_account() && account.is_writable());
+    let has_system_program = instruction.accounts.iter()
+        .any(|account| account.address.as_deref() == Some(SYSTEM_PROGRAM_ID) || account.name == "system_program");
+    
+    if !has_payer || !has_system_program {
+        return None;
+    }
+    
+    instruction.accounts.iter()
+        .find(|account| account.pda.is_some() && account.is_writable() && !account.is_signer_account())
+}
+
+/// Generates an idempotent `ensureX` helper for an initialization instruction.
+/// 
+/// The helper derives the account's PDA and fetches it with `fetchNullable`
+/// (or `getAccountInfo` when the IDL has no matching account type). The
+/// instruction is only sent when the account is missing.
+/// 
+/// # Arguments
+/// 
+/// * `code` - Mutable string to append the generated function to
+/// * `instruction` - The initialization instruction
+/// * `account` - The PDA account the instruction initializes
+/// * `idl` - The complete IDL, used to find the account type
+/// * `remaining_accounts` - Whether the instruction wrapper accepts remaining accounts
+/// 
+/// # Returns
+/// 
+/// Returns `Ok(())` on success, or an error if PDA seed parsing fails.
+fn generate_ensure_function(code: &mut String, instruction: &IdlInstruction, account: &IdlAccount, idl: &Idl, remaining_accounts: bool) -> Result<()> {
+    let Some(pda) = &account.pda else {
+        return Ok(());
+    };
+    
+    let function_name = format!("ensure{}", snake_to_pascal(&account.name));
+    let instruction_function = snake_to_camel(&instruction.name);
+    let pda_function_name = format!("get{}PDA", snake_to_pascal(&account.name));
+    let pda_var_name = format!("{}Pda", snake_to_camel(&account.name));
+    
+    let mut call_args = vec!["wallet".to_string()];
+    call_args.extend(instruction_params(instruction)?);
+    let mut signature_params = call_args.clone();
+    if remaining_accounts {
+        call_args.push("remainingAccounts".to_string());
+        signature_params.push("remainingAccounts = []".to_string());
+    }
+    
+    code.push_str(&format!("// Create {} with {} unless it already exists\n", account.name, instruction_function));
+    code.push_str(&format!("export const {} = async ({}) => {{\n", function_name, signature_params.join(", ")));
+    code.push_str(&format!("  const [{}] = {}({});\n",
+        pda_var_name, pda_function_name, pda_call_params(pda, instruction)?.join(", ")));
+    
+    match account_client_name(idl, &account.name) {
+        Some(account_type) => {
+            code.push_str("  const program = getProgram(wallet);\n");
+            code.push_str(&format!("  const existing = await program.account.{}.fetchNullable({});\n", account_type, pda_var_name));
+        }
+        None => {
+            code.push_str(&format!("  const existing = await connection.getAccountInfo({});\n", pda_var_name));
+        }
+    }
+    
+    code.push_str("  if (existing !== null) {\n");
+    code.push_str(&format!("    return {{ tx: null, pda: {}, created: false }};\n", pda_var_name));
+    code.push_str("  }\n");
+    code.push_str("  \n");
+    code.push_str(&format!("  const {{ tx }} = await {}({});\n", instruction_function, call_args.join(", ")));
+    code.push_str(&format!("  return {{ tx, pda: {}, created: true }};\n", pda_var_name));
+    code.push_str("};\n\n");
+    
+    Ok(())
+}
+
+/// Finds the Anchor client name of the account type stored at an instruction account.
+/// 
+/// Account types are matched by name, ignoring case and underscores, so an
+/// instruction account `feedback_board` matches the type `FeedbackBoard`.
+/// 
+/// # Arguments
+/// 
+/// * `idl` - The complete IDL
+/// * `account_name` - The instruction account name
+/// 
+/// # Returns
+/// 
+/// Returns the camelCase name used under `program.account`, or `None` if no
+/// account type matches.
+fn account_client_name(idl: &Idl, account_name: &str) -> Option<String> {
+    let normalize = |name: &str| name.replace('_', "").to_lowercase();
+    let wanted = normalize(account_name);
+    
+    idl.accounts.iter()
+        .flatten()
+        .filter_map(|account_type| account_type["name"].as_str())
+        .find(|name| normalize(name) == wanted)
+        .map(|name| {
+            let pascal = snake_to_pascal(name);
+            let mut chars = pascal.chars();
+            match chars.next() {
+                Some(first) => first.to_lowercase().collect::<String>() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+}
+
+/// Determines whether an instruction's wrapper should accept remaining accounts.
+/// 
+/// # Arguments
+/// 
+/// * `instruction` - The IDL instruction definition
+/// * `options` - Codegen options listing explicitly opted-in instructions
+/// 
+/// # Returns
+/// 
+/// Returns `true` if the instruction is named in the options (in snake_case or
+/// camelCase) or its docs contain the `@remaining-accounts` marker.
+fn uses_remaining_accounts(instruction: &IdlInstruction, options: &CodegenOptions) -> bool {
+    let camel_name = snake_to_camel(&instruction.name);
+    options.accounts_as_remaining.iter().any(|name| name == &instruction.name || name == &camel_name)
+        || instruction.docs.iter().any(|doc| doc.contains(REMAINING_ACCOUNTS_MARKER))
+}
+
 /// Converts snake_case strings to camelCase.
 /// 
 /// # Arguments
@@ -372,7 +1104,7 @@ fn generate_instruction_function(code: &mut String, instruction: &IdlInstruction
 /// # Returns
 /// 
 /// Returns the string converted to camelCase format.
-fn snake_to_camel(s: &str) -> String {
+pub fn snake_to_camel(s: &str) -> String {
     let mut result = String::new();
     let mut capitalize_next = false;
     
@@ -399,7 +1131,7 @@ fn snake_to_camel(s: &str) -> String {
 /// # Returns
 /// 
 /// Returns the string converted to PascalCase format.
-fn snake_to_pascal(s: &str) -> String {
+pub fn snake_to_pascal(s: &str) -> String {
     s.split('_')
         .map(|word| {
             let mut chars = word.chars();
@@ -411,6 +1143,54 @@ fn snake_to_pascal(s: &str) -> String {
         .collect()
 }
 
+/// Derives a PascalCase JavaScript identifier from a program name.
+/// 
+/// Splits the name on any non-alphanumeric character (underscores, dashes,
+/// slashes, spaces) and joins the capitalized words.
+/// 
+/// # Arguments
+/// 
+/// * `program_name` - The program name from SolanaPrograms.json
+/// 
+/// # Returns
+/// 
+/// Returns the identifier, or an error if the name contains no usable
+/// characters or would produce an identifier starting with a digit.
+pub fn program_identifier(program_name: &str) -> Result<String> {
+    let identifier: String = program_name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .map(snake_to_pascal)
+        .collect();
+    
+    match identifier.chars().next() {
+        Some(first) if !first.is_ascii_digit() => Ok(identifier),
+        _ => Err(SolanaPmError::InvalidIdl(format!(
+            "Program name '{}' cannot be converted to a valid TypeScript identifier. Rename the entry in {}.",
+            program_name, SOLANA_PROGRAMS_FILE
+        ))),
+    }
+}
+
+/// Derives the client identifier of an installed program.
+/// 
+/// Uses the program's `alias` when it has one, so a program such as
+/// `myorg_feedback_board_v2` added with `--alias feedback` gets
+/// `FeedbackClient.ts`. The IDL is still read by the program name, and
+/// instruction wrappers keep the IDL instruction names.
+/// 
+/// # Arguments
+/// 
+/// * `program_name` - The program name from SolanaPrograms.json
+/// * `program_info` - The installed program, holding the alias
+/// 
+/// # Returns
+/// 
+/// Returns the identifier, or an error if the alias (or name) cannot be
+/// converted to a TypeScript identifier (see [`program_identifier`]).
+pub fn client_identifier(program_name: &str, program_info: &Program) -> Result<String> {
+    program_identifier(program_info.alias.as_deref().unwrap_or(program_name))
+}
+
 /// Converts byte array to a string representation.
 /// 
 /// Attempts to convert bytes to UTF-8 string, falling back to hex representation
@@ -444,7 +1224,85 @@ fn bytes_to_string(bytes: &[u8]) -> String {
 fn extract_param_from_path(path: &str) -> String {
     // "feedback_board.creator" → "creator"
     // "creator" → "creator" 
-    path.split('.').last().unwrap().to_string()
+    path.split('.').next_back().unwrap().to_string()
+}
+
+/// The program a PDA is derived against.
+enum PdaProgram {
+    /// The IDL's own program (`PROGRAM_ID`)
+    Own,
+    /// A fixed program, emitted as a constant named `name`
+    Constant { name: String, address: String },
+    /// A program passed to the PDA helper as this parameter
+    Param(String),
+}
+
+/// Resolves the program a PDA is derived against from its `pda.program`.
+/// 
+/// Without `pda.program`, the PDA belongs to the IDL's own program. A
+/// `const` program (32 bytes) and an `account` program whose account has a
+/// fixed `address` become a constant named `{ACCOUNT}_PDA_PROGRAM_ID`; any
+/// other `account` or `arg` reference becomes a parameter of the helper.
+/// 
+/// # Arguments
+/// 
+/// * `account_name` - The name of the PDA account
+/// * `pda` - The PDA definition of the account
+/// * `instruction` - The instruction the account belongs to
+/// 
+/// # Returns
+/// 
+/// Returns the program, or an error if the program seed is malformed.
+fn pda_program(account_name: &str, pda: &IdlPda, instruction: &IdlInstruction) -> Result<PdaProgram> {
+    let Some(program) = &pda.program else {
+        return Ok(PdaProgram::Own);
+    };
+    let constant = |address: String| PdaProgram::Constant {
+        name: format!("{}_PDA_PROGRAM_ID", account_name.replace('-', "_").to_uppercase()),
+        address,
+    };
+    
+    match (program.kind.as_str(), &program.value, &program.path) {
+        ("const", Some(bytes), _) if bytes.len() == 32 => Ok(constant(bs58::encode(bytes).into_string())),
+        ("account", _, Some(path)) => {
+            let param = extract_param_from_path(path);
+            let fixed_address = instruction.accounts.iter()
+                .find(|account| account.name == param)
+                .and_then(|account| account.address.clone());
+            Ok(match fixed_address {
+                Some(address) => constant(address),
+                None => PdaProgram::Param(param),
+            })
+        }
+        ("arg", _, Some(path)) => Ok(PdaProgram::Param(path.clone())),
+        (kind, _, _) => Err(SolanaPmError::InvalidIdl(format!(
+            "PDA `{}` has an unsupported program seed of kind '{}'; expected a 32-byte const, an account, or an arg",
+            account_name, kind
+        ))),
+    }
+}
+
+/// Parses the seeds and program of a PDA into helper parameters and seed buffers.
+/// 
+/// Like [`parse_pda_seeds`], with the parameter of a cross-program PDA's
+/// program (see [`pda_program`]) appended when it is passed in.
+/// 
+/// # Arguments
+/// 
+/// * `pda` - The PDA definition of the account
+/// * `instruction` - The instruction the account belongs to
+/// 
+/// # Returns
+/// 
+/// Returns the helper parameters and the seed buffer expressions.
+fn parse_pda(pda: &IdlPda, instruction: &IdlInstruction) -> Result<(Vec<String>, Vec<String>)> {
+    let (mut params, seed_buffers) = parse_pda_seeds(&pda.seeds, &instruction.args)?;
+    if let Some(PdaProgram::Param(param)) = pda_program("", pda, instruction).ok()
+        && !params.contains(&param)
+    {
+        params.push(param);
+    }
+    Ok((params, seed_buffers))
 }
 
 /// Parses PDA seeds to generate function parameters and buffer conversion code.
@@ -502,32 +1360,15 @@ fn parse_pda_seeds(seeds: &[IdlSeed], instruction_args: &[crate::commands::types
                         .unwrap_or_else(|| "string".to_string());
                     
                     // Generate appropriate buffer conversion based on type
-                    let buffer_code = match arg_type.as_str() {
-                        "string" => format!("Buffer.from({})", param_name),
-                        "u8" => format!("Buffer.from([{}])", param_name),
-                        "u16" => format!("Buffer.from(new Uint16Array([{}]))", param_name),
-                        "u32" => format!("Buffer.from(new Uint32Array([{}]))", param_name),
-                        "u64" => format!("Buffer.from(new anchor.BN({}).toArray('le', 8))", param_name),
-                        "i8" => format!("Buffer.from([{} < 0 ? {} + 256 : {}])", param_name, param_name, param_name),
-                        "i16" => format!("Buffer.from(new Int16Array([{}]))", param_name),
-                        "i32" => format!("Buffer.from(new Int32Array([{}]))", param_name),
-                        "i64" => format!("Buffer.from(new anchor.BN({}).toArray('le', 8))", param_name),
-                        "bool" => format!("Buffer.from([{} ? 1 : 0])", param_name),
-                        "bytes" | "Vec<u8>" => format!("Buffer.from({})", param_name),
-                        "publicKey" => format!("{}.toBuffer()", param_name),
-                        // Handle custom types and pubkey
-                        "pubkey" | "Pubkey" | "PublicKey" => format!("{}.toBuffer()", param_name),
-                        // Default fallback for unknown types
-                        _ => {
-                            // If it looks like a number type we missed, treat as u32
-                            if arg_type.starts_with('u') || arg_type.starts_with('i') {
-                                format!("Buffer.from(new Uint32Array([{}]))", param_name)
-                            } else {
-                                // Default to string handling with a comment
-                                format!("Buffer.from({}) // TODO: Verify type handling for '{}'", param_name, arg_type)
-                            }
+                    let buffer_code = seed_arg_buffer(&param_name, &arg_type).unwrap_or_else(|| {
+                        // If it looks like a number type we missed, treat as u32
+                        if arg_type.starts_with('u') || arg_type.starts_with('i') {
+                            format!("Buffer.from(new Uint32Array([{}]))", param_name)
+                        } else {
+                            // Default to string handling with a comment
+                            format!("Buffer.from({}) // TODO: Verify type handling for '{}'", param_name, arg_type)
                         }
-                    };
+                    });
                     
                     seed_buffers.push(buffer_code);
                 }
@@ -539,4 +1380,179 @@ fn parse_pda_seeds(seeds: &[IdlSeed], instruction_args: &[crate::commands::types
     }
     
     Ok((params, seed_buffers))
-}
\ No newline at end of file
+}
+
+/// Converts an instruction arg used as a PDA seed into seed bytes.
+/// 
+/// # Arguments
+/// 
+/// * `param_name` - The TypeScript parameter holding the arg
+/// * `arg_type` - The flattened IDL type of the arg
+/// 
+/// # Returns
+/// 
+/// Returns the TypeScript buffer expression, or `None` if the type has no
+/// known seed encoding.
+fn seed_arg_buffer(param_name: &str, arg_type: &str) -> Option<String> {
+    let buffer_code = match arg_type {
+        "string" => format!("Buffer.from({})", param_name),
+        "u8" => format!("Buffer.from([{}])", param_name),
+        "u16" => format!("Buffer.from(new Uint16Array([{}]))", param_name),
+        "u32" => format!("Buffer.from(new Uint32Array([{}]))", param_name),
+        "u64" => format!("Buffer.from(new anchor.BN({}).toArray('le', 8))", param_name),
+        "i8" => format!("Buffer.from([{} < 0 ? {} + 256 : {}])", param_name, param_name, param_name),
+        "i16" => format!("Buffer.from(new Int16Array([{}]))", param_name),
+        "i32" => format!("Buffer.from(new Int32Array([{}]))", param_name),
+        "i64" => format!("Buffer.from(new anchor.BN({}).toArray('le', 8))", param_name),
+        "bool" => format!("Buffer.from([{} ? 1 : 0])", param_name),
+        "bytes" | "Vec<u8>" => format!("Buffer.from({})", param_name),
+        "publicKey" => format!("{}.toBuffer()", param_name),
+        // Handle custom types and pubkey
+        "pubkey" | "Pubkey" | "PublicKey" => format!("{}.toBuffer()", param_name),
+        _ => return None,
+    };
+    Some(buffer_code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    
+    /// A devnet dependency with the given custom IDL path.
+    fn program(idl_path: Option<&str>) -> Program {
+        Program {
+            version: "1.0.0".to_string(),
+            program_id: "Vest1ngZbdCMbGVTwfM8fBNBfWqBqTBBHwCbUHmBxjr".to_string(),
+            network: "devnet".to_string(),
+            idl_path: idl_path.map(str::to_string),
+            installed_at: None,
+            source: None,
+            alias: None,
+            tags: Vec::new(),
+        }
+    }
+    
+    /// Parses an Anchor IDL from JSON.
+    fn idl(value: serde_json::Value) -> Idl {
+        serde_json::from_value(value).unwrap()
+    }
+    
+    #[test]
+    fn emits_each_program_constructor_form() {
+        let idl = idl(json!({ "instructions": [] }));
+        let cases = [
+            (AnchorVersion::V029, false, "  return new anchor.Program(idl, PROGRAM_ID, provider);\n"),
+            (AnchorVersion::V030, false, "  return new anchor.Program({ ...idl, address: PROGRAM_ID.toBase58() }, provider);\n"),
+            (AnchorVersion::V029, true, "  return new anchor.Program<Vesting>(idl as Vesting, PROGRAM_ID, provider);\n"),
+            (AnchorVersion::V030, true, "  return new anchor.Program<Vesting>({ ...idl, address: PROGRAM_ID.toBase58() } as Vesting, provider);\n"),
+        ];
+        
+        for (anchor_version, emit_idl_types, expected) in cases {
+            let options = CodegenOptions { anchor_version, emit_idl_types, ..Default::default() };
+            let code = generate_ts_code(&idl, "vesting", &program(None), &options, "program/client").unwrap();
+            assert!(code.contains(expected), "did not emit {:?}:\n{}", expected, code);
+            assert_eq!(code.matches("new anchor.Program").count(), 1);
+            assert_eq!(code.contains("import type { Vesting } from './Vesting';"), emit_idl_types);
+        }
+    }
+    
+    #[test]
+    fn cross_program_pda_derives_against_its_program() {
+        let associated_token_program = [
+            140, 151, 37, 143, 78, 36, 137, 241, 187, 61, 16, 41, 20, 142, 13, 131,
+            11, 90, 19, 153, 218, 255, 16, 132, 4, 142, 123, 216, 219, 233, 248, 89,
+        ];
+        let idl = idl(json!({
+            "instructions": [{
+                "name": "deposit",
+                "accounts": [
+                    { "name": "owner", "signer": true },
+                    { "name": "mint" },
+                    { "name": "vault", "pda": { "seeds": [{ "kind": "const", "value": [118, 97, 117, 108, 116] }] } },
+                    {
+                        "name": "owner_token",
+                        "writable": true,
+                        "pda": {
+                            "seeds": [
+                                { "kind": "account", "path": "owner" },
+                                { "kind": "account", "path": "mint" }
+                            ],
+                            "program": { "kind": "const", "value": associated_token_program }
+                        }
+                    }
+                ],
+                "args": []
+            }]
+        }));
+        
+        let mut code = String::new();
+        generate_pda_functions(&mut code, &idl).unwrap();
+        
+        assert!(code.contains("const OWNER_TOKEN_PDA_PROGRAM_ID = new PublicKey('ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL');\n"), "{}", code);
+        let own = code.split("// Get vault PDA").nth(1).unwrap();
+        assert!(own.split("};").next().unwrap().contains("    PROGRAM_ID\n"), "{}", code);
+        let cross = code.split("// Get owner_token PDA").nth(1).unwrap();
+        assert!(cross.contains("    OWNER_TOKEN_PDA_PROGRAM_ID\n"), "{}", code);
+        assert!(!cross.contains("    PROGRAM_ID\n"), "{}", code);
+    }
+    
+    #[test]
+    fn idl_without_instructions_generates_only_get_program() {
+        for idl_json in [json!({}), json!({ "instructions": null })] {
+            let idl = idl(idl_json);
+            assert!(idl.instructions.is_empty());
+            
+            let code = generate_ts_code(&idl, "vesting", &program(None), &CodegenOptions::default(), "program/client").unwrap();
+            assert!(code.contains("import idl from '../idl/vesting.json';"), "{}", code);
+            assert!(code.contains("const getProgram = (wallet) => {"), "{}", code);
+            assert!(!code.contains("export const"), "{}", code);
+            assert!(!code.contains("findProgramAddressSync"), "{}", code);
+        }
+    }
+    
+    #[test]
+    fn custom_idl_paths_in_nested_directories_import_relative_to_the_client() {
+        let cases = [
+            ("program/client", "idls/vesting.json", "../../idls/vesting.json"),
+            ("program/client", "./idls/nested/deep/vesting.json", "../../idls/nested/deep/vesting.json"),
+            ("program/client", "program/client/idl/vesting.json", "./idl/vesting.json"),
+            ("web/src/clients", "program/../shared/idl/vesting.json", "../../../shared/idl/vesting.json"),
+            ("web\\src\\clients", "shared\\idl\\vesting.json", "../../../shared/idl/vesting.json"),
+            ("./apps/web/src/generated/", "apps/web/src/idl/vesting.json", "../idl/vesting.json"),
+        ];
+        
+        for (client_dir, idl_path, expected) in cases {
+            assert_eq!(relative_import_path(client_dir, idl_path), expected, "{} -> {}", client_dir, idl_path);
+            
+            let idl = idl(json!({ "instructions": [] }));
+            let code = generate_ts_code(&idl, "vesting", &program(Some(idl_path)), &CodegenOptions::default(), client_dir).unwrap();
+            assert!(code.contains(&format!("import idl from '{}';", expected)), "{}", code);
+        }
+    }
+    
+    #[test]
+    fn relative_import_path_normalizes_separators_and_parent_segments() {
+        assert_eq!(relative_import_path("./program/client", "./program/idl/vault.json"), "../idl/vault.json");
+        assert_eq!(relative_import_path("web\\src\\clients", "program/../idl/vault.json"), "../../../idl/vault.json");
+    }
+    
+    #[test]
+    fn shank_idls_get_instruction_builders_instead_of_anchor_program() {
+        let shank: ShankIdl = serde_json::from_value(json!({
+            "instructions": [{ "name": "Close", "accounts": [], "args": [], "discriminant": { "type": "u8", "value": 7 } }]
+        })).unwrap();
+        let options = CodegenOptions { idl_format: IdlFormat::Shank, ..Default::default() };
+        
+        let code = generate_ts_code(&Idl::from(shank), "vesting", &program(None), &options, "program/client").unwrap();
+        assert!(code.contains("export const createCloseInstruction = (accounts, programId = PROGRAM_ID) => {\n  const data = Buffer.from([7]);\n"), "{}", code);
+        assert!(!code.contains("anchor.Program"), "{}", code);
+        assert!(!code.contains("import idl"), "{}", code);
+    }
+    
+    #[test]
+    fn idl_type_is_named_after_the_program() {
+        let code = generate_idl_type("Vault", &serde_json::json!({ "instructions": [] })).unwrap();
+        assert!(code.starts_with("export type Vault = {"), "{}", code);
+    }
+}
@@ -0,0 +1,56 @@
+//! # Shell Completions Module
+//!
+//! This module implements the `completions` command which prints a shell
+//! completion script generated from the CLI definition.
+//!
+//! Features:
+//! - Completion scripts for bash, zsh, fish, PowerShell, and elvish
+//! - Scripts always match the current set of commands and flags
+//!
+//! The script is written to stdout so users can redirect it to the location
+//! their shell loads completions from. With `--json` it is returned as the
+//! `script` field of the result instead.
+
+use crate::cli::Cli;
+use crate::error::Result;
+use crate::output::{is_json_output, set_result};
+use clap::CommandFactory;
+use clap_complete::{Shell, generate};
+use std::io;
+
+/// Prints a completion script for the given shell to stdout.
+/// 
+/// # Arguments
+/// 
+/// * `shell` - The shell to generate completions for
+/// 
+/// # Returns
+/// 
+/// Returns `Ok(())` once the script has been written.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use clap_complete::Shell;
+/// use solpm::commands::completions::print_completions;
+/// 
+/// # fn main() -> solpm::error::Result<()> {
+/// // Print bash completions
+/// print_completions(Shell::Bash)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn print_completions(shell: Shell) -> Result<()> {
+    let mut command = Cli::command();
+    let bin_name = command.get_name().to_string();
+    
+    // In JSON mode the script is part of the result instead
+    if is_json_output() {
+        let mut script = Vec::new();
+        generate(shell, &mut command, bin_name, &mut script);
+        set_result(serde_json::json!({ "shell": shell.to_string(), "script": String::from_utf8_lossy(&script) }));
+    } else {
+        generate(shell, &mut command, bin_name, &mut io::stdout());
+    }
+    Ok(())
+}
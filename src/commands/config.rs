@@ -0,0 +1,604 @@
+//! # Config Module
+//!
+//! This module loads the user's `~/.solpm/config.toml` and the project's
+//! `[solpm]` settings, and implements the `config list` command.
+//!
+//! Features:
+//! - Global defaults for settings otherwise passed on every invocation:
+//!   `network`, `registry`, `client_dir`, `retries`, `timeout_secs`,
+//!   `connect_timeout_secs`, `proxy`, `mirrors`, `anonymous_headers`, and
+//!   `rpc`
+//! - Per-project `idl_dir`, `client_dir`, `default_network`, `registry`, and
+//!   `rpc` from the `[solpm]` section of SolanaPrograms.toml or from
+//!   `.solpm.toml`
+//! - `rpc.mainnet`, `rpc.devnet`, `rpc.testnet`, and `rpc.localnet` replace
+//!   the public RPC endpoints in generated clients and on-chain checks
+//! - Unknown keys are reported as warnings so newer config files still load
+//! - `solpm config list` shows each effective value and where it came from
+//!
+//! Project settings sit below command-line flags and environment variables;
+//! the global file sits below the project and above the built-in defaults.
+
+use crate::cli::Network;
+use crate::commands::constants::{DEFAULT_CONNECT_TIMEOUT_SECS, DEFAULT_DOWNLOAD_RETRIES, DEFAULT_REQUEST_TIMEOUT_SECS, DEVNET_RPC_URL, LOCALNET_RPC_URL, MAINNET_RPC_URL, PROGRAM_CLIENT_DIR, PROGRAM_IDL_DIR, TESTNET_RPC_URL, TIMEOUT_ENV_VAR};
+use crate::commands::http::proxy_setting;
+use crate::commands::registry::{mirror_base_urls, registry_setting};
+use crate::commands::types::{GlobalConfig, ProjectSettings, PublishConfig, RpcEndpoints, SolanaProgramsConfig};
+use crate::error::{Result, SolanaPmError};
+use crate::outln;
+use crate::output::{is_quiet, set_result};
+use crate::utils::{CliStyle, parse_network};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+const GLOBAL_CONFIG_FILE: &str = "config.toml";
+const SOLANA_PROGRAMS_TOML: &str = "SolanaPrograms.toml";
+const PROJECT_SETTINGS_FILE: &str = ".solpm.toml";
+
+/// Keys read from `~/.solpm/config.toml`.
+const GLOBAL_CONFIG_KEYS: &[&str] = &["network", "registry", "client_dir", "retries", "timeout_secs", "connect_timeout_secs", "proxy", "mirrors", "anonymous_headers", "rpc"];
+
+/// Keys read from `[solpm]` in SolanaPrograms.toml and from `.solpm.toml`.
+const PROJECT_SETTINGS_KEYS: &[&str] = &["idl_dir", "client_dir", "default_network", "registry", "rpc"];
+
+/// Keys read from an `rpc` table.
+const RPC_KEYS: &[&str] = &["mainnet", "devnet", "testnet", "localnet"];
+
+/// The `--timeout` flag, set once by `main` before any command runs.
+static TIMEOUT_FLAG: OnceLock<u64> = OnceLock::new();
+
+/// The loaded global config, read on first use.
+static GLOBAL_CONFIG: OnceLock<GlobalConfig> = OnceLock::new();
+
+/// The loaded project settings, read by `main` before any command runs.
+static PROJECT_SETTINGS: OnceLock<ProjectSettings> = OnceLock::new();
+
+/// Where an effective setting came from, highest precedence first.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ConfigOrigin {
+    /// A command-line flag
+    Flag,
+    /// An environment variable
+    Env,
+    /// A project file (`.solpmrc`, `.solpm.toml`, or SolanaPrograms.toml)
+    Project,
+    /// `~/.solpm/config.toml`
+    Global,
+    /// The built-in default
+    Default,
+}
+
+impl ConfigOrigin {
+    /// Returns the name shown by `solpm config list`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConfigOrigin::Flag => "flag",
+            ConfigOrigin::Env => "env",
+            ConfigOrigin::Project => "project",
+            ConfigOrigin::Global => "global",
+            ConfigOrigin::Default => "default",
+        }
+    }
+}
+
+/// Records the `--timeout` flag so it takes precedence over every other source.
+/// 
+/// # Arguments
+/// 
+/// * `timeout_secs` - The seconds passed with `--timeout`, if any
+pub fn set_timeout_flag(timeout_secs: Option<u64>) {
+    if let Some(timeout_secs) = timeout_secs {
+        let _ = TIMEOUT_FLAG.set(timeout_secs);
+    }
+}
+
+/// Returns the path of the global config file, `~/.solpm/config.toml`.
+pub fn global_config_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home_dir| home_dir.join(".solpm").join(GLOBAL_CONFIG_FILE))
+}
+
+/// Returns the global config, loading it on first use.
+/// 
+/// # Returns
+/// 
+/// Returns the parsed config (all defaults if the file does not exist), or
+/// an error if the file cannot be read, is not valid TOML, or holds an
+/// invalid value.
+/// 
+/// # Examples
+/// 
+/// ```no_run
+/// use solpm::commands::config::global_config;
+/// use solpm::commands::constants::DEFAULT_DOWNLOAD_RETRIES;
+/// 
+/// # fn example() -> solpm::error::Result<()> {
+/// let retries = global_config()?.retries.unwrap_or(DEFAULT_DOWNLOAD_RETRIES);
+/// # Ok(())
+/// # }
+/// ```
+pub fn global_config() -> Result<&'static GlobalConfig> {
+    if let Some(config) = GLOBAL_CONFIG.get() {
+        return Ok(config);
+    }
+    
+    let config = load_global_config()?;
+    Ok(GLOBAL_CONFIG.get_or_init(|| config))
+}
+
+/// Reads and validates `~/.solpm/config.toml`.
+fn load_global_config() -> Result<GlobalConfig> {
+    let Some(path) = global_config_path().filter(|path| path.exists()) else {
+        return Ok(GlobalConfig::default());
+    };
+    
+    let content = fs::read_to_string(&path)
+        .map_err(|e| SolanaPmError::InvalidPath(format!("Failed to read {}: {}", path.display(), e)))?;
+    let table: toml::Table = toml::from_str(&content)
+        .map_err(|e| SolanaPmError::InvalidPath(format!("Invalid {}: {}", path.display(), e)))?;
+    
+    for key in table.keys().filter(|key| !GLOBAL_CONFIG_KEYS.contains(&key.as_str())) {
+        if !is_quiet() {
+            eprintln!("{}", CliStyle::warning(&format!("Ignoring unknown key '{}' in {}", key, path.display())));
+        }
+    }
+    warn_unknown_rpc_keys(&table, &path.display().to_string());
+    
+    let config: GlobalConfig = toml::Value::Table(table).try_into()
+        .map_err(|e| SolanaPmError::InvalidPath(format!("Invalid {}: {}", path.display(), e)))?;
+    validate_global_config(&config)
+        .map_err(|problem| SolanaPmError::InvalidPath(format!("Invalid {}: {}", path.display(), problem)))?;
+    
+    Ok(config)
+}
+
+/// Checks the values of a global config.
+fn validate_global_config(config: &GlobalConfig) -> std::result::Result<(), String> {
+    if let Some(network) = &config.network {
+        parse_network(network)
+            .map_err(|_| format!("network '{}' must be mainnet, devnet, testnet, or localnet", network))?;
+    }
+    if let Some(client_dir) = &config.client_dir
+        && (client_dir.trim().is_empty() || Path::new(client_dir).is_absolute())
+    {
+        return Err(format!("client_dir '{}' must be a path relative to the project", client_dir));
+    }
+    if config.timeout_secs == Some(0) {
+        return Err("timeout_secs must be at least 1 second".to_string());
+    }
+    if config.connect_timeout_secs == Some(0) {
+        return Err("connect_timeout_secs must be at least 1 second".to_string());
+    }
+    validate_rpc_endpoints(&config.rpc)?;
+    
+    Ok(())
+}
+
+/// Checks that every configured RPC endpoint is an http(s) URL.
+fn validate_rpc_endpoints(rpc: &RpcEndpoints) -> std::result::Result<(), String> {
+    for (network, url) in rpc.entries() {
+        if let Some(url) = url
+            && !(url.starts_with("https://") || url.starts_with("http://"))
+        {
+            return Err(format!("rpc.{} '{}' must start with https:// or http://", network, url));
+        }
+    }
+    
+    Ok(())
+}
+
+/// Warns about keys of an `rpc` table that name no network.
+fn warn_unknown_rpc_keys(table: &toml::Table, source: &str) {
+    let Some(toml::Value::Table(rpc)) = table.get("rpc") else {
+        return;
+    };
+    for key in rpc.keys().filter(|key| !RPC_KEYS.contains(&key.as_str())) {
+        if !is_quiet() {
+            eprintln!("{}", CliStyle::warning(&format!("Ignoring unknown key 'rpc.{}' in {}", key, source)));
+        }
+    }
+}
+
+/// Loads the project settings from the current directory.
+/// 
+/// `[solpm]` in SolanaPrograms.toml takes precedence over `.solpm.toml`,
+/// key by key. A SolanaPrograms.toml that is not valid TOML is skipped here
+/// and reported by the command that reads it.
+/// 
+/// # Returns
+/// 
+/// Returns `Ok(())` once the settings are loaded, or an error if a settings
+/// file cannot be read or holds an invalid value.
+/// 
+/// # Examples
+/// 
+/// ```no_run
+/// use solpm::commands::config::{idl_dir, load_project_settings};
+/// 
+/// # fn example() -> solpm::error::Result<()> {
+/// load_project_settings()?;
+/// let idl_dir = idl_dir();
+/// # Ok(())
+/// # }
+/// ```
+pub fn load_project_settings() -> Result<()> {
+    let mut settings = read_project_settings(PROJECT_SETTINGS_FILE, false)?;
+    let section = read_project_settings(SOLANA_PROGRAMS_TOML, true)?;
+    
+    settings.idl_dir = section.idl_dir.or(settings.idl_dir);
+    settings.client_dir = section.client_dir.or(settings.client_dir);
+    settings.default_network = section.default_network.or(settings.default_network);
+    settings.registry = section.registry.or(settings.registry);
+    settings.rpc = RpcEndpoints {
+        mainnet: section.rpc.mainnet.or(settings.rpc.mainnet),
+        devnet: section.rpc.devnet.or(settings.rpc.devnet),
+        testnet: section.rpc.testnet.or(settings.rpc.testnet),
+        localnet: section.rpc.localnet.or(settings.rpc.localnet),
+    };
+    
+    let _ = PROJECT_SETTINGS.set(settings);
+    Ok(())
+}
+
+/// Returns the project settings loaded by [`load_project_settings`].
+pub fn project_settings() -> &'static ProjectSettings {
+    PROJECT_SETTINGS.get_or_init(ProjectSettings::default)
+}
+
+/// Reads project settings from `.solpm.toml`, or from the `[solpm]` section
+/// of SolanaPrograms.toml when `section` is set.
+fn read_project_settings(file: &str, section: bool) -> Result<ProjectSettings> {
+    if !Path::new(file).exists() {
+        return Ok(ProjectSettings::default());
+    }
+    
+    let content = fs::read_to_string(file)
+        .map_err(|e| SolanaPmError::InvalidPath(format!("Failed to read {}: {}", file, e)))?;
+    let (table, source) = if section {
+        let Ok(mut table) = toml::from_str::<toml::Table>(&content) else {
+            return Ok(ProjectSettings::default());
+        };
+        match table.remove("solpm") {
+            Some(toml::Value::Table(table)) => (table, format!("[solpm] in {}", file)),
+            Some(_) => return Err(SolanaPmError::InvalidPath(format!("Invalid {}: 'solpm' must be a table", file))),
+            None => return Ok(ProjectSettings::default()),
+        }
+    } else {
+        let table: toml::Table = toml::from_str(&content)
+            .map_err(|e| SolanaPmError::InvalidPath(format!("Invalid {}: {}", file, e)))?;
+        (table, file.to_string())
+    };
+    
+    for key in table.keys().filter(|key| !PROJECT_SETTINGS_KEYS.contains(&key.as_str())) {
+        if !is_quiet() {
+            eprintln!("{}", CliStyle::warning(&format!("Ignoring unknown key '{}' in {}", key, source)));
+        }
+    }
+    warn_unknown_rpc_keys(&table, &source);
+    
+    let mut settings: ProjectSettings = toml::Value::Table(table).try_into()
+        .map_err(|e| SolanaPmError::InvalidPath(format!("Invalid {}: {}", source, e)))?;
+    normalize_project_settings(&mut settings)
+        .map_err(|problem| SolanaPmError::InvalidPath(format!("Invalid {}: {}", source, problem)))?;
+    
+    Ok(settings)
+}
+
+/// Checks the values of project settings and rewrites directories as `./dir`.
+fn normalize_project_settings(settings: &mut ProjectSettings) -> std::result::Result<(), String> {
+    for (key, dir) in [("idl_dir", &mut settings.idl_dir), ("client_dir", &mut settings.client_dir)] {
+        let Some(value) = dir else {
+            continue;
+        };
+        let trimmed = value.trim().trim_end_matches('/');
+        let relative = trimmed.strip_prefix("./").unwrap_or(trimmed);
+        if relative.is_empty() || relative == "." || Path::new(relative).is_absolute() {
+            return Err(format!("{} '{}' must be a directory relative to the project root", key, value));
+        }
+        if relative.split(['/', '\\']).any(|part| part == "..") {
+            return Err(format!("{} '{}' must stay inside the project root", key, value));
+        }
+        *value = format!("./{}", relative);
+    }
+    
+    if let Some(network) = &mut settings.default_network {
+        *network = parse_network(network)
+            .map_err(|_| format!("default_network '{}' must be mainnet, devnet, testnet, or localnet", network))?
+            .as_str()
+            .to_string();
+    }
+    validate_rpc_endpoints(&settings.rpc)?;
+    
+    Ok(())
+}
+
+/// Resolves the network for commands that take `--network`.
+/// 
+/// # Arguments
+/// 
+/// * `flag` - The `--network` flag, which takes precedence
+/// 
+/// # Returns
+/// 
+/// Returns the flag, the project `default_network`, the global `network`,
+/// or devnet, or an error if the global config cannot be loaded.
+pub fn resolve_network(flag: Option<&Network>) -> Result<Network> {
+    Ok(network_setting(flag)?.0)
+}
+
+/// Resolves the directory generated clients are written to.
+/// 
+/// # Returns
+/// 
+/// Returns the project `client_dir`, the global `client_dir`, or
+/// `./program/client`, or an error if the global config cannot be loaded.
+pub fn client_dir() -> Result<String> {
+    Ok(client_dir_setting()?.0)
+}
+
+/// Resolves the directory IDL files are stored in.
+/// 
+/// # Returns
+/// 
+/// Returns the project `idl_dir`, or `./program/idl`.
+pub fn idl_dir() -> String {
+    idl_dir_setting().0
+}
+
+/// Resolves the RPC endpoint for a network.
+/// 
+/// # Arguments
+/// 
+/// * `network` - The network to connect to
+/// 
+/// # Returns
+/// 
+/// Returns the project `rpc` entry, the global `rpc` entry, or the public
+/// endpoint, or an error if the global config cannot be loaded.
+/// 
+/// # Examples
+/// 
+/// ```no_run
+/// use solpm::Network;
+/// use solpm::commands::config::rpc_url;
+/// 
+/// # fn example() -> solpm::error::Result<()> {
+/// let url = rpc_url(&Network::Main)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn rpc_url(network: &Network) -> Result<String> {
+    Ok(rpc_url_setting(network)?.0)
+}
+
+/// Resolves how many times a failed download is retried.
+/// 
+/// # Returns
+/// 
+/// Returns the global `retries`, or 2, or an error if the global config
+/// cannot be loaded.
+pub fn download_retries() -> Result<usize> {
+    Ok(retries_setting()?.0)
+}
+
+/// Resolves the HTTP request timeout in seconds.
+/// 
+/// # Arguments
+/// 
+/// * `project` - `timeout_secs` from the `[publish]` section of SolanaPrograms.toml
+/// 
+/// # Returns
+/// 
+/// Returns `--timeout`, `SOLPM_TIMEOUT`, the project value, the global
+/// `timeout_secs`, or 60, or an error if `SOLPM_TIMEOUT` is not a positive
+/// number or the global config cannot be loaded.
+pub fn request_timeout_secs(project: Option<u64>) -> Result<u64> {
+    Ok(timeout_setting(project)?.0)
+}
+
+/// Resolves the HTTP connect timeout in seconds.
+/// 
+/// # Arguments
+/// 
+/// * `project` - `connect_timeout_secs` from the `[publish]` section of SolanaPrograms.toml
+/// 
+/// # Returns
+/// 
+/// Returns the project value, the global `connect_timeout_secs`, or 10, or
+/// an error if the global config cannot be loaded.
+pub fn connect_timeout_secs(project: Option<u64>) -> Result<u64> {
+    Ok(connect_timeout_setting(project)?.0)
+}
+
+/// Resolves the network and its origin.
+fn network_setting(flag: Option<&Network>) -> Result<(Network, ConfigOrigin)> {
+    if let Some(network) = flag {
+        return Ok((network.clone(), ConfigOrigin::Flag));
+    }
+    if let Some(network) = &project_settings().default_network
+        && let Ok(network) = parse_network(network)
+    {
+        return Ok((network, ConfigOrigin::Project));
+    }
+    if let Some(network) = &global_config()?.network
+        && let Ok(network) = parse_network(network)
+    {
+        return Ok((network, ConfigOrigin::Global));
+    }
+    
+    Ok((Network::Dev, ConfigOrigin::Default))
+}
+
+/// Resolves the client directory and its origin.
+fn client_dir_setting() -> Result<(String, ConfigOrigin)> {
+    if let Some(client_dir) = &project_settings().client_dir {
+        return Ok((client_dir.clone(), ConfigOrigin::Project));
+    }
+    
+    Ok(match &global_config()?.client_dir {
+        Some(client_dir) => (client_dir.trim_end_matches('/').to_string(), ConfigOrigin::Global),
+        None => (PROGRAM_CLIENT_DIR.to_string(), ConfigOrigin::Default),
+    })
+}
+
+/// Resolves the IDL directory and its origin.
+fn idl_dir_setting() -> (String, ConfigOrigin) {
+    match &project_settings().idl_dir {
+        Some(idl_dir) => (idl_dir.clone(), ConfigOrigin::Project),
+        None => (PROGRAM_IDL_DIR.to_string(), ConfigOrigin::Default),
+    }
+}
+
+/// Resolves the RPC endpoint for a network and its origin.
+fn rpc_url_setting(network: &Network) -> Result<(String, ConfigOrigin)> {
+    if let Some(url) = project_settings().rpc.get(network) {
+        return Ok((url.clone(), ConfigOrigin::Project));
+    }
+    if let Some(url) = global_config()?.rpc.get(network) {
+        return Ok((url.clone(), ConfigOrigin::Global));
+    }
+    
+    let url = match network {
+        Network::Main => MAINNET_RPC_URL,
+        Network::Dev => DEVNET_RPC_URL,
+        Network::Test => TESTNET_RPC_URL,
+        Network::Local => LOCALNET_RPC_URL,
+    };
+    Ok((url.to_string(), ConfigOrigin::Default))
+}
+
+/// Resolves the download retry count and its origin.
+fn retries_setting() -> Result<(usize, ConfigOrigin)> {
+    Ok(match global_config()?.retries {
+        Some(retries) => (retries, ConfigOrigin::Global),
+        None => (DEFAULT_DOWNLOAD_RETRIES, ConfigOrigin::Default),
+    })
+}
+
+/// Resolves the request timeout and its origin.
+fn timeout_setting(project: Option<u64>) -> Result<(u64, ConfigOrigin)> {
+    if let Some(timeout_secs) = TIMEOUT_FLAG.get() {
+        return Ok((*timeout_secs, ConfigOrigin::Flag));
+    }
+    if let Ok(value) = std::env::var(TIMEOUT_ENV_VAR)
+        && !value.trim().is_empty()
+    {
+        let timeout_secs = value.trim().parse::<u64>().ok().filter(|timeout_secs| *timeout_secs > 0).ok_or_else(|| {
+            SolanaPmError::InvalidPath(format!("{} must be a whole number of seconds, at least 1; got '{}'", TIMEOUT_ENV_VAR, value))
+        })?;
+        return Ok((timeout_secs, ConfigOrigin::Env));
+    }
+    if let Some(timeout_secs) = project {
+        return Ok((timeout_secs, ConfigOrigin::Project));
+    }
+    
+    Ok(match global_config()?.timeout_secs {
+        Some(timeout_secs) => (timeout_secs, ConfigOrigin::Global),
+        None => (DEFAULT_REQUEST_TIMEOUT_SECS, ConfigOrigin::Default),
+    })
+}
+
+/// Resolves the connect timeout and its origin.
+fn connect_timeout_setting(project: Option<u64>) -> Result<(u64, ConfigOrigin)> {
+    if let Some(connect_timeout_secs) = project {
+        return Ok((connect_timeout_secs, ConfigOrigin::Project));
+    }
+    
+    Ok(match global_config()?.connect_timeout_secs {
+        Some(connect_timeout_secs) => (connect_timeout_secs, ConfigOrigin::Global),
+        None => (DEFAULT_CONNECT_TIMEOUT_SECS, ConfigOrigin::Default),
+    })
+}
+
+/// Displays the effective value of every setting and where it came from.
+/// 
+/// Project values are read from `.solpmrc`, `.solpm.toml`, and
+/// SolanaPrograms.toml in the current directory; `--registry`, `--proxy`,
+/// and `--timeout` are the only flags that apply here.
+/// 
+/// # Returns
+/// 
+/// Returns `Ok(())` on success, or an error if a config file cannot be read.
+/// 
+/// # Examples
+/// 
+/// ```no_run
+/// use solpm::commands::config::list_config;
+/// 
+/// # fn example() -> solpm::error::Result<()> {
+/// list_config()?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn list_config() -> Result<()> {
+    let publish = if Path::new(SOLANA_PROGRAMS_TOML).exists() {
+        let content = fs::read_to_string(SOLANA_PROGRAMS_TOML)?;
+        let config: SolanaProgramsConfig = toml::from_str(&content)
+            .map_err(|e| SolanaPmError::InvalidPath(format!("Invalid {}: {}", SOLANA_PROGRAMS_TOML, e)))?;
+        config.publish
+    } else {
+        PublishConfig::default()
+    };
+    
+    let (network, network_origin) = network_setting(None)?;
+    let (registry, registry_origin) = registry_setting()?;
+    let (idl_dir, idl_dir_origin) = idl_dir_setting();
+    let (client_dir, client_dir_origin) = client_dir_setting()?;
+    let (retries, retries_origin) = retries_setting()?;
+    let (timeout_secs, timeout_origin) = timeout_setting(publish.timeout_secs)?;
+    let (connect_timeout_secs, connect_timeout_origin) = connect_timeout_setting(publish.connect_timeout_secs)?;
+    let (proxy, proxy_origin) = proxy_setting()?.unwrap_or_else(|| ("none".to_string(), ConfigOrigin::Default));
+    let mirrors = mirror_base_urls()?;
+    let (mirrors, mirrors_origin) = if mirrors.is_empty() {
+        ("none".to_string(), ConfigOrigin::Default)
+    } else {
+        (mirrors.join(", "), ConfigOrigin::Global)
+    };
+    let (anonymous_headers, anonymous_headers_origin) = match global_config()?.anonymous_headers {
+        Some(anonymous_headers) => (anonymous_headers, ConfigOrigin::Global),
+        None => (false, ConfigOrigin::Default),
+    };
+    let mut rpc = Vec::new();
+    for network in [Network::Main, Network::Dev, Network::Test, Network::Local] {
+        let (url, origin) = rpc_url_setting(&network)?;
+        rpc.push((format!("rpc.{}", network.as_str()), url, origin));
+    }
+    let settings = [
+        ("network", network.as_str().to_string(), network_origin),
+        ("registry", registry, registry_origin),
+        ("idl_dir", idl_dir, idl_dir_origin),
+        ("client_dir", client_dir, client_dir_origin),
+        ("retries", retries.to_string(), retries_origin),
+        ("timeout_secs", timeout_secs.to_string(), timeout_origin),
+        ("connect_timeout_secs", connect_timeout_secs.to_string(), connect_timeout_origin),
+        ("proxy", proxy, proxy_origin),
+        ("mirrors", mirrors, mirrors_origin),
+        ("anonymous_headers", anonymous_headers.to_string(), anonymous_headers_origin),
+    ];
+    
+    outln!("{}", CliStyle::header("Effective Configuration"));
+    for (key, value, origin) in &settings {
+        outln!("  {:<21} {:<50} {}", key, value, CliStyle::highlight(origin.as_str()));
+    }
+    for (key, value, origin) in &rpc {
+        outln!("  {:<21} {:<50} {}", key, value, CliStyle::highlight(origin.as_str()));
+    }
+    
+    match global_config_path() {
+        Some(path) if path.exists() => outln!("\n{}", CliStyle::info(&format!("Global config: {}", path.display()))),
+        Some(path) => outln!("\n{}", CliStyle::info(&format!("Global config: {} (not found)", path.display()))),
+        None => outln!("\n{}", CliStyle::warning("Could not find home directory; no global config is read")),
+    }
+    
+    let settings: Vec<serde_json::Value> = settings.iter()
+        .map(|(key, value, origin)| (key.to_string(), value, origin))
+        .chain(rpc.iter().map(|(key, value, origin)| (key.clone(), value, origin)))
+        .map(|(key, value, origin)| serde_json::json!({ "key": key, "value": value, "origin": origin.as_str() }))
+        .collect();
+    set_result(serde_json::json!({
+        "settings": settings,
+        "global_config": global_config_path().map(|path| path.display().to_string()),
+    }));
+    
+    Ok(())
+}
@@ -0,0 +1,287 @@
+//! # Config Template Module
+//!
+//! This module writes SolanaPrograms.toml as a commented template.
+//!
+//! Features:
+//! - A header explaining the file and linking to its reference
+//! - A comment above every known field saying what it is for, whether
+//!   publish requires it, and an example
+//! - Commented-out optional fields (`license`, `keywords`, `idl`, `[hooks]`,
+//!   `[publish]`, `[solpm]`) so they can be discovered without reading the docs
+//!
+//! `toml` cannot emit comments, so each field and table is serialized on its
+//! own and written under its comment. Keys without a known comment are written
+//! unchanged, and comments are ignored when the file is parsed, so the file
+//! round-trips through every command that rewrites it.
+
+use crate::commands::types::{ProgramConfig, SolanaProgramsConfig};
+use crate::error::{Result, SolanaPmError};
+
+/// Header written at the top of the file.
+const HEADER: &str = "\
+# SolanaPrograms.toml describes the program(s) this project publishes to the
+# solpm registry. Check it with 'solpm verify' and publish with 'solpm publish'.
+# Reference: https://github.com/0xsouravm/solpm#configuration-files
+";
+
+/// A field of a `[program]` or `[[programs]]` table.
+struct ProgramField {
+    /// The TOML key
+    key: &'static str,
+    /// What the field is for, whether publish requires it, and an example
+    comment: &'static str,
+    /// Commented-out line written when an optional field is absent
+    example: Option<&'static str>,
+}
+
+/// The fields of a program table, in the order they are written.
+const PROGRAM_FIELDS: &[ProgramField] = &[
+    ProgramField {
+        key: "name",
+        comment: "Registry name of the program (required; letters, digits, - and _), e.g. \"vesting\"",
+        example: None,
+    },
+    ProgramField {
+        key: "version",
+        comment: "Version being published (required; semver), e.g. \"1.2.0\"",
+        example: None,
+    },
+    ProgramField {
+        key: "program_id",
+        comment: "Deployed program address (required; base58 public key)",
+        example: None,
+    },
+    ProgramField {
+        key: "network",
+        comment: "Cluster the program is deployed to (required): mainnet, devnet, testnet, or localnet",
+        example: None,
+    },
+    ProgramField {
+        key: "description",
+        comment: "One-line summary shown in search results (required; 10 to 500 characters)",
+        example: None,
+    },
+    ProgramField {
+        key: "repository",
+        comment: "Source repository URL (required), e.g. \"https://github.com/acme/vesting\"",
+        example: None,
+    },
+    ProgramField {
+        key: "license",
+        comment: "SPDX license expression (optional; publish warns while empty), e.g. \"MIT OR Apache-2.0\"",
+        example: Some("# license = \"MIT OR Apache-2.0\"\n"),
+    },
+    ProgramField {
+        key: "authority_keypair",
+        comment: "Key that signs the publication (required): a keypair file, \"usb://ledger\", or\n# \"prompt://\" for a seed phrase. Use the upgrade authority for --verify-onchain",
+        example: None,
+    },
+    ProgramField {
+        key: "keywords",
+        comment: "Search keywords shown in the registry (optional)",
+        example: Some("# keywords = [\"vesting\", \"token\"]\n"),
+    },
+    ProgramField {
+        key: "idl",
+        comment: "IDL file publish reads instead of searching target/idl, idl, and target/deploy (optional)",
+        example: Some("# idl = \"./artifacts/my_program.json\"\n"),
+    },
+];
+
+/// Comment above the `[hooks]` table.
+const HOOKS_COMMENT: &str = "# Shell commands run in order before publishing (optional; skip with --no-hooks)\n";
+
+/// Commented-out `[hooks]` example written when no hooks are configured.
+const HOOKS_EXAMPLE: &str = "# [hooks]\n# pre_publish = [\"anchor build\"]\n";
+
+/// Comment above the `[publish]` table.
+const PUBLISH_COMMENT: &str = "# Registry timeouts in seconds (optional; defaults 10 and 60)\n";
+
+/// Commented-out `[publish]` example written when no settings are configured.
+const PUBLISH_EXAMPLE: &str = "# [publish]\n# connect_timeout_secs = 10\n# timeout_secs = 60\n";
+
+/// Comment above the `[solpm]` table.
+const SOLPM_COMMENT: &str = "# Project directories and defaults for every solpm command (optional;\n# paths are relative to this file)\n";
+
+/// Commented-out `[solpm]` example written when no settings are configured.
+const SOLPM_EXAMPLE: &str = "# [solpm]\n# idl_dir = \"./program/idl\"\n# client_dir = \"./program/client\"\n# default_network = \"devnet\"\n#\n# [solpm.rpc]\n# mainnet = \"https://mainnet.rpc.example.com\"\n";
+
+/// Renders a configuration as a commented SolanaPrograms.toml.
+/// 
+/// A single `[program]` and `[[programs]]` entries are both supported; each
+/// program table gets the field comments, and absent optional fields and
+/// tables are written as commented-out examples.
+/// 
+/// # Arguments
+/// 
+/// * `config` - The configuration to write
+/// 
+/// # Returns
+/// 
+/// Returns the file contents, or an error if a table cannot be serialized.
+/// 
+/// # Examples
+/// 
+/// ```no_run
+/// use solpm::commands::config_template::render_config_toml;
+/// use solpm::commands::types::SolanaProgramsConfig;
+/// use std::fs;
+/// 
+/// # fn example(config: SolanaProgramsConfig) -> solpm::error::Result<()> {
+/// let content = render_config_toml(&config)?;
+/// fs::write("SolanaPrograms.toml", content)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn render_config_toml(config: &SolanaProgramsConfig) -> Result<String> {
+    let mut content = String::from(HEADER);
+    
+    if let Some(program) = &config.program {
+        content.push_str("\n[program]\n");
+        push_program_fields(&mut content, program)?;
+    }
+    if !config.programs.is_empty() {
+        content.push_str("\n# One [[programs]] entry per program; publish each with 'solpm publish --program <name>'\n");
+    }
+    for program in &config.programs {
+        content.push_str("\n[[programs]]\n");
+        push_program_fields(&mut content, program)?;
+    }
+    
+    content.push('\n');
+    content.push_str(HOOKS_COMMENT);
+    if config.hooks.is_empty() {
+        content.push_str(HOOKS_EXAMPLE);
+    } else {
+        content.push_str("[hooks]\n");
+        content.push_str(&serialize_table(&config.hooks)?);
+    }
+    
+    content.push('\n');
+    content.push_str(PUBLISH_COMMENT);
+    if config.publish.is_empty() {
+        content.push_str(PUBLISH_EXAMPLE);
+    } else {
+        content.push_str("[publish]\n");
+        content.push_str(&serialize_table(&config.publish)?);
+    }
+    
+    content.push('\n');
+    content.push_str(SOLPM_COMMENT);
+    if config.solpm.is_empty() {
+        content.push_str(SOLPM_EXAMPLE);
+    } else {
+        // Serialized under its key so a nested `rpc` table becomes [solpm.rpc]
+        let mut table = toml::Table::new();
+        let solpm = toml::Value::try_from(&config.solpm)
+            .map_err(|e| SolanaPmError::InvalidPath(format!("Failed to serialize TOML: {}", e)))?;
+        table.insert("solpm".to_string(), solpm);
+        content.push_str(&serialize_table(&table)?);
+    }
+    
+    Ok(content)
+}
+
+/// Appends the fields of one program table with their comments.
+/// 
+/// Known fields are written in [`PROGRAM_FIELDS`] order, each under its
+/// comment; an absent optional field is written as its commented-out example.
+/// Fields without an entry are appended unchanged.
+fn push_program_fields(content: &mut String, program: &ProgramConfig) -> Result<()> {
+    let mut table = toml::Table::try_from(program)
+        .map_err(|e| SolanaPmError::InvalidPath(format!("Failed to serialize TOML: {}", e)))?;
+    
+    for field in PROGRAM_FIELDS {
+        // An empty optional string is written as its example, like a missing one
+        let value = table.remove(field.key)
+            .filter(|value| field.example.is_none() || value.as_str().is_none_or(|value| !value.is_empty()));
+        match (value, field.example) {
+            (Some(value), _) => {
+                content.push_str(&format!("# {}\n", field.comment));
+                content.push_str(&serialize_field(field.key, value)?);
+            }
+            (None, Some(example)) => {
+                content.push_str(&format!("# {}\n", field.comment));
+                content.push_str(example);
+            }
+            (None, None) => {}
+        }
+    }
+    content.push_str(&serialize_table(&table)?);
+    
+    Ok(())
+}
+
+/// Serializes a single `key = value` line.
+fn serialize_field(key: &str, value: toml::Value) -> Result<String> {
+    let mut table = toml::Table::new();
+    table.insert(key.to_string(), value);
+    serialize_table(&table)
+}
+
+/// Serializes a struct as the body of a TOML table.
+fn serialize_table<T: serde::Serialize>(value: &T) -> Result<String> {
+    toml::to_string_pretty(value)
+        .map_err(|e| SolanaPmError::InvalidPath(format!("Failed to serialize TOML: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    /// Parses a SolanaPrograms.toml.
+    fn parse(content: &str) -> SolanaProgramsConfig {
+        toml::from_str(content).unwrap()
+    }
+    
+    const MINIMAL: &str = r#"
+        [program]
+        name = "vesting"
+        version = "1.2.0"
+        program_id = "Vest1ngZbdCMbGVTwfM8fBNBfWqBqTBBHwCbUHmBxjr"
+        network = "devnet"
+        description = "Token vesting schedules"
+        repository = "https://github.com/acme/vesting"
+        authority_keypair = "~/.config/solana/id.json"
+    "#;
+    
+    #[test]
+    fn comments_every_field_and_round_trips() {
+        let content = render_config_toml(&parse(MINIMAL)).unwrap();
+        
+        for field in PROGRAM_FIELDS {
+            let comment = format!("# {}\n", field.comment);
+            let line = content.lines()
+                .position(|line| line.starts_with(&format!("{} = ", field.key)) || line.starts_with(&format!("# {} = ", field.key)))
+                .unwrap_or_else(|| panic!("{} is missing:\n{}", field.key, content));
+            let above = content.lines().take(line).collect::<Vec<_>>().join("\n") + "\n";
+            assert!(above.ends_with(&comment), "{} has no comment above it:\n{}", field.key, content);
+        }
+        assert!(content.contains("# license = \"MIT OR Apache-2.0\"\n"), "{}", content);
+        assert!(content.contains("# keywords = [\"vesting\", \"token\"]\n"), "{}", content);
+        assert!(content.contains("# idl = \"./artifacts/my_program.json\"\n"), "{}", content);
+        
+        let program = parse(&content).program.unwrap();
+        assert_eq!(program.name, "vesting");
+        assert_eq!(program.authority_keypair, "~/.config/solana/id.json");
+        assert_eq!(program.license, "");
+        assert!(program.keywords.is_empty());
+        assert!(program.idl.is_none());
+        assert_eq!(render_config_toml(&parse(&content)).unwrap(), content);
+    }
+    
+    #[test]
+    fn writes_optional_fields_that_are_set() {
+        let config = format!("{}license = \"MIT\"\nkeywords = [\"vesting\", \"defi\"]\nidl = \"./idl/vesting.json\"\n", MINIMAL);
+        let content = render_config_toml(&parse(&config)).unwrap();
+        
+        assert!(content.contains("\nlicense = \"MIT\"\n"), "{}", content);
+        assert!(content.contains("\nidl = \"./idl/vesting.json\"\n"), "{}", content);
+        assert!(!content.contains("# idl = "), "{}", content);
+        
+        let program = parse(&content).program.unwrap();
+        assert_eq!(program.license, "MIT");
+        assert_eq!(program.keywords, ["vesting", "defi"]);
+        assert_eq!(program.idl.as_deref(), Some("./idl/vesting.json"));
+    }
+}
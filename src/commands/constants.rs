@@ -3,7 +3,7 @@
 //! This module defines all configuration constants used throughout the
 //! Solana Program Manager application. It centralizes:
 //!
-//! - Backend API URLs and endpoints
+//! - The default registry URL and API endpoint paths
 //! - File and directory paths for project structure
 //! - Network RPC endpoints for Solana clusters
 //! - System program identifiers and addresses
@@ -11,20 +11,45 @@
 //! These constants ensure consistency across all modules and provide
 //! a single location for configuration management.
 
-// Backend API URLs
-pub const BACKEND_BASE_URL: &str = "https://solpm-registry-production.up.railway.app";
-pub const PUBLISH_PROGRAM_URL: &str = "https://solpm-registry-production.up.railway.app/programs";
-pub const GET_PROGRAM_URL: &str = "https://solpm-registry-production.up.railway.app/programs";
-pub const AUTH_VERIFY_URL: &str = "https://solpm-registry-production.up.railway.app/auth/verify";
+// Registry used when no other is configured (see `registry::registry_base_url`)
+pub const DEFAULT_REGISTRY_URL: &str = "https://solpm-registry-production.up.railway.app";
+
+// Registry API paths, joined onto the registry base URL
+pub const PROGRAMS_PATH: &str = "programs";
+pub const SEARCH_PROGRAMS_PATH: &str = "programs/search";
+pub const AUTH_VERIFY_PATH: &str = "auth/verify";
+pub const AUTH_DEVICE_CODE_PATH: &str = "auth/device/code";
+pub const AUTH_DEVICE_TOKEN_PATH: &str = "auth/device/token";
+
+// Latest solpm release, checked by `solpm self-update`
+pub const SELF_UPDATE_RELEASES_URL: &str = "https://api.github.com/repos/0xsouravm/solpm/releases/latest";
+
+// Environment variables
+pub const TOKEN_ENV_VAR: &str = "SOLPM_TOKEN";
+pub const AUTHORITY_KEY_ENV_VAR: &str = "SOLPM_AUTHORITY_KEY";
+pub const MAX_IDL_SIZE_ENV_VAR: &str = "SOLPM_MAX_IDL_SIZE";
+pub const REGISTRY_ENV_VAR: &str = "SOLPM_REGISTRY_URL";
+pub const TIMEOUT_ENV_VAR: &str = "SOLPM_TIMEOUT";
+pub const OUTPUT_ENV_VAR: &str = "SOLPM_OUTPUT";
 
 // File paths
 pub const SOLANA_PROGRAMS_FILE: &str = "SolanaPrograms.json";
 pub const PROGRAM_CLIENT_DIR: &str = "./program/client";
 pub const PROGRAM_IDL_DIR: &str = "./program/idl";
 
+// Defaults below flags, project files, and ~/.solpm/config.toml
+pub const DEFAULT_DOWNLOAD_RETRIES: usize = 2;
+pub const DEFAULT_REQUEST_TIMEOUT_SECS: u64 = 60;
+pub const DEFAULT_CONNECT_TIMEOUT_SECS: u64 = 10;
+
 // Network RPC URLs
 pub const MAINNET_RPC_URL: &str = "https://api.mainnet-beta.solana.com";
 pub const DEVNET_RPC_URL: &str = "https://api.devnet.solana.com";
+pub const TESTNET_RPC_URL: &str = "https://api.testnet.solana.com";
+pub const LOCALNET_RPC_URL: &str = "http://127.0.0.1:8899";
 
 // System Program ID
-pub const SYSTEM_PROGRAM_ID: &str = "11111111111111111111111111111111";
\ No newline at end of file
+pub const SYSTEM_PROGRAM_ID: &str = "11111111111111111111111111111111";
+
+// Program ID written by `solpm init --allow-placeholder`
+pub const PLACEHOLDER_PROGRAM_ID: &str = "PLACEHOLDER_PROGRAM_ID";
\ No newline at end of file
@@ -0,0 +1,298 @@
+//! # Diff Command Implementation
+//!
+//! This module implements the `diff` command which compares an installed
+//! program's IDL with the latest version published to the registry.
+//!
+//! Features:
+//! - Fetches the latest version for the program's network without counting
+//!   a download
+//! - Compares instructions, their args and accounts, and account types by name
+//! - Highlights breaking changes: removed instructions, removed args, and
+//!   args whose type changed
+//! - Reports other IDL sections (types, events, errors, constants) that differ
+//!
+//! Both IDLs are canonicalized before comparing, so key order and formatting
+//! never show up as changes.
+
+use crate::commands::constants::SOLANA_PROGRAMS_FILE;
+use crate::commands::registry::{RegistryApi, RegistryClient};
+use crate::commands::types::{Program, SolanaPrograms, idl_type_string};
+use crate::error::{Result, SolanaPmError};
+use crate::outln;
+use crate::output::set_result;
+use crate::utils::{CliProgress, CliStyle, canonicalize_idl, installed_idl_path};
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::fs;
+
+/// IDL sections compared as a whole.
+const OTHER_SECTIONS: &[&str] = &["types", "events", "errors", "constants"];
+
+/// One line of an IDL diff.
+pub struct DiffLine {
+    /// The indented, human-readable change
+    pub text: String,
+    /// Whether the change breaks existing callers
+    pub breaking: bool,
+}
+
+/// Compares an installed program's IDL with the registry's latest version.
+/// 
+/// This function:
+/// 1. Finds the program in SolanaPrograms.json and reads its IDL file
+/// 2. Fetches the latest version for the same network from the registry
+/// 3. Prints added (`+`), removed (`-`), and changed (`~`) instructions,
+///    args, instruction accounts, and account types
+/// 4. Prints breaking changes as warnings and counts them
+/// 
+/// # Arguments
+/// 
+/// * `program` - The name of an installed program
+/// 
+/// # Returns
+/// 
+/// Returns `Ok(())` on success, or an error if the program is not installed,
+/// its IDL file cannot be read, or the registry request fails.
+/// 
+/// # Examples
+/// 
+/// ```no_run
+/// use solpm::commands::diff::diff;
+/// 
+/// # async fn example() -> solpm::error::Result<()> {
+/// diff("token-vesting").await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn diff(program: &str) -> Result<()> {
+    let (installed, local_idl) = read_installed_idl(program)?;
+    
+    let spinner = CliProgress::new_spinner(&format!("Fetching the latest {}...", CliStyle::package(program)));
+    let latest = RegistryClient::new()?.get_program(program, "latest", &installed.network).await;
+    spinner.finish_and_clear();
+    let latest = latest?;
+    
+    outln!("{}", CliStyle::header(&format!(
+        "{}: installed {} → registry {}",
+        program,
+        installed.version,
+        latest.version
+    )));
+    
+    if canonicalize_idl(&local_idl) == canonicalize_idl(&latest.idl) {
+        outln!("{}", CliStyle::success("The IDLs are identical"));
+        set_result(json!({
+            "name": program,
+            "installed_version": installed.version,
+            "latest_version": latest.version,
+            "identical": true,
+            "changes": [],
+        }));
+        return Ok(());
+    }
+    
+    let lines = diff_idls(&local_idl, &latest.idl);
+    for line in &lines {
+        if line.breaking {
+            outln!("{}", CliStyle::warning(&format!("{} (breaking)", line.text)));
+        } else {
+            outln!("{}", line.text);
+        }
+    }
+    
+    let breaking = lines.iter().filter(|line| line.breaking).count();
+    outln!();
+    if breaking > 0 {
+        outln!("{}", CliStyle::warning(&format!(
+            "{} breaking change{}; update callers before upgrading",
+            breaking,
+            if breaking == 1 { "" } else { "s" }
+        )));
+    } else {
+        outln!("{}", CliStyle::success("No breaking changes"));
+    }
+    set_result(json!({
+        "name": program,
+        "installed_version": installed.version,
+        "latest_version": latest.version,
+        "identical": false,
+        "changes": lines.iter().map(|line| json!({ "text": line.text, "breaking": line.breaking })).collect::<Vec<_>>(),
+    }));
+    
+    Ok(())
+}
+
+/// Reads an installed program's manifest entry and IDL.
+/// 
+/// # Arguments
+/// 
+/// * `program` - The program name, as a regular or dev dependency
+/// 
+/// # Returns
+/// 
+/// Returns the manifest entry and the parsed IDL, or an error if the program
+/// is not installed or its IDL file is missing or invalid.
+fn read_installed_idl(program: &str) -> Result<(Program, Value)> {
+    let content = fs::read_to_string(SOLANA_PROGRAMS_FILE).map_err(|_| {
+        SolanaPmError::ConfigNotFound(format!("{} not found. Run 'solpm add <program>' first.", SOLANA_PROGRAMS_FILE))
+    })?;
+    let mut solana_programs: SolanaPrograms = serde_json::from_str(&content)?;
+    
+    let installed = solana_programs.programs.remove(program)
+        .or_else(|| solana_programs.dev_programs.remove(program))
+        .ok_or_else(|| SolanaPmError::DataMissing(format!("{} is not installed", program)))?;
+    
+    let idl_path = installed_idl_path(program, &installed);
+    let idl_content = fs::read_to_string(&idl_path).map_err(|e| {
+        SolanaPmError::InvalidPath(format!("Failed to read {}: {}. Run 'solpm install' first.", idl_path, e))
+    })?;
+    let idl = serde_json::from_str(&idl_content)
+        .map_err(|e| SolanaPmError::InvalidIdl(format!("{}: {}", idl_path, e)))?;
+    
+    Ok((installed, idl))
+}
+
+/// Computes the structural differences between two IDLs.
+/// 
+/// # Arguments
+/// 
+/// * `old` - The installed IDL
+/// * `new` - The registry's IDL
+/// 
+/// # Returns
+/// 
+/// Returns the differences in instruction, account type, and section order.
+pub fn diff_idls(old: &Value, new: &Value) -> Vec<DiffLine> {
+    let mut lines = Vec::new();
+    
+    let old_instructions = by_name(&old["instructions"]);
+    let new_instructions = by_name(&new["instructions"]);
+    let mut instruction_lines = Vec::new();
+    for (name, old_instruction) in &old_instructions {
+        match new_instructions.get(name) {
+            None => instruction_lines.push(change(format!("  - {}", name), true)),
+            Some(new_instruction) => {
+                let details = diff_instruction(old_instruction, new_instruction);
+                if !details.is_empty() {
+                    instruction_lines.push(change(format!("  ~ {}", name), false));
+                    instruction_lines.extend(details);
+                }
+            }
+        }
+    }
+    for name in new_instructions.keys().filter(|name| !old_instructions.contains_key(*name)) {
+        instruction_lines.push(change(format!("  + {}", name), false));
+    }
+    if !instruction_lines.is_empty() {
+        lines.push(change("Instructions".to_string(), false));
+        lines.extend(instruction_lines);
+    }
+    
+    let old_accounts = by_name(&old["accounts"]);
+    let new_accounts = by_name(&new["accounts"]);
+    let mut account_lines = Vec::new();
+    for (name, old_account) in &old_accounts {
+        match new_accounts.get(name) {
+            None => account_lines.push(change(format!("  - {}", name), false)),
+            Some(new_account) if canonicalize_idl(old_account) != canonicalize_idl(new_account) => {
+                account_lines.push(change(format!("  ~ {}", name), false));
+            }
+            Some(_) => {}
+        }
+    }
+    for name in new_accounts.keys().filter(|name| !old_accounts.contains_key(*name)) {
+        account_lines.push(change(format!("  + {}", name), false));
+    }
+    if !account_lines.is_empty() {
+        lines.push(change("Accounts".to_string(), false));
+        lines.extend(account_lines);
+    }
+    
+    let changed_sections: Vec<&str> = OTHER_SECTIONS.iter()
+        .copied()
+        .filter(|section| canonicalize_idl(&old[*section]) != canonicalize_idl(&new[*section]))
+        .collect();
+    if !changed_sections.is_empty() {
+        lines.push(change(format!("Also changed: {}", changed_sections.join(", ")), false));
+    }
+    
+    lines
+}
+
+/// Computes the arg and account differences of one instruction.
+/// 
+/// # Arguments
+/// 
+/// * `old` - The installed instruction
+/// * `new` - The registry's instruction
+/// 
+/// # Returns
+/// 
+/// Returns one line per added, removed, or changed arg or account.
+fn diff_instruction(old: &Value, new: &Value) -> Vec<DiffLine> {
+    let mut lines = Vec::new();
+    
+    let old_args = by_name(&old["args"]);
+    let new_args = by_name(&new["args"]);
+    for (name, old_arg) in &old_args {
+        let old_type = idl_type_string(&old_arg["type"]);
+        match new_args.get(name) {
+            None => lines.push(change(format!("      - arg {}: {}", name, old_type), true)),
+            Some(new_arg) => {
+                let new_type = idl_type_string(&new_arg["type"]);
+                if canonicalize_idl(&old_arg["type"]) != canonicalize_idl(&new_arg["type"]) {
+                    lines.push(change(format!("      ~ arg {}: {} → {}", name, old_type, new_type), true));
+                }
+            }
+        }
+    }
+    for (name, new_arg) in new_args.iter().filter(|(name, _)| !old_args.contains_key(*name)) {
+        lines.push(change(format!("      + arg {}: {}", name, idl_type_string(&new_arg["type"])), false));
+    }
+    
+    let old_accounts = by_name(&old["accounts"]);
+    let new_accounts = by_name(&new["accounts"]);
+    for (name, old_account) in &old_accounts {
+        match new_accounts.get(name) {
+            None => lines.push(change(format!("      - account {}", name), false)),
+            Some(new_account) => {
+                let (old_flags, new_flags) = (account_flags(old_account), account_flags(new_account));
+                if old_flags != new_flags {
+                    lines.push(change(format!("      ~ account {}: {} → {}", name, old_flags, new_flags), false));
+                }
+            }
+        }
+    }
+    for (name, new_account) in new_accounts.iter().filter(|(name, _)| !old_accounts.contains_key(*name)) {
+        lines.push(change(format!("      + account {} ({})", name, account_flags(new_account)), false));
+    }
+    
+    lines
+}
+
+/// Describes whether an instruction account is writable and a signer.
+/// 
+/// Reads both the current (`writable`/`signer`) and legacy
+/// (`isMut`/`isSigner`) IDL fields.
+fn account_flags(account: &Value) -> String {
+    let flag = |current: &str, legacy: &str| account[current].as_bool().or_else(|| account[legacy].as_bool()).unwrap_or(false);
+    let mut flags = vec![if flag("writable", "isMut") { "writable" } else { "readonly" }];
+    if flag("signer", "isSigner") {
+        flags.push("signer");
+    }
+    flags.join(", ")
+}
+
+/// Indexes the named entries of an IDL array, in name order.
+fn by_name(items: &Value) -> BTreeMap<String, &Value> {
+    items.as_array()
+        .map(|items| items.iter()
+            .filter_map(|item| Some((item["name"].as_str()?.to_string(), item)))
+            .collect())
+        .unwrap_or_default()
+}
+
+/// Creates a diff line.
+fn change(text: String, breaking: bool) -> DiffLine {
+    DiffLine { text, breaking }
+}
@@ -0,0 +1,566 @@
+//! # Download Module
+//!
+//! This module fetches program install responses from the registry and writes
+//! the contained IDL files to disk.
+//!
+//! Features:
+//! - Streaming downloads into a `<idl_path>.tmp` file next to the target
+//! - Resuming partial downloads with HTTP range requests
+//! - Automatic fallback to a full download when ranges are not supported
+//! - Atomic replacement of IDL files once a download completes
+//! - A maximum response size (`--max-idl-size` or `SOLPM_MAX_IDL_SIZE`), with
+//!   a request timeout that grows with it so large IDLs can finish
+//! - Conditional downloads: the `ETag` and `Last-Modified` of each IDL are
+//!   saved in a `<idl_path>.validators` file, and the next install sends
+//!   them back so the registry can answer `304 Not Modified`
+//! - Warnings for versions the registry marks as yanked or deprecated
+//! - An optional byte progress bar for responses with a `Content-Length`
+//!
+//! `RegistryClient::install_program` runs the download against the registry
+//! and its mirrors. A failed or interrupted download leaves its temporary file in place so the
+//! next attempt (or the next `solpm install`) can resume from where it stopped.
+
+use crate::commands::config::download_retries;
+use crate::commands::constants::{MAX_IDL_SIZE_ENV_VAR, TIMEOUT_ENV_VAR};
+use crate::commands::http::{SendLogged, client_timeouts, request_error};
+use crate::commands::registry::{InstallRequest, RegistryApi, registry_error};
+use crate::commands::types::{IdlValidators, ProgramResponse};
+use crate::error::{Context, Result, SolanaPmError};
+use crate::utils::{CliProgress, CliStyle};
+use reqwest::StatusCode;
+use reqwest::header::{CONTENT_LENGTH, CONTENT_RANGE, ETAG, HeaderMap, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED, RANGE};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::time::Duration;
+
+/// Default maximum size of a program install response (10 MB).
+const DEFAULT_MAX_IDL_SIZE: u64 = 10 * 1024 * 1024;
+
+/// Slowest download speed the install timeout allows for.
+const DOWNLOAD_MIN_BYTES_PER_SEC: u64 = 32 * 1024;
+
+/// The result of a single download attempt.
+enum DownloadState {
+    /// The temporary file holds a complete response; `resumed` is true if
+    /// any of its bytes came from an earlier attempt
+    Complete { resumed: bool, validators: IdlValidators },
+    /// The partial file could not be resumed and was discarded
+    Restart,
+    /// The registry answered a conditional request with `304 Not Modified`
+    NotModified,
+}
+
+/// Returns the temporary file used while downloading an IDL.
+/// 
+/// # Arguments
+/// 
+/// * `idl_file_path` - The final location of the IDL file
+/// 
+/// # Returns
+/// 
+/// Returns the path with a `.tmp` suffix (e.g. `./program/idl/foo.json.tmp`).
+pub fn temp_path_for(idl_file_path: &str) -> String {
+    format!("{}.tmp", idl_file_path)
+}
+
+/// Returns the file the validators of an installed IDL are saved in.
+/// 
+/// # Arguments
+/// 
+/// * `idl_file_path` - The location of the IDL file
+/// 
+/// # Returns
+/// 
+/// Returns the path with a `.validators` suffix (e.g. `./program/idl/foo.json.validators`).
+pub fn validators_path_for(idl_file_path: &str) -> String {
+    format!("{}.validators", idl_file_path)
+}
+
+/// Reads the validators saved for an installed IDL.
+/// 
+/// # Arguments
+/// 
+/// * `idl_file_path` - The location of the IDL file
+/// 
+/// # Returns
+/// 
+/// Returns the validators, or `None` if none were saved or the file cannot
+/// be read, in which case the next download is unconditional.
+pub fn read_validators(idl_file_path: &str) -> Option<IdlValidators> {
+    let content = fs::read_to_string(validators_path_for(idl_file_path)).ok()?;
+    serde_json::from_str::<IdlValidators>(&content).ok()
+        .filter(|validators| !validators.is_empty())
+}
+
+/// Saves the validators an IDL was served with next to the IDL file.
+/// 
+/// Stale validators are removed when the response had none, so they are
+/// never sent for an IDL they do not describe.
+/// 
+/// # Arguments
+/// 
+/// * `idl_file_path` - The location of the IDL file
+/// * `validators` - The validators of the response the IDL was written from
+/// 
+/// # Returns
+/// 
+/// Returns `Ok(())` on success, or an error if the file cannot be written.
+pub fn write_validators(idl_file_path: &str, validators: &IdlValidators) -> Result<()> {
+    let validators_path = validators_path_for(idl_file_path);
+    if validators.is_empty() {
+        return match fs::remove_file(&validators_path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        };
+    }
+    
+    fs::write(&validators_path, serde_json::to_string_pretty(validators)?)
+        .map_err(|e| SolanaPmError::InvalidPath(format!("Failed to write {}: {}", validators_path, e)))
+}
+
+/// Resolves the maximum install response size.
+/// 
+/// # Arguments
+/// 
+/// * `max_idl_size` - The `--max-idl-size` flag, which takes precedence
+/// 
+/// # Returns
+/// 
+/// Returns the limit in bytes from the flag, the `SOLPM_MAX_IDL_SIZE`
+/// environment variable, or the 10 MB default, or an error if the
+/// environment variable is not a number of bytes.
+pub fn resolve_max_idl_size(max_idl_size: Option<u64>) -> Result<u64> {
+    if let Some(max_idl_size) = max_idl_size {
+        return Ok(max_idl_size);
+    }
+    
+    match std::env::var(MAX_IDL_SIZE_ENV_VAR) {
+        Ok(value) => value.trim().parse().map_err(|_| SolanaPmError::DataMissing(format!(
+            "Invalid {} '{}': expected a size in bytes", MAX_IDL_SIZE_ENV_VAR, value
+        ))),
+        Err(_) => Ok(DEFAULT_MAX_IDL_SIZE),
+    }
+}
+
+/// Downloads a program's install response from one registry, resuming
+/// interrupted downloads.
+/// 
+/// The response body is streamed into the temporary file for the request's
+/// IDL path. If a partial temporary file already exists, a `Range` request
+/// resumes it; servers that ignore ranges send the full body, which replaces
+/// the partial file. Network failures are retried (twice, or `retries` from
+/// ~/.solpm/config.toml), resuming each time. A resumed body
+/// that does not parse (for example, a stale partial file from another
+/// version) is discarded and downloaded again from scratch.
+/// 
+/// Responses larger than the request's `max_idl_size` are aborted, and the
+/// partial file is removed, without being retried.
+/// 
+/// With `validators`, the request carries `If-None-Match` and
+/// `If-Modified-Since` and is never resumed; a `304 Not Modified` answer
+/// means the installed IDL is current.
+/// 
+/// When the request has a `progress` spinner and the response has a
+/// `Content-Length`, the spinner is turned into a byte progress bar;
+/// otherwise it keeps spinning.
+/// 
+/// # Arguments
+/// 
+/// * `registry` - The registry client to send the request with
+/// * `url` - The registry or mirror install endpoint
+/// * `request_body` - The JSON body sent with the install request
+/// * `request` - The program, IDL path, size limit, and spinner
+/// 
+/// # Returns
+/// 
+/// Returns the parsed install response with the validators it was served
+/// with, `None` if the registry answered `304 Not Modified`, or an error if
+/// the program does not exist, the registry rejects the request, the response
+/// is too large, or all attempts fail.
+/// 
+/// # Examples
+/// 
+/// ```no_run
+/// use solpm::commands::download::{download_install_response, write_idl_file};
+/// use solpm::commands::registry::{InstallRequest, RegistryClient};
+/// 
+/// # async fn example(request: &InstallRequest<'_>) -> solpm::error::Result<()> {
+/// # let registry = RegistryClient::new()?;
+/// # let url = "https://registry.example.com/programs/vesting/latest/install";
+/// # let request_body = serde_json::json!({ "network": request.network, "project_hash": request.project_hash });
+/// if let Some(program_response) = download_install_response(&registry, url, &request_body, request).await? {
+///     write_idl_file(request.idl_file_path, &program_response.idl)?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub async fn download_install_response(
+    registry: &impl RegistryApi,
+    url: &str,
+    request_body: &serde_json::Value,
+    request: &InstallRequest<'_>,
+) -> Result<Option<ProgramResponse>> {
+    let InstallRequest { name: package_name, idl_file_path, .. } = *request;
+    let temp_path = temp_path_for(idl_file_path);
+    let max_attempts = download_retries()? + 1;
+    let mut attempt = 0;
+    
+    loop {
+        attempt += 1;
+        
+        let (resumed, response_validators) = match download_to_file(registry, url, request_body, request, &temp_path).await {
+            Ok(DownloadState::Complete { resumed, validators }) => (resumed, validators),
+            Ok(DownloadState::NotModified) => return Ok(None),
+            Ok(DownloadState::Restart) if attempt < max_attempts => {
+                tracing::debug!("Restarting the download of {} (attempt {} of {})", package_name, attempt + 1, max_attempts);
+                continue;
+            }
+            Err(e @ (SolanaPmError::Http(_) | SolanaPmError::Timeout(_) | SolanaPmError::Io(_))) if attempt < max_attempts => {
+                tracing::debug!("Download of {} failed ({}); retrying (attempt {} of {})", package_name, e, attempt + 1, max_attempts);
+                continue;
+            }
+            Ok(DownloadState::Restart) => {
+                return Err(SolanaPmError::UploadFailed(format!("Failed to download {} after {} attempts", package_name, attempt)));
+            }
+            Err(e) => return Err(e),
+        };
+        
+        let body = fs::read(&temp_path)
+            .with_context(|| format!("Failed to read {}", temp_path))?;
+        match serde_json::from_slice::<ProgramResponse>(&body) {
+            Ok(mut program_response) => {
+                program_response.validators = response_validators;
+                return Ok(Some(program_response));
+            }
+            Err(e) => {
+                fs::remove_file(&temp_path)?;
+                if !resumed || attempt >= max_attempts {
+                    return Err(e).with_context(|| format!("Invalid install response for {} from {}", package_name, url));
+                }
+                tracing::debug!("Resumed download of {} is corrupt; downloading it again", package_name);
+            }
+        }
+    }
+}
+
+/// Performs a single download attempt into the temporary file.
+/// 
+/// # Arguments
+/// 
+/// * `registry` - The registry client to send the request with
+/// * `url` - The registry install endpoint
+/// * `request_body` - The JSON body sent with the install request
+/// * `request` - The program, size limit, spinner, and validators of the
+///   installed IDL (for a conditional request)
+/// * `temp_path` - The temporary file to write to or resume
+/// 
+/// # Returns
+/// 
+/// Returns the state of the temporary file after the attempt, or an error if
+/// the request fails, the response is too large, or the transfer does not
+/// finish within the request timeout plus one second per 32 KiB of
+/// `max_idl_size`. Partial data is kept when the transfer is interrupted, and
+/// removed when the limit is exceeded.
+async fn download_to_file(
+    registry: &impl RegistryApi,
+    url: &str,
+    request_body: &serde_json::Value,
+    request: &InstallRequest<'_>,
+    temp_path: &str,
+) -> Result<DownloadState> {
+    let InstallRequest { name: package_name, max_idl_size, progress, validators, .. } = *request;
+    let mut offset = fs::metadata(temp_path).map(|metadata| metadata.len()).unwrap_or(0);
+    
+    // A partial file over the limit cannot become valid, and a conditional
+    // request asks for the whole IDL; start over
+    if offset > max_idl_size || (offset > 0 && validators.is_some()) {
+        fs::remove_file(temp_path)?;
+        offset = 0;
+    }
+    
+    // The largest allowed response must be able to arrive on a slow link
+    let (_, request_timeout) = client_timeouts()?;
+    let timeout = request_timeout + Duration::from_secs(max_idl_size / DOWNLOAD_MIN_BYTES_PER_SEC);
+    let operation = format!("Downloading {}", package_name);
+    let download_error = |e: reqwest::Error| {
+        if e.is_timeout() && !e.is_connect() {
+            SolanaPmError::Timeout(format!(
+                "{}: {} did not finish within {} seconds; raise the limit with --timeout or {}",
+                operation, url, timeout.as_secs(), TIMEOUT_ENV_VAR
+            ))
+        } else {
+            request_error(&operation, e)
+        }
+    };
+    
+    let mut request = registry.post(url).json(request_body).timeout(timeout);
+    if offset > 0 {
+        request = request.header(RANGE, format!("bytes={}-", offset));
+    }
+    if let Some(validators) = validators {
+        if let Some(etag) = &validators.etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &validators.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, last_modified);
+        }
+    }
+    
+    let mut response = request.send_logged().await.map_err(download_error)?;
+    let status = response.status();
+    
+    if validators.is_some() && status == StatusCode::NOT_MODIFIED {
+        return Ok(DownloadState::NotModified);
+    }
+    
+    // The partial file already holds the whole body
+    if offset > 0 && status == StatusCode::RANGE_NOT_SATISFIABLE {
+        return Ok(DownloadState::Complete { resumed: true, validators: IdlValidators::default() });
+    }
+    
+    if !status.is_success() {
+        if status == StatusCode::NOT_FOUND {
+            return Err(SolanaPmError::ProgramNotFound(package_name.to_string()));
+        }
+        return Err(registry_error(response, format!("Downloading {} from {}", package_name, url)).await);
+    }
+    
+    let resumed = status == StatusCode::PARTIAL_CONTENT;
+    if resumed {
+        let expected_prefix = format!("bytes {}-", offset);
+        let range_matches = response.headers()
+            .get(CONTENT_RANGE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.starts_with(&expected_prefix));
+        
+        if !range_matches {
+            fs::remove_file(temp_path)?;
+            return Ok(DownloadState::Restart);
+        }
+    }
+    
+    let validators = response_validators(response.headers());
+    
+    // Reject oversized responses before writing anything when the size is known
+    let mut written = if resumed { offset } else { 0 };
+    let content_length = response.headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok());
+    if let Some(content_length) = content_length
+        && written + content_length > max_idl_size
+    {
+        let _ = fs::remove_file(temp_path);
+        return Err(idl_too_large(package_name, max_idl_size));
+    }
+    
+    let progress = progress.filter(|_| content_length.is_some());
+    if let (Some(pb), Some(content_length)) = (progress, content_length) {
+        CliProgress::start_download_bar(pb, written, written + content_length);
+    }
+    
+    // Append to a resumed download, otherwise start the file over
+    let mut file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(resumed)
+        .truncate(!resumed)
+        .open(temp_path)
+        .map_err(|e| SolanaPmError::InvalidPath(format!("Failed to open {}: {}", temp_path, e)))?;
+    
+    while let Some(chunk) = response.chunk().await.map_err(download_error)? {
+        written += chunk.len() as u64;
+        if written > max_idl_size {
+            drop(file);
+            fs::remove_file(temp_path)?;
+            return Err(idl_too_large(package_name, max_idl_size));
+        }
+        file.write_all(&chunk)?;
+        if let Some(pb) = progress {
+            CliProgress::set_bytes_position(pb, written);
+        }
+    }
+    file.flush()?;
+    tracing::debug!("Wrote {} bytes to {}", written, temp_path);
+    
+    Ok(DownloadState::Complete { resumed, validators })
+}
+
+/// Reads the `ETag` and `Last-Modified` headers of a response.
+fn response_validators(headers: &HeaderMap) -> IdlValidators {
+    let header = |name| headers.get(name)
+        .and_then(|value: &reqwest::header::HeaderValue| value.to_str().ok())
+        .map(str::to_string);
+    IdlValidators {
+        etag: header(ETAG),
+        last_modified: header(LAST_MODIFIED),
+    }
+}
+
+/// Describes a downloaded version the registry marks as yanked or deprecated.
+/// 
+/// # Arguments
+/// 
+/// * `package_name` - The program name
+/// * `program_response` - The registry install response
+/// 
+/// # Returns
+/// 
+/// Returns the warning to show, including the registry's deprecation message
+/// if any, or `None` if the version is neither yanked nor deprecated.
+pub fn version_status_warning(package_name: &str, program_response: &ProgramResponse) -> Option<String> {
+    let label = format!("{} {}", CliStyle::package(package_name), CliStyle::version(&program_response.version));
+    let message = program_response.deprecation_message.as_deref()
+        .map(str::trim)
+        .filter(|message| !message.is_empty());
+    
+    match (program_response.yanked, message) {
+        (true, Some(message)) => Some(format!("{} has been yanked from the registry: {}", label, message)),
+        (true, None) => Some(format!("{} has been yanked from the registry", label)),
+        (false, Some(message)) => Some(format!("{} is deprecated: {}", label, message)),
+        (false, None) => None,
+    }
+}
+
+/// Builds the error returned when an install response exceeds the size limit.
+/// 
+/// # Arguments
+/// 
+/// * `package_name` - The program name
+/// * `max_idl_size` - The limit that was exceeded, in bytes
+/// 
+/// # Returns
+/// 
+/// Returns an `InvalidIdl` error explaining how to raise the limit.
+fn idl_too_large(package_name: &str, max_idl_size: u64) -> SolanaPmError {
+    SolanaPmError::InvalidIdl(format!(
+        "Response for {} exceeds the maximum IDL size of {} bytes. Raise it with --max-idl-size or {} if this is expected.",
+        package_name, max_idl_size, MAX_IDL_SIZE_ENV_VAR
+    ))
+}
+
+/// Writes an IDL file atomically.
+/// 
+/// The IDL is written to the temporary file and then renamed over the
+/// target, so an interrupted write never leaves a truncated IDL behind.
+/// 
+/// # Arguments
+/// 
+/// * `idl_file_path` - The final location of the IDL file
+/// * `idl` - The IDL JSON to write
+/// 
+/// # Returns
+/// 
+/// Returns `Ok(())` on success, or an error if the file cannot be written.
+pub fn write_idl_file(idl_file_path: &str, idl: &serde_json::Value) -> Result<()> {
+    let temp_path = temp_path_for(idl_file_path);
+    let idl_content = serde_json::to_string_pretty(idl)?;
+    
+    fs::write(&temp_path, idl_content)
+        .map_err(|e| SolanaPmError::InvalidPath(format!("Failed to write IDL file {}: {}", temp_path, e)))?;
+    fs::rename(&temp_path, idl_file_path)
+        .map_err(|e| SolanaPmError::InvalidPath(format!("Failed to write IDL file {}: {}", idl_file_path, e)))?;
+    tracing::debug!("Wrote {}", idl_file_path);
+    
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::mock_server::{MockResponse, serve};
+    use crate::commands::registry::RegistryClient;
+    
+    const INSTALL_RESPONSE: &str = r#"{
+        "version": "1.0.0",
+        "program_id": "Vest1ngZbdCMbGVTwfM8fBNBfWqBqTBBHwCbUHmBxjr",
+        "idl": { "metadata": { "name": "vesting", "version": "1.0.0" }, "instructions": [] }
+    }"#;
+    
+    const LAST_MODIFIED_AT: &str = "Wed, 21 Oct 2026 07:28:00 GMT";
+    
+    /// An install request for `vesting` into `idl_file_path`.
+    fn install_request<'a>(idl_file_path: &'a str, validators: Option<&'a IdlValidators>) -> InstallRequest<'a> {
+        InstallRequest {
+            name: "vesting",
+            version: None,
+            network: "devnet",
+            project_hash: "test",
+            idl_file_path,
+            max_idl_size: DEFAULT_MAX_IDL_SIZE,
+            progress: None,
+            validators,
+        }
+    }
+    
+    #[tokio::test]
+    async fn not_modified_keeps_the_installed_idl() {
+        let (base_url, requests) = serve(|request| {
+            if request.header("if-none-match") == Some("\"v1\"") {
+                MockResponse::json("304 Not Modified", "")
+            } else {
+                MockResponse::json("200 OK", INSTALL_RESPONSE)
+                    .with_header("ETag", "\"v1\"")
+                    .with_header("Last-Modified", LAST_MODIFIED_AT)
+            }
+        }).await;
+        let registry = RegistryClient::with_base_url(reqwest::Client::new(), base_url);
+        let dir = tempfile::tempdir().unwrap();
+        let idl_file_path = dir.path().join("vesting.json").to_string_lossy().to_string();
+        
+        // A first install is unconditional and returns the validators to save
+        let program_response = registry.install_program(&install_request(&idl_file_path, None)).await.unwrap().unwrap();
+        let expected = IdlValidators { etag: Some("\"v1\"".to_string()), last_modified: Some(LAST_MODIFIED_AT.to_string()) };
+        assert_eq!(program_response.validators, expected);
+        write_idl_file(&idl_file_path, &program_response.idl).unwrap();
+        write_validators(&idl_file_path, &program_response.validators).unwrap();
+        let installed_idl = fs::read_to_string(&idl_file_path).unwrap();
+        
+        // The next install sends them back and keeps the IDL on a 304
+        let saved = read_validators(&idl_file_path).unwrap();
+        assert_eq!(saved, expected);
+        let revalidated = registry.install_program(&install_request(&idl_file_path, Some(&saved))).await.unwrap();
+        assert!(revalidated.is_none());
+        assert_eq!(fs::read_to_string(&idl_file_path).unwrap(), installed_idl);
+        assert!(!std::path::Path::new(&temp_path_for(&idl_file_path)).exists());
+        
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].header("if-none-match"), None);
+        assert_eq!(requests[0].header("if-modified-since"), None);
+        assert_eq!(requests[1].header("if-none-match"), Some("\"v1\""));
+        assert_eq!(requests[1].header("if-modified-since"), Some(LAST_MODIFIED_AT));
+    }
+    
+    #[tokio::test]
+    async fn changed_idl_is_downloaded_with_new_validators() {
+        let (base_url, _) = serve(|_| {
+            MockResponse::json("200 OK", INSTALL_RESPONSE).with_header("ETag", "\"v2\"")
+        }).await;
+        let registry = RegistryClient::with_base_url(reqwest::Client::new(), base_url);
+        let dir = tempfile::tempdir().unwrap();
+        let idl_file_path = dir.path().join("vesting.json").to_string_lossy().to_string();
+        let stale = IdlValidators { etag: Some("\"v1\"".to_string()), last_modified: None };
+        
+        let program_response = registry.install_program(&install_request(&idl_file_path, Some(&stale))).await.unwrap().unwrap();
+        assert_eq!(program_response.version, "1.0.0");
+        assert_eq!(program_response.validators.etag.as_deref(), Some("\"v2\""));
+        assert_eq!(program_response.validators.last_modified, None);
+    }
+    
+    #[test]
+    fn validators_without_values_are_not_saved() {
+        let dir = tempfile::tempdir().unwrap();
+        let idl_file_path = dir.path().join("vesting.json").to_string_lossy().to_string();
+        assert!(read_validators(&idl_file_path).is_none());
+        
+        let validators = IdlValidators { etag: Some("\"v1\"".to_string()), last_modified: None };
+        write_validators(&idl_file_path, &validators).unwrap();
+        assert_eq!(read_validators(&idl_file_path), Some(validators));
+        
+        // A response without validators removes the stale ones
+        write_validators(&idl_file_path, &IdlValidators::default()).unwrap();
+        assert!(!std::path::Path::new(&validators_path_for(&idl_file_path)).exists());
+        assert!(read_validators(&idl_file_path).is_none());
+    }
+}
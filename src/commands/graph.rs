@@ -0,0 +1,252 @@
+//! # Graph Command Implementation
+//!
+//! This module implements the `graph` command which shows how installed
+//! programs reference each other.
+//!
+//! Features:
+//! - Reads the IDL of every regular and dev dependency
+//! - Detects references from fixed-address instruction accounts that match
+//!   another installed program's ID
+//! - Prints references as plain text or as a Graphviz DOT digraph
+//!
+//! Programs whose IDL file is missing are still shown as nodes, but their
+//! outgoing references cannot be determined.
+
+use crate::commands::constants::SOLANA_PROGRAMS_FILE;
+use crate::commands::types::SolanaPrograms;
+use crate::error::{Result, SolanaPmError};
+use crate::outln;
+use crate::statusln;
+use crate::output::{is_json_output, is_quiet, set_result};
+use crate::utils::{CliStyle, installed_idl_path};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+/// An installed program in the dependency graph.
+struct GraphNode {
+    name: String,
+    version: String,
+    program_id: String,
+    network: String,
+    /// Fixed account addresses referenced by the program's instructions
+    referenced_addresses: BTreeSet<String>,
+}
+
+impl GraphNode {
+    /// Returns the node label in `name@version` form.
+    fn label(&self) -> String {
+        format!("{}@{}", self.name, self.version)
+    }
+}
+
+/// Prints the dependency graph of installed programs.
+/// 
+/// An edge from program A to program B means that one of A's instructions
+/// takes an account with a fixed address equal to B's program ID on the same
+/// network. With `dot`, the graph is printed as a Graphviz digraph that can
+/// be piped to `dot -Tpng`.
+/// 
+/// # Arguments
+/// 
+/// * `dot` - Whether to print Graphviz DOT instead of plain text
+/// 
+/// # Returns
+/// 
+/// Returns `Ok(())` on success, or an error if SolanaPrograms.json is missing
+/// or cannot be parsed.
+/// 
+/// # Examples
+/// 
+/// ```no_run
+/// use solpm::commands::graph::print_graph;
+/// 
+/// # fn example() -> solpm::error::Result<()> {
+/// // Print references as text
+/// print_graph(false)?;
+/// 
+/// // Print a Graphviz digraph
+/// print_graph(true)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn print_graph(dot: bool) -> Result<()> {
+    if !Path::new(SOLANA_PROGRAMS_FILE).exists() {
+        return Err(SolanaPmError::ConfigNotFound(format!("{} not found. Run 'solpm add <program>' first.", SOLANA_PROGRAMS_FILE)));
+    }
+    
+    let content = fs::read_to_string(SOLANA_PROGRAMS_FILE)?;
+    let solana_programs: SolanaPrograms = serde_json::from_str(&content)?;
+    
+    let mut nodes = BTreeMap::new();
+    let mut missing_idls = Vec::new();
+    for (package_name, program_info) in solana_programs.programs.iter().chain(solana_programs.dev_programs.iter()) {
+        let idl_file_path = installed_idl_path(package_name, program_info);
+        let referenced_addresses = match fs::read_to_string(&idl_file_path) {
+            Ok(idl_content) => {
+                let idl_json: serde_json::Value = serde_json::from_str(&idl_content)
+                    .map_err(|e| SolanaPmError::InvalidIdl(format!("Invalid JSON in {}: {}", idl_file_path, e)))?;
+                collect_fixed_addresses(&idl_json)
+            }
+            Err(_) => {
+                missing_idls.push(package_name.clone());
+                BTreeSet::new()
+            }
+        };
+        
+        nodes.insert(package_name.clone(), GraphNode {
+            name: package_name.clone(),
+            version: program_info.version.clone(),
+            program_id: program_info.program_id.clone(),
+            network: program_info.network.clone(),
+            referenced_addresses,
+        });
+    }
+    
+    let edges = find_edges(&nodes);
+    
+    // In JSON mode the DOT source is part of the result instead
+    if dot && !is_json_output() {
+        print!("{}", render_dot(&nodes, &edges));
+    } else if !dot {
+        print_text(&nodes, &edges);
+    }
+    
+    for package_name in &missing_idls {
+        if !is_quiet() {
+            eprintln!("{}", CliStyle::warning(&format!(
+                "No IDL file found for {}; its references are unknown. Run {} to reinstall it.",
+                CliStyle::package(package_name),
+                CliStyle::command("solpm install")
+            )));
+        }
+    }
+    
+    set_result(serde_json::json!({
+        "nodes": nodes.values().map(|node| serde_json::json!({
+            "name": node.name,
+            "version": node.version,
+            "program_id": node.program_id,
+            "network": node.network,
+        })).collect::<Vec<_>>(),
+        "edges": edges.iter().map(|(from, to)| serde_json::json!({ "from": from, "to": to })).collect::<Vec<_>>(),
+        "missing_idls": missing_idls,
+        "dot": dot.then(|| render_dot(&nodes, &edges)),
+    }));
+    
+    Ok(())
+}
+
+/// Collects the fixed account addresses used by an IDL's instructions.
+/// 
+/// Handles nested account groups and both `address` (Anchor 0.30+) and
+/// `fixedAddress` fields. The program's own address is excluded.
+/// 
+/// # Arguments
+/// 
+/// * `idl_json` - The IDL as raw JSON
+/// 
+/// # Returns
+/// 
+/// Returns the set of referenced addresses.
+fn collect_fixed_addresses(idl_json: &serde_json::Value) -> BTreeSet<String> {
+    fn visit(accounts: &serde_json::Value, addresses: &mut BTreeSet<String>) {
+        for account in accounts.as_array().into_iter().flatten() {
+            let address = account.get("address")
+                .or_else(|| account.get("fixedAddress"))
+                .and_then(|address| address.as_str());
+            if let Some(address) = address {
+                addresses.insert(address.to_string());
+            }
+            if let Some(nested) = account.get("accounts") {
+                visit(nested, addresses);
+            }
+        }
+    }
+    
+    let mut addresses = BTreeSet::new();
+    for instruction in idl_json["instructions"].as_array().into_iter().flatten() {
+        visit(&instruction["accounts"], &mut addresses);
+    }
+    
+    if let Some(own_address) = idl_json["address"].as_str() {
+        addresses.remove(own_address);
+    }
+    
+    addresses
+}
+
+/// Finds references between installed programs.
+/// 
+/// # Arguments
+/// 
+/// * `nodes` - The installed programs keyed by name
+/// 
+/// # Returns
+/// 
+/// Returns `(from, to)` pairs of program names.
+fn find_edges(nodes: &BTreeMap<String, GraphNode>) -> Vec<(String, String)> {
+    let mut edges = Vec::new();
+    
+    for from in nodes.values() {
+        for to in nodes.values() {
+            if from.name != to.name
+                && from.network == to.network
+                && from.referenced_addresses.contains(&to.program_id)
+            {
+                edges.push((from.name.clone(), to.name.clone()));
+            }
+        }
+    }
+    
+    edges
+}
+
+/// Renders the graph as a Graphviz DOT digraph.
+/// 
+/// # Arguments
+/// 
+/// * `nodes` - The installed programs keyed by name
+/// * `edges` - The references between programs
+/// 
+/// # Returns
+/// 
+/// Returns the DOT source.
+fn render_dot(nodes: &BTreeMap<String, GraphNode>, edges: &[(String, String)]) -> String {
+    let quote = |value: &str| format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""));
+    
+    let mut dot = String::from("digraph solpm {\n");
+    dot.push_str("    rankdir=LR;\n");
+    dot.push_str("    node [shape=box];\n");
+    
+    for node in nodes.values() {
+        dot.push_str(&format!("    {} [label={}];\n", quote(&node.name), quote(&node.label())));
+    }
+    
+    for (from, to) in edges {
+        dot.push_str(&format!("    {} -> {};\n", quote(from), quote(to)));
+    }
+    
+    dot.push_str("}\n");
+    dot
+}
+
+/// Prints the graph as an indented list of programs and their references.
+/// 
+/// # Arguments
+/// 
+/// * `nodes` - The installed programs keyed by name
+/// * `edges` - The references between programs
+fn print_text(nodes: &BTreeMap<String, GraphNode>, edges: &[(String, String)]) {
+    if nodes.is_empty() {
+        statusln!("{}", CliStyle::info(&format!("No programs found in {}", SOLANA_PROGRAMS_FILE)));
+        return;
+    }
+    
+    for node in nodes.values() {
+        outln!("{}", CliStyle::package(&node.label()));
+        for (_, to) in edges.iter().filter(|(from, _)| *from == node.name) {
+            outln!("  └── {}", nodes[to].label());
+        }
+    }
+}
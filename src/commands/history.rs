@@ -0,0 +1,175 @@
+//! # Publish History Module
+//!
+//! This module keeps a local log of what this machine has published and
+//! implements the `history` command which displays it.
+//!
+//! Features:
+//! - Appends one JSON line per successful publish or release to
+//!   `~/.solpm/publish-log.jsonl`
+//! - Records the registry, name, version, network, program ID, authority,
+//!   IDL hash, and git commit of each publish
+//! - Filters the displayed log by package name and version
+//!
+//! The log is for local auditing only; nothing in it is sent to the registry.
+//! Callers treat write failures as warnings so logging never blocks a publish.
+
+use crate::commands::types::PublishLogEntry;
+use crate::error::{Result, SolanaPmError};
+use crate::outln;
+use crate::statusln;
+use crate::output::set_result;
+use crate::utils::{CliStyle, parse_package_spec};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+const PUBLISH_LOG_FILE: &str = "publish-log.jsonl";
+
+/// Gets the path of the publish log.
+/// 
+/// # Returns
+/// 
+/// Returns the path to `~/.solpm/publish-log.jsonl`, or an error if the
+/// home directory cannot be found.
+fn get_publish_log_path() -> Result<PathBuf> {
+    let home_dir = dirs::home_dir()
+        .ok_or_else(|| SolanaPmError::InvalidPath("Could not find home directory".to_string()))?;
+    
+    Ok(home_dir.join(".solpm").join(PUBLISH_LOG_FILE))
+}
+
+/// Appends an entry to the publish log.
+/// 
+/// Creates `~/.solpm` and the log file if they do not exist.
+/// 
+/// # Arguments
+/// 
+/// * `entry` - The publish or release to record
+/// 
+/// # Returns
+/// 
+/// Returns `Ok(())` on success, or an error if the log cannot be written.
+pub fn append_publish_log(entry: &PublishLogEntry) -> Result<()> {
+    let log_path = get_publish_log_path()?;
+    if let Some(config_dir) = log_path.parent() {
+        fs::create_dir_all(config_dir)?;
+    }
+    
+    let mut line = serde_json::to_string(entry)?;
+    line.push('\n');
+    
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&log_path)
+        .map_err(|e| SolanaPmError::InvalidPath(format!("Failed to open {}: {}", log_path.display(), e)))?;
+    file.write_all(line.as_bytes())?;
+    
+    Ok(())
+}
+
+/// Displays the publish log, optionally filtered by package.
+/// 
+/// Entries are shown oldest first. Lines that cannot be parsed are skipped
+/// and counted in a warning.
+/// 
+/// # Arguments
+/// 
+/// * `package` - A package name, optionally with `@version`, to filter by
+/// 
+/// # Returns
+/// 
+/// Returns `Ok(())` on success, or an error if the log cannot be read.
+/// 
+/// # Examples
+/// 
+/// ```no_run
+/// use solpm::commands::history::print_history;
+/// 
+/// # fn example() -> solpm::error::Result<()> {
+/// // Everything this machine has published
+/// print_history(None)?;
+/// 
+/// // One package, or one version of it
+/// print_history(Some("my-program"))?;
+/// print_history(Some("my-program@1.2.0"))?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn print_history(package: Option<&str>) -> Result<()> {
+    let log_path = get_publish_log_path()?;
+    if !log_path.exists() {
+        outln!("{}", CliStyle::info("Nothing has been published from this machine yet."));
+        set_result(serde_json::json!({ "entries": [] }));
+        return Ok(());
+    }
+    
+    let content = fs::read_to_string(&log_path)
+        .map_err(|e| SolanaPmError::InvalidPath(format!("Failed to read {}: {}", log_path.display(), e)))?;
+    
+    let filter = package.map(parse_package_spec);
+    let mut skipped = 0;
+    let entries: Vec<PublishLogEntry> = content.lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str::<PublishLogEntry>(line) {
+            Ok(entry) => Some(entry),
+            Err(_) => {
+                skipped += 1;
+                None
+            }
+        })
+        .filter(|entry| match &filter {
+            Some(spec) => entry.name == spec.name
+                && spec.version.as_ref().is_none_or(|version| version.is_empty() || *version == entry.version),
+            None => true,
+        })
+        .collect();
+    set_result(serde_json::json!({ "entries": entries, "skipped_lines": skipped }));
+    
+    if skipped > 0 {
+        statusln!("{}", CliStyle::warning(&format!(
+            "Skipped {} unreadable line{} in {}",
+            skipped,
+            if skipped == 1 { "" } else { "s" },
+            log_path.display()
+        )));
+    }
+    
+    if entries.is_empty() {
+        match package {
+            Some(package) => outln!("{}", CliStyle::info(&format!("No history for {}.", CliStyle::package(package)))),
+            None => outln!("{}", CliStyle::info("The publish log is empty.")),
+        }
+        return Ok(());
+    }
+    
+    outln!("{}", CliStyle::header("Publish History"));
+    for entry in &entries {
+        outln!(
+            "{}  {:<7}  {} {}{}",
+            entry.timestamp,
+            entry.action,
+            CliStyle::package(&entry.name),
+            CliStyle::version(&entry.version),
+            entry.network.as_ref().map(|network| format!(" on {}", CliStyle::highlight(network))).unwrap_or_default()
+        );
+        
+        let mut details = Vec::new();
+        if let Some(program_id) = &entry.program_id {
+            details.push(format!("program {}", program_id));
+        }
+        details.push(format!("authority {}", entry.authority));
+        if let Some(idl_hash) = &entry.idl_hash {
+            details.push(format!("idl sha256 {}", idl_hash));
+        }
+        if let Some(commit) = &entry.commit {
+            details.push(format!("commit {}", commit));
+        }
+        details.push(format!("registry {}", entry.registry));
+        for detail in details {
+            outln!("    {}", detail);
+        }
+    }
+    
+    Ok(())
+}
@@ -0,0 +1,126 @@
+//! # Hooks Module
+//!
+//! This module runs project-defined shell commands around solpm commands.
+//!
+//! Features:
+//! - Reads the project `.solpmrc` file (TOML), which also holds project
+//!   settings such as `per_network_idl`
+//! - Runs the `post_install` command after a successful `solpm install`
+//! - Provides the platform shell used by every hook, including the
+//!   `pre_publish` hooks from SolanaPrograms.toml
+//!
+//! ## Security
+//!
+//! Hooks run arbitrary commands with the user's permissions. A `.solpmrc`
+//! can arrive with a cloned repository or a pulled commit, so the
+//! `post_install` hook only runs when `--allow-hooks` is passed; otherwise it
+//! is printed and skipped. Review `.solpmrc` before allowing its hooks.
+
+use crate::commands::types::SolpmRc;
+use crate::error::{Result, SolanaPmError};
+use crate::statusln;
+use crate::output::is_json_output;
+use crate::utils::CliStyle;
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+const SOLPMRC_FILE: &str = ".solpmrc";
+
+/// Builds a command that runs a line through the platform shell.
+/// 
+/// # Arguments
+/// 
+/// * `command` - The command line to run
+/// 
+/// # Returns
+/// 
+/// Returns `cmd /C <command>` on Windows and `sh -c <command>` elsewhere.
+/// In JSON mode the command's stdout goes to stderr, keeping stdout free for
+/// the result document.
+pub fn shell_command(command: &str) -> Command {
+    let mut shell = if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.args(["/C", command]);
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.args(["-c", command]);
+        shell
+    };
+    if is_json_output() {
+        shell.stdout(Stdio::from(std::io::stderr()));
+    }
+    shell
+}
+
+/// Reads the project `.solpmrc` file.
+/// 
+/// # Returns
+/// 
+/// Returns the parsed settings (empty if the file does not exist), or an
+/// error if the file cannot be read or is not valid TOML.
+pub fn load_solpmrc() -> Result<SolpmRc> {
+    if !Path::new(SOLPMRC_FILE).exists() {
+        return Ok(SolpmRc::default());
+    }
+    
+    let content = fs::read_to_string(SOLPMRC_FILE)
+        .map_err(|e| SolanaPmError::InvalidPath(format!("Failed to read {}: {}", SOLPMRC_FILE, e)))?;
+    
+    toml::from_str(&content)
+        .map_err(|e| SolanaPmError::InvalidPath(format!("Invalid {}: {}", SOLPMRC_FILE, e)))
+}
+
+/// Runs the `post_install` hook from `.solpmrc`.
+/// 
+/// The command runs through the platform shell with its output streamed to
+/// the terminal. Without `--allow-hooks` the hook is only printed.
+/// 
+/// # Arguments
+/// 
+/// * `allow_hooks` - Whether the user allowed project hooks to run
+/// 
+/// # Returns
+/// 
+/// Returns `Ok(())` if there is no hook, it was skipped, or it succeeded, or
+/// an error if `.solpmrc` is invalid or the hook could not be started or
+/// exited unsuccessfully.
+/// 
+/// # Examples
+/// 
+/// ```no_run
+/// use solpm::commands::hooks::run_post_install_hook;
+/// 
+/// # fn example() -> solpm::error::Result<()> {
+/// // .solpmrc: post_install = "cp -r program/client ../app/src/solana"
+/// run_post_install_hook(true)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn run_post_install_hook(allow_hooks: bool) -> Result<()> {
+    let Some(command) = load_solpmrc()?.post_install.filter(|command| !command.trim().is_empty()) else {
+        return Ok(());
+    };
+    
+    if !allow_hooks {
+        statusln!("{}", CliStyle::warning(&format!(
+            "Skipping post_install hook from {}: {}. Review it and pass --allow-hooks to run it.",
+            SOLPMRC_FILE,
+            CliStyle::command(&command)
+        )));
+        return Ok(());
+    }
+    
+    statusln!("{}", CliStyle::progress(&format!("Running post_install hook: {}", CliStyle::command(&command))));
+    
+    let status = shell_command(&command).status()
+        .map_err(|e| SolanaPmError::HookFailed(format!("Failed to run '{}': {}", command, e)))?;
+    
+    if !status.success() {
+        let code = status.code().map_or_else(|| "a signal".to_string(), |code| format!("exit code {}", code));
+        return Err(SolanaPmError::HookFailed(format!("post_install hook '{}' failed with {}", command, code)));
+    }
+    
+    Ok(())
+}
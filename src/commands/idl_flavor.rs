@@ -0,0 +1,122 @@
+//! # IDL Flavor Detection
+//!
+//! This module classifies IDL files by the tool that generated them and adapts
+//! Shank IDLs to the Anchor-compatible shape used by `solpm codegen`.
+//!
+//! Flavors are detected from the IDL structure:
+//! - `anchor-0.30`: `metadata.spec` is set (Anchor 0.30 and later)
+//! - `anchor-legacy`: top-level `name` and `version` with an `instructions`
+//!   array (Anchor 0.29 and earlier)
+//! - `shank`: `metadata.origin` is `"shank"`
+//! - `codama`: a Codama root node (`"standard": "codama"` or `"kind": "rootNode"`)
+//! - `unknown`: anything else, including IDLs without an `instructions` array
+
+use crate::commands::types::pascal_to_snake;
+
+/// The tool an IDL was generated by.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IdlFlavor {
+    /// Anchor 0.30+ IDL with `metadata.spec`
+    Anchor030,
+    /// Anchor IDL from before 0.30
+    AnchorLegacy,
+    /// Shank IDL from a native program
+    Shank,
+    /// Codama root node
+    Codama,
+    /// Unrecognized structure
+    Unknown,
+}
+
+impl IdlFlavor {
+    /// Returns the name sent to the registry and shown to users.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IdlFlavor::Anchor030 => "anchor-0.30",
+            IdlFlavor::AnchorLegacy => "anchor-legacy",
+            IdlFlavor::Shank => "shank",
+            IdlFlavor::Codama => "codama",
+            IdlFlavor::Unknown => "unknown",
+        }
+    }
+    
+    /// Returns whether `solpm codegen` reads this flavor with the default
+    /// `--idl-format anchor`.
+    pub fn is_anchor(&self) -> bool {
+        matches!(self, IdlFlavor::Anchor030 | IdlFlavor::AnchorLegacy)
+    }
+}
+
+/// Detects the flavor of an IDL from its structure.
+/// 
+/// # Arguments
+/// 
+/// * `idl_json` - The IDL as raw JSON
+/// 
+/// # Returns
+/// 
+/// Returns the detected flavor, or `IdlFlavor::Unknown` if the structure is
+/// not recognized.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use solpm::commands::idl_flavor::{IdlFlavor, detect_idl_flavor};
+/// 
+/// let idl = serde_json::json!({"metadata": {"spec": "0.1.0"}, "instructions": []});
+/// assert_eq!(detect_idl_flavor(&idl), IdlFlavor::Anchor030);
+/// ```
+pub fn detect_idl_flavor(idl_json: &serde_json::Value) -> IdlFlavor {
+    if idl_json["standard"] == "codama" || idl_json["kind"] == "rootNode" {
+        return IdlFlavor::Codama;
+    }
+    
+    if !idl_json["instructions"].is_array() {
+        return IdlFlavor::Unknown;
+    }
+    
+    let metadata = &idl_json["metadata"];
+    if metadata["origin"] == "shank" {
+        IdlFlavor::Shank
+    } else if metadata["spec"].is_string() {
+        IdlFlavor::Anchor030
+    } else if idl_json["name"].is_string() && idl_json["version"].is_string() {
+        IdlFlavor::AnchorLegacy
+    } else {
+        IdlFlavor::Unknown
+    }
+}
+
+/// Converts a Shank IDL into the Anchor-compatible shape read by codegen.
+/// 
+/// Instruction names are converted from PascalCase to snake_case and missing
+/// `accounts` and `args` arrays are added, matching the conversion done by
+/// `solpm codegen --idl-format shank`. All other fields, including the Shank
+/// `discriminant` of each instruction, are kept so the IDL remains a faithful
+/// description of the program.
+/// 
+/// # Arguments
+/// 
+/// * `idl_json` - The Shank IDL as raw JSON
+/// 
+/// # Returns
+/// 
+/// Returns the adapted IDL.
+pub fn adapt_shank_idl(idl_json: &serde_json::Value) -> serde_json::Value {
+    let mut adapted = idl_json.clone();
+    
+    for instruction in adapted["instructions"].as_array_mut().into_iter().flatten() {
+        let Some(instruction) = instruction.as_object_mut() else {
+            continue;
+        };
+        
+        if let Some(name) = instruction.get("name").and_then(|name| name.as_str()) {
+            let name = pascal_to_snake(name);
+            instruction.insert("name".to_string(), serde_json::Value::String(name));
+        }
+        instruction.entry("accounts").or_insert_with(|| serde_json::json!([]));
+        instruction.entry("args").or_insert_with(|| serde_json::json!([]));
+    }
+    
+    adapted
+}
@@ -6,23 +6,59 @@
 //! Features:
 //! - Creates SolanaPrograms.toml configuration file
 //! - Auto-detects existing program information from project files
-//! - Supports network selection (mainnet/devnet)
+//! - Supports network selection (mainnet/devnet/testnet/localnet)
 //! - Validates project structure and dependencies
 //! - Provides interactive setup with confirmation prompts
 //! - Attempts to discover GitHub repository information
+//! - Detects the project license from Cargo.toml or LICENSE files
+//! - Selects one program of a multi-program workspace, or configures all of
+//!   them as `[[programs]]` entries
+//! - Uses the default Solana CLI keypair, or the one `solana config` reports,
+//!   as the authority keypair when it holds a keypair
+//! - Prompts for the description, repository, license, network, and authority keypair
+//!   in a terminal, offering detected values as defaults
+//! - Falls back to the Cargo.toml and Anchor.toml metadata before the first
+//!   build, when no IDL exists yet
+//! - Prefers the program ID that Anchor.toml declares for the target cluster
+//!   over the IDL address, reporting any mismatch
+//! - Optionally scaffolds the dependency side (SolanaPrograms.json and the
+//!   program/idl and program/client directories) with `--with-deps`
+//! - Can be driven entirely by flags for scripted setup, optionally printing
+//!   the configuration to stdout with `--print`
 //!
 //! The initialization process creates a standardized project structure that
 //! enables dependency management and program publishing through the registry.
 
-use crate::commands::types::{SolanaProgramsConfig, ProgramConfig};
+use crate::commands::config_template::render_config_toml;
+use crate::commands::license::{check_license, detect_license, detect_program_license};
+use crate::commands::publish::{expand_path, normalize_repository_url, validate_program_name, validate_version};
+use crate::commands::config::{client_dir, idl_dir, resolve_network};
+use crate::commands::constants::{PLACEHOLDER_PROGRAM_ID, SOLANA_PROGRAMS_FILE};
+use crate::commands::types::{HooksConfig, ProjectSettings, PublishConfig, SolanaPrograms, SolanaProgramsConfig, ProgramConfig};
+use crate::commands::verify::{FieldValidator, check_authority_keypair, check_description, check_program_id, detect_authority_keypair, detect_cargo_description, prompt_valid};
 use crate::cli::Network;
-use crate::error::{Result, SolanaPmError};
-use crate::utils::{CliStyle, CliProgress, confirm_action};
-use std::fs;
+use crate::error::{Context, Result, SolanaPmError};
+use crate::outln;
+use crate::statusln;
+use crate::output::{is_json_output, set_result};
+use crate::utils::{CliStyle, CliProgress, confirm_action, is_placeholder_program_id, sanitize_file_name};
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::io::IsTerminal;
 use std::path::Path;
 
 const SOLANA_PROGRAMS_TOML: &str = "SolanaPrograms.toml";
 const IDL_PATHS: &[&str] = &["target/idl", "idl", "target/deploy"];
+const DEFAULT_AUTHORITY_KEYPAIR: &str = "~/.config/solana/id.json";
+const NETWORKS: &[&str] = &["mainnet", "devnet", "testnet", "localnet"];
+const ANCHOR_TOML: &str = "Anchor.toml";
+const ANCHOR_CLUSTERS: &[&str] = &["mainnet", "devnet", "testnet", "localnet"];
+const GITIGNORE_FILE: &str = ".gitignore";
+const KEYPAIR_SUFFIX: &str = "-keypair.json";
+const CARGO_TOML: &str = "Cargo.toml";
+const PROGRAMS_DIR: &str = "programs";
+const PREBUILD_NOTE: &str = "# Configured before the first build from Cargo.toml/Anchor.toml metadata.\n# Re-run 'solpm init' after 'anchor build' to refresh it from the IDL.\n\n";
 
 /// Attempts to get the GitHub repository URL from git remote origin.
 /// 
@@ -30,12 +66,12 @@ const IDL_PATHS: &[&str] = &["target/idl", "idl", "target/deploy"];
 /// 
 /// Returns `Some(String)` with the GitHub repository URL if found,
 /// or `None` if not in a git repository or no GitHub remote found.
-fn get_github_repository_url() -> Option<String> {
+pub fn get_github_repository_url() -> Option<String> {
     use std::process::Command;
     
     // Try to get the git remote origin URL
     let output = Command::new("git")
-        .args(&["config", "--get", "remote.origin.url"])
+        .args(["config", "--get", "remote.origin.url"])
         .output()
         .ok()?;
     
@@ -76,145 +112,1394 @@ fn normalize_github_url(url: String) -> String {
     }
 }
 
+/// Options for the `init` command.
+pub struct InitOptions {
+    /// The target network, or `None` to choose interactively (the global
+    /// `network`, or devnet, when not interactive)
+    pub network: Option<Network>,
+    /// Skip the prompts even in a terminal
+    pub no_interactive: bool,
+    /// Require a terminal for the prompts and refuse to write a file that
+    /// still has missing required fields
+    pub interactive: bool,
+    /// The IDL metadata name of the program to configure
+    pub program: Option<String>,
+    /// Configure every program found
+    pub all: bool,
+    /// IDL file to configure and record instead of searching for one
+    pub from_idl: Option<String>,
+    /// Program name, overriding the IDL
+    pub name: Option<String>,
+    /// Program version, overriding the IDL
+    pub program_version: Option<String>,
+    /// Program ID, overriding the IDL address
+    pub program_id: Option<String>,
+    /// Program description
+    pub description: Option<String>,
+    /// Repository URL, overriding the git remote
+    pub repository: Option<String>,
+    /// Authority keypair path
+    pub authority_keypair: Option<String>,
+    /// Update or overwrite an existing SolanaPrograms.toml without asking
+    pub yes: bool,
+    /// Replace an existing SolanaPrograms.toml instead of merging into it
+    pub fresh: bool,
+    /// Write `PLACEHOLDER_PROGRAM_ID` when no valid program ID is found
+    pub allow_placeholder: bool,
+    /// Fail when no IDL exists instead of reading Cargo.toml and Anchor.toml
+    pub require_idl: bool,
+    /// Print the configuration to stdout instead of writing the file
+    pub print: bool,
+    /// Also create SolanaPrograms.json and the program/idl and program/client
+    /// directories
+    pub with_deps: bool,
+}
+
 /// Initializes a new Solana project with package configuration.
 /// 
 /// This function creates a `SolanaPrograms.toml` configuration file by:
-/// 1. Checking if a configuration already exists (with overwrite confirmation)
+/// 1. Checking if a configuration already exists (with confirmation unless
+///    `--yes`/`--force` is given)
 /// 2. Auto-detecting GitHub repository URL if available
 /// 3. Searching for IDL files in common locations (target/idl, idl, target/deploy)
-/// 4. Extracting metadata from the IDL file (name, version, program ID)
-/// 5. Creating a configuration template with detected/specified values
+/// 4. Choosing the program to configure when several IDLs are found: by
+///    `--program` or `--from-idl`, from a list in a terminal, or all of them with
+///    `--all`
+/// 5. Extracting metadata from the IDL file (name, version, program ID)
+/// 6. Detecting the license from the program's Cargo.toml, the workspace
+///    Cargo.toml, or a LICENSE file
+/// 7. Applying the values passed as flags, which override detected values
+/// 8. In a terminal, prompting for the description, repository, license, network (unless
+///    `--network` was given), and authority keypair that were not passed as
+///    flags, with detected values as defaults
+/// 9. Writing the configuration as a commented template (see
+///    [`render_config_toml`]), or printing it to stdout with `--print`
+/// 10. With `--with-deps`, scaffolding the dependency side (see
+///     [`scaffold_dependency_files`])
+/// 
+/// A single program is written as a `[program]` section; several programs
+/// are written as `[[programs]]` entries. When no IDL exists yet, the program
+/// is configured from `--name` and `--program-version`, or else from the
+/// Cargo.toml and Anchor.toml metadata (see [`find_manifest_candidates`]),
+/// and the written file notes that init should be re-run after the first
+/// build. `--require-idl` makes a missing IDL an error instead.
+/// 
+/// `--from-idl` skips the search and records the file as the program's `idl`
+/// key, which publish reads instead of searching. Paths inside the project
+/// are recorded relative to the project root, and `~` is expanded.
+/// 
+/// An existing configuration is merged unless `--fresh` is given: the
+/// description, repository, authority keypair, license, network, and
+/// recorded IDL path the user set are kept (unless passed as flags), name,
+/// version, and program ID are refreshed from the IDL, and the hooks, publish
+/// settings, `[solpm]` settings, and programs not selected this time are
+/// preserved. The updated
+/// and kept fields are printed.
+/// 
+/// Detected program IDs must be base58 public keys. When none is valid, a
+/// terminal prompts for one; otherwise `PLACEHOLDER_PROGRAM_ID` is only
+/// written with `--allow-placeholder`, which publish and codegen refuse.
+/// 
+/// The authority keypair defaults to `~/.config/solana/id.json`, or to the
+/// path `solana config get keypair` reports, whichever holds a keypair; the
+/// prompt validates that an entered path loads as a keypair.
+/// 
+/// When Anchor.toml declares the program for the selected network (or for
+/// localnet), that program ID is used instead of the IDL address, and a
+/// warning lists the clusters whose ID differs from the IDL.
+/// 
+/// Without prompts, a missing name, version, description, or repository is
+/// an error listing every missing value. `--interactive` requires a terminal
+/// and applies the same check after the prompts, so the written file is
+/// ready to publish. With `--print`, status messages go
+/// to stderr so stdout holds only the TOML.
 /// 
 /// # Arguments
 /// 
-/// * `network` - The target network (mainnet or devnet) for the project
+/// * `options` - The network, program selection, and field values
 /// 
 /// # Returns
 /// 
-/// Returns `Ok(())` on success, or an error if IDL files are not found,
-/// file operations fail, or IDL parsing fails.
+/// Returns `Ok(())` on success, or an error if IDL files are not found, the
+/// program to configure is ambiguous or unknown, a flag value is invalid,
+/// required values are missing, file operations fail, or IDL parsing fails.
 /// 
 /// # Examples
 /// 
-/// ```rust
-/// // Initialize project configuration for devnet
-/// init_project(&Network::Dev)?;
+/// ```no_run
+/// use solpm::Network;
+/// use solpm::commands::init::{InitOptions, init_project};
 /// 
-/// // Initialize project configuration for mainnet
-/// init_project(&Network::Main)?;
+/// # fn example() -> solpm::error::Result<()> {
+/// // Scripted setup from a project template
+/// init_project(&InitOptions {
+///     network: Some(Network::Dev),
+///     no_interactive: true,
+///     interactive: false,
+///     program: None,
+///     all: false,
+///     from_idl: None,
+///     name: Some("vesting".to_string()),
+///     program_version: Some("0.1.0".to_string()),
+///     program_id: None,
+///     description: Some("Token vesting with cliffs".to_string()),
+///     repository: Some("https://github.com/acme/vesting".to_string()),
+///     authority_keypair: None,
+///     yes: true,
+///     fresh: false,
+///     allow_placeholder: false,
+///     require_idl: false,
+///     print: false,
+///     with_deps: true,
+/// })?;
+/// # Ok(())
+/// # }
 /// ```
-pub fn init_project(network: &Network) -> Result<()> {
+pub fn init_project(options: &InitOptions) -> Result<()> {
+    // Keep stdout for the TOML when printing it
+    let status = |msg: String| if options.print { eprintln!("{}", msg) } else { outln!("{}", msg) };
+    
+    let config_exists = Path::new(SOLANA_PROGRAMS_TOML).exists();
+    let merge = config_exists && !options.fresh;
+    
     // Check if config already exists and ask for confirmation
-    if Path::new(SOLANA_PROGRAMS_TOML).exists() {
-        println!("{}", CliStyle::warning(&format!("{} already exists.", SOLANA_PROGRAMS_TOML)));
-        if !confirm_action("Do you want to overwrite it?") {
-            println!("{}", CliStyle::info("Initialization cancelled."));
+    if !options.print && !options.yes && config_exists {
+        status(CliStyle::warning(&format!("{} already exists.", SOLANA_PROGRAMS_TOML)));
+        let question = if merge {
+            "Update it? Your description, repository, authority keypair, and license are kept."
+        } else {
+            "Do you want to overwrite it?"
+        };
+        if !confirm_action(question)? {
+            status(CliStyle::info("Initialization cancelled."));
             return Ok(());
         }
     }
-
-    println!("{}", CliStyle::info("Initializing Solana program configuration..."));
     
-    // Find IDL file
-    let spinner = CliProgress::new_spinner("Looking for IDL files...");
-    let idl_file_path = find_idl_file()?;
-    spinner.finish_and_clear();
+    // Merging keeps what the user filled in; --fresh starts over
+    let (mut previous_programs, hooks, publish, solpm) = if merge {
+        let existing = read_existing_config()?;
+        let previous: Vec<ProgramConfig> = existing.program.into_iter().chain(existing.programs).collect();
+        (previous, existing.hooks, existing.publish, existing.solpm)
+    } else {
+        (Vec::new(), HooksConfig::default(), PublishConfig::default(), ProjectSettings::default())
+    };
     
-    println!("{}", CliStyle::success(&format!("Found IDL file: {}", idl_file_path)));
+    status(CliStyle::info("Initializing Solana program configuration..."));
     
-    // Read and parse IDL
-    let spinner = CliProgress::new_spinner("Reading IDL metadata...");
-    let idl_content = fs::read_to_string(&idl_file_path)
-        .map_err(|e| SolanaPmError::InvalidPath(format!("Failed to read IDL file: {}", e)))?;
+    let interactive = !options.no_interactive && !is_json_output() && std::io::stdin().is_terminal();
+    if options.interactive && !interactive {
+        return Err(SolanaPmError::DataMissing(
+            "--interactive needs a terminal to prompt in; pass the values as flags instead".to_string()
+        ));
+    }
+    let overrides = validate_overrides(options)?;
     
-    let idl_json: serde_json::Value = serde_json::from_str(&idl_content)
-        .map_err(|e| SolanaPmError::InvalidIdl(format!("Invalid JSON in IDL: {}", e)))?;
+    // Find and read IDL files
+    let candidates = match &options.from_idl {
+        Some(idl) => Some(read_idl_candidates(Path::new(&expand_path(idl))))
+            .filter(|candidates| !candidates.is_empty())
+            .ok_or_else(|| SolanaPmError::InvalidIdl(format!(
+                "{} is not an IDL: expected JSON with instructions and metadata.name and metadata.version, or an object mapping program names to such IDLs",
+                idl
+            ))),
+        None => {
+            let spinner = CliProgress::new_spinner("Looking for IDL files...");
+            let mut candidates = find_idl_candidates();
+            spinner.finish_and_clear();
+            
+            // IDLs recorded by an earlier --from-idl are outside the searched directories
+            let configured: Vec<IdlCandidate> = previous_programs.iter()
+                .filter_map(|previous| previous.idl.as_deref())
+                .flat_map(|idl| read_idl_candidates(Path::new(&expand_path(idl))))
+                .collect();
+            if !configured.is_empty() {
+                let mut discovered = candidates.unwrap_or_default();
+                discovered.retain(|candidate| !configured.iter().any(|configured| configured.name == candidate.name));
+                candidates = Ok(configured.into_iter().chain(discovered).collect());
+            }
+            candidates
+        }
+    };
     
-    spinner.finish_and_clear();
+    let from_flags = overrides.name.is_some() || overrides.version.is_some();
+    let mut prebuild = false;
+    let mut selected = match candidates {
+        // Nothing built yet: configure the program from the flags alone
+        Err(SolanaPmError::InvalidPath(_)) if from_flags && options.program.is_none() && !options.all => {
+            status(CliStyle::info("No IDL file found; using the program values passed as flags"));
+            prebuild = true;
+            Vec::new()
+        }
+        // Nothing built yet: read what the manifests declare
+        Err(SolanaPmError::InvalidPath(e)) if !options.require_idl => {
+            let manifests = find_manifest_candidates();
+            if manifests.is_empty() {
+                return Err(SolanaPmError::InvalidPath(e));
+            }
+            status(CliStyle::info(&format!(
+                "No IDL file found; reading program metadata from {} and {}",
+                CARGO_TOML, ANCHOR_TOML
+            )));
+            prebuild = true;
+            select_idl_candidates(manifests, options.program.as_deref(), options.all, interactive)?
+        }
+        candidates => select_idl_candidates(candidates?, options.program.as_deref(), options.all, interactive)?,
+    };
+    for candidate in &selected {
+        status(CliStyle::success(&format!(
+            "Found {}: {} ({} {})",
+            if prebuild { "program manifest" } else { "IDL file" },
+            candidate.path,
+            CliStyle::package(&candidate.name),
+            CliStyle::version(&candidate.version)
+        )));
+    }
     
-    // Extract metadata
-    let name = idl_json["metadata"]["name"]
-        .as_str()
-        .ok_or_else(|| SolanaPmError::InvalidIdl("Program name not found in IDL metadata".to_string()))?
-        .to_string();
-        
-    let version = idl_json["metadata"]["version"]
-        .as_str()
-        .ok_or_else(|| SolanaPmError::InvalidIdl("Program version not found in IDL metadata".to_string()))?
-        .to_string();
+    if selected.len() > 1 && overrides.is_program_specific() {
+        return Err(SolanaPmError::DataMissing(
+            "--name, --program-version, --program-id, and --description configure a single program; pass --program <name> instead of --all".to_string()
+        ));
+    }
     
-    let program_id = idl_json["address"]
-        .as_str()
-        .unwrap_or("PLACEHOLDER_PROGRAM_ID")
-        .to_string();
+    // Each program from a combined file gets its own IDL file, written with the config
+    let mut combined_splits = Vec::new();
+    for candidate in selected.iter_mut().filter(|candidate| candidate.entry.is_some() && !options.print) {
+        let split_path = split_idl_path(&candidate.name);
+        if Path::new(&split_path) == Path::new(&candidate.path) {
+            return Err(SolanaPmError::InvalidPath(format!(
+                "{} is a combined IDL file named after one of its programs; rename it so {}'s IDL can be extracted",
+                candidate.path, candidate.name
+            )));
+        }
+        let combined_path = std::mem::replace(&mut candidate.path, split_path.clone());
+        combined_splits.push((combined_path, candidate.entry.take().unwrap_or_default(), split_path));
+    }
     
     // Convert network enum to string
-    let network_str = match network {
-        Network::Main => "mainnet",
-        Network::Dev => "devnet",
-    };
+    let network_str = resolve_network(options.network.as_ref())?.as_str();
     
     // Detect GitHub repository URL if available
-    let repository_url = get_github_repository_url().unwrap_or_else(|| "".to_string());
+    let repository_url = match &overrides.repository {
+        Some(repository) => repository.clone(),
+        None => get_github_repository_url().unwrap_or_default(),
+    };
     
-    if !repository_url.is_empty() {
-        println!("{}", CliStyle::success(&format!(
+    if overrides.repository.is_none() && !repository_url.is_empty() {
+        status(CliStyle::success(&format!(
             "Detected GitHub repository: {}",
             CliStyle::highlight(&repository_url)
         )));
     }
     
-    // Create config structure
-    let config = SolanaProgramsConfig {
-        program: ProgramConfig {
-            name,
-            version,
-            program_id,
+    // Detect license from Cargo.toml or LICENSE file if available
+    let license = detect_license().unwrap_or_default();
+    
+    if !license.is_empty() {
+        status(CliStyle::success(&format!(
+            "Detected license: {}",
+            CliStyle::highlight(&license)
+        )));
+    }
+    
+    // The default keypair path is only written when it holds a keypair
+    let authority_keypair = match (&overrides.authority_keypair, detect_authority_keypair()) {
+        (Some(authority_keypair), _) => authority_keypair.clone(),
+        (None, Some(detected)) => {
+            if detected != DEFAULT_AUTHORITY_KEYPAIR {
+                status(CliStyle::success(&format!(
+                    "Detected authority keypair from 'solana config': {}",
+                    CliStyle::path(&detected)
+                )));
+            }
+            detected
+        }
+        (None, None) => {
+            status(CliStyle::warning(&format!(
+                "No authority keypair found at {} or in 'solana config get keypair'",
+                DEFAULT_AUTHORITY_KEYPAIR
+            )));
+            DEFAULT_AUTHORITY_KEYPAIR.to_string()
+        }
+    };
+    
+    // Create config structures
+    let mut programs: Vec<ProgramConfig> = selected.into_iter()
+        .map(|candidate| ProgramConfig {
+            // An IDL address that is not a public key counts as no address
+            program_id: candidate.program_id
+                .filter(|address| match check_program_id(address) {
+                    Ok(_) => true,
+                    Err(problem) => {
+                        status(CliStyle::warning(&format!("Ignoring the IDL address of {}: {}", candidate.name, problem)));
+                        false
+                    }
+                })
+                .unwrap_or_default(),
+            license: detect_program_license(&candidate.name).unwrap_or_default(),
+            name: candidate.name,
+            version: candidate.version,
             network: network_str.to_string(),
             description: "".to_string(), // Left blank for user to fill
             repository: repository_url.clone(),
-            authority_keypair: "~/.config/solana/id.json".to_string(),
-        },
+            authority_keypair: authority_keypair.clone(),
+            keywords: Vec::new(),
+            idl: options.from_idl.as_ref().map(|_| recorded_idl_path(&candidate.path)),
+        })
+        .collect();
+    if programs.is_empty() {
+        programs.push(ProgramConfig {
+            name: "".to_string(),
+            version: "".to_string(),
+            program_id: "".to_string(),
+            network: network_str.to_string(),
+            description: "".to_string(),
+            repository: repository_url.clone(),
+            license: license.clone(),
+            authority_keypair: authority_keypair.clone(),
+            keywords: Vec::new(),
+            idl: None,
+        });
+    }
+    
+    // Flag values override everything detected
+    for program in &mut programs {
+        overrides.apply(program);
+    }
+    
+    // Carry over the user-entered fields of the existing configuration; a
+    // lone program is matched even when it was renamed
+    let match_single = programs.len() == 1 && previous_programs.len() == 1;
+    let merged: Vec<Option<(ProgramConfig, Vec<&'static str>)>> = programs.iter_mut()
+        .map(|program| {
+            let position = previous_programs.iter()
+                .position(|previous| previous.name == program.name)
+                .or(if match_single { Some(0) } else { None })?;
+            let previous = previous_programs.remove(position);
+            let kept = keep_user_fields(program, &previous, &overrides, options.network.is_some());
+            Some((previous, kept))
+        })
+        .collect();
+    let kept_fields = |index: usize| merged[index].as_ref().map(|(_, kept)| kept.as_slice()).unwrap_or_default();
+    
+    if interactive {
+        let multiple = programs.len() > 1;
+        let (first, rest) = programs.split_first_mut().expect("at least one program is selected");
+        if multiple {
+            status(CliStyle::header(&format!("Program {}", first.name)));
+        }
+        let first_kept = kept_fields(0);
+        prompt_remaining_fields(first, &overrides, first_kept, options.network.is_none())?;
+        
+        // The workspace shares one repository, network, and authority
+        for (index, program) in rest.iter_mut().enumerate() {
+            status(CliStyle::header(&format!("Program {}", program.name)));
+            let kept = kept_fields(index + 1);
+            let shared = [
+                ("repository", &mut program.repository, &first.repository),
+                ("license", &mut program.license, &first.license),
+                ("network", &mut program.network, &first.network),
+                ("authority_keypair", &mut program.authority_keypair, &first.authority_keypair),
+            ];
+            for (field, value, first_value) in shared {
+                if !kept.contains(&field) {
+                    *value = first_value.clone();
+                }
+            }
+            if !kept.contains(&"description") {
+                prompt_description(program)?;
+            }
+        }
+    }
+    
+    // Anchor.toml knows the deployed address per cluster; the IDL only has the last build's
+    if overrides.program_id.is_none() {
+        for program in &mut programs {
+            match read_anchor_program_ids(&program.name) {
+                Ok(anchor_ids) => choose_program_id(program, &anchor_ids, &status),
+                Err(e) => status(CliStyle::warning(&format!("Ignoring {}: {}", ANCHOR_TOML, e))),
+            }
+        }
+    }
+    
+    // Without a valid program ID, ask for one; the placeholder needs consent
+    for program in programs.iter_mut().filter(|program| program.program_id.is_empty()) {
+        if interactive {
+            status(CliStyle::warning(&format!("No valid program ID found for {}", CliStyle::package(&program.name))));
+            if let Some(program_id) = prompt_valid("Program ID", None, check_program_id)? {
+                program.program_id = program_id;
+                continue;
+            }
+        }
+        if options.allow_placeholder {
+            program.program_id = PLACEHOLDER_PROGRAM_ID.to_string();
+            status(CliStyle::warning(&format!(
+                "{} has no program ID; wrote {}. publish and codegen refuse it until you run {}",
+                CliStyle::package(&program.name),
+                PLACEHOLDER_PROGRAM_ID,
+                CliStyle::command("solpm init --program-id <ID>")
+            )));
+        } else if interactive {
+            return Err(SolanaPmError::DataMissing(format!(
+                "A program ID is required for {}. Pass --program-id <ID>, or --allow-placeholder to set it later.",
+                program.name
+            )));
+        }
+    }
+    
+    if !interactive {
+        let detected_description = detect_cargo_description().and_then(|description| check_description(&description).ok());
+        if let Some(detected_description) = detected_description {
+            for program in programs.iter_mut().filter(|program| program.description.is_empty()) {
+                program.description = detected_description.clone();
+            }
+        }
+    }
+    
+    // --interactive promises a file that is ready to publish
+    if !interactive || options.interactive {
+        let multiple = programs.len() > 1;
+        let missing: Vec<String> = programs.iter()
+            .flat_map(|program| missing_fields(program).into_iter().map(move |(field, flag)| {
+                let field = if multiple { format!("{}.{}", program.name, field) } else { field.to_string() };
+                format!("{} ({})", field, flag)
+            }))
+            .collect();
+        if !missing.is_empty() {
+            let hint = if interactive { "Answer their prompts or pass them as flags." } else { "Pass them as flags or run 'solpm init' in a terminal." };
+            return Err(SolanaPmError::DataMissing(format!(
+                "Missing required values:\n  - {}\n{}",
+                missing.join("\n  - "),
+                hint
+            )));
+        }
+    }
+    
+    for (program, merged) in programs.iter().zip(&merged) {
+        if let Some((previous, kept)) = merged {
+            report_merge(program, previous, kept, &status);
+        }
+    }
+    // Programs not selected this time stay configured
+    for previous in &previous_programs {
+        status(CliStyle::info(&format!("Kept {} unchanged", CliStyle::package(&previous.name))));
+    }
+    programs.append(&mut previous_programs);
+    
+    let config = if programs.len() == 1 {
+        SolanaProgramsConfig {
+            program: programs.pop(),
+            programs: Vec::new(),
+            hooks,
+            publish,
+            solpm,
+        }
+    } else {
+        SolanaProgramsConfig {
+            program: None,
+            programs,
+            hooks,
+            publish,
+            solpm,
+        }
     };
     
     // Write TOML file
-    let toml_content = toml::to_string_pretty(&config)
-        .map_err(|e| SolanaPmError::InvalidPath(format!("Failed to serialize TOML: {}", e)))?;
+    let mut toml_content = render_config_toml(&config)?;
+    if prebuild {
+        toml_content.insert_str(0, PREBUILD_NOTE);
+    }
     
-    fs::write(SOLANA_PROGRAMS_TOML, toml_content)?;
+    if options.print {
+        // In JSON mode the TOML is part of the result instead
+        if is_json_output() {
+            set_result(serde_json::json!({ "toml": toml_content }));
+        } else {
+            print!("{}", toml_content);
+        }
+        return Ok(());
+    }
     
-    println!("{}", CliStyle::success(&format!(
-        "Created {} for {} network",
+    for (combined_path, entry, split_path) in &combined_splits {
+        split_combined_idl(combined_path, entry, split_path)?;
+        outln!("{}", CliStyle::success(&format!("Extracted {} from {} to {}", entry, combined_path, split_path)));
+    }
+    fs::write(SOLANA_PROGRAMS_TOML, toml_content)
+        .with_context(|| format!("Failed to write {}", SOLANA_PROGRAMS_TOML))?;
+    
+    let configured: Vec<&ProgramConfig> = config.program.iter().chain(config.programs.iter()).collect();
+    let networks: HashSet<&str> = configured.iter().map(|program| program.network.as_str()).collect();
+    let mut networks: Vec<&str> = networks.into_iter().collect();
+    networks.sort();
+    outln!("{}", CliStyle::success(&format!(
+        "{} {} for {} network",
+        if merge { "Updated" } else { "Created" },
         SOLANA_PROGRAMS_TOML,
-        CliStyle::highlight(network_str)
+        CliStyle::highlight(&networks.join(", "))
     )));
+    if configured.len() > 1 {
+        statusln!("{}", CliStyle::info(&format!(
+            "Configured {} programs; publish each with {}",
+            configured.len(),
+            CliStyle::command("solpm publish --program <name>")
+        )));
+    }
+    
+    if prebuild {
+        statusln!("{}", CliStyle::info(&format!(
+            "No IDL yet; re-run {} after {} to refresh the name, version, and program ID from it",
+            CliStyle::command("solpm init"),
+            CliStyle::command("anchor build")
+        )));
+    }
     
-    if repository_url.is_empty() {
-        println!("{}", CliStyle::info("Please fill in the 'description' and 'repository' fields before publishing."));
+    for program in configured.iter().filter(|program| is_placeholder_program_id(&program.program_id)) {
+        statusln!("{}", CliStyle::warning(&format!(
+            "{} uses the placeholder program ID; set the deployed ID with {} before publishing",
+            CliStyle::package(&program.name),
+            CliStyle::command("solpm init --program-id <ID>")
+        )));
+    }
+    
+    let mut missing = Vec::new();
+    if configured.iter().any(|program| program.description.is_empty()) {
+        missing.push("'description'");
+    }
+    if configured.iter().any(|program| program.repository.is_empty()) {
+        missing.push("'repository'");
+    }
+    if !missing.is_empty() {
+        statusln!("{}", CliStyle::info(&format!(
+            "Please fill in the {} field{} before publishing.",
+            missing.join(" and "),
+            if missing.len() == 1 { "" } else { "s" }
+        )));
+    }
+    
+    set_result(serde_json::json!({
+        "path": SOLANA_PROGRAMS_TOML,
+        "updated": merge,
+        "programs": configured.iter().map(|program| serde_json::json!({
+            "name": program.name,
+            "version": program.version,
+            "network": program.network,
+            "program_id": program.program_id,
+        })).collect::<Vec<_>>(),
+    }));
+    
+    if options.with_deps {
+        scaffold_dependency_files(options.yes, interactive)?;
+    }
+    
+    Ok(())
+}
+
+/// Creates the files `solpm add` and `solpm install` work with.
+/// 
+/// This function:
+/// 1. Creates an empty SolanaPrograms.json, unless a non-empty one exists
+/// 2. Creates the `program/idl` and client directories (`program/client`, or
+///    `client_dir` from ~/.solpm/config.toml)
+/// 3. Offers to add the client directory to an existing .gitignore (added
+///    without asking with `--yes`, skipped when not interactive)
+/// 
+/// # Arguments
+/// 
+/// * `yes` - Whether to update .gitignore without asking
+/// * `interactive` - Whether the user can be asked
+/// 
+/// # Returns
+/// 
+/// Returns `Ok(())` on success, or an error if a file or directory cannot be
+/// written.
+pub fn scaffold_dependency_files(yes: bool, interactive: bool) -> Result<()> {
+    let existing = fs::read_to_string(SOLANA_PROGRAMS_FILE).unwrap_or_default();
+    if existing.trim().is_empty() {
+        let solana_programs = SolanaPrograms {
+            programs: HashMap::new(),
+            dev_programs: HashMap::new(),
+        };
+        fs::write(SOLANA_PROGRAMS_FILE, serde_json::to_string_pretty(&solana_programs)?).with_context(|| format!("Failed to write {}", SOLANA_PROGRAMS_FILE))?;
+        outln!("{}", CliStyle::success(&format!("Created {}", SOLANA_PROGRAMS_FILE)));
     } else {
-        println!("{}", CliStyle::info("Please fill in the 'description' field before publishing."));
+        statusln!("{}", CliStyle::info(&format!("{} already exists; left unchanged", SOLANA_PROGRAMS_FILE)));
+    }
+    
+    let idl_dir = idl_dir();
+    let client_dir = client_dir()?;
+    for dir in [idl_dir.as_str(), client_dir.as_str()] {
+        fs::create_dir_all(dir)
+            .map_err(|e| SolanaPmError::InvalidPath(format!("Failed to create directory {}: {}", dir, e)))?;
+    }
+    outln!("{}", CliStyle::success(&format!("Created {} and {}", idl_dir, client_dir)));
+    
+    let Ok(gitignore) = fs::read_to_string(GITIGNORE_FILE) else {
+        return Ok(());
+    };
+    let client_pattern = format!("{}/", client_dir.trim_start_matches("./").trim_end_matches('/'));
+    let ignored = gitignore.lines()
+        .map(|line| line.trim().trim_start_matches('/').trim_end_matches('/'))
+        .any(|line| line == client_pattern.trim_end_matches('/'));
+    if ignored {
+        return Ok(());
+    }
+    
+    let prompt = format!("Add {} to {}?", client_pattern, GITIGNORE_FILE);
+    let add = yes || (interactive && confirm_action(&prompt)?);
+    if !add {
+        statusln!("{}", CliStyle::info(&format!(
+            "Generated clients are not ignored by git; add {} to {} to ignore them",
+            client_pattern,
+            GITIGNORE_FILE
+        )));
+        return Ok(());
     }
     
+    let separator = if gitignore.is_empty() || gitignore.ends_with('\n') { "" } else { "\n" };
+    let mut file = OpenOptions::new()
+        .append(true)
+        .open(GITIGNORE_FILE)
+        .map_err(|e| SolanaPmError::InvalidPath(format!("Failed to open {}: {}", GITIGNORE_FILE, e)))?;
+    file.write_all(format!("{}{}\n", separator, client_pattern).as_bytes())?;
+    outln!("{}", CliStyle::success(&format!("Added {} to {}", client_pattern, GITIGNORE_FILE)));
+    
     Ok(())
 }
 
-/// Searches for an IDL file in common Solana project directories.
+/// Field values passed to `init` as flags, validated and normalized.
+pub struct InitOverrides {
+    /// `--name`
+    pub name: Option<String>,
+    /// `--program-version`
+    pub version: Option<String>,
+    /// `--program-id`
+    pub program_id: Option<String>,
+    /// `--description`
+    pub description: Option<String>,
+    /// `--repository`, normalized
+    pub repository: Option<String>,
+    /// `--authority-keypair`
+    pub authority_keypair: Option<String>,
+}
+
+impl InitOverrides {
+    /// Whether any value that only fits a single program was passed.
+    pub fn is_program_specific(&self) -> bool {
+        self.name.is_some() || self.version.is_some() || self.program_id.is_some() || self.description.is_some()
+    }
+    
+    /// Replaces the fields of a program configuration with the passed values.
+    pub fn apply(&self, program: &mut ProgramConfig) {
+        let fields = [
+            (&self.name, &mut program.name),
+            (&self.version, &mut program.version),
+            (&self.program_id, &mut program.program_id),
+            (&self.description, &mut program.description),
+            (&self.repository, &mut program.repository),
+            (&self.authority_keypair, &mut program.authority_keypair),
+        ];
+        for (value, field) in fields {
+            if let Some(value) = value {
+                *field = value.clone();
+            }
+        }
+    }
+}
+
+/// Validates the field values passed to `init` as flags.
+/// 
+/// # Arguments
+/// 
+/// * `options` - The `init` options holding the flag values
+/// 
+/// # Returns
+/// 
+/// Returns the normalized values, or an error listing every invalid flag.
+fn validate_overrides(options: &InitOptions) -> Result<InitOverrides> {
+    let mut problems = Vec::new();
+    let mut check = |flag: &str, value: &Option<String>, validate: FieldValidator| -> Option<String> {
+        let value = value.as_deref()?;
+        match validate(value.trim()) {
+            Ok(value) => Some(value),
+            Err(problem) => {
+                problems.push(format!("--{}: {}", flag, problem));
+                None
+            }
+        }
+    };
+    
+    let overrides = InitOverrides {
+        name: check("name", &options.name, |name| validate_program_name(name).map(|_| name.to_string())),
+        version: check("program-version", &options.program_version, |version| validate_version(version).map(|_| version.to_string())),
+        program_id: check("program-id", &options.program_id, check_program_id),
+        description: check("description", &options.description, check_description),
+        repository: check("repository", &options.repository, normalize_repository_url),
+        authority_keypair: check("authority-keypair", &options.authority_keypair, |path| {
+            if path.is_empty() { Err("required".to_string()) } else { Ok(path.to_string()) }
+        }),
+    };
+    
+    if !problems.is_empty() {
+        return Err(SolanaPmError::DataMissing(format!("Invalid values:\n  - {}", problems.join("\n  - "))));
+    }
+    
+    Ok(overrides)
+}
+
+/// Reads the program IDs that Anchor.toml declares for a program.
+/// 
+/// Looks the program up in the `[programs.mainnet]`, `[programs.devnet]`,
+/// `[programs.testnet]`, and `[programs.localnet]` tables, treating `-` and
+/// `_` in names as equal.
+/// 
+/// # Arguments
+/// 
+/// * `name` - The program name
+/// 
+/// # Returns
+/// 
+/// Returns the cluster and program ID of each table that lists the program
+/// (empty if there is no Anchor.toml), or an error if Anchor.toml cannot be
+/// read or parsed.
+pub fn read_anchor_program_ids(name: &str) -> std::result::Result<Vec<(&'static str, String)>, String> {
+    if !Path::new(ANCHOR_TOML).exists() {
+        return Ok(Vec::new());
+    }
+    
+    let content = fs::read_to_string(ANCHOR_TOML).map_err(|e| e.to_string())?;
+    let manifest: toml::Value = toml::from_str(&content).map_err(|e| e.to_string())?;
+    let name = name.replace('-', "_");
+    
+    Ok(ANCHOR_CLUSTERS.iter()
+        .filter_map(|cluster| {
+            let programs = manifest.get("programs")?.get(*cluster)?.as_table()?;
+            programs.iter()
+                .find(|(key, _)| key.replace('-', "_") == name)
+                .and_then(|(_, program_id)| program_id.as_str())
+                .map(|program_id| (*cluster, program_id.to_string()))
+        })
+        .collect())
+}
+
+/// Chooses between the IDL address and the Anchor.toml program IDs.
+/// 
+/// Prints every candidate with its source, then uses the Anchor.toml ID for
+/// the program's network, falling back to localnet and then to the IDL
+/// address. Clusters whose ID differs from the IDL address are reported so
+/// the build can be fixed, and IDs that are not public keys are ignored.
+/// 
+/// # Arguments
+/// 
+/// * `program` - The program configuration, holding the IDL address
+/// * `anchor_ids` - The cluster and program ID pairs from Anchor.toml
+/// * `status` - Prints a status line
+fn choose_program_id(program: &mut ProgramConfig, anchor_ids: &[(&str, String)], status: &dyn Fn(String)) {
+    let source = |cluster: &str| format!("{} [programs.{}]", ANCHOR_TOML, cluster);
+    let anchor_ids: Vec<(&str, String)> = anchor_ids.iter()
+        .filter(|(cluster, program_id)| match check_program_id(program_id) {
+            Ok(_) => true,
+            Err(problem) => {
+                status(CliStyle::warning(&format!("Ignoring {}: {}", source(cluster), problem)));
+                false
+            }
+        })
+        .cloned()
+        .collect();
+    if anchor_ids.is_empty() {
+        return;
+    }
+    
+    let idl_address = Some(program.program_id.clone()).filter(|program_id| !program_id.is_empty());
+    
+    status(CliStyle::info(&format!("Program ID candidates for {}:", CliStyle::package(&program.name))));
+    if let Some(idl_address) = &idl_address {
+        status(format!("    {:<32} {}", "IDL address", idl_address));
+    }
+    for (cluster, program_id) in &anchor_ids {
+        status(format!("    {:<32} {}", source(cluster), program_id));
+    }
+    
+    let chosen = anchor_ids.iter()
+        .find(|(cluster, _)| *cluster == program.network)
+        .or_else(|| anchor_ids.iter().find(|(cluster, _)| *cluster == "localnet"));
+    match (chosen, &idl_address) {
+        (Some((cluster, program_id)), _) => {
+            program.program_id = program_id.clone();
+            status(CliStyle::success(&format!("Using program ID {} from {}", CliStyle::highlight(program_id), source(cluster))));
+        }
+        (None, Some(idl_address)) => {
+            status(CliStyle::success(&format!("Using program ID {} from the IDL address", CliStyle::highlight(idl_address))));
+        }
+        (None, None) => {}
+    }
+    
+    if let Some(idl_address) = &idl_address {
+        let differing: Vec<String> = anchor_ids.iter()
+            .filter(|(_, program_id)| program_id != idl_address)
+            .map(|(cluster, program_id)| format!("{} ({})", source(cluster), program_id))
+            .collect();
+        if !differing.is_empty() {
+            status(CliStyle::warning(&format!(
+                "The IDL address {} differs from {}; update declare_id! and rebuild if the IDL is stale",
+                idl_address,
+                differing.join(", ")
+            )));
+        }
+    }
+}
+
+/// Returns how a `--from-idl` path is recorded in SolanaPrograms.toml.
+/// 
+/// Paths starting with `~` are kept as given so they work for every user,
+/// paths inside the project are stored relative to the project root, and
+/// other paths are stored absolute.
+/// 
+/// # Arguments
+/// 
+/// * `path` - The `--from-idl` path
+/// 
+/// # Returns
+/// 
+/// Returns the path to record in the `idl` key.
+pub fn recorded_idl_path(path: &str) -> String {
+    if path.starts_with('~') {
+        return path.to_string();
+    }
+    
+    let Ok(absolute) = std::path::absolute(path) else {
+        return path.to_string();
+    };
+    match std::env::current_dir() {
+        Ok(root) => match absolute.strip_prefix(&root) {
+            Ok(relative) => relative.display().to_string(),
+            Err(_) => absolute.display().to_string(),
+        },
+        Err(_) => absolute.display().to_string(),
+    }
+}
+
+/// Reads the existing SolanaPrograms.toml to merge into.
+/// 
+/// # Returns
+/// 
+/// Returns the parsed configuration, or an error suggesting `--fresh` if it
+/// cannot be read or parsed.
+fn read_existing_config() -> Result<SolanaProgramsConfig> {
+    let content = fs::read_to_string(SOLANA_PROGRAMS_TOML)
+        .with_context(|| format!("Failed to read {}", SOLANA_PROGRAMS_TOML))?;
+    toml::from_str(&content).map_err(|e| SolanaPmError::InvalidPath(format!(
+        "Failed to parse the existing {}: {}. Pass --fresh to overwrite it.",
+        SOLANA_PROGRAMS_TOML, e
+    )))
+}
+
+/// Copies the user-entered fields of an existing program configuration.
+/// 
+/// The description, repository, authority keypair, and license are kept
+/// unless they are empty or were passed as flags, and keywords and a custom
+/// IDL path are kept when set; the network is kept unless
+/// `--network` was given. Name, version, and program ID stay as detected,
+/// except that a valid program ID is kept when none was detected.
+/// 
+/// # Arguments
+/// 
+/// * `program` - The newly detected program configuration
+/// * `previous` - The same program in the existing configuration
+/// * `overrides` - The values passed as flags
+/// * `network_given` - Whether `--network` was passed
+/// 
+/// # Returns
+/// 
+/// Returns the names of the kept fields.
+fn keep_user_fields(program: &mut ProgramConfig, previous: &ProgramConfig, overrides: &InitOverrides, network_given: bool) -> Vec<&'static str> {
+    let fields = [
+        ("description", overrides.description.is_some(), &previous.description, &mut program.description),
+        ("repository", overrides.repository.is_some(), &previous.repository, &mut program.repository),
+        ("authority_keypair", overrides.authority_keypair.is_some(), &previous.authority_keypair, &mut program.authority_keypair),
+        ("license", false, &previous.license, &mut program.license),
+        ("network", network_given, &previous.network, &mut program.network),
+    ];
+    
+    let mut kept = Vec::new();
+    for (field, passed, previous_value, value) in fields {
+        if !passed && !previous_value.is_empty() {
+            *value = previous_value.clone();
+            kept.push(field);
+        }
+    }
+    if program.program_id.is_empty() && check_program_id(&previous.program_id).is_ok() {
+        program.program_id = previous.program_id.clone();
+        kept.push("program_id");
+    }
+    if program.idl.is_none() && previous.idl.is_some() {
+        program.idl = previous.idl.clone();
+        kept.push("idl");
+    }
+    if program.keywords.is_empty() && !previous.keywords.is_empty() {
+        program.keywords = previous.keywords.clone();
+        kept.push("keywords");
+    }
+    kept
+}
+
+/// Prints which fields of a merged program were updated and which were kept.
+/// 
+/// # Arguments
+/// 
+/// * `program` - The merged program configuration
+/// * `previous` - The program in the existing configuration
+/// * `kept` - The fields copied from the existing configuration
+/// * `status` - Prints a status line
+fn report_merge(program: &ProgramConfig, previous: &ProgramConfig, kept: &[&str], status: &dyn Fn(String)) {
+    let fields = [
+        ("name", &previous.name, &program.name),
+        ("version", &previous.version, &program.version),
+        ("program_id", &previous.program_id, &program.program_id),
+        ("network", &previous.network, &program.network),
+        ("description", &previous.description, &program.description),
+        ("repository", &previous.repository, &program.repository),
+        ("authority_keypair", &previous.authority_keypair, &program.authority_keypair),
+        ("license", &previous.license, &program.license),
+    ];
+    let mut updated: Vec<String> = fields.iter()
+        .filter(|(_, old, new)| old != new)
+        .map(|(field, old, new)| format!("{} ({} → {})", field, old, new))
+        .collect();
+    if let Some(idl) = program.idl.as_ref().filter(|idl| previous.idl.as_ref() != Some(*idl)) {
+        updated.push(format!("idl ({} → {})", previous.idl.as_deref().unwrap_or("searched"), idl));
+    }
+    
+    let name = CliStyle::package(&program.name);
+    if updated.is_empty() {
+        status(CliStyle::info(&format!("{}: nothing to update", name)));
+    } else {
+        status(CliStyle::success(&format!("{}: updated {}", name, updated.join(", "))));
+    }
+    if !kept.is_empty() {
+        status(CliStyle::info(&format!("{}: kept {}", name, kept.join(", "))));
+    }
+}
+
+/// Lists the required fields of a program configuration that are empty.
+/// 
+/// # Arguments
+/// 
+/// * `program` - The program configuration to check
+/// 
+/// # Returns
+/// 
+/// Returns the field name and the flag that sets it for each empty field.
+fn missing_fields(program: &ProgramConfig) -> Vec<(&'static str, &'static str)> {
+    [
+        ("name", "--name", &program.name),
+        ("version", "--program-version", &program.version),
+        ("program_id", "--program-id or --allow-placeholder", &program.program_id),
+        ("description", "--description", &program.description),
+        ("repository", "--repository", &program.repository),
+    ]
+    .into_iter()
+    .filter(|(_, _, value)| value.is_empty())
+    .map(|(field, flag, _)| (field, flag))
+    .collect()
+}
+
+/// Chooses which discovered IDLs to configure.
+/// 
+/// # Arguments
+/// 
+/// * `candidates` - The discovered IDLs
+/// * `program` - The IDL metadata name to select, if given
+/// * `all` - Whether to select every IDL
+/// * `interactive` - Whether the user can pick from a list
+/// 
+/// # Returns
+/// 
+/// Returns the selected IDLs, or an error if `program` matches none of them
+/// or several IDLs were found in a non-interactive session.
+fn select_idl_candidates(mut candidates: Vec<IdlCandidate>, program: Option<&str>, all: bool, interactive: bool) -> Result<Vec<IdlCandidate>> {
+    use dialoguer::Select;
+    
+    let available = || candidates.iter()
+        .map(|candidate| format!("{} ({})", candidate.name, candidate.path))
+        .collect::<Vec<_>>()
+        .join(", ");
+    
+    if let Some(program) = program {
+        let index = candidates.iter().position(|candidate| candidate.name == program).ok_or_else(|| {
+            SolanaPmError::DataMissing(format!("No IDL named '{}' found. Available: {}", program, available()))
+        })?;
+        return Ok(vec![candidates.swap_remove(index)]);
+    }
+    
+    if all || candidates.len() == 1 {
+        return Ok(candidates);
+    }
+    
+    if !interactive {
+        return Err(SolanaPmError::DataMissing(format!(
+            "Found {} IDLs: {}. Pass --program <name> to choose one or --all to configure every program.",
+            candidates.len(),
+            available()
+        )));
+    }
+    
+    let items: Vec<String> = candidates.iter()
+        .map(|candidate| format!("{} v{} ({})", candidate.name, candidate.version, candidate.path))
+        .collect();
+    let selection = Select::new()
+        .with_prompt(format!("Found {} programs; select one to configure", candidates.len()))
+        .items(&items)
+        .default(0)
+        .interact()
+        .map_err(|e| SolanaPmError::DataMissing(format!("Failed to read selection: {}", e)))?;
+    
+    Ok(vec![candidates.swap_remove(selection)])
+}
+
+/// Prompts for the configuration fields that cannot be fully detected.
+/// 
+/// Every prompt offers the current (detected) value as its default, so
+/// pressing enter accepts it. Entered values are validated with the same
+/// rules as `solpm verify`, and aborting a prompt keeps the current value.
+/// Fields passed as flags are not prompted for, and the name and version are
+/// only prompted for when no IDL provided them.
+/// 
+/// # Arguments
+/// 
+/// * `program` - The program configuration to update
+/// * `overrides` - The values passed as flags
+/// * `select_network` - Whether to prompt for the network
+/// 
+/// # Returns
+/// 
+/// Returns `Ok(())` on success, or an error if the network selection fails.
+fn prompt_remaining_fields(program: &mut ProgramConfig, overrides: &InitOverrides, kept: &[&str], select_network: bool) -> Result<()> {
+    use dialoguer::Select;
+    
+    if program.name.is_empty()
+        && let Some(name) = prompt_valid("Program name", None, |name| validate_program_name(name).map(|_| name.to_string()))?
+    {
+        program.name = name;
+    }
+    
+    if program.version.is_empty()
+        && let Some(version) = prompt_valid("Program version", Some("0.1.0"), |version| validate_version(version).map(|_| version.to_string()))?
+    {
+        program.version = version;
+    }
+    
+    if overrides.description.is_none() && !kept.contains(&"description") {
+        prompt_description(program)?;
+    }
+    
+    if overrides.repository.is_none() && !kept.contains(&"repository") {
+        let detected_repository = Some(program.repository.as_str()).filter(|repository| !repository.is_empty());
+        if let Some(repository) = prompt_valid("Repository URL", detected_repository, normalize_repository_url)? {
+            program.repository = repository;
+        }
+    }
+    
+    if !kept.contains(&"license") {
+        let detected_license = program.license.clone();
+        if let Some(license) = prompt_valid("License (SPDX, empty to skip)", Some(&detected_license), check_license)? {
+            program.license = license;
+        }
+    }
+    
+    if select_network && !kept.contains(&"network") {
+        let default_index = NETWORKS.iter().position(|network| *network == program.network).unwrap_or(0);
+        let selection = Select::new()
+            .with_prompt("Network")
+            .items(NETWORKS)
+            .default(default_index)
+            .interact()
+            .map_err(|e| SolanaPmError::DataMissing(format!("Failed to read selection: {}", e)))?;
+        program.network = NETWORKS[selection].to_string();
+    }
+    
+    if overrides.authority_keypair.is_none()
+        && !kept.contains(&"authority_keypair")
+        && let Some(authority_keypair) = prompt_valid(
+            "Authority keypair",
+            Some(program.authority_keypair.as_str()).filter(|path| check_authority_keypair(path).is_ok()),
+            check_authority_keypair,
+        )?
+    {
+        program.authority_keypair = authority_keypair;
+    }
+    
+    Ok(())
+}
+
+/// Prompts for a program description.
+/// 
+/// The description from Cargo.toml is offered as the default, and aborting
+/// the prompt keeps the current value.
+/// 
+/// # Arguments
+/// 
+/// * `program` - The program configuration to update
+/// 
+/// # Returns
+/// 
+/// Returns `Ok(())` on success, or `SolanaPmError::InputRequired` in JSON mode.
+fn prompt_description(program: &mut ProgramConfig) -> Result<()> {
+    let detected_description = detect_cargo_description().and_then(|description| check_description(&description).ok());
+    if let Some(description) = prompt_valid("Description", detected_description.as_deref(), check_description)? {
+        program.description = description;
+    }
+    
+    Ok(())
+}
+
+/// An IDL file found in the project and the program metadata it declares.
+pub struct IdlCandidate {
+    /// Path to the IDL file
+    pub path: String,
+    /// `metadata.name`
+    pub name: String,
+    /// `metadata.version`
+    pub version: String,
+    /// `address`, if the IDL declares one
+    pub program_id: Option<String>,
+    /// Key of the program in a combined IDL file, or `None` for a single IDL
+    pub entry: Option<String>,
+}
+
+/// Reads a file as IDL candidates.
+/// 
+/// A file is an IDL if it parses as JSON with an `instructions` array and
+/// string `metadata.name` and `metadata.version` fields. A combined file, a
+/// JSON object mapping program names to such IDLs, yields one candidate per
+/// program.
+/// 
+/// # Arguments
+/// 
+/// * `path` - The file to read
+/// 
+/// # Returns
+/// 
+/// Returns the candidates, or an empty list if the file cannot be read or
+/// holds no IDL.
+pub fn read_idl_candidates(path: &Path) -> Vec<IdlCandidate> {
+    let Some(idl_json) = fs::read_to_string(path).ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+    else {
+        return Vec::new();
+    };
+    let candidate = |idl: &serde_json::Value, entry: Option<&String>| -> Option<IdlCandidate> {
+        idl["instructions"].as_array()?;
+        Some(IdlCandidate {
+            path: path.to_string_lossy().to_string(),
+            name: idl["metadata"]["name"].as_str()?.to_string(),
+            version: idl["metadata"]["version"].as_str()?.to_string(),
+            program_id: idl["address"].as_str().map(|address| address.to_string()),
+            entry: entry.cloned(),
+        })
+    };
+    
+    match combined_idl_entries(&idl_json) {
+        Some(entries) => entries.into_iter().filter_map(|(key, idl)| candidate(idl, Some(key))).collect(),
+        None => candidate(&idl_json, None).into_iter().collect(),
+    }
+}
+
+/// Returns the programs of a combined IDL file, keyed by name.
+/// 
+/// A combined file is a JSON object without `instructions` whose values are
+/// all objects with an `instructions` array.
+/// 
+/// # Arguments
+/// 
+/// * `idl_json` - The parsed file
+/// 
+/// # Returns
+/// 
+/// Returns the entries in file order, or `None` if the file is not a combined file.
+pub fn combined_idl_entries(idl_json: &serde_json::Value) -> Option<Vec<(&String, &serde_json::Value)>> {
+    let entries = idl_json.as_object()?;
+    if entries.is_empty() || entries.contains_key("instructions") {
+        return None;
+    }
+    if !entries.values().all(|idl| idl["instructions"].is_array()) {
+        return None;
+    }
+    
+    Some(entries.iter().collect())
+}
+
+/// Takes one program's IDL out of a combined IDL file.
+/// 
+/// A single IDL is returned unchanged. In a combined file, the entry whose
+/// key or `metadata.name` matches the program (treating `-` and `_` alike)
+/// is returned.
+/// 
+/// # Arguments
+/// 
+/// * `idl_json` - The parsed IDL file
+/// * `program_name` - The program to take
+/// * `path` - The file the IDL was read from, for error messages
+/// 
+/// # Returns
+/// 
+/// Returns the program's IDL, or an error if a combined file has no entry
+/// for the program.
+pub fn select_combined_idl(idl_json: serde_json::Value, program_name: &str, path: &str) -> Result<serde_json::Value> {
+    let normalized_name = program_name.replace('-', "_");
+    let matches = |key: &str, idl: &serde_json::Value| {
+        key.replace('-', "_") == normalized_name
+            || idl["metadata"]["name"].as_str().is_some_and(|name| name.replace('-', "_") == normalized_name)
+    };
+    
+    let Some(entries) = combined_idl_entries(&idl_json) else {
+        return Ok(idl_json);
+    };
+    let names: Vec<&str> = entries.iter().map(|(key, _)| key.as_str()).collect();
+    let Some(key) = entries.iter().find(|(key, idl)| matches(key, idl)).map(|(key, _)| key.to_string()) else {
+        return Err(SolanaPmError::InvalidIdl(format!(
+            "{} holds the IDLs of {} but not '{}'",
+            path, names.join(", "), program_name
+        )));
+    };
+    
+    let serde_json::Value::Object(mut entries) = idl_json else {
+        unreachable!("combined IDL files are objects");
+    };
+    Ok(entries.remove(&key).unwrap_or_default())
+}
+
+/// Returns where a program's IDL extracted from a combined file is written.
+fn split_idl_path(program_name: &str) -> String {
+    Path::new(IDL_PATHS[0]).join(format!("{}.json", sanitize_file_name(program_name))).to_string_lossy().to_string()
+}
+
+/// Writes one program of a combined IDL file to its own file.
+/// 
+/// # Arguments
+/// 
+/// * `combined_path` - The combined IDL file
+/// * `entry` - The program's key in the combined file
+/// * `split_path` - Where to write the program's IDL
+/// 
+/// # Returns
+/// 
+/// Returns `Ok(())` once the IDL is written, or an error if the combined
+/// file cannot be read or the IDL cannot be written.
+fn split_combined_idl(combined_path: &str, entry: &str, split_path: &str) -> Result<()> {
+    let content = fs::read_to_string(combined_path)
+        .map_err(|e| SolanaPmError::InvalidPath(format!("Failed to read IDL file {}: {}", combined_path, e)))?;
+    let combined = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse {}", combined_path))?;
+    let idl = select_combined_idl(combined, entry, combined_path)?;
+    
+    if let Some(parent) = Path::new(split_path).parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| SolanaPmError::InvalidPath(format!("Failed to create directory {}: {}", parent.display(), e)))?;
+    }
+    fs::write(split_path, serde_json::to_string_pretty(&idl)?)
+        .map_err(|e| SolanaPmError::InvalidPath(format!("Failed to write {}: {}", split_path, e)))?;
+    
+    Ok(())
+}
+
+/// Reads the programs a project declares before its first build.
+/// 
+/// Each `programs/*/Cargo.toml` package is a program (the Anchor layout),
+/// or else the root Cargo.toml package. Names use `_` like IDL metadata
+/// names, and a `version.workspace = true` version comes from the root
+/// `[workspace.package]`. Without any Cargo package, the programs listed in
+/// Anchor.toml are used with the workspace version, if any.
+/// 
+/// The candidates have no program ID; init takes it from Anchor.toml or a
+/// prompt.
+/// 
+/// # Returns
+/// 
+/// Returns the programs found, sorted by directory name, with the manifest
+/// each was read from as its path (empty if there are none).
+pub fn find_manifest_candidates() -> Vec<IdlCandidate> {
+    let read_manifest = |path: &Path| -> Option<toml::Value> {
+        toml::from_str(&fs::read_to_string(path).ok()?).ok()
+    };
+    let root = read_manifest(Path::new(CARGO_TOML));
+    let workspace_version = root.as_ref()
+        .and_then(|root| root.get("workspace")?.get("package")?.get("version")?.as_str())
+        .unwrap_or_default()
+        .to_string();
+    let candidate = |path: &Path, manifest: &toml::Value| -> Option<IdlCandidate> {
+        let package = manifest.get("package")?;
+        let version = match package.get("version") {
+            Some(toml::Value::String(version)) => version.clone(),
+            _ => workspace_version.clone(),
+        };
+        Some(IdlCandidate {
+            path: path.to_string_lossy().to_string(),
+            name: package.get("name")?.as_str()?.replace('-', "_"),
+            version,
+            program_id: None,
+            entry: None,
+        })
+    };
+    
+    let mut manifest_paths: Vec<_> = fs::read_dir(PROGRAMS_DIR)
+        .map(|entries| entries.flatten().map(|entry| entry.path().join(CARGO_TOML)).collect())
+        .unwrap_or_default();
+    manifest_paths.sort();
+    let mut candidates: Vec<IdlCandidate> = manifest_paths.iter()
+        .filter_map(|path| candidate(path, &read_manifest(path)?))
+        .collect();
+    
+    if candidates.is_empty()
+        && let Some(candidate) = root.as_ref().and_then(|root| candidate(Path::new(CARGO_TOML), root))
+    {
+        candidates.push(candidate);
+    }
+    
+    if candidates.is_empty()
+        && let Some(anchor) = read_manifest(Path::new(ANCHOR_TOML))
+    {
+        let names: std::collections::BTreeSet<String> = ANCHOR_CLUSTERS.iter()
+            .filter_map(|cluster| anchor.get("programs")?.get(*cluster)?.as_table())
+            .flat_map(|programs| programs.keys().map(|name| name.replace('-', "_")))
+            .collect();
+        candidates.extend(names.into_iter().map(|name| IdlCandidate {
+            path: ANCHOR_TOML.to_string(),
+            name,
+            version: workspace_version.clone(),
+            program_id: None,
+            entry: None,
+        }));
+    }
+    
+    candidates
+}
+
+/// Searches for IDL files in common Solana project directories.
 /// 
 /// This function looks for `.json` IDL files in the following directories (in order):
 /// - `target/idl` - Standard Anchor build output
 /// - `idl` - Custom IDL directory
 /// - `target/deploy` - Alternative build output location
 /// 
+/// `*-keypair.json` files are skipped, as are JSON files that are not IDLs
+/// (see [`read_idl_candidates`]). Every program of a combined IDL file is
+/// listed, and a program found in several places is listed once, from the
+/// first directory.
+/// 
 /// # Returns
 /// 
-/// Returns the path to the first IDL file found, or an error if no IDL files
-/// are found in any of the searched directories.
+/// Returns the IDLs found, sorted by directory and file name, or an error if
+/// no IDL files are found in any of the searched directories.
 /// 
 /// # Errors
 /// 
-/// Returns `SolanaPmError::InvalidPath` if no IDL file is found or if
-/// directory reading fails.
-fn find_idl_file() -> Result<String> {
+/// Returns `SolanaPmError::InvalidPath` if no JSON file is found or if
+/// directory reading fails, and `SolanaPmError::InvalidIdl` if JSON files
+/// exist but none is an IDL.
+pub fn find_idl_candidates() -> Result<Vec<IdlCandidate>> {
+    let mut candidates: Vec<IdlCandidate> = Vec::new();
+    let mut skipped = Vec::new();
+    
     for idl_dir in IDL_PATHS {
         let dir_path = Path::new(idl_dir);
         if dir_path.exists() && dir_path.is_dir() {
@@ -222,17 +1507,118 @@ fn find_idl_file() -> Result<String> {
             let entries = fs::read_dir(dir_path)
                 .map_err(|e| SolanaPmError::InvalidPath(format!("Failed to read directory {}: {}", idl_dir, e)))?;
             
+            let mut paths = Vec::new();
             for entry in entries {
-                let entry = entry?;
-                let path = entry.path();
-                if path.extension().map_or(false, |ext| ext == "json") {
-                    return Ok(path.to_string_lossy().to_string());
+                let path = entry?.path();
+                if path.extension().is_some_and(|ext| ext == "json") {
+                    paths.push(path);
+                }
+            }
+            paths.sort();
+            
+            for path in paths {
+                // Program keypairs sit next to the IDL in target/deploy
+                if path.to_string_lossy().ends_with(KEYPAIR_SUFFIX) {
+                    continue;
+                }
+                
+                let found = read_idl_candidates(&path);
+                if found.is_empty() {
+                    skipped.push(path.to_string_lossy().to_string());
+                }
+                for candidate in found {
+                    if !candidates.iter().any(|existing| existing.name == candidate.name) {
+                        candidates.push(candidate);
+                    }
                 }
             }
         }
     }
     
-    Err(SolanaPmError::InvalidPath(
-        "No IDL file found. Please build/deploy your program first. Searched paths: target/idl, idl, target/deploy".to_string()
-    ))
-}
\ No newline at end of file
+    if candidates.is_empty() && !skipped.is_empty() {
+        return Err(SolanaPmError::InvalidIdl(format!(
+            "No IDL with instructions and a program name and version in its metadata found. Checked: {}",
+            skipped.join(", ")
+        )));
+    }
+    
+    if candidates.is_empty() {
+        return Err(SolanaPmError::InvalidPath(
+            "No IDL file found. Please build/deploy your program first. Searched paths: target/idl, idl, target/deploy".to_string()
+        ));
+    }
+    
+    Ok(candidates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    /// A combined IDL file holding two programs keyed by name.
+    fn combined_idl() -> serde_json::Value {
+        serde_json::json!({
+            "token_vesting": {
+                "address": "Vest1ngZbdCMbGVTwfM8fBNBfWqBqTBBHwCbUHmBxjr",
+                "metadata": { "name": "token_vesting", "version": "0.1.0" },
+                "instructions": [{ "name": "create_vesting", "accounts": [], "args": [] }]
+            },
+            "staking": {
+                "metadata": { "name": "staking", "version": "0.2.0" },
+                "instructions": []
+            }
+        })
+    }
+    
+    #[test]
+    fn reads_a_candidate_per_program_of_a_combined_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("all_idls.json");
+        fs::write(&path, combined_idl().to_string()).unwrap();
+        
+        let candidates = read_idl_candidates(&path);
+        let summary: Vec<_> = candidates.iter()
+            .map(|candidate| (candidate.name.as_str(), candidate.version.as_str(), candidate.entry.as_deref(), candidate.program_id.as_deref()))
+            .collect();
+        assert_eq!(summary.len(), 2);
+        assert!(summary.contains(&("token_vesting", "0.1.0", Some("token_vesting"), Some("Vest1ngZbdCMbGVTwfM8fBNBfWqBqTBBHwCbUHmBxjr"))));
+        assert!(summary.contains(&("staking", "0.2.0", Some("staking"), None)));
+        assert!(candidates.iter().all(|candidate| candidate.path == path.to_string_lossy()));
+    }
+    
+    #[test]
+    fn selects_one_program_of_a_combined_file() {
+        let vesting = select_combined_idl(combined_idl(), "token-vesting", "all_idls.json").unwrap();
+        assert_eq!(vesting["metadata"]["name"], "token_vesting");
+        assert_eq!(vesting["instructions"][0]["name"], "create_vesting");
+        
+        let staking = select_combined_idl(combined_idl(), "staking", "all_idls.json").unwrap();
+        assert_eq!(staking["metadata"]["version"], "0.2.0");
+        
+        let error = select_combined_idl(combined_idl(), "lending", "all_idls.json").err().unwrap();
+        assert!(matches!(error, SolanaPmError::InvalidIdl(_)), "{:?}", error);
+        assert!(error.to_string().contains("all_idls.json holds the IDLs of"), "{}", error);
+        assert!(error.to_string().contains("but not 'lending'"), "{}", error);
+    }
+    
+    #[test]
+    fn single_idl_files_are_not_combined() {
+        let single = combined_idl()["staking"].clone();
+        assert!(combined_idl_entries(&single).is_none());
+        assert!(combined_idl_entries(&serde_json::json!({})).is_none());
+        assert_eq!(select_combined_idl(single.clone(), "anything", "staking.json").unwrap(), single);
+    }
+    
+    #[test]
+    fn splits_a_program_out_of_a_combined_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let combined_path = dir.path().join("all_idls.json");
+        let split_path = dir.path().join("idl").join("staking.json");
+        fs::write(&combined_path, combined_idl().to_string()).unwrap();
+        
+        split_combined_idl(&combined_path.to_string_lossy(), "staking", &split_path.to_string_lossy()).unwrap();
+        
+        let split: serde_json::Value = serde_json::from_str(&fs::read_to_string(&split_path).unwrap()).unwrap();
+        assert_eq!(split, combined_idl()["staking"]);
+    }
+}
@@ -10,74 +10,184 @@
 //! - Network-specific program resolution
 //! - Optional TypeScript client code generation
 //! - Progress reporting and error handling
-//! - Incremental installation (skips existing dependencies)
+//! - Incremental installation (skips existing dependencies, or revalidates
+//!   them with a conditional request when their `ETag`/`Last-Modified` was saved)
+//! - Resumable downloads for large IDL files
+//! - Detection of entries that share an on-chain program ID
+//! - Warnings for versions the registry marks as yanked or deprecated
+//! - An optional `post_install` hook from `.solpmrc` (requires `--allow-hooks`)
 //!
 //! The installation process downloads IDL files from the registry and saves them
 //! locally for use in development and code generation workflows.
 
-use crate::commands::constants::{GET_PROGRAM_URL, PROGRAM_IDL_DIR, SOLANA_PROGRAMS_FILE};
-use crate::commands::types::{Program, ProgramResponse, SolanaPrograms};
+use crate::commands::constants::SOLANA_PROGRAMS_FILE;
+use crate::commands::registry::{InstallRequest, RegistryApi, RegistryClient};
+use crate::commands::types::{IdlValidators, Program, ProgramResponse, SolanaPrograms};
 use crate::commands::codegen;
-use crate::error::{Result, SolanaPmError};
-use crate::utils::{CliProgress, CliStyle, generate_project_hash};
+use crate::commands::hooks;
+use crate::commands::download::{read_validators, resolve_max_idl_size, version_status_warning, write_idl_file, write_validators};
+use crate::error::{Context, Result, SolanaPmError};
+use crate::outln;
+use crate::statusln;
+use crate::utils::{CliProgress, CliStyle, current_timestamp, generate_project_hash, installed_idl_path, network_idl_path};
+use std::collections::BTreeMap;
 use std::fs;
-use serde_json::json;
+use serde::Serialize;
+
+/// Options for the `install` command.
+#[derive(Default)]
+pub struct InstallOptions {
+    /// Generate TypeScript client code after installing programs
+    pub codegen: bool,
+    /// Fail instead of warning about duplicate program IDs
+    pub strict: bool,
+    /// The `--max-idl-size` limit in bytes, if given
+    pub max_idl_size: Option<u64>,
+    /// Run the `post_install` hook from `.solpmrc`
+    pub allow_hooks: bool,
+    /// Store IDLs of entries without an `idl_path` under
+    /// `./program/idl/{network}/` (also enabled by `per_network_idl` in `.solpmrc`)
+    pub per_network_idl: bool,
+    /// Only install programs with one of these tags; empty installs every program
+    pub tags: Vec<String>,
+    /// API token sent with this invocation's registry requests, never stored
+    pub registry_token: Option<String>,
+    /// Registry base URL to use instead of the active registry and its
+    /// mirrors; the CLI sets the registry with `--registry` instead
+    pub registry: Option<String>,
+}
+
+/// What [`install_dependencies`] did.
+#[derive(Debug, Clone, Serialize)]
+pub struct InstallOutcome {
+    /// Programs whose IDL was downloaded
+    pub installed: Vec<InstalledProgram>,
+    /// Programs that could not be installed
+    pub failed: Vec<FailedProgram>,
+    /// The number of programs selected for installation
+    pub total: usize,
+    /// Whether client generation was requested and failed
+    pub codegen_failed: bool,
+}
+
+/// A program [`install_dependencies`] downloaded.
+#[derive(Debug, Clone, Serialize)]
+pub struct InstalledProgram {
+    /// The program name
+    pub name: String,
+    /// The installed version
+    pub version: String,
+    /// Whether it is a dev dependency
+    pub dev: bool,
+    /// Where the IDL was written
+    pub idl_path: Option<String>,
+}
+
+/// A program [`install_dependencies`] could not download.
+#[derive(Debug, Clone, Serialize)]
+pub struct FailedProgram {
+    /// The program name
+    pub name: String,
+    /// Whether it is a dev dependency
+    pub dev: bool,
+    /// Why the download failed
+    pub error: String,
+}
 
 /// Installs all program dependencies defined in SolanaPrograms.json.
 /// 
 /// This function reads the SolanaPrograms.json configuration file and installs
 /// all program dependencies by:
-/// 1. Checking if IDL files already exist locally (skipping if they do)
-/// 2. Fetching program metadata and IDL files from the registry API
-/// 3. Saving IDL files to the configured paths
-/// 4. Updating the configuration with IDL paths if needed
-/// 5. Optionally generating TypeScript client code if the codegen flag is enabled
+/// 1. Checking that no two entries share the same program ID on a network
+/// 2. Checking if IDL files already exist locally; these are skipped, or
+///    revalidated when the validators of their download were saved
+/// 3. Fetching program metadata and IDL files from the registry API
+/// 4. Saving IDL files to the configured paths
+/// 5. Updating the configuration with IDL paths if needed
+/// 6. Optionally generating TypeScript client code if the codegen flag is enabled
+/// 7. Running the `post_install` hook from `.solpmrc` if every program was
+///    installed (and generated), and `--allow-hooks` was passed
 /// 
 /// The function processes both regular and development dependencies, displaying
 /// progress information and handling errors gracefully by continuing with remaining
-/// dependencies.
+/// dependencies. With `tags`, only programs carrying one of the tags are
+/// installed (and generated with `codegen`).
+/// 
+/// Revalidation sends the saved `ETag` as `If-None-Match` and `Last-Modified`
+/// as `If-Modified-Since`; a `304 Not Modified` answer keeps the IDL on disk
+/// untouched, and any other answer replaces it like a fresh install.
 /// 
 /// # Arguments
 /// 
-/// * `codegen` - Whether to generate TypeScript client code after installing programs
+/// * `options` - The codegen, strictness, download, hook, IDL location, and tag flags
+/// 
+/// With `registry`, programs are downloaded from that registry instead of the
+/// active one, without mirrors.
 /// 
 /// # Returns
 /// 
-/// Returns `Ok(())` on success, or an error if the configuration file is not found,
-/// cannot be parsed, or critical file operations fail.
+/// Returns what was installed, or an error if the configuration file is not found,
+/// cannot be parsed, or critical file operations fail. Programs that fail to
+/// download are listed in the outcome instead.
 /// 
 /// # Errors
 /// 
 /// * `SolanaPmError::ConfigNotFound` - If SolanaPrograms.json doesn't exist
+/// * `SolanaPmError::DataMissing` - If entries share a program ID in strict mode
+/// * `SolanaPmError::HookFailed` - If the `post_install` hook exits unsuccessfully
 /// * File I/O errors during configuration reading/writing
-/// * Network errors when fetching from the registry (continues with other dependencies)
+/// * Network errors or oversized responses when fetching from the registry (continues with other dependencies)
 /// 
 /// # Examples
 /// 
-/// ```rust
+/// ```no_run
+/// use solpm::commands::install::{InstallOptions, install_dependencies};
+/// 
+/// # async fn example() -> solpm::error::Result<()> {
 /// // Install all dependencies from SolanaPrograms.json
-/// install_dependencies(false).await?;
+/// let options = InstallOptions { codegen: false, strict: false, max_idl_size: None, allow_hooks: false, per_network_idl: false, tags: vec![], registry_token: None, registry: None };
+/// install_dependencies(&options).await?;
+/// 
+/// // Install only the programs tagged for the frontend
+/// install_dependencies(&InstallOptions { tags: vec!["frontend".to_string()], ..Default::default() }).await?;
 /// 
 /// // Install dependencies and generate TypeScript client code
-/// install_dependencies(true).await?;
+/// install_dependencies(&InstallOptions { codegen: true, ..Default::default() }).await?;
+/// 
+/// // Install, then run the post_install hook from .solpmrc
+/// install_dependencies(&InstallOptions { allow_hooks: true, ..Default::default() }).await?;
+/// # Ok(())
+/// # }
 /// ```
-pub async fn install_dependencies(codegen: bool) -> Result<()> {
+pub async fn install_dependencies(options: &InstallOptions) -> Result<InstallOutcome> {
+    let max_idl_size = resolve_max_idl_size(options.max_idl_size)?;
+    let per_network_idl = options.per_network_idl || hooks::load_solpmrc()?.per_network_idl;
+    
     // Check if SolanaPrograms.json exists
     if !std::path::Path::new(SOLANA_PROGRAMS_FILE).exists() {
         return Err(SolanaPmError::ConfigNotFound(format!("{} not found. Run 'solpm add <program>' first.", SOLANA_PROGRAMS_FILE)));
     }
     
     // Read SolanaPrograms.json
-    let content = fs::read_to_string(SOLANA_PROGRAMS_FILE)?;
-    let mut solana_programs: SolanaPrograms = serde_json::from_str(&content)?;
+    let content = fs::read_to_string(SOLANA_PROGRAMS_FILE).with_context(|| format!("Failed to read {}", SOLANA_PROGRAMS_FILE))?;
+    let mut solana_programs: SolanaPrograms = serde_json::from_str(&content).with_context(|| format!("Failed to parse {}", SOLANA_PROGRAMS_FILE))?;
+    
+    check_duplicate_program_ids(&solana_programs, options.strict)?;
     
-    let client = reqwest::Client::new();
-    let mut installed_count = 0;
+    let registry = RegistryClient::for_registry(options.registry.as_deref())?.with_optional_token(options.registry_token.as_deref());
+    let mut installed = Vec::new();
+    let mut failed = Vec::new();
     let mut total_count = 0;
     let mut programs_updated = false;
     
-    // Count total programs for progress bar
-    let all_programs_count = solana_programs.programs.len() + solana_programs.dev_programs.len();
+    // Select the programs to install, then count them for the progress bar
+    let regular_programs: Vec<(String, Program)> = solana_programs.programs.clone().into_iter()
+        .filter(|(_, program_info)| program_info.has_any_tag(&options.tags))
+        .collect();
+    let dev_programs: Vec<(String, Program)> = solana_programs.dev_programs.clone().into_iter()
+        .filter(|(_, program_info)| program_info.has_any_tag(&options.tags))
+        .collect();
+    let all_programs_count = regular_programs.len() + dev_programs.len();
     let progress_bar = if all_programs_count > 1 {
         Some(CliProgress::new_progress_bar(all_programs_count as u64, "Installing dependencies"))
     } else {
@@ -85,14 +195,20 @@ pub async fn install_dependencies(codegen: bool) -> Result<()> {
     };
     
     // Process regular programs
-    let regular_programs: Vec<(String, Program)> = solana_programs.programs.clone().into_iter().collect();
     for (package_name, mut program_info) in regular_programs {
         total_count += 1;
-        let default_path = format!("{}/{}.json", PROGRAM_IDL_DIR, package_name);
+        let default_path = if per_network_idl {
+            network_idl_path(&package_name, &program_info.network)
+        } else {
+            installed_idl_path(&package_name, &program_info)
+        };
         let idl_file_path = program_info.idl_path.as_deref().unwrap_or(&default_path);
         
-        // Check if IDL already exists
-        if std::path::Path::new(idl_file_path).exists() {
+        // An installed IDL is revalidated if its validators were saved, and kept otherwise
+        let on_disk = std::path::Path::new(idl_file_path).exists();
+        let validators = read_validators(idl_file_path).filter(|_| on_disk);
+        if on_disk && validators.is_none() {
+            tracing::debug!("{} is already installed at {}; skipping the download", package_name, idl_file_path);
             // Ensure the path is stored in the config
             if program_info.idl_path.is_none() {
                 program_info.idl_path = Some(idl_file_path.to_string());
@@ -102,58 +218,62 @@ pub async fn install_dependencies(codegen: bool) -> Result<()> {
             continue;
         }
         
-        println!("{}", CliStyle::progress(&format!("Installing {} {}...", 
-            CliStyle::package(&package_name), 
-            CliStyle::version(&program_info.version)
-        )));
-        
-        // Install program using backend API with download tracking
-        let project_hash = generate_project_hash();
-        let url = format!("{}/{}/latest/install", GET_PROGRAM_URL, package_name);
-        
-        // Create request body with network and project hash for download tracking
-        let request_body = json!({
-            "network": program_info.network,
-            "project_hash": project_hash
-        });
-        
-        let response = client
-            .post(&url)
-            .json(&request_body)
-            .send()
-            .await?;
-        
-        if !response.status().is_success() {
-            if let Some(ref pb) = progress_bar {
-                CliProgress::finish_with_error(pb.clone(), &format!("Failed to fetch {}", package_name));
-            } else {
-                eprintln!("{}", CliStyle::error(&format!("Failed to fetch {}: {}", package_name, response.status())));
-            }
-            continue;
+        if on_disk {
+            tracing::debug!("Checking whether {} changed since it was installed at {}", package_name, idl_file_path);
+        } else {
+            statusln!("{}", CliStyle::progress(&format!("Installing {} {}...", 
+                CliStyle::package(&package_name), 
+                CliStyle::version(&program_info.version)
+            )));
         }
         
-        let program_response: ProgramResponse = response.json().await?;
+        // Download, resuming any partial download left by an earlier attempt
+        let program_response = match fetch_program(&registry, &package_name, &program_info.network, idl_file_path, max_idl_size, validators.as_ref()).await {
+            Ok(Some(program_response)) => program_response,
+            Ok(None) => {
+                tracing::debug!("{} has not changed; keeping {}", package_name, idl_file_path);
+                if program_info.idl_path.is_none() {
+                    program_info.idl_path = Some(idl_file_path.to_string());
+                    solana_programs.programs.insert(package_name.clone(), program_info);
+                    programs_updated = true;
+                }
+                continue;
+            }
+            Err(e) => {
+                if let Some(ref pb) = progress_bar {
+                    CliProgress::finish_with_error(pb.clone(), &format!("Failed to fetch {}: {}", package_name, e));
+                } else {
+                    eprintln!("{}", CliStyle::error(&format!("Failed to fetch {}: {}", package_name, e)));
+                }
+                failed.push(FailedProgram { name: package_name, dev: false, error: e.to_string() });
+                continue;
+            }
+        };
         
-        // Create directory for IDL file
-        if let Some(parent) = std::path::Path::new(idl_file_path).parent() {
-            std::fs::create_dir_all(parent)?;
+        if let Some(warning) = version_status_warning(&package_name, &program_response) {
+            match progress_bar {
+                Some(ref pb) => pb.suspend(|| statusln!("{}", CliStyle::warning(&warning))),
+                None => statusln!("{}", CliStyle::warning(&warning)),
+            }
         }
         
-        // Save IDL file
-        let idl_content = serde_json::to_string_pretty(&program_response.idl)?;
-        fs::write(idl_file_path, idl_content)?;
+        // Save IDL file and the validators the next install revalidates it with
+        write_idl_file(idl_file_path, &program_response.idl)?;
+        write_validators(idl_file_path, &program_response.validators)?;
         
-        // Update program info with IDL path
+        // Update program info with IDL path and provenance
         program_info.idl_path = Some(idl_file_path.to_string());
+        program_info.installed_at = Some(current_timestamp());
+        program_info.source = Some(registry.base_url().to_string());
         let version = program_info.version.clone();
+        installed.push(InstalledProgram { name: package_name.clone(), version: version.clone(), dev: false, idl_path: program_info.idl_path.clone() });
         solana_programs.programs.insert(package_name.clone(), program_info);
         programs_updated = true;
         
-        installed_count += 1;
         if let Some(ref pb) = progress_bar {
-            pb.inc(1);
+            CliProgress::inc(pb, 1);
         } else {
-            println!("{}", CliStyle::success(&format!(
+            outln!("{}", CliStyle::success(&format!(
                 "{} {} - installed successfully",
                 CliStyle::package(&package_name),
                 CliStyle::version(&version)
@@ -162,14 +282,20 @@ pub async fn install_dependencies(codegen: bool) -> Result<()> {
     }
     
     // Process dev programs
-    let dev_programs: Vec<(String, Program)> = solana_programs.dev_programs.clone().into_iter().collect();
     for (package_name, mut program_info) in dev_programs {
         total_count += 1;
-        let default_path = format!("{}/{}.json", PROGRAM_IDL_DIR, package_name);
+        let default_path = if per_network_idl {
+            network_idl_path(&package_name, &program_info.network)
+        } else {
+            installed_idl_path(&package_name, &program_info)
+        };
         let idl_file_path = program_info.idl_path.as_deref().unwrap_or(&default_path);
         
-        // Check if IDL already exists
-        if std::path::Path::new(idl_file_path).exists() {
+        // An installed IDL is revalidated if its validators were saved, and kept otherwise
+        let on_disk = std::path::Path::new(idl_file_path).exists();
+        let validators = read_validators(idl_file_path).filter(|_| on_disk);
+        if on_disk && validators.is_none() {
+            tracing::debug!("{} is already installed at {}; skipping the download", package_name, idl_file_path);
             // Ensure the path is stored in the config
             if program_info.idl_path.is_none() {
                 program_info.idl_path = Some(idl_file_path.to_string());
@@ -179,58 +305,62 @@ pub async fn install_dependencies(codegen: bool) -> Result<()> {
             continue;
         }
         
-        println!("{}", CliStyle::progress(&format!("Installing {} {}...", 
-            CliStyle::package(&package_name), 
-            CliStyle::version(&program_info.version)
-        )));
-        
-        // Install program using backend API with download tracking
-        let project_hash = generate_project_hash();
-        let url = format!("{}/{}/latest/install", GET_PROGRAM_URL, package_name);
-        
-        // Create request body with network and project hash for download tracking
-        let request_body = json!({
-            "network": program_info.network,
-            "project_hash": project_hash
-        });
-        
-        let response = client
-            .post(&url)
-            .json(&request_body)
-            .send()
-            .await?;
-        
-        if !response.status().is_success() {
-            if let Some(ref pb) = progress_bar {
-                CliProgress::finish_with_error(pb.clone(), &format!("Failed to fetch {}", package_name));
-            } else {
-                eprintln!("{}", CliStyle::error(&format!("Failed to fetch {}: {}", package_name, response.status())));
-            }
-            continue;
+        if on_disk {
+            tracing::debug!("Checking whether {} changed since it was installed at {}", package_name, idl_file_path);
+        } else {
+            statusln!("{}", CliStyle::progress(&format!("Installing {} {}...", 
+                CliStyle::package(&package_name), 
+                CliStyle::version(&program_info.version)
+            )));
         }
         
-        let program_response: ProgramResponse = response.json().await?;
+        // Download, resuming any partial download left by an earlier attempt
+        let program_response = match fetch_program(&registry, &package_name, &program_info.network, idl_file_path, max_idl_size, validators.as_ref()).await {
+            Ok(Some(program_response)) => program_response,
+            Ok(None) => {
+                tracing::debug!("{} has not changed; keeping {}", package_name, idl_file_path);
+                if program_info.idl_path.is_none() {
+                    program_info.idl_path = Some(idl_file_path.to_string());
+                    solana_programs.dev_programs.insert(package_name.clone(), program_info);
+                    programs_updated = true;
+                }
+                continue;
+            }
+            Err(e) => {
+                if let Some(ref pb) = progress_bar {
+                    CliProgress::finish_with_error(pb.clone(), &format!("Failed to fetch {}: {}", package_name, e));
+                } else {
+                    eprintln!("{}", CliStyle::error(&format!("Failed to fetch {}: {}", package_name, e)));
+                }
+                failed.push(FailedProgram { name: package_name, dev: true, error: e.to_string() });
+                continue;
+            }
+        };
         
-        // Create directory for IDL file
-        if let Some(parent) = std::path::Path::new(idl_file_path).parent() {
-            std::fs::create_dir_all(parent)?;
+        if let Some(warning) = version_status_warning(&package_name, &program_response) {
+            match progress_bar {
+                Some(ref pb) => pb.suspend(|| statusln!("{}", CliStyle::warning(&warning))),
+                None => statusln!("{}", CliStyle::warning(&warning)),
+            }
         }
         
-        // Save IDL file
-        let idl_content = serde_json::to_string_pretty(&program_response.idl)?;
-        fs::write(idl_file_path, idl_content)?;
+        // Save IDL file and the validators the next install revalidates it with
+        write_idl_file(idl_file_path, &program_response.idl)?;
+        write_validators(idl_file_path, &program_response.validators)?;
         
-        // Update program info with IDL path
+        // Update program info with IDL path and provenance
         program_info.idl_path = Some(idl_file_path.to_string());
+        program_info.installed_at = Some(current_timestamp());
+        program_info.source = Some(registry.base_url().to_string());
         let version = program_info.version.clone();
+        installed.push(InstalledProgram { name: package_name.clone(), version: version.clone(), dev: true, idl_path: program_info.idl_path.clone() });
         solana_programs.dev_programs.insert(package_name.clone(), program_info);
         programs_updated = true;
         
-        installed_count += 1;
         if let Some(ref pb) = progress_bar {
-            pb.inc(1);
+            CliProgress::inc(pb, 1);
         } else {
-            println!("{}", CliStyle::success(&format!(
+            outln!("{}", CliStyle::success(&format!(
                 "{} {} - installed successfully",
                 CliStyle::package(&package_name),
                 CliStyle::version(&version)
@@ -241,48 +371,246 @@ pub async fn install_dependencies(codegen: bool) -> Result<()> {
     // Write back updated SolanaPrograms.json if any programs were updated
     if programs_updated {
         let json = serde_json::to_string_pretty(&solana_programs)?;
-        fs::write(SOLANA_PROGRAMS_FILE, json)?;
+        fs::write(SOLANA_PROGRAMS_FILE, json).with_context(|| format!("Failed to write {}", SOLANA_PROGRAMS_FILE))?;
+        tracing::debug!("Wrote {}", SOLANA_PROGRAMS_FILE);
     }
     
     // Finish progress bar and print summary
     if let Some(pb) = progress_bar {
-        if installed_count > 0 {
+        if !installed.is_empty() {
             CliProgress::finish_with_message(pb, &format!(
                 "Installed {} program{}", 
-                installed_count, 
-                if installed_count == 1 { "" } else { "s" }
+                installed.len(), 
+                if installed.len() == 1 { "" } else { "s" }
             ));
         } else {
             CliProgress::finish_with_message(pb, "All programs up to date");
         }
     } else {
-        if total_count == 0 {
-            println!("{}", CliStyle::warning(&format!("No programs found in {}", SOLANA_PROGRAMS_FILE)));
-        } else if installed_count == 0 {
-            println!("{}", CliStyle::info(&format!(
+        if total_count == 0 && !options.tags.is_empty() {
+            statusln!("{}", CliStyle::warning(&format!(
+                "No programs tagged {} in {}",
+                options.tags.join(" or "),
+                SOLANA_PROGRAMS_FILE
+            )));
+        } else if total_count == 0 {
+            statusln!("{}", CliStyle::warning(&format!("No programs found in {}", SOLANA_PROGRAMS_FILE)));
+        } else if installed.is_empty() {
+            statusln!("{}", CliStyle::info(&format!(
                 "Up to date, {} program{} installed", 
                 total_count, 
                 if total_count == 1 { "" } else { "s" }
             )));
         } else {
-            println!("{}", CliStyle::success(&format!(
+            outln!("{}", CliStyle::success(&format!(
                 "Added {} program{}, {} program{} total", 
-                installed_count, if installed_count == 1 { "" } else { "s" },
+                installed.len(), if installed.len() == 1 { "" } else { "s" },
                 total_count, if total_count == 1 { "" } else { "s" }
             )));
         }
     }
     
     // Generate TypeScript client code if requested
-    if codegen {
-        println!("\n{}", CliStyle::info("Generating TypeScript client code..."));
-        if let Err(e) = codegen::generate_typescript_client() {
-            println!("{}", CliStyle::warning(&format!(
+    let mut codegen_failed = false;
+    if options.codegen {
+        statusln!("\n{}", CliStyle::info("Generating TypeScript client code..."));
+        let codegen_options = codegen::CodegenOptions { tags: options.tags.clone(), ..Default::default() };
+        if let Err(e) = codegen::generate_typescript_client(&codegen_options) {
+            codegen_failed = true;
+            statusln!("{}", CliStyle::warning(&format!(
                 "Failed to generate TypeScript client: {}",
                 e
             )));
         }
     }
     
+    let outcome = InstallOutcome { installed, failed, total: total_count, codegen_failed };
+    
+    // The hook may depend on every IDL and client being in place
+    if !outcome.failed.is_empty() || codegen_failed {
+        if hooks::load_solpmrc()?.post_install.is_some() {
+            statusln!("{}", CliStyle::warning("Skipping post_install hook because the install did not complete"));
+        }
+        return Ok(outcome);
+    }
+    
+    hooks::run_post_install_hook(options.allow_hooks)?;
+    Ok(outcome)
+}
+
+/// Downloads a program's install response for [`install_dependencies`].
+/// 
+/// The download is tracked by the project hash and streamed into the IDL's
+/// temporary file, creating its directory first.
+/// 
+/// # Arguments
+/// 
+/// * `registry` - The registry to download from
+/// * `package_name` - The program name
+/// * `network` - The network to download from
+/// * `idl_file_path` - Where the IDL goes
+/// * `max_idl_size` - The maximum response size in bytes
+/// * `validators` - The validators of the installed IDL, if it is revalidated
+/// 
+/// # Returns
+/// 
+/// Returns the install response, `None` if the installed IDL has not changed,
+/// or an error if the directory cannot be created or the download fails.
+async fn fetch_program(
+    registry: &impl RegistryApi,
+    package_name: &str,
+    network: &str,
+    idl_file_path: &str,
+    max_idl_size: u64,
+    validators: Option<&IdlValidators>,
+) -> Result<Option<ProgramResponse>> {
+    let project_hash = generate_project_hash();
+    
+    // Create directory for IDL file
+    if let Some(parent) = std::path::Path::new(idl_file_path).parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory {}", parent.display()))?;
+    }
+    
+    registry.install_program(&InstallRequest {
+        name: package_name,
+        version: None,
+        network,
+        project_hash: &project_hash,
+        idl_file_path,
+        max_idl_size,
+        progress: None,
+        validators,
+    }).await
+}
+
+/// Checks SolanaPrograms.json for entries that resolve to the same program.
+/// 
+/// Two differently named dependencies pointing at the same `program_id` on
+/// the same network are usually a copy-paste mistake and lead to confusing
+/// client behavior. Regular and dev dependencies are checked together.
+/// 
+/// # Arguments
+/// 
+/// * `solana_programs` - The parsed SolanaPrograms.json
+/// * `strict` - Whether to return an error instead of printing a warning
+/// 
+/// # Returns
+/// 
+/// Returns `Ok(())` if there are no duplicates or they were only reported,
+/// or an error listing the duplicates in strict mode.
+pub fn check_duplicate_program_ids(solana_programs: &SolanaPrograms, strict: bool) -> Result<()> {
+    let mut names_by_program: BTreeMap<(&str, &str), Vec<&str>> = BTreeMap::new();
+    for (package_name, program_info) in solana_programs.programs.iter().chain(solana_programs.dev_programs.iter()) {
+        let names = names_by_program
+            .entry((program_info.program_id.as_str(), program_info.network.as_str()))
+            .or_default();
+        if !names.contains(&package_name.as_str()) {
+            names.push(package_name);
+        }
+    }
+    
+    let duplicates: Vec<String> = names_by_program
+        .into_iter()
+        .filter(|(_, names)| names.len() > 1)
+        .map(|((program_id, network), mut names)| {
+            names.sort_unstable();
+            format!("{} on {} is used by {}", program_id, network, names.join(", "))
+        })
+        .collect();
+    
+    if duplicates.is_empty() {
+        return Ok(());
+    }
+    
+    let message = format!(
+        "Multiple entries in {} share a program ID:\n  {}",
+        SOLANA_PROGRAMS_FILE,
+        duplicates.join("\n  ")
+    );
+    
+    if strict {
+        return Err(SolanaPmError::DataMissing(message));
+    }
+    
+    statusln!("{}", CliStyle::warning(&message));
     Ok(())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::download::temp_path_for;
+    use crate::commands::mock_server::{MockResponse, serve};
+    
+    const INSTALL_RESPONSE: &str = r#"{
+        "version": "1.0.0",
+        "program_id": "Vest1ngZbdCMbGVTwfM8fBNBfWqBqTBBHwCbUHmBxjr",
+        "idl": { "metadata": { "name": "vesting", "version": "1.0.0" }, "instructions": [] }
+    }"#;
+    
+    const MAX_IDL_SIZE: u64 = 1024 * 1024;
+    
+    #[tokio::test]
+    async fn fetch_program_downloads_the_latest_version() {
+        let (base_url, requests) = serve(|_| MockResponse::json("200 OK", INSTALL_RESPONSE)).await;
+        let registry = RegistryClient::with_base_url(reqwest::Client::new(), base_url);
+        let dir = tempfile::tempdir().unwrap();
+        let idl_file_path = dir.path().join("idls/vesting.json").to_string_lossy().to_string();
+        
+        let program_response = fetch_program(&registry, "vesting", "devnet", &idl_file_path, MAX_IDL_SIZE, None).await.unwrap().unwrap();
+        assert_eq!(program_response.version, "1.0.0");
+        assert_eq!(program_response.idl["metadata"]["name"], "vesting");
+        assert!(program_response.validators.is_empty());
+        
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].path, "/programs/vesting/latest/install");
+        let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+        assert_eq!(body["network"], "devnet");
+        assert!(body["project_hash"].is_string());
+    }
+    
+    #[tokio::test]
+    async fn fetch_program_reports_a_missing_program() {
+        let (base_url, _) = serve(|_| MockResponse::json("404 Not Found", r#"{"error": "not found"}"#)).await;
+        let registry = RegistryClient::with_base_url(reqwest::Client::new(), base_url);
+        let dir = tempfile::tempdir().unwrap();
+        let idl_file_path = dir.path().join("vesting.json").to_string_lossy().to_string();
+        
+        let Err(error) = fetch_program(&registry, "vesting", "devnet", &idl_file_path, MAX_IDL_SIZE, None).await else {
+            panic!("the download succeeded");
+        };
+        assert!(matches!(error, SolanaPmError::ProgramNotFound(ref name) if name == "vesting"), "{}", error);
+    }
+    
+    #[tokio::test]
+    async fn fetch_program_stops_a_streamed_response_at_the_limit() {
+        let (base_url, _) = serve(|_| MockResponse {
+            content_length: false,
+            ..MockResponse::json("200 OK", INSTALL_RESPONSE)
+        }).await;
+        let registry = RegistryClient::with_base_url(reqwest::Client::new(), base_url);
+        let dir = tempfile::tempdir().unwrap();
+        let idl_file_path = dir.path().join("vesting.json").to_string_lossy().to_string();
+        
+        let Err(error) = fetch_program(&registry, "vesting", "devnet", &idl_file_path, 64, None).await else {
+            panic!("the download succeeded");
+        };
+        assert!(matches!(error, SolanaPmError::InvalidIdl(_)), "{}", error);
+        assert!(error.to_string().contains("exceeds the maximum IDL size of 64 bytes"), "{}", error);
+        assert!(!std::path::Path::new(&temp_path_for(&idl_file_path)).exists());
+    }
+    
+    #[tokio::test]
+    async fn fetch_program_keeps_an_unchanged_idl() {
+        let (base_url, _) = serve(|_| MockResponse::json("304 Not Modified", "")).await;
+        let registry = RegistryClient::with_base_url(reqwest::Client::new(), base_url);
+        let dir = tempfile::tempdir().unwrap();
+        let idl_file_path = dir.path().join("vesting.json").to_string_lossy().to_string();
+        let validators = IdlValidators { etag: Some("\"v1\"".to_string()), last_modified: None };
+        
+        let program_response = fetch_program(&registry, "vesting", "devnet", &idl_file_path, MAX_IDL_SIZE, Some(&validators)).await.unwrap();
+        assert!(program_response.is_none());
+    }
+}
@@ -0,0 +1,209 @@
+//! # Ledger Signing Module
+//!
+//! This module signs publish challenges with a key held on a Ledger hardware
+//! wallet instead of a local keypair.
+//!
+//! Features:
+//! - `ledger://` and `usb://ledger` authority URIs
+//! - `?key=<account>/<change>` and `?full-path=<path>` derivation path selection
+//! - Selecting a specific device by its wallet pubkey (`ledger://<pubkey>`)
+//! - Off-chain message signing supported by the Ledger Solana app
+//!
+//! Ledger support needs the system HID libraries (libudev on Linux) and is
+//! only compiled with the `ledger` cargo feature:
+//!
+//! ```text
+//! cargo install solpm --features ledger
+//! ```
+//!
+//! Without the feature, selecting a Ledger authority fails with an error
+//! explaining how to enable it.
+
+use crate::error::{Result, SolanaPmError};
+use solana_sdk::derivation_path::DerivationPath;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+
+/// The URI used by `--sign-with-ledger` (first connected device, default derivation path).
+pub const DEFAULT_LEDGER_URI: &str = "ledger://";
+
+/// Returns whether an authority path refers to a Ledger device.
+/// 
+/// # Arguments
+/// 
+/// * `path` - The authority keypair path or URI
+/// 
+/// # Returns
+/// 
+/// Returns true for `ledger://...` and `usb://ledger...` URIs.
+pub fn is_ledger_uri(path: &str) -> bool {
+    path.starts_with("ledger://") || path.starts_with("usb://ledger")
+}
+
+/// Converts a Ledger authority URI into a Solana remote wallet locator.
+/// 
+/// # Arguments
+/// 
+/// * `uri` - A `ledger://[<pubkey>][?query]` or `usb://ledger[/<pubkey>][?query]` URI
+/// 
+/// # Returns
+/// 
+/// Returns the `usb://ledger[/<pubkey>]` locator and the query string (without `?`).
+fn split_ledger_uri(uri: &str) -> (String, &str) {
+    let (base, query) = uri.split_once('?').unwrap_or((uri, ""));
+    let wallet = base
+        .strip_prefix("ledger://")
+        .or_else(|| base.strip_prefix("usb://ledger"))
+        .unwrap_or("")
+        .trim_matches('/');
+    
+    let locator = if wallet.is_empty() {
+        "usb://ledger".to_string()
+    } else {
+        format!("usb://ledger/{}", wallet)
+    };
+    
+    (locator, query)
+}
+
+/// Parses the derivation path query of a Ledger URI.
+/// 
+/// # Arguments
+/// 
+/// * `query` - The URI query, e.g. `key=0/0` or `full-path=m/44/501/0/0`
+/// 
+/// # Returns
+/// 
+/// Returns the derivation path (`m/44'/501'` when the query is empty), or an
+/// error if the query is not supported.
+fn parse_derivation_query(query: &str) -> Result<DerivationPath> {
+    if query.is_empty() {
+        Ok(DerivationPath::default())
+    } else if let Some(key) = query.strip_prefix("key=") {
+        DerivationPath::from_key_str(key)
+            .map_err(|e| SolanaPmError::InvalidPath(format!("Invalid derivation path '{}': {}", key, e)))
+    } else if let Some(full_path) = query.strip_prefix("full-path=") {
+        DerivationPath::from_absolute_path_str(full_path)
+            .map_err(|e| SolanaPmError::InvalidPath(format!("Invalid derivation path '{}': {}", full_path, e)))
+    } else {
+        Err(SolanaPmError::InvalidPath(format!(
+            "Unsupported Ledger query '{}'. Use ledger://?key=<account>/<change> or ledger://?full-path=<path>", query
+        )))
+    }
+}
+
+/// A publish authority whose key lives on a connected Ledger device.
+pub struct LedgerSigner {
+    #[cfg(feature = "ledger")]
+    keypair: solana_remote_wallet::remote_keypair::RemoteKeypair,
+    #[cfg(not(feature = "ledger"))]
+    never: std::convert::Infallible,
+}
+
+impl LedgerSigner {
+    /// Connects to the Ledger device selected by a URI.
+    /// 
+    /// The device must be unlocked with the Solana app open. The public key
+    /// is read without an on-device confirmation.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `uri` - A `ledger://` or `usb://ledger` URI
+    /// 
+    /// # Returns
+    /// 
+    /// Returns the connected signer, or an error if no matching device is
+    /// found, the URI is invalid, or Ledger support is not compiled in.
+    /// 
+    /// # Examples
+    /// 
+    /// ```no_run
+    /// use solpm::commands::ledger::LedgerSigner;
+    /// 
+    /// # fn example() -> solpm::error::Result<()> {
+    /// // First connected Ledger, default derivation path
+    /// let signer = LedgerSigner::connect("ledger://")?;
+    /// 
+    /// // Account 1 on the first connected Ledger
+    /// let signer = LedgerSigner::connect("ledger://?key=1")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "ledger")]
+    pub fn connect(uri: &str) -> Result<Self> {
+        use solana_remote_wallet::locator::Locator;
+        use solana_remote_wallet::remote_keypair::generate_remote_keypair;
+        use solana_remote_wallet::remote_wallet::maybe_wallet_manager;
+        
+        let (locator, query) = split_ledger_uri(uri);
+        let derivation_path = parse_derivation_query(query)?;
+        let locator = Locator::new_from_path(&locator)
+            .map_err(|e| SolanaPmError::InvalidPath(format!("Invalid Ledger URI '{}': {}", uri, e)))?;
+        
+        let wallet_manager = maybe_wallet_manager()
+            .map_err(|e| SolanaPmError::DataMissing(format!("Failed to access USB devices: {}", e)))?
+            .ok_or_else(|| SolanaPmError::DataMissing(
+                "No Ledger device found. Connect and unlock your Ledger and open the Solana app.".to_string()
+            ))?;
+        
+        let keypair = generate_remote_keypair(locator, derivation_path, &wallet_manager, false, "authority")
+            .map_err(|e| SolanaPmError::DataMissing(format!("Failed to read the authority key from the Ledger: {}", e)))?;
+        
+        Ok(Self { keypair })
+    }
+    
+    #[cfg(not(feature = "ledger"))]
+    pub fn connect(uri: &str) -> Result<Self> {
+        // Validate the URI so typos are reported before the missing feature
+        let (_, query) = split_ledger_uri(uri);
+        parse_derivation_query(query)?;
+        
+        Err(SolanaPmError::DataMissing(
+            "This build of solpm does not include Ledger support. Reinstall with `cargo install solpm --features ledger`.".to_string()
+        ))
+    }
+    
+    /// Returns the public key of the selected Ledger account.
+    #[cfg(feature = "ledger")]
+    pub fn pubkey(&self) -> Pubkey {
+        self.keypair.pubkey
+    }
+    
+    #[cfg(not(feature = "ledger"))]
+    pub fn pubkey(&self) -> Pubkey {
+        match self.never {}
+    }
+    
+    /// Signs a message as a Solana off-chain message on the device.
+    /// 
+    /// The Ledger Solana app only signs transactions and off-chain messages,
+    /// so the message is wrapped in a version 0 off-chain message (prefixed
+    /// with `\xffsolana offchain`) and the signature covers the serialized
+    /// wrapper. The user must approve the request on the device.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `message` - The message to sign
+    /// 
+    /// # Returns
+    /// 
+    /// Returns the signature, or an error if the message is too long or the
+    /// user rejects the request on the device.
+    #[cfg(feature = "ledger")]
+    pub fn sign_offchain_message(&self, message: &[u8]) -> Result<Signature> {
+        use solana_sdk::offchain_message::OffchainMessage;
+        use solana_sdk::signature::Signer;
+        
+        let serialized = OffchainMessage::new(0, message)
+            .and_then(|offchain_message| offchain_message.serialize())
+            .map_err(|e| SolanaPmError::DataMissing(format!("Challenge cannot be signed by a Ledger: {}", e)))?;
+        
+        self.keypair.try_sign_message(&serialized)
+            .map_err(|e| SolanaPmError::DataMissing(format!("Ledger signing failed: {}", e)))
+    }
+    
+    #[cfg(not(feature = "ledger"))]
+    pub fn sign_offchain_message(&self, _message: &[u8]) -> Result<Signature> {
+        match self.never {}
+    }
+}
@@ -0,0 +1,204 @@
+//! # License Module
+//!
+//! This module handles SPDX license identifiers for published programs.
+//!
+//! Features:
+//! - Validation of SPDX license expressions against known identifiers
+//! - License detection from LICENSE files by matching common templates
+//! - License detection from the `license` field of Cargo.toml manifests
+//!
+//! Only the most commonly used SPDX identifiers are recognized; unknown
+//! identifiers are reported so callers can warn rather than fail.
+
+use std::fs;
+use std::path::Path;
+
+/// Commonly used SPDX license identifiers.
+const SPDX_LICENSE_IDS: &[&str] = &[
+    "0BSD", "AGPL-3.0", "AGPL-3.0-only", "AGPL-3.0-or-later", "Apache-2.0",
+    "BSD-2-Clause", "BSD-3-Clause", "BSL-1.0", "BUSL-1.1", "CC0-1.0",
+    "CC-BY-4.0", "CC-BY-SA-4.0", "EPL-2.0", "GPL-2.0", "GPL-2.0-only",
+    "GPL-2.0-or-later", "GPL-3.0", "GPL-3.0-only", "GPL-3.0-or-later", "ISC",
+    "LGPL-2.1", "LGPL-2.1-only", "LGPL-2.1-or-later", "LGPL-3.0", "LGPL-3.0-only",
+    "LGPL-3.0-or-later", "MIT", "MIT-0", "MPL-2.0", "Unlicense", "WTFPL", "Zlib",
+];
+
+/// Operators and exception keywords allowed in SPDX expressions.
+const SPDX_OPERATORS: &[&str] = &["AND", "OR", "WITH"];
+
+/// File names checked for license text, in priority order.
+const LICENSE_FILES: &[&str] = &["LICENSE", "LICENSE.md", "LICENSE.txt", "LICENSE-MIT", "LICENSE-APACHE", "COPYING"];
+
+/// Returns the identifiers in an SPDX expression that are not recognized.
+/// 
+/// The expression is split on whitespace and parentheses; operators and the
+/// exception following `WITH` are skipped, and a trailing `+` is ignored.
+/// 
+/// # Arguments
+/// 
+/// * `expression` - The SPDX license expression (e.g. `MIT OR Apache-2.0`)
+/// 
+/// # Returns
+/// 
+/// Returns the list of unrecognized identifiers, empty if all are known.
+pub fn unknown_spdx_identifiers(expression: &str) -> Vec<String> {
+    let mut unknown = Vec::new();
+    let mut skip_next = false;
+    
+    for token in expression.split(|c: char| c.is_whitespace() || c == '(' || c == ')') {
+        if token.is_empty() {
+            continue;
+        }
+        if skip_next {
+            skip_next = false;
+            continue;
+        }
+        if SPDX_OPERATORS.contains(&token) {
+            skip_next = token == "WITH";
+            continue;
+        }
+        
+        let id = token.trim_end_matches('+');
+        if !SPDX_LICENSE_IDS.iter().any(|known| known.eq_ignore_ascii_case(id)) {
+            unknown.push(token.to_string());
+        }
+    }
+    
+    unknown
+}
+
+/// Detects the project's license from Cargo.toml or a LICENSE file.
+/// 
+/// Checks, in order:
+/// 1. The `license` field of `Cargo.toml` (package or workspace package)
+/// 2. The `license` field of each `programs/*/Cargo.toml`
+/// 3. The text of common LICENSE files matched against known templates
+/// 
+/// # Returns
+/// 
+/// Returns `Some(identifier)` if a license was detected, `None` otherwise.
+pub fn detect_license() -> Option<String> {
+    if let Some(license) = read_cargo_license(Path::new("Cargo.toml")) {
+        return Some(license);
+    }
+    
+    if let Ok(entries) = fs::read_dir("programs") {
+        for entry in entries.flatten() {
+            if let Some(license) = read_cargo_license(&entry.path().join("Cargo.toml")) {
+                return Some(license);
+            }
+        }
+    }
+    
+    LICENSE_FILES.iter()
+        .filter_map(|file| fs::read_to_string(file).ok())
+        .find_map(|text| match_license_text(&text))
+}
+
+/// Detects the license of one program in a workspace.
+/// 
+/// Prefers the `license` field of the `programs/*/Cargo.toml` whose package
+/// is the program (treating `-` and `_` as equal), then falls back to
+/// [`detect_license`].
+/// 
+/// # Arguments
+/// 
+/// * `program_name` - The program name, as in the IDL metadata
+/// 
+/// # Returns
+/// 
+/// Returns `Some(identifier)` if a license was detected, `None` otherwise.
+pub fn detect_program_license(program_name: &str) -> Option<String> {
+    let program_name = program_name.replace('-', "_");
+    let own_license = fs::read_dir("programs").ok().and_then(|entries| {
+        entries.flatten()
+            .map(|entry| entry.path().join("Cargo.toml"))
+            .filter(|path| read_cargo_package_name(path).is_some_and(|name| name.replace('-', "_") == program_name))
+            .find_map(|path| read_cargo_license(&path))
+    });
+    
+    own_license.or_else(detect_license)
+}
+
+/// Reads the `[package] name` from a Cargo.toml manifest.
+fn read_cargo_package_name(path: &Path) -> Option<String> {
+    let manifest: toml::Value = toml::from_str(&fs::read_to_string(path).ok()?).ok()?;
+    manifest.get("package")?.get("name")?.as_str().map(|name| name.to_string())
+}
+
+/// Checks a license entered at a prompt.
+/// 
+/// An empty value is accepted (publish warns about it later); otherwise every
+/// identifier in the expression must be a known SPDX identifier.
+pub fn check_license(license: &str) -> std::result::Result<String, String> {
+    let license = license.trim();
+    let unknown = unknown_spdx_identifiers(license);
+    if unknown.is_empty() {
+        Ok(license.to_string())
+    } else {
+        Err(format!(
+            "unrecognized SPDX identifier{} {} (e.g. MIT, Apache-2.0, GPL-3.0-only, or 'MIT OR Apache-2.0')",
+            if unknown.len() == 1 { "" } else { "s" },
+            unknown.join(", ")
+        ))
+    }
+}
+
+/// Reads the `license` field from a Cargo.toml manifest.
+/// 
+/// # Arguments
+/// 
+/// * `path` - The path to the Cargo.toml file
+/// 
+/// # Returns
+/// 
+/// Returns `Some(license)` if the manifest declares a license, `None` otherwise.
+fn read_cargo_license(path: &Path) -> Option<String> {
+    let content = fs::read_to_string(path).ok()?;
+    let manifest: toml::Value = toml::from_str(&content).ok()?;
+    
+    manifest.get("package")
+        .and_then(|package| package.get("license"))
+        .or_else(|| manifest.get("workspace")
+            .and_then(|workspace| workspace.get("package"))
+            .and_then(|package| package.get("license")))
+        .and_then(|license| license.as_str())
+        .map(|license| license.to_string())
+}
+
+/// Matches license file text against common license templates.
+/// 
+/// # Arguments
+/// 
+/// * `text` - The contents of a license file
+/// 
+/// # Returns
+/// 
+/// Returns the SPDX identifier of the matched license, or `None` if unknown.
+fn match_license_text(text: &str) -> Option<String> {
+    let text = text.to_lowercase();
+    
+    let id = if text.contains("gnu affero general public license") {
+        "AGPL-3.0"
+    } else if text.contains("gnu lesser general public license") {
+        if text.contains("version 2.1") { "LGPL-2.1" } else { "LGPL-3.0" }
+    } else if text.contains("gnu general public license") {
+        if text.contains("version 2") && !text.contains("version 3") { "GPL-2.0" } else { "GPL-3.0" }
+    } else if text.contains("apache license") && text.contains("version 2.0") {
+        "Apache-2.0"
+    } else if text.contains("mozilla public license") && text.contains("2.0") {
+        "MPL-2.0"
+    } else if text.contains("permission is hereby granted, free of charge") {
+        "MIT"
+    } else if text.contains("this is free and unencumbered software released into the public domain") {
+        "Unlicense"
+    } else if text.contains("permission to use, copy, modify, and/or distribute this software") {
+        "ISC"
+    } else if text.contains("redistribution and use in source and binary forms") {
+        if text.contains("neither the name") { "BSD-3-Clause" } else { "BSD-2-Clause" }
+    } else {
+        return None;
+    };
+    
+    Some(id.to_string())
+}
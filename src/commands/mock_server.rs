@@ -0,0 +1,160 @@
+//! # Mock Server Module
+//!
+//! This module serves canned HTTP responses on a local port for tests that
+//! exercise registry requests end to end.
+//!
+//! Features:
+//! - One responder function decides each response from the request
+//! - Every request is recorded with its method, path, headers, and body
+//! - Responses can leave out `Content-Length` to exercise streamed bodies
+//!
+//! Each connection carries one request and is closed after the response.
+
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// A request the server received.
+#[derive(Debug, Clone)]
+pub struct MockRequest {
+    /// The request method, e.g. `POST`
+    pub method: String,
+    /// The request path, with the query string
+    pub path: String,
+    /// The headers, with lowercase names
+    pub headers: Vec<(String, String)>,
+    /// The request body
+    pub body: Vec<u8>,
+}
+
+impl MockRequest {
+    /// Returns the value of a header, by lowercase name.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter()
+            .find(|(header, _)| header == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// A response the server sends.
+pub struct MockResponse {
+    /// The status line after `HTTP/1.1`, e.g. `200 OK`
+    pub status: &'static str,
+    /// Extra headers to send
+    pub headers: Vec<(&'static str, String)>,
+    /// The response body
+    pub body: Vec<u8>,
+    /// Whether to send a `Content-Length`; without one the body ends when
+    /// the connection closes
+    pub content_length: bool,
+}
+
+impl MockResponse {
+    /// Creates a JSON response with a `Content-Length`.
+    pub fn json(status: &'static str, body: impl Into<Vec<u8>>) -> Self {
+        MockResponse { status, headers: Vec::new(), body: body.into(), content_length: true }
+    }
+    
+    /// Adds a header to the response.
+    pub fn with_header(mut self, name: &'static str, value: impl Into<String>) -> Self {
+        self.headers.push((name, value.into()));
+        self
+    }
+}
+
+/// The requests a mock server has received, in order.
+pub type RecordedRequests = Arc<Mutex<Vec<MockRequest>>>;
+
+/// Starts a server that answers every request with `respond`.
+/// 
+/// # Arguments
+/// 
+/// * `respond` - Builds the response to each request
+/// 
+/// # Returns
+/// 
+/// Returns the server's base URL (e.g. `http://127.0.0.1:41234`) and the
+/// requests it records.
+pub async fn serve<F>(respond: F) -> (String, RecordedRequests)
+where
+    F: Fn(&MockRequest) -> MockResponse + Send + Sync + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let requests = RecordedRequests::default();
+    let respond = Arc::new(respond);
+    
+    let recorded = requests.clone();
+    tokio::spawn(async move {
+        while let Ok((socket, _)) = listener.accept().await {
+            let respond = respond.clone();
+            let recorded = recorded.clone();
+            tokio::spawn(async move {
+                let _ = handle(socket, &*respond, &recorded).await;
+            });
+        }
+    });
+    
+    (base_url, requests)
+}
+
+/// Reads one request from a connection and writes its response.
+async fn handle<F>(mut socket: TcpStream, respond: &F, recorded: &RecordedRequests) -> std::io::Result<()>
+where
+    F: Fn(&MockRequest) -> MockResponse,
+{
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 8192];
+    let head_end = loop {
+        let read = socket.read(&mut chunk).await?;
+        if read == 0 {
+            return Ok(());
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+        if let Some(position) = buffer.windows(4).position(|window| window == b"\r\n\r\n") {
+            break position + 4;
+        }
+    };
+    
+    let head = String::from_utf8_lossy(&buffer[..head_end]).to_string();
+    let mut lines = head.lines();
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let method = request_line.next().unwrap_or_default().to_string();
+    let path = request_line.next().unwrap_or_default().to_string();
+    let headers: Vec<(String, String)> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string()))
+        .collect();
+    
+    let body_length = headers.iter()
+        .find(|(name, _)| name == "content-length")
+        .and_then(|(_, value)| value.parse::<usize>().ok())
+        .unwrap_or(0);
+    let mut body = buffer[head_end..].to_vec();
+    while body.len() < body_length {
+        let read = socket.read(&mut chunk).await?;
+        if read == 0 {
+            break;
+        }
+        body.extend_from_slice(&chunk[..read]);
+    }
+    
+    let request = MockRequest { method, path, headers, body };
+    let response = respond(&request);
+    recorded.lock().unwrap().push(request);
+    
+    let mut head = format!("HTTP/1.1 {}\r\nConnection: close\r\n", response.status);
+    if response.content_length {
+        head.push_str(&format!("Content-Length: {}\r\n", response.body.len()));
+    }
+    for (name, value) in &response.headers {
+        head.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    head.push_str("\r\n");
+    socket.write_all(head.as_bytes()).await?;
+    // The client may stop reading an oversized body, so write errors are expected
+    for part in response.body.chunks(64 * 1024) {
+        socket.write_all(part).await?;
+    }
+    socket.shutdown().await
+}
@@ -6,11 +6,34 @@
 //! - `add`: Add program dependencies to a project
 //! - `auth`: Authentication and credential management
 //! - `codegen`: TypeScript client code generation
+//! - `completions`: Shell completion script generation
+//! - `config`: Global `~/.solpm/config.toml` defaults and `config list`
+//! - `config_template`: Commented SolanaPrograms.toml writer
 //! - `constants`: API URLs and configuration constants
+//! - `diff`: Compare an installed IDL with the registry's latest
+//! - `download`: Resumable registry downloads and atomic IDL writes
+//! - `graph`: Dependency graph export
+//! - `history`: Local publish history log
+//! - `hooks`: `.solpmrc` post-install hooks and the shared hook shell
+//! - `http`: Shared HTTP client construction and proxy settings
+//! - `idl_flavor`: IDL flavor detection and Shank IDL adaptation
 //! - `init`: Project initialization and configuration
 //! - `install`: Install program dependencies from existing file
+//! - `ledger`: Publish challenge signing with a Ledger hardware wallet
+//! - `license`: SPDX license validation and detection
+//! - `mock_server`: Local HTTP server for tests of registry requests
 //! - `publish`: Program publishing to the registry
+//! - `release`: Make unlisted or draft versions public
+//! - `registry`: Registry URL resolution and the `RegistryClient` API client
+//! - `relink`: Repair stale IDL paths in SolanaPrograms.json
+//! - `reset`: Remove all dependencies and their downloaded files
+//! - `rpc`: On-chain account reads via Solana JSON-RPC
+//! - `schema`: JSON Schema export for the manifest files
+//! - `self_update`: Replace the solpm binary with the latest release
+//! - `shank_client`: TypeScript instruction builders for Shank IDLs
+//! - `test_project`: Temporary working directories for tests of project commands
 //! - `types`: Shared data structures and types
+//! - `verify`: SolanaPrograms.toml validation and repair
 //!
 //! All commands follow a consistent pattern of input validation, API communication,
 //! file management, and user feedback.
@@ -18,8 +41,33 @@
 pub mod add;
 pub mod auth;
 pub mod codegen;
+pub mod completions;
+pub mod config;
+pub mod config_template;
 pub mod constants;
+pub mod diff;
+pub mod download;
+pub mod graph;
+pub mod history;
+pub mod hooks;
+pub mod http;
+pub mod idl_flavor;
 pub mod init;
 pub mod install;
+pub mod ledger;
+pub mod license;
+#[cfg(test)]
+pub mod mock_server;
 pub mod publish;
-pub mod types;
\ No newline at end of file
+pub mod release;
+pub mod registry;
+pub mod relink;
+pub mod reset;
+pub mod rpc;
+pub mod schema;
+pub mod self_update;
+pub mod shank_client;
+#[cfg(test)]
+pub mod test_project;
+pub mod types;
+pub mod verify;
\ No newline at end of file
@@ -3,26 +3,53 @@
 //! This module implements the `publish` command which uploads Solana programs
 //! to the registry for sharing and distribution.
 //!
-//! Features:
-//! - Secure program publishing with authentication
-//! - IDL file validation and upload
-//! - Digital signature verification for program authenticity  
-//! - Program metadata extraction from configuration files
-//! - Authority keypair validation and signing
-//! - Comprehensive error handling and user feedback
-//! - Support for custom IDL and keypair file paths
+//! The program's metadata comes from SolanaPrograms.toml and the IDL is
+//! validated, classified by flavor, and hashed. The authority keypair (a
+//! file, an environment variable, or a Ledger) signs a challenge binding the
+//! registry, program, version, network, and IDL hash. The upload is streamed
+//! with a size-scaled timeout, read back to verify what the registry stored,
+//! and logged to `~/.solpm/publish-log.jsonl` (see `solpm history`).
 //!
-//! The publishing process ensures program integrity through cryptographic
-//! signatures and validates all required metadata before submission.
-
-use crate::commands::auth::ensure_authenticated;
-use crate::commands::constants::PUBLISH_PROGRAM_URL;
-use crate::commands::types::{UploadProgramRequest, SolanaProgramsConfig};
-use crate::error::{Result, SolanaPmError};
-use crate::utils::{CliProgress, CliStyle};
-use solana_sdk::signature::{Keypair, Signer};
+//! The challenge formats, size limits, timeouts, read-back verification, and
+//! `--json` result are described under "How Publishing Works" in the README.
+
+use crate::cli::{Network, Visibility};
+use crate::commands::auth::{ensure_authenticated, get_env_token};
+use crate::commands::constants::{AUTHORITY_KEY_ENV_VAR, TOKEN_ENV_VAR};
+use crate::commands::config::request_timeout_secs;
+use crate::commands::http::{SendLogged, describe_request_error, http_client, set_project_timeouts};
+use crate::commands::registry::{PublishUpload, PublishUploadResponse, RegistryApi, RegistryClient, registry_base_url, registry_error_from_body};
+use crate::commands::history::append_publish_log;
+use crate::commands::hooks::shell_command;
+use crate::commands::init::{find_idl_candidates, select_combined_idl};
+use crate::commands::idl_flavor::{IdlFlavor, adapt_shank_idl, detect_idl_flavor};
+use crate::commands::ledger::{DEFAULT_LEDGER_URI, LedgerSigner, is_ledger_uri};
+use crate::commands::license::unknown_spdx_identifiers;
+use crate::commands::rpc::{get_deployed_program_bytes, get_program_upgrade_authority, rpc_url_for_network};
+use crate::commands::types::{HooksConfig, ProgramConfig, PublishConfig, PublishLogEntry, PublishReceipt, PublishResponse, RegistryErrorResponse, UploadProgramRequest, SolanaProgramsConfig};
+use crate::commands::verify::{check_authority_keypair, check_program_id};
+use crate::error::{Context, Result, SolanaPmError};
+use crate::outln;
+use crate::statusln;
+use crate::output::{is_json_output, phase, require_interactive, set_result};
+use crate::utils::{CliProgress, CliStyle, GitRevision, canonicalize_idl, confirm_action, current_timestamp, get_git_revision};
+use futures_util::StreamExt;
+use indicatif::{HumanBytes, ProgressBar};
+use rand::RngCore;
+use rand::rngs::OsRng;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use solana_sdk::derivation_path::DerivationPath;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_sdk::signer::keypair::{
+    generate_seed_from_seed_phrase_and_passphrase, keypair_from_seed, keypair_from_seed_and_derivation_path,
+    keypair_from_seed_phrase_and_passphrase,
+};
 use std::fs;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::io::IsTerminal;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use zeroize::Zeroizing;
 
 /// Expands tilde (~) in file paths to the user's home directory.
 /// 
@@ -34,23 +61,31 @@ use std::time::{SystemTime, UNIX_EPOCH};
 /// 
 /// Returns the expanded path as a string, or the original path if
 /// expansion fails or no tilde is present.
-fn expand_path(path: &str) -> String {
-    if path.starts_with('~') {
-        if let Some(home) = dirs::home_dir() {
-            return path.replacen('~', &home.to_string_lossy(), 1);
-        }
+pub fn expand_path(path: &str) -> String {
+    if path.starts_with('~')
+        && let Some(home) = dirs::home_dir()
+    {
+        return path.replacen('~', &home.to_string_lossy(), 1);
     }
     path.to_string()
 }
 
-/// Loads a Solana keypair from a file.
+/// Loads a Solana keypair from a file or an interactive seed phrase.
+/// 
+/// Accepts the following formats:
+/// - JSON byte array with a 64-byte keypair (Solana CLI standard) or a 32-byte seed
+/// - JSON object with a `secretKey` field (byte array or base58 string)
+/// - Base58-encoded 64-byte secret key or 32-byte seed
+/// - Raw 64-byte keypair or 32-byte seed
+/// - `prompt://` to enter a seed phrase and optional BIP39 passphrase, with
+///   `prompt://?key=<account>/<change>` or `prompt://?full-path=<path>` selecting
+///   a derivation path
 /// 
-/// Supports both JSON array format (Solana CLI standard) and raw 64-byte format.
 /// Automatically expands tilde paths to the user's home directory.
 /// 
 /// # Arguments
 /// 
-/// * `path` - The file path to the keypair file (supports ~/ prefix)
+/// * `path` - The file path to the keypair file (supports ~/ prefix) or a `prompt://` URI
 /// 
 /// # Returns
 /// 
@@ -59,50 +94,458 @@ fn expand_path(path: &str) -> String {
 /// 
 /// # Examples
 /// 
-/// ```rust
+/// ```text
 /// // Load from standard Solana CLI location
 /// let keypair = load_keypair_from_file("~/.config/solana/id.json")?;
 /// 
 /// // Load from custom path
 /// let keypair = load_keypair_from_file("./my-keypair.json")?;
+/// 
+/// // Derive from a seed phrase entered at the terminal
+/// let keypair = load_keypair_from_file("prompt://?key=0/0")?;
 /// ```
 fn load_keypair_from_file(path: &str) -> Result<Keypair> {
+    if let Some(query) = path.strip_prefix("prompt://") {
+        return keypair_from_seed_phrase_prompt(query);
+    }
+    
     let expanded_path = expand_path(path);
     let keypair_bytes = fs::read(&expanded_path)
         .map_err(|e| SolanaPmError::InvalidPath(format!("Failed to read keypair file '{}': {}", expanded_path, e)))?;
     
-    // Try to parse as JSON first (Solana CLI format)
-    if let Ok(keypair_json) = serde_json::from_slice::<Vec<u8>>(&keypair_bytes) {
-        if keypair_json.len() == 64 {
-            return Keypair::from_bytes(&keypair_json)
-                .map_err(|e| SolanaPmError::InvalidPath(format!("Invalid keypair format: {}", e)));
+    parse_keypair_bytes(&keypair_bytes).map_err(|e| match e {
+        SolanaPmError::InvalidPath(msg) => SolanaPmError::InvalidPath(format!("{} ({})", msg, expanded_path)),
+        other => other,
+    })
+}
+
+/// Parses keypair material in any of the supported formats.
+/// 
+/// Formats are attempted in order: JSON byte array, JSON object with a
+/// `secretKey` field, base58 string, and raw bytes.
+/// 
+/// # Arguments
+/// 
+/// * `data` - The raw keypair material
+/// 
+/// # Returns
+/// 
+/// Returns the parsed Keypair, or an error naming the formats attempted.
+pub fn parse_keypair_bytes(data: &[u8]) -> Result<Keypair> {
+    // JSON byte array (Solana CLI format)
+    if let Ok(bytes) = serde_json::from_slice::<Vec<u8>>(data) {
+        let bytes = Zeroizing::new(bytes);
+        return keypair_from_secret_bytes(&bytes);
+    }
+    
+    // JSON object with a secretKey field (wallet exports)
+    if let Ok(serde_json::Value::Object(object)) = serde_json::from_slice::<serde_json::Value>(data)
+        && let Some(secret_key) = object.get("secretKey")
+    {
+        return match secret_key {
+            serde_json::Value::String(encoded) => keypair_from_base58(encoded),
+            value => {
+                let bytes: Zeroizing<Vec<u8>> = serde_json::from_value(value.clone())
+                    .map(Zeroizing::new)
+                    .map_err(|e| SolanaPmError::InvalidPath(format!("Invalid 'secretKey' field: {}", e)))?;
+                keypair_from_secret_bytes(&bytes)
+            }
+        };
+    }
+    
+    // Base58-encoded secret key
+    if let Ok(text) = std::str::from_utf8(data)
+        && let Ok(keypair) = keypair_from_base58(text.trim())
+    {
+        return Ok(keypair);
+    }
+    
+    // Raw binary keypair or seed
+    if data.len() == 64 || data.len() == 32 {
+        return keypair_from_secret_bytes(data);
+    }
+    
+    Err(SolanaPmError::InvalidPath(
+        "Unrecognized keypair format. Tried: JSON byte array (64-byte keypair or 32-byte seed), \
+         JSON object with 'secretKey', base58-encoded secret key, raw 64-byte keypair or 32-byte seed".to_string()
+    ))
+}
+
+/// The key that signs the publish challenge.
+pub enum AuthoritySigner {
+    /// A keypair loaded into memory
+    Keypair(Box<Keypair>),
+    /// An account on a connected Ledger device
+    Ledger(LedgerSigner),
+}
+
+impl AuthoritySigner {
+    /// Returns the authority's public key.
+    pub fn pubkey(&self) -> Pubkey {
+        match self {
+            AuthoritySigner::Keypair(keypair) => keypair.pubkey(),
+            AuthoritySigner::Ledger(ledger) => ledger.pubkey(),
+        }
+    }
+    
+    /// Signs the publish challenge.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `challenge` - The challenge string
+    /// 
+    /// # Returns
+    /// 
+    /// Returns the signature and the `signature_format` to report to the
+    /// registry (`None` for a signature over the bare challenge).
+    pub fn sign_challenge(&self, challenge: &str) -> Result<(Signature, Option<String>)> {
+        match self {
+            AuthoritySigner::Keypair(keypair) => Ok((keypair.sign_message(challenge.as_bytes()), None)),
+            AuthoritySigner::Ledger(ledger) => {
+                let signature = ledger.sign_offchain_message(challenge.as_bytes())?;
+                Ok((signature, Some("offchain".to_string())))
+            }
+        }
+    }
+}
+
+/// Loads the authority signer from the highest-priority configured source.
+/// 
+/// Sources are checked in order:
+/// 1. The `--sign-with-ledger` flag
+/// 2. The `--authority-keypair` flag
+/// 3. The environment variable named by `--authority-env`, or `SOLPM_AUTHORITY_KEY`
+/// 4. The `authority_keypair` path in SolanaPrograms.toml
+/// 
+/// Environment values may be a base58-encoded secret key or a JSON byte
+/// array. The value is never printed and its buffer is zeroized once parsed.
+/// An explicitly named variable must be set; the default one is optional.
+/// Paths starting with `ledger://` or `usb://ledger` select a Ledger device.
+/// 
+/// # Arguments
+/// 
+/// * `options` - Publish options containing the keypair flags
+/// * `config_path` - The `authority_keypair` path from SolanaPrograms.toml
+/// 
+/// # Returns
+/// 
+/// Returns the authority signer, or an error if the selected source is
+/// missing, cannot be read, or does not contain a valid key.
+pub fn load_authority_signer(options: &PublishOptions, config_path: &str) -> Result<AuthoritySigner> {
+    let path = match &options.authority_keypair {
+        _ if options.sign_with_ledger => DEFAULT_LEDGER_URI,
+        Some(path) => path.as_str(),
+        None => {
+            let var_name = options.authority_env.as_deref().unwrap_or(AUTHORITY_KEY_ENV_VAR);
+            match std::env::var(var_name) {
+                Ok(value) => {
+                    let value = Zeroizing::new(value);
+                    statusln!("{}", CliStyle::info(&format!(
+                        "Using authority key from {}",
+                        CliStyle::highlight(&format!("${}", var_name))
+                    )));
+                    return parse_keypair_bytes(value.trim().as_bytes())
+                        .map(|keypair| AuthoritySigner::Keypair(Box::new(keypair)))
+                        .map_err(|e| match e {
+                            SolanaPmError::InvalidPath(msg) => SolanaPmError::InvalidPath(format!("{} (from ${})", msg, var_name)),
+                            other => other,
+                        });
+                }
+                Err(_) if options.authority_env.is_some() => {
+                    return Err(SolanaPmError::DataMissing(format!(
+                        "Environment variable {} is not set or is not valid UTF-8", var_name
+                    )));
+                }
+                Err(_) => config_path,
+            }
         }
+    };
+    
+    if is_json_output() && path.starts_with("prompt://") {
+        return Err(SolanaPmError::InputRequired(
+            "--json cannot prompt for a seed phrase. Use a keypair file or the SOLPM_AUTHORITY_KEY environment variable.".to_string()
+        ));
     }
     
-    // Try to parse as raw bytes
-    if keypair_bytes.len() == 64 {
-        return Keypair::from_bytes(&keypair_bytes)
-            .map_err(|e| SolanaPmError::InvalidPath(format!("Invalid keypair format: {}", e)));
+    if is_ledger_uri(path) {
+        return LedgerSigner::connect(path).map(AuthoritySigner::Ledger);
     }
     
-    Err(SolanaPmError::InvalidPath(format!(
-        "Invalid keypair file format. Expected 64-byte keypair in JSON array or raw bytes format."
+    load_keypair_from_file(path).map(|keypair| AuthoritySigner::Keypair(Box::new(keypair)))
+}
+
+/// Checks the authority keypair file before any slow or interactive step.
+/// 
+/// Runs when the signer comes from a file (the `--authority-keypair` flag or
+/// `authority_keypair` in SolanaPrograms.toml) rather than a Ledger or an
+/// environment variable, so a missing or malformed file is reported before
+/// hooks run and before the credentials password is asked for.
+/// 
+/// # Arguments
+/// 
+/// * `program` - The program configuration holding `authority_keypair`
+/// * `options` - Publish options containing the keypair flags
+/// 
+/// # Returns
+/// 
+/// Returns `Ok(())` if the keypair file loads, or an error describing the
+/// problem and how to fix it.
+fn check_authority_keypair_file(program: &ProgramConfig, options: &PublishOptions) -> Result<()> {
+    let env_var = options.authority_env.as_deref().unwrap_or(AUTHORITY_KEY_ENV_VAR);
+    let path = match &options.authority_keypair {
+        _ if options.sign_with_ledger => return Ok(()),
+        Some(path) => path,
+        None if std::env::var_os(env_var).is_some() => return Ok(()),
+        None => &program.authority_keypair,
+    };
+    
+    check_authority_keypair(path).map(|_| ()).map_err(|problem| SolanaPmError::InvalidPath(format!(
+        "Authority keypair: {}. Set authority_keypair in SolanaPrograms.toml (or run 'solpm verify --fix'), or pass --authority-keypair",
+        problem
     )))
 }
 
+/// Builds a keypair from a 64-byte keypair or a 32-byte seed.
+/// 
+/// # Arguments
+/// 
+/// * `bytes` - The secret key bytes
+/// 
+/// # Returns
+/// 
+/// Returns the Keypair, or an error if the length or contents are invalid.
+fn keypair_from_secret_bytes(bytes: &[u8]) -> Result<Keypair> {
+    match bytes.len() {
+        64 => Keypair::from_bytes(bytes)
+            .map_err(|e| SolanaPmError::InvalidPath(format!("Invalid keypair format: {}", e))),
+        32 => keypair_from_seed(bytes)
+            .map_err(|e| SolanaPmError::InvalidPath(format!("Invalid keypair seed: {}", e))),
+        len => Err(SolanaPmError::InvalidPath(format!(
+            "Invalid keypair length: expected 64-byte keypair or 32-byte seed, found {} bytes", len
+        ))),
+    }
+}
+
+/// Builds a keypair from a base58-encoded 64-byte keypair or 32-byte seed.
+/// 
+/// # Arguments
+/// 
+/// * `encoded` - The base58 string
+/// 
+/// # Returns
+/// 
+/// Returns the Keypair, or an error if decoding fails or the length is invalid.
+fn keypair_from_base58(encoded: &str) -> Result<Keypair> {
+    let bytes = bs58::decode(encoded)
+        .into_vec()
+        .map(Zeroizing::new)
+        .map_err(|e| SolanaPmError::InvalidPath(format!("Invalid base58 secret key: {}", e)))?;
+    keypair_from_secret_bytes(&bytes)
+}
+
+/// Derives a keypair from a seed phrase entered at the terminal.
+/// 
+/// Without a query, the legacy Solana CLI derivation (no derivation path) is
+/// used. `key=<account>/<change>` selects a BIP44 path under `m/44'/501'`,
+/// and `full-path=<path>` selects an absolute path.
+/// 
+/// # Arguments
+/// 
+/// * `query` - The part of the `prompt://` URI after the scheme
+/// 
+/// # Returns
+/// 
+/// Returns the derived Keypair, or an error if input fails or the
+/// derivation path is invalid.
+fn keypair_from_seed_phrase_prompt(query: &str) -> Result<Keypair> {
+    let query = query.trim_start_matches('?');
+    let derivation_path = if query.is_empty() {
+        None
+    } else if let Some(key) = query.strip_prefix("key=") {
+        Some(DerivationPath::from_key_str(key)
+            .map_err(|e| SolanaPmError::InvalidPath(format!("Invalid derivation path '{}': {}", key, e)))?)
+    } else if let Some(full_path) = query.strip_prefix("full-path=") {
+        Some(DerivationPath::from_absolute_path_str(full_path)
+            .map_err(|e| SolanaPmError::InvalidPath(format!("Invalid derivation path '{}': {}", full_path, e)))?)
+    } else {
+        return Err(SolanaPmError::InvalidPath(format!(
+            "Unsupported prompt query '{}'. Use prompt://?key=<account>/<change> or prompt://?full-path=<path>", query
+        )));
+    };
+    
+    require_interactive("A seed phrase")?;
+    let seed_phrase = rpassword::prompt_password("Enter seed phrase: ")
+        .map_err(|e| SolanaPmError::InvalidPath(format!("Failed to read seed phrase: {}", e)))?;
+    let passphrase = rpassword::prompt_password("Enter BIP39 passphrase (leave empty for none): ")
+        .map_err(|e| SolanaPmError::InvalidPath(format!("Failed to read passphrase: {}", e)))?;
+    
+    let seed_phrase = seed_phrase.split_whitespace().collect::<Vec<_>>().join(" ");
+    if seed_phrase.is_empty() {
+        return Err(SolanaPmError::InvalidPath("Seed phrase cannot be empty".to_string()));
+    }
+    
+    let keypair = match derivation_path {
+        None => keypair_from_seed_phrase_and_passphrase(&seed_phrase, &passphrase),
+        Some(path) => {
+            let seed = generate_seed_from_seed_phrase_and_passphrase(&seed_phrase, &passphrase);
+            keypair_from_seed_and_derivation_path(&seed, Some(path))
+        }
+    };
+    
+    keypair.map_err(|e| SolanaPmError::InvalidPath(format!("Failed to derive keypair from seed phrase: {}", e)))
+}
+
 
 
 const SOLANA_PROGRAMS_TOML: &str = "SolanaPrograms.toml";
+const CHANGELOG_FILE: &str = "CHANGELOG.md";
+const MAX_RELEASE_NOTES_BYTES: usize = 10 * 1024;
+const DESCRIPTION_LENGTH_RANGE: std::ops::RangeInclusive<usize> = 10..=500;
+const MAX_NAME_LENGTH: usize = 64;
+const CHALLENGE_VERSION: u8 = 3;
+const LEGACY_CHALLENGE_VERSION: u8 = 2;
+const CHALLENGE_NONCE_BYTES: usize = 32;
+const MAX_UPLOAD_IDL_SIZE: u64 = 10 * 1024 * 1024;
+const DEFAULT_WARN_IDL_SIZE: u64 = 1024 * 1024;
+
+/// Options controlling how a program is published.
+/// 
+/// Collects the optional flags of the `publish` command so they can be
+/// passed through the publishing flow as a single value.
+#[derive(Debug, Default)]
+pub struct PublishOptions {
+    /// Path to the program binary (defaults to target/deploy/<name>.so)
+    pub binary: Option<String>,
+    /// Whether to compare the local binary with the deployed program
+    pub verify_onchain: bool,
+    /// Whether to fail instead of warning about a missing license
+    pub strict: bool,
+    /// Release notes provided inline
+    pub notes: Option<String>,
+    /// Path to a file containing release notes
+    pub notes_file: Option<String>,
+    /// Whether to skip the confirmation prompt
+    pub yes: bool,
+    /// Whether to upload the IDL in canonical (sorted-key) form
+    pub canonical_idl: bool,
+    /// Path to the authority keypair, overriding the environment and TOML
+    pub authority_keypair: Option<String>,
+    /// Environment variable holding the authority secret key
+    pub authority_env: Option<String>,
+    /// Whether to sign the challenge with a connected Ledger device
+    pub sign_with_ledger: bool,
+    /// Whether to sign the version 2 challenge without a nonce or registry scope
+    pub legacy_challenge: bool,
+    /// Whether to continue when the keypair is not the on-chain upgrade authority
+    pub allow_authority_mismatch: bool,
+    /// Whether to publish from a dirty git working tree without confirmation
+    pub allow_dirty: bool,
+    /// Whether to skip the pre-publish hooks
+    pub no_hooks: bool,
+    /// Whether to stop before authenticating and uploading
+    pub dry_run: bool,
+    /// Who can discover the published version in the registry
+    pub visibility: Visibility,
+    /// Program name to publish under instead of the TOML value
+    pub name: Option<String>,
+    /// Version to publish instead of the TOML value
+    pub version: Option<String>,
+    /// Network to publish to instead of the TOML value
+    pub network: Option<Network>,
+    /// IDL size in bytes above which publishing needs interactive confirmation
+    pub warn_idl_size: Option<u64>,
+    /// Whether to skip reading the published version back from the registry
+    pub no_verify_readback: bool,
+    /// Whether to publish IDLs whose flavor is not recognized
+    pub any_idl: bool,
+    /// Whether to convert Shank IDLs to the Anchor-compatible layout
+    pub adapt_idl: bool,
+    /// The program to publish from a `[[programs]]` SolanaPrograms.toml
+    pub program: Option<String>,
+    /// Path to write a JSON receipt of a successful publish to
+    pub output_receipt: Option<String>,
+    /// URL to download the IDL from instead of reading a local file
+    pub from_idl_url: Option<String>,
+}
+
+/// The SolanaPrograms.toml values replaced by command line overrides.
+#[derive(Default)]
+struct MetadataOverrides {
+    /// The TOML name, if `--name` replaced it
+    name: Option<String>,
+    /// The TOML version, if `--version` replaced it
+    version: Option<String>,
+    /// The TOML network, if `--network` replaced it
+    network: Option<String>,
+}
+
+/// The result of a publish attempt that did not fail locally.
+enum PublishOutcome {
+    /// The registry accepted the upload
+    Published(PublishReport),
+    /// The registry rejected the upload with the given status, content type, and body
+    Rejected { status: reqwest::StatusCode, content_type: Option<String>, body: String },
+    /// All local checks passed in a dry run; nothing was uploaded
+    DryRun(PublishReport),
+    /// The user declined the confirmation prompt
+    Cancelled,
+}
+
+/// The details of a successful publish, reported in JSON mode.
+struct PublishReport {
+    name: String,
+    version: String,
+    network: String,
+    program_id: String,
+    authority: String,
+    idl_hash: String,
+    idl_flavor: IdlFlavor,
+    visibility: Visibility,
+    url: Option<String>,
+    readback: Readback,
+}
+
+/// The result of reading a published version back from the registry.
+enum Readback {
+    /// The registry returned the program ID and IDL that were sent
+    Verified,
+    /// The registry returned different data or could not return the version
+    Mismatch(Vec<String>),
+    /// Read-back was disabled or not applicable
+    Skipped,
+}
+
+impl Readback {
+    /// Returns the readback object included in JSON output.
+    fn to_json(&self) -> serde_json::Value {
+        match self {
+            Readback::Verified => json!({ "status": "verified" }),
+            Readback::Mismatch(mismatches) => json!({ "status": "mismatch", "mismatches": mismatches }),
+            Readback::Skipped => json!({ "status": "skipped" }),
+        }
+    }
+}
 
 /// Publishes a Solana program to the registry.
 /// 
 /// This function performs the complete program publishing flow:
-/// 1. Ensures user authentication with stored credentials
-/// 2. Reads and validates the SolanaPrograms.toml configuration
-/// 3. Locates and parses the program's IDL file
-/// 4. Loads the authority keypair for cryptographic verification
-/// 5. Generates a signed challenge for program ownership proof
-/// 6. Uploads the program metadata and IDL to the registry
+/// 1. Reads and validates the SolanaPrograms.toml configuration
+/// 2. Checks the git working tree for uncommitted changes
+/// 3. Runs the `pre_publish` hooks from SolanaPrograms.toml
+/// 4. Resolves release notes from flags or CHANGELOG.md
+/// 5. Locates and parses the program's IDL file (the `idl` key of
+///    SolanaPrograms.toml, or the first match in the standard locations)
+/// 6. Hashes the compiled program binary, optionally checking it against the chain
+/// 7. Loads the authority keypair, optionally checking it is the upgrade authority
+/// 8. Ensures user authentication with stored credentials
+/// 9. Generates a signed challenge for program ownership proof
+/// 10. Uploads the program metadata, IDL, and source commit to the registry
+/// 11. Reads the version back and checks its program ID and IDL hash
+/// 12. With `--output-receipt`, writes the signed challenge and registry
+///     response to a JSON receipt, unless the read-back did not match
+/// 
+/// Hooks, local checks, and on-chain checks run before the password prompt so
+/// that failures and misconfiguration fail fast. A dry run stops after step 7.
 /// 
 /// The function requires:
 /// - Valid authentication (run `solpm login` first)
@@ -110,29 +553,90 @@ const SOLANA_PROGRAMS_TOML: &str = "SolanaPrograms.toml";
 /// - An IDL file in standard locations (target/idl, idl, target/deploy)
 /// - Access to the authority keypair specified in the config
 /// 
+/// # Arguments
+/// 
+/// * `options` - Publish flags such as the binary path, strictness, and release notes
+/// 
 /// # Returns
 /// 
 /// Returns `Ok(())` on successful publication, or an error if any step fails.
+/// In JSON mode the result is also written to stdout as a JSON object.
 /// 
 /// # Errors
 /// 
 /// * `SolanaPmError::ConfigNotFound` - If not authenticated or config missing
 /// * `SolanaPmError::DataMissing` - If required config fields are empty
 /// * `SolanaPmError::InvalidPath` - If files cannot be read or keypair is invalid
-/// * `SolanaPmError::UploadFailed` - If registry upload fails
+/// * `SolanaPmError::Registry` - If the registry rejects the upload
+/// * `SolanaPmError::UploadFailed` - If the registry read-back does not match
 /// 
 /// # Examples
 /// 
-/// ```rust
+/// ```no_run
+/// use solpm::commands::publish::{PublishOptions, publish_program};
+/// 
+/// # async fn example() -> solpm::error::Result<()> {
 /// // Publish the program configured in SolanaPrograms.toml
-/// publish_program().await?;
+/// publish_program(&PublishOptions::default()).await?;
+/// 
+/// // Publish with an explicit binary and verify it against the deployed program
+/// publish_program(&PublishOptions {
+///     binary: Some("./target/deploy/my_program.so".to_string()),
+///     verify_onchain: true,
+///     ..Default::default()
+/// }).await?;
+/// # Ok(())
+/// # }
 /// ```
-pub async fn publish_program() -> Result<()> {
-    // Ensure user is authenticated
-    let token = ensure_authenticated().await?;
+pub async fn publish_program(options: &PublishOptions) -> Result<()> {
+    let outcome = run_publish(options).await;
+    
+    if is_json_output() {
+        set_result(publish_result_json(&outcome));
+    }
+    
+    match outcome? {
+        PublishOutcome::Rejected { status, content_type, body } => Err(registry_error_from_body(
+            status, content_type.as_deref(), &body, format!("Publishing to {}", registry_base_url()?)
+        )),
+        PublishOutcome::Published(PublishReport { name, version, readback: Readback::Mismatch(mismatches), .. }) => {
+            Err(SolanaPmError::UploadFailed(format!(
+                "Published {}@{}, but the registry read-back did not match:\n  - {}",
+                name, version, mismatches.join("\n  - ")
+            )))
+        }
+        PublishOutcome::Published(_) | PublishOutcome::DryRun(_) | PublishOutcome::Cancelled => Ok(()),
+    }
+}
+
+/// Runs the publishing flow described in [`publish_program`].
+/// 
+/// # Arguments
+/// 
+/// * `options` - Publish flags such as the binary path, strictness, and release notes
+/// 
+/// # Returns
+/// 
+/// Returns the outcome of the upload, or an error if a local, network, or
+/// validation step fails before the registry responds.
+async fn run_publish(options: &PublishOptions) -> Result<PublishOutcome> {
+    let json = is_json_output();
+    
+    // JSON mode cannot prompt, so authentication must come from the environment
+    if json && !options.dry_run && get_env_token().is_none() {
+        return Err(SolanaPmError::AuthRequired(format!(
+            "--json requires the {} environment variable to be set to a registry API token",
+            TOKEN_ENV_VAR
+        )));
+    }
+    
+    // Fail before uploading if the receipt could not be written afterwards
+    if let Some(receipt_path) = &options.output_receipt {
+        check_receipt_path(receipt_path)?;
+    }
     
     // Read TOML configuration
-    let spinner = CliProgress::new_spinner("Reading SolanaPrograms.toml...");
+    let spinner = new_spinner(json, "Reading SolanaPrograms.toml...");
     
     if !std::path::Path::new(SOLANA_PROGRAMS_TOML).exists() {
         spinner.finish_and_clear();
@@ -144,145 +648,1658 @@ pub async fn publish_program() -> Result<()> {
     let toml_content = fs::read_to_string(SOLANA_PROGRAMS_TOML)
         .map_err(|e| SolanaPmError::InvalidPath(format!("Failed to read SolanaPrograms.toml: {}", e)))?;
     
-    let config: SolanaProgramsConfig = toml::from_str(&toml_content)
+    let mut config: SolanaProgramsConfig = toml::from_str(&toml_content)
         .map_err(|e| SolanaPmError::InvalidPath(format!("Invalid TOML format: {}", e)))?;
     
     spinner.finish_and_clear();
     
-    // Validate required fields
-    if config.program.description.trim().is_empty() {
-        return Err(SolanaPmError::DataMissing(
-            "Description is required. Please fill in the 'description' field in SolanaPrograms.toml".to_string()
-        ));
+    let client = build_registry_client(&config.publish)?;
+    let mut program = config.take_program(options.program.as_deref())?;
+    
+    // Apply command line overrides, then validate and normalize metadata fields
+    let overrides = apply_metadata_overrides(&mut program, options)?;
+    validate_program_metadata(&mut program, options.strict).await?;
+    check_authority_keypair_file(&program, options)?;
+    
+    let license = program.license.trim();
+    if license.is_empty() {
+        statusln!("{}", CliStyle::warning("No license specified. Consider adding an SPDX 'license' field to SolanaPrograms.toml"));
+    } else {
+        let unknown = unknown_spdx_identifiers(license);
+        if !unknown.is_empty() {
+            statusln!("{}", CliStyle::warning(&format!(
+                "Unrecognized SPDX license identifier{}: {}",
+                if unknown.len() == 1 { "" } else { "s" },
+                unknown.join(", ")
+            )));
+        }
     }
     
-    if config.program.repository.trim().is_empty() {
-        return Err(SolanaPmError::DataMissing(
-            "Repository is required. Please fill in the 'repository' field in SolanaPrograms.toml".to_string()
-        ));
+    // Check the source revision being published
+    let git_revision = get_git_revision();
+    if let Some(revision) = &git_revision
+        && revision.is_dirty()
+        && !confirm_dirty_tree(revision, options)?
+    {
+        statusln!("{}", CliStyle::info("Publish cancelled."));
+        return Ok(PublishOutcome::Cancelled);
     }
     
-    // Find and read IDL file
-    let spinner = CliProgress::new_spinner("Finding IDL file...");
-    let idl_file_path = find_idl_file()?;
-    let idl_content = fs::read_to_string(&idl_file_path)
-        .map_err(|e| SolanaPmError::InvalidPath(format!("Failed to read IDL file: {}", e)))?;
+    // Run pre-publish hooks, which may rebuild the IDL and binary
+    run_pre_publish_hooks(&config.hooks, options)?;
+    
+    // Resolve release notes from flags or CHANGELOG.md
+    let release_notes = resolve_release_notes(options, &program.version)?;
+    
+    // Find and read the IDL, or download it
+    let idl_program_name = overrides.name.as_deref().unwrap_or(&program.name);
+    let (idl_file_path, idl_content) = match &options.from_idl_url {
+        // Built by another job and published at a URL
+        Some(url) => {
+            let spinner = new_spinner(json, &format!("Downloading IDL from {}...", url));
+            let idl_content = download_idl(url).await;
+            spinner.finish_and_clear();
+            (url.clone(), idl_content?)
+        }
+        None => {
+            let spinner = new_spinner(json, "Finding IDL file...");
+            let idl_file_path = match &program.idl {
+                // Recorded by `init --from-idl` for builds outside the searched directories
+                Some(idl) => expand_path(idl),
+                None => find_idl_file(idl_program_name)?,
+            };
+            spinner.finish_and_clear();
+            let idl_content = fs::read_to_string(&idl_file_path)
+                .map_err(|e| SolanaPmError::InvalidPath(format!("Failed to read IDL file {}: {}", idl_file_path, e)))?;
+            (idl_file_path, idl_content)
+        }
+    };
     
     let idl_json: serde_json::Value = serde_json::from_str(&idl_content)
-        .map_err(|e| SolanaPmError::InvalidIdl(format!("Invalid JSON in IDL: {}", e)))?;
+        .map_err(|e| SolanaPmError::InvalidIdl(format!("Invalid JSON in IDL {}: {}", idl_file_path, e)))?;
+    // A combined file holds several programs; upload only this one
+    let idl_json = select_combined_idl(idl_json, idl_program_name, &idl_file_path)?;
     
-    spinner.finish_and_clear();
+    // Classify the IDL, adapting Shank IDLs before hashing if requested
+    let idl_flavor = detect_idl_flavor(&idl_json);
+    let (idl_json, idl_adapted) = resolve_idl_flavor(idl_json, idl_flavor, &idl_file_path, options)?;
+    
+    let canonical_idl = canonicalize_idl(&idl_json);
+    let idl_hash = format!("{:x}", Sha256::digest(canonical_idl.as_bytes()));
+    
+    // Serialize the uploaded form once; the canonical form is already serialized
+    let upload_idl = if options.canonical_idl {
+        serde_json::value::RawValue::from_string(canonical_idl)
+    } else {
+        serde_json::value::to_raw_value(&idl_json)
+    }.context("Failed to serialize the IDL for upload")?;
+    drop(idl_json);
+    let idl_size = upload_idl.get().len() as u64;
+    
+    if idl_size > MAX_UPLOAD_IDL_SIZE {
+        return Err(SolanaPmError::InvalidIdl(format!(
+            "IDL is {} ({} bytes), above the registry's upload limit of {} ({} bytes)",
+            HumanBytes(idl_size), idl_size, HumanBytes(MAX_UPLOAD_IDL_SIZE), MAX_UPLOAD_IDL_SIZE
+        )));
+    }
+    
+    // Hash the program binary if available
+    let binary_hash = match find_program_binary(&program.name, options.binary.as_deref())? {
+        Some(path) => {
+            let binary_bytes = fs::read(&path)
+                .map_err(|e| SolanaPmError::InvalidPath(format!("Failed to read program binary '{}': {}", path, e)))?;
+            let hash = format!("{:x}", Sha256::digest(&binary_bytes));
+            statusln!("{}", CliStyle::info(&format!(
+                "Program binary {} (sha256 {})",
+                CliStyle::path(&path),
+                CliStyle::highlight(&hash)
+            )));
+            
+            if options.verify_onchain {
+                verify_deployed_binary(&program.program_id, &program.network, &binary_bytes, json).await?;
+            }
+            
+            Some(hash)
+        }
+        None => {
+            if options.verify_onchain {
+                statusln!("{}", CliStyle::warning("No program binary found; skipping on-chain verification."));
+            }
+            None
+        }
+    };
     
     // Load authority keypair
-    let spinner = CliProgress::new_spinner("Loading authority keypair...");
-    let authority_keypair = load_keypair_from_file(&config.program.authority_keypair)?;
+    let spinner = new_spinner(json, "Loading authority keypair...");
+    let authority_signer = load_authority_signer(options, &program.authority_keypair);
     spinner.finish_and_clear();
+    let authority_signer = authority_signer?;
+    let authority_pubkey = authority_signer.pubkey();
+    
+    if options.verify_onchain {
+        verify_upgrade_authority(
+            &program.program_id,
+            &program.network,
+            &authority_pubkey,
+            options.allow_authority_mismatch,
+            json,
+        ).await?;
+    } else if let Some(toml_network) = &overrides.network {
+        statusln!("{}", CliStyle::warning(&format!(
+            "Publishing to {} with the program ID configured for {}; pass --verify-onchain to check it is deployed there",
+            program.network, toml_network
+        )));
+    }
+    
+    // Show a summary and ask for confirmation
+    print_publish_summary(&program, &authority_pubkey.to_string(), &idl_file_path, idl_size, git_revision.as_ref(), &overrides, options);
+    
+    if options.dry_run {
+        report(json, &CliStyle::success("Dry run complete; skipping authentication and upload."));
+        return Ok(PublishOutcome::DryRun(PublishReport {
+            name: program.name.clone(),
+            version: program.version.clone(),
+            network: program.network.clone(),
+            program_id: program.program_id.clone(),
+            authority: authority_pubkey.to_string(),
+            idl_hash,
+            idl_flavor,
+            visibility: options.visibility,
+            url: None,
+            readback: Readback::Skipped,
+        }));
+    }
+    
+    if !options.yes && !json {
+        if !std::io::stdin().is_terminal() {
+            return Err(SolanaPmError::DataMissing(
+                "Refusing to publish without confirmation in a non-interactive session. Pass --yes to publish.".to_string()
+            ));
+        }
+        if !confirm_action("Publish now?")? {
+            statusln!("{}", CliStyle::info("Publish cancelled."));
+            return Ok(PublishOutcome::Cancelled);
+        }
+    }
+    
+    if !confirm_large_idl(idl_size, options)? {
+        statusln!("{}", CliStyle::info("Publish cancelled."));
+        return Ok(PublishOutcome::Cancelled);
+    }
+    
+    // Ensure user is authenticated
+    let registry = registry_base_url()?;
+    let token = ensure_authenticated().await?;
+    let registry_client = RegistryClient::with_base_url(client, &registry).with_token(token);
     
     // Generate challenge and sign it
-    let spinner = CliProgress::new_spinner("Generating cryptographic proof...");
+    let spinner = match authority_signer {
+        AuthoritySigner::Keypair(_) => new_spinner(json, "Generating cryptographic proof..."),
+        AuthoritySigner::Ledger(_) => {
+            statusln!("{}", CliStyle::info("Confirm the publish challenge on your Ledger device..."));
+            ProgressBar::hidden()
+        }
+    };
     
     let timestamp = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .map_err(|e| SolanaPmError::InvalidPath(format!("System time error: {}", e)))?
         .as_secs();
     
-    let challenge = format!("Publish program {} to {} registry at {}", 
-        config.program.program_id, config.program.network, timestamp);
-    let signature = authority_keypair.sign_message(challenge.as_bytes());
-    let authority_pubkey = authority_keypair.pubkey();
+    let (challenge, challenge_version, nonce) = if options.legacy_challenge {
+        let challenge = format!("Publish program {} to {} registry at {} with IDL sha256 {}", 
+            program.program_id, program.network, timestamp, idl_hash);
+        (challenge, LEGACY_CHALLENGE_VERSION, None)
+    } else {
+        let nonce = generate_challenge_nonce();
+        let challenge = build_challenge(&registry, &program, &idl_hash, timestamp, &nonce);
+        (challenge, CHALLENGE_VERSION, Some(nonce))
+    };
+    let signed = authority_signer.sign_challenge(&challenge);
+    drop(authority_signer);
     
     spinner.finish_and_clear();
+    let (signature, signature_format) = signed?;
     
-    println!("{}", CliStyle::progress(&format!(
+    statusln!("{}", CliStyle::progress(&format!(
         "Publishing {} {} to {} with authority {}...", 
-        CliStyle::package(&config.program.name), 
-        CliStyle::version(&config.program.version),
-        CliStyle::highlight(&config.program.network),
+        CliStyle::package(&program.name), 
+        CliStyle::version(&program.version),
+        CliStyle::highlight(&program.network),
         CliStyle::highlight(&authority_pubkey.to_string())
     )));
     
     // Create upload request with cryptographic proof
     let upload_request = UploadProgramRequest {
-        name: config.program.name.clone(),
-        version: config.program.version.clone(),
-        program_id: config.program.program_id.clone(),
-        network: config.program.network.clone(),
-        idl: idl_json,
-        description: config.program.description.clone(),
-        repository: config.program.repository.clone(),
+        name: program.name.clone(),
+        version: program.version.clone(),
+        program_id: program.program_id.clone(),
+        network: program.network.clone(),
+        idl: upload_idl,
+        description: program.description.clone(),
+        repository: program.repository.clone(),
+        license: if license.is_empty() { None } else { Some(license.to_string()) },
+        keywords: program.keywords.clone(),
+        binary_hash,
+        release_notes,
+        commit_hash: git_revision.as_ref().map(|revision| revision.commit.clone()),
+        dirty: git_revision.as_ref().map(GitRevision::is_dirty),
+        visibility: options.visibility.as_str().to_string(),
+        idl_flavor: idl_flavor.as_str().to_string(),
+        idl_adapted,
         // Cryptographic verification fields
+        idl_hash: idl_hash.clone(),
         challenge,
+        challenge_version,
+        nonce,
         signature: bs58::encode(signature.as_ref()).into_string(),
+        signature_format,
         authority_pubkey: bs58::encode(authority_pubkey.as_ref()).into_string(),
     };
     
+    // Keep the signed proof for the receipt; the request itself holds the IDL
+    let receipt = options.output_receipt.as_ref().map(|_| PublishReceipt {
+        registry: registry.clone(),
+        name: upload_request.name.clone(),
+        version: upload_request.version.clone(),
+        network: upload_request.network.clone(),
+        program_id: upload_request.program_id.clone(),
+        idl_hash: upload_request.idl_hash.clone(),
+        timestamp,
+        challenge: upload_request.challenge.clone(),
+        challenge_version: upload_request.challenge_version,
+        nonce: upload_request.nonce.clone(),
+        signature: upload_request.signature.clone(),
+        signature_format: upload_request.signature_format.clone(),
+        authority_pubkey: upload_request.authority_pubkey.clone(),
+        publication_id: None,
+        url: None,
+        published_at: None,
+        registry_response: serde_json::Value::Null,
+    });
+    
     // Upload to registry
-    let spinner = CliProgress::new_spinner("Publishing to registry...");
+    let body = serde_json::to_vec(&upload_request)?;
+    drop(upload_request);
+    let upload = PublishUpload { body, base_timeout: request_timeout(&config.publish)?, show_progress: !json };
+    let body = match registry_client.publish_program(upload).await? {
+        PublishUploadResponse::Accepted { body } => body,
+        PublishUploadResponse::Rejected { status, content_type, body } => {
+            return Ok(PublishOutcome::Rejected { status, content_type, body });
+        }
+    };
     
-    let client = reqwest::Client::new();
-    let publish_response = client
-        .post(PUBLISH_PROGRAM_URL)
-        .header("Authorization", format!("Bearer {}", token))
-        .json(&upload_request)
-        .send()
-        .await?;
+    report(json, &CliStyle::success(&format!(
+        "Successfully published {} {} to {}",
+        CliStyle::package(&program.name),
+        CliStyle::version(&program.version),
+        CliStyle::highlight(&program.network)
+    )));
     
-    spinner.finish_and_clear();
+    let log_entry = PublishLogEntry {
+        timestamp: current_timestamp(),
+        action: "publish".to_string(),
+        registry: registry.clone(),
+        name: program.name.clone(),
+        version: program.version.clone(),
+        network: Some(program.network.clone()),
+        program_id: Some(program.program_id.clone()),
+        authority: authority_pubkey.to_string(),
+        idl_hash: Some(idl_hash.clone()),
+        commit: git_revision.as_ref().map(|revision| revision.commit.clone()),
+    };
+    if let Err(e) = append_publish_log(&log_entry) {
+        statusln!("{}", CliStyle::warning(&format!("Could not record the publish in the local history: {}", e)));
+    }
     
-    if publish_response.status().is_success() {
-        println!("{}", CliStyle::success(&format!(
-            "Successfully published {} {} to {}",
-            CliStyle::package(&config.program.name),
-            CliStyle::version(&config.program.version),
-            CliStyle::highlight(&config.program.network)
-        )));
+    // Show listing details when the registry response can be parsed
+    let published = serde_json::from_str::<PublishResponse>(&body).ok();
+    if let Some(published) = &published
+        && !json
+    {
+        print_publish_details(published, &program, options.visibility);
+    }
+    
+    let readback = if options.no_verify_readback {
+        Readback::Skipped
     } else {
-        let status = publish_response.status();
-        let error_text = publish_response.text().await?;
-        return Err(SolanaPmError::UploadFailed(format!(
-            "Failed to publish program ({}): {}", status, error_text
-        )));
+        verify_readback(&registry_client, &program, &idl_hash, json).await
+    };
+    
+    // A receipt is proof of what was published, so a mismatched read-back gets none
+    if let (Some(receipt_path), Some(mut receipt)) = (&options.output_receipt, receipt) {
+        if let Readback::Mismatch(_) = readback {
+            statusln!("{}", CliStyle::warning(&format!(
+                "Not writing the receipt to {}: the registry read-back did not match",
+                receipt_path
+            )));
+        } else {
+            if let Some(published) = &published {
+                receipt.version = published.version.clone().unwrap_or(receipt.version);
+                receipt.publication_id = published.id.clone();
+                receipt.url = published.url.clone();
+                receipt.published_at = published.published_at.clone();
+            }
+            receipt.registry_response = serde_json::from_str(&body).unwrap_or_else(|_| serde_json::Value::String(body.clone()));
+            write_receipt(receipt_path, &receipt, json);
+        }
     }
     
-    Ok(())
+    Ok(PublishOutcome::Published(PublishReport {
+        name: program.name.clone(),
+        version: program.version.clone(),
+        network: program.network.clone(),
+        program_id: program.program_id.clone(),
+        authority: authority_pubkey.to_string(),
+        idl_hash,
+        idl_flavor,
+        visibility: options.visibility,
+        url: published.and_then(|published| published.url),
+        readback,
+    }))
 }
 
-/// Searches for an IDL file in standard Solana project directories.
+/// Checks that a publish receipt can be written to a path.
 /// 
-/// This function looks for `.json` IDL files in the following directories (in order):
-/// - `target/idl` - Standard Anchor build output
-/// - `idl` - Custom IDL directory
-/// - `target/deploy` - Alternative build output location
+/// # Arguments
+/// 
+/// * `receipt_path` - The `--output-receipt` path
 /// 
 /// # Returns
 /// 
-/// Returns the path to the first IDL file found, or an error if no IDL files
-/// are found in any of the searched directories.
+/// Returns `Ok(())` if the path is not a directory and its parent directory
+/// exists, or an error otherwise.
+fn check_receipt_path(receipt_path: &str) -> Result<()> {
+    let path = std::path::Path::new(receipt_path);
+    if path.is_dir() {
+        return Err(SolanaPmError::InvalidPath(format!("Receipt path {} is a directory", receipt_path)));
+    }
+    if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty())
+        && !parent.is_dir()
+    {
+        return Err(SolanaPmError::InvalidPath(format!(
+            "Cannot write the receipt to {}: directory {} does not exist",
+            receipt_path,
+            parent.display()
+        )));
+    }
+    Ok(())
+}
+
+/// Writes the receipt of a successful publish.
+/// 
+/// The version is already published, so a write failure only warns.
 /// 
-/// # Errors
+/// # Arguments
+/// 
+/// * `receipt_path` - The `--output-receipt` path
+/// * `receipt` - The signed proof and registry response
+/// * `json` - Whether JSON mode is enabled
+fn write_receipt(receipt_path: &str, receipt: &PublishReceipt, json: bool) {
+    let written = serde_json::to_string_pretty(receipt)
+        .map_err(SolanaPmError::from)
+        .and_then(|content| fs::write(receipt_path, content + "\n").map_err(SolanaPmError::from));
+    match written {
+        Ok(()) => report(json, &CliStyle::success(&format!("Wrote publish receipt to {}", CliStyle::path(receipt_path)))),
+        Err(e) => statusln!("{}", CliStyle::warning(&format!(
+            "Published, but could not write the receipt to {}: {}",
+            receipt_path, e
+        ))),
+    }
+}
+
+/// Reads a published version back from the registry and compares it.
+/// 
+/// Checks the program ID, version, and canonical IDL hash returned by the
+/// registry against what was uploaded, and reports a successful match.
 /// 
-/// Returns `SolanaPmError::InvalidPath` if no IDL file is found or if
-/// directory reading fails.
-fn find_idl_file() -> Result<String> {
-    const IDL_PATHS: &[&str] = &["target/idl", "idl", "target/deploy"];
+/// # Arguments
+/// 
+/// * `registry` - The registry, with the API token needed to read unlisted
+///   and draft versions
+/// * `program` - The program configuration that was published
+/// * `idl_hash` - The hex SHA-256 of the canonical IDL that was uploaded
+/// * `json` - Whether JSON mode is enabled
+/// 
+/// # Returns
+/// 
+/// Returns `Readback::Verified` if everything matches, or `Readback::Mismatch`
+/// with one entry per difference or read failure.
+async fn verify_readback(registry: &impl RegistryApi, program: &ProgramConfig, idl_hash: &str, json: bool) -> Readback {
+    let _phase = phase("Read back the published version");
+    let spinner = new_spinner(json, "Verifying the published version...");
     
-    for idl_dir in IDL_PATHS {
-        let dir_path = std::path::Path::new(idl_dir);
-        if dir_path.exists() && dir_path.is_dir() {
-            let entries = fs::read_dir(dir_path)
-                .map_err(|e| SolanaPmError::InvalidPath(format!("Failed to read directory {}: {}", idl_dir, e)))?;
-            
-            for entry in entries {
-                let entry = entry?;
-                let path = entry.path();
-                if path.extension().map_or(false, |ext| ext == "json") {
-                    return Ok(path.to_string_lossy().to_string());
-                }
+    let fetched = registry.get_program(&program.name, &program.version, &program.network).await
+        .map_err(|e| format!("could not read the version back: {}", e));
+    
+    spinner.finish_and_clear();
+    
+    let mismatches = match fetched {
+        Ok(fetched) => {
+            let fetched_idl_hash = format!("{:x}", Sha256::digest(canonicalize_idl(&fetched.idl).as_bytes()));
+            let mut mismatches = Vec::new();
+            if fetched.version != program.version {
+                mismatches.push(format!("version: sent {}, registry returned {}", program.version, fetched.version));
+            }
+            if fetched.program_id != program.program_id {
+                mismatches.push(format!("program_id: sent {}, registry returned {}", program.program_id, fetched.program_id));
+            }
+            if fetched_idl_hash != idl_hash {
+                mismatches.push(format!("idl_sha256: sent {}, registry returned {}", idl_hash, fetched_idl_hash));
             }
+            mismatches
         }
-    }
+        Err(problem) => vec![problem],
+    };
     
-    Err(SolanaPmError::InvalidPath(
-        "No IDL file found. Please build/deploy your program first. Searched paths: target/idl, idl, target/deploy".to_string()
-    ))
-}
\ No newline at end of file
+    // Mismatches are reported by the caller once the outcome is known
+    if mismatches.is_empty() {
+        report(json, &CliStyle::success("Verified: the registry returned the same program ID and IDL hash"));
+        Readback::Verified
+    } else {
+        Readback::Mismatch(mismatches)
+    }
+}
+
+/// Builds the version 3 publish challenge.
+/// 
+/// See "Challenge Format" in the README for the canonical format.
+/// 
+/// # Arguments
+/// 
+/// * `registry` - The registry base URL the challenge is scoped to
+/// * `program` - The program being published
+/// * `idl_hash` - The hex SHA-256 of the canonical IDL
+/// * `timestamp` - The current unix timestamp in seconds
+/// * `nonce` - The random nonce sent with the request
+/// 
+/// # Returns
+/// 
+/// Returns the challenge string to sign.
+fn build_challenge(registry: &str, program: &ProgramConfig, idl_hash: &str, timestamp: u64, nonce: &str) -> String {
+    format!(
+        "solpm-publish-v{} registry={} name={} version={} program={} network={} idl_sha256={} timestamp={} nonce={}",
+        CHALLENGE_VERSION,
+        registry,
+        program.name,
+        program.version,
+        program.program_id,
+        program.network,
+        idl_hash,
+        timestamp,
+        nonce
+    )
+}
+
+/// Generates a random single-use challenge nonce.
+/// 
+/// # Returns
+/// 
+/// Returns 32 bytes from the operating system RNG as lowercase hex.
+pub fn generate_challenge_nonce() -> String {
+    let mut nonce = [0u8; CHALLENGE_NONCE_BYTES];
+    OsRng.fill_bytes(&mut nonce);
+    nonce.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Builds the JSON result reported in JSON mode.
+/// 
+/// # Arguments
+/// 
+/// * `outcome` - The outcome of the publish attempt
+/// 
+/// # Returns
+/// 
+/// Returns the JSON object describing the published program or the failure.
+fn publish_result_json(outcome: &Result<PublishOutcome>) -> serde_json::Value {
+    match outcome {
+        Ok(PublishOutcome::Published(report)) => json!({
+            "success": !matches!(report.readback, Readback::Mismatch(_)),
+            "name": report.name,
+            "version": report.version,
+            "network": report.network,
+            "program_id": report.program_id,
+            "authority": report.authority,
+            "idl_hash": report.idl_hash,
+            "idl_flavor": report.idl_flavor.as_str(),
+            "visibility": report.visibility.as_str(),
+            "url": report.url,
+            "readback": report.readback.to_json(),
+        }),
+        Ok(PublishOutcome::DryRun(report)) => json!({
+            "success": true,
+            "dry_run": true,
+            "name": report.name,
+            "version": report.version,
+            "network": report.network,
+            "program_id": report.program_id,
+            "authority": report.authority,
+            "idl_hash": report.idl_hash,
+            "idl_flavor": report.idl_flavor.as_str(),
+            "visibility": report.visibility.as_str(),
+        }),
+        Ok(PublishOutcome::Rejected { status, content_type, body }) => {
+            let category = match status.as_u16() {
+                401 | 403 => "auth",
+                409 => "conflict",
+                _ => "registry",
+            };
+            let registry_error = serde_json::from_str::<RegistryErrorResponse>(body).ok();
+            let field_errors: Vec<serde_json::Value> = registry_error.iter()
+                .flat_map(|registry_error| &registry_error.field_errors)
+                .map(|field_error| json!({ "field": field_error.field, "message": field_error.message }))
+                .collect();
+            let body = serde_json::from_str::<serde_json::Value>(body)
+                .unwrap_or_else(|_| serde_json::Value::String(body.clone()));
+            json!({
+                "success": false,
+                "error": {
+                    "category": category,
+                    "message": format!("Registry rejected the upload ({})", status),
+                    "status": status.as_u16(),
+                    "code": registry_error.as_ref().and_then(|registry_error| registry_error.code.clone()),
+                    "registry_message": registry_error.as_ref().and_then(|registry_error| registry_error.message.clone()),
+                    "field_errors": field_errors,
+                    "content_type": content_type,
+                    "body": body,
+                }
+            })
+        }
+        Ok(PublishOutcome::Cancelled) => json!({
+            "success": false,
+            "error": { "category": "cancelled", "message": "Publish cancelled" }
+        }),
+        Err(e) => json!({
+            "success": false,
+            "error": { "category": e.kind(), "message": e.to_string() }
+        }),
+    }
+}
+
+/// Prints a human-oriented message.
+/// 
+/// In JSON mode the message goes to stderr so stdout only carries the result.
+/// 
+/// # Arguments
+/// 
+/// * `json` - Whether JSON mode is enabled
+/// * `message` - The message to print
+fn report(json: bool, message: &str) {
+    if json {
+        eprintln!("{}", message);
+    } else {
+        println!("{}", message);
+    }
+}
+
+/// Creates a spinner, or a hidden progress bar in JSON mode.
+/// 
+/// # Arguments
+/// 
+/// * `json` - Whether JSON mode is enabled
+/// * `msg` - The message to display next to the spinner
+/// 
+/// # Returns
+/// 
+/// Returns the progress bar to finish once the step completes.
+fn new_spinner(json: bool, msg: &str) -> ProgressBar {
+    if json {
+        ProgressBar::hidden()
+    } else {
+        CliProgress::new_spinner(msg)
+    }
+}
+
+/// Checks the detected IDL flavor and adapts Shank IDLs if requested.
+/// 
+/// Anchor IDLs are used as-is. Codama IDLs and unknown IDLs published with
+/// `--any-idl` are uploaded with a warning that codegen cannot read them.
+/// Shank IDLs are converted with `adapt_shank_idl` when `--adapt-idl` is set,
+/// and otherwise uploaded with a note about `--idl-format shank`.
+/// 
+/// # Arguments
+/// 
+/// * `idl_json` - The parsed IDL
+/// * `idl_flavor` - The detected flavor
+/// * `idl_file_path` - The IDL file, used in messages
+/// * `options` - Publish options containing the IDL and output flags
+/// 
+/// # Returns
+/// 
+/// Returns the IDL to upload and whether it was adapted, or an error if the
+/// flavor is unknown and `--any-idl` is not set.
+fn resolve_idl_flavor(
+    idl_json: serde_json::Value,
+    idl_flavor: IdlFlavor,
+    idl_file_path: &str,
+    options: &PublishOptions,
+) -> Result<(serde_json::Value, bool)> {
+    if options.adapt_idl && idl_flavor != IdlFlavor::Shank {
+        statusln!("{}", CliStyle::warning(&format!(
+            "--adapt-idl only converts Shank IDLs; uploading the {} IDL unchanged.",
+            idl_flavor.as_str()
+        )));
+    }
+    
+    match idl_flavor {
+        _ if idl_flavor.is_anchor() => Ok((idl_json, false)),
+        IdlFlavor::Shank if options.adapt_idl => {
+            statusln!("{}", CliStyle::info("Adapted the Shank IDL to the Anchor-compatible layout read by solpm codegen."));
+            Ok((adapt_shank_idl(&idl_json), true))
+        }
+        IdlFlavor::Shank => {
+            statusln!("{}", CliStyle::warning(&format!(
+                "{} is a Shank IDL. Consumers must run {}, or pass --adapt-idl to upload an Anchor-compatible layout.",
+                idl_file_path,
+                CliStyle::command("solpm codegen --idl-format shank")
+            )));
+            Ok((idl_json, false))
+        }
+        IdlFlavor::Unknown if !options.any_idl => Err(SolanaPmError::InvalidIdl(format!(
+            "Unrecognized IDL format in {}. Expected an Anchor, Shank, or Codama IDL; pass --any-idl to publish it anyway.",
+            idl_file_path
+        ))),
+        _ => {
+            let description = if idl_flavor == IdlFlavor::Codama { "a Codama IDL" } else { "in an unrecognized format" };
+            statusln!("{}", CliStyle::warning(&format!(
+                "{} is {}, which solpm codegen does not support. Consumers can install it but not generate a client.",
+                idl_file_path,
+                description
+            )));
+            Ok((idl_json, false))
+        }
+    }
+}
+
+/// Asks for confirmation before uploading an IDL above the warning threshold.
+/// 
+/// Large IDLs need interactive confirmation even with `--yes`, so scripted
+/// publishes of unexpectedly large IDLs fail instead of uploading silently.
+/// 
+/// # Arguments
+/// 
+/// * `idl_size` - The serialized IDL size in bytes
+/// * `options` - Publish options containing the threshold and output flags
+/// 
+/// # Returns
+/// 
+/// Returns whether to continue publishing, or an error if confirmation is
+/// required in a non-interactive or JSON session.
+fn confirm_large_idl(idl_size: u64, options: &PublishOptions) -> Result<bool> {
+    let threshold = options.warn_idl_size.unwrap_or(DEFAULT_WARN_IDL_SIZE);
+    if idl_size <= threshold {
+        return Ok(true);
+    }
+    
+    if is_json_output() || !std::io::stdin().is_terminal() {
+        return Err(SolanaPmError::DataMissing(format!(
+            "IDL is {}, above the {} warning threshold. Pass --warn-idl-size {} to publish it non-interactively.",
+            HumanBytes(idl_size), HumanBytes(threshold), idl_size
+        )));
+    }
+    
+    statusln!("{}", CliStyle::warning(&format!(
+        "IDL is {}, above the {} warning threshold; the upload may be slow.",
+        HumanBytes(idl_size), HumanBytes(threshold)
+    )));
+    confirm_action("Upload it anyway?")
+}
+
+/// Builds the HTTP client used for registry requests.
+/// 
+/// The connect and request timeouts come from the `[publish]` section of
+/// SolanaPrograms.toml, falling back to the shared client timeouts.
+/// 
+/// # Arguments
+/// 
+/// * `publish_config` - The `[publish]` section of SolanaPrograms.toml
+/// 
+/// # Returns
+/// 
+/// Returns the client, or an error if a timeout is zero or the client
+/// cannot be created.
+fn build_registry_client(publish_config: &PublishConfig) -> Result<reqwest::Client> {
+    if publish_config.connect_timeout_secs == Some(0) || publish_config.timeout_secs == Some(0) {
+        return Err(SolanaPmError::InvalidPath(
+            "Invalid TOML format: publish timeouts must be at least 1 second".to_string()
+        ));
+    }
+    
+    set_project_timeouts(publish_config.connect_timeout_secs, publish_config.timeout_secs);
+    http_client()
+}
+
+/// Returns the configured registry request timeout.
+/// 
+/// # Arguments
+/// 
+/// * `publish_config` - The `[publish]` section of SolanaPrograms.toml
+/// 
+/// # Returns
+/// 
+/// Returns `--timeout`, `SOLPM_TIMEOUT`, `timeout_secs`, `timeout_secs` from
+/// ~/.solpm/config.toml, or 60 seconds, or an error if a setting is invalid
+/// or the global config cannot be loaded.
+fn request_timeout(publish_config: &PublishConfig) -> Result<Duration> {
+    Ok(Duration::from_secs(request_timeout_secs(publish_config.timeout_secs)?))
+}
+
+/// Replaces the TOML name, version, and network with the `--name`,
+/// `--version`, and `--network` flags.
+/// 
+/// The overrides apply to this invocation only; SolanaPrograms.toml is not
+/// modified. Overridden values are validated like the TOML fields.
+/// 
+/// # Arguments
+/// 
+/// * `program` - The program configuration to update
+/// * `options` - Publish options containing the overrides
+/// 
+/// # Returns
+/// 
+/// Returns the TOML values that were replaced, or an error naming the
+/// invalid flag.
+fn apply_metadata_overrides(program: &mut ProgramConfig, options: &PublishOptions) -> Result<MetadataOverrides> {
+    let mut overrides = MetadataOverrides::default();
+    
+    if let Some(name) = &options.name {
+        validate_program_name(name)
+            .map_err(|problem| SolanaPmError::DataMissing(format!("Invalid --name '{}': {}", name, problem)))?;
+        overrides.name = Some(std::mem::replace(&mut program.name, name.clone()));
+    }
+    
+    if let Some(version) = &options.version {
+        validate_version(version)
+            .map_err(|problem| SolanaPmError::DataMissing(format!("Invalid --version '{}': {}", version, problem)))?;
+        overrides.version = Some(std::mem::replace(&mut program.version, version.clone()));
+    }
+    
+    if let Some(network) = &options.network
+        && network.as_str() != program.network
+    {
+        overrides.network = Some(std::mem::replace(&mut program.network, network.as_str().to_string()));
+    }
+    
+    Ok(overrides)
+}
+
+/// Validates and normalizes the metadata fields of SolanaPrograms.toml.
+/// 
+/// Checks every field before reporting so all problems are listed at once:
+/// - `name` must be 1 to 64 ASCII letters, digits, `-` or `_`, starting
+///   with a letter or digit
+/// - `version` must be a semantic version (`MAJOR.MINOR.PATCH`, with optional
+///   `-prerelease` and `+build` parts)
+/// - `program_id` must be a base58 public key, not the placeholder `init`
+///   writes with `--allow-placeholder`
+/// - `description` must be 10 to 500 characters after trimming
+/// - `repository` must be an https URL; GitHub and GitLab SSH remotes are
+///   converted to https and must name an owner and repository
+/// - in strict mode, `license` must be set and the repository must respond
+///   to a HEAD request
+/// 
+/// On success the description and repository are replaced by their trimmed,
+/// normalized forms.
+/// 
+/// # Arguments
+/// 
+/// * `program` - The program configuration to validate and normalize
+/// * `strict` - Whether to apply the strict-mode checks
+/// 
+/// # Returns
+/// 
+/// Returns `Ok(())` if all fields are valid, or an error listing every
+/// invalid field.
+async fn validate_program_metadata(program: &mut ProgramConfig, strict: bool) -> Result<()> {
+    let _phase = phase("Validate metadata");
+    let mut problems = Vec::new();
+    
+    if let Err(problem) = validate_program_name(&program.name) {
+        problems.push(format!("name: {}", problem));
+    }
+    if let Err(problem) = validate_version(&program.version) {
+        problems.push(format!("version: {}", problem));
+    }
+    if let Err(problem) = check_program_id(&program.program_id) {
+        problems.push(format!("program_id: {} (fix it with 'solpm init --program-id <ID>')", problem));
+    }
+    
+    let description = program.description.trim().to_string();
+    if let Err(problem) = validate_description(&description) {
+        problems.push(format!("description: {}", problem));
+    }
+    
+    let repository = match normalize_repository_url(program.repository.trim()) {
+        Ok(repository) => Some(repository),
+        Err(problem) => {
+            problems.push(format!("repository: {}", problem));
+            None
+        }
+    };
+    
+    if strict {
+        if program.license.trim().is_empty() {
+            problems.push("license: required in strict mode".to_string());
+        }
+        
+        if let Some(repository) = &repository {
+            let reachable = http_client()?
+                .head(repository)
+                .send_logged()
+                .await
+                .map(|response| response.status());
+            match reachable {
+                Ok(status) if status.is_success() || status == reqwest::StatusCode::METHOD_NOT_ALLOWED => {}
+                Ok(status) => problems.push(format!("repository: {} responded with {}", repository, status)),
+                Err(e) => problems.push(format!("repository: {} is unreachable: {}", repository, describe_request_error(&e))),
+            }
+        }
+    }
+    
+    if !problems.is_empty() {
+        return Err(SolanaPmError::DataMissing(format!(
+            "Invalid fields in {}:\n  - {}",
+            SOLANA_PROGRAMS_TOML,
+            problems.join("\n  - ")
+        )));
+    }
+    
+    program.description = description;
+    if let Some(repository) = repository {
+        program.repository = repository;
+    }
+    
+    Ok(())
+}
+
+/// Checks that a program name uses the allowed character set.
+/// 
+/// # Arguments
+/// 
+/// * `name` - The program name
+/// 
+/// # Returns
+/// 
+/// Returns `Ok(())` for 1 to 64 ASCII letters, digits, `-` or `_` starting
+/// with a letter or digit, or a description of the problem.
+pub fn validate_program_name(name: &str) -> std::result::Result<(), String> {
+    if name.is_empty() {
+        return Err("required".to_string());
+    }
+    if name.len() > MAX_NAME_LENGTH {
+        return Err(format!("must be at most {} characters (found {})", MAX_NAME_LENGTH, name.len()));
+    }
+    if !name.starts_with(|c: char| c.is_ascii_alphanumeric()) {
+        return Err("must start with a letter or digit".to_string());
+    }
+    if let Some(c) = name.chars().find(|c| !c.is_ascii_alphanumeric() && *c != '-' && *c != '_') {
+        return Err(format!("contains '{}'; only letters, digits, '-' and '_' are allowed", c));
+    }
+    Ok(())
+}
+
+/// Checks that a description has an acceptable length.
+/// 
+/// # Arguments
+/// 
+/// * `description` - The trimmed description
+/// 
+/// # Returns
+/// 
+/// Returns `Ok(())` for 10 to 500 characters, or a description of the problem.
+pub fn validate_description(description: &str) -> std::result::Result<(), String> {
+    let description_length = description.chars().count();
+    if description.is_empty() {
+        return Err("required".to_string());
+    }
+    if !DESCRIPTION_LENGTH_RANGE.contains(&description_length) {
+        return Err(format!(
+            "must be {} to {} characters (found {})",
+            DESCRIPTION_LENGTH_RANGE.start(), DESCRIPTION_LENGTH_RANGE.end(), description_length
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that a version is a semantic version.
+/// 
+/// Accepts `MAJOR.MINOR.PATCH` with optional `-prerelease` and `+build`
+/// suffixes made of dot-separated alphanumeric identifiers, such as
+/// `1.2.0`, `0.5.0-nightly.20240601`, or `1.0.0+build.7`. Numeric parts
+/// must not have leading zeros.
+/// 
+/// # Arguments
+/// 
+/// * `version` - The version string
+/// 
+/// # Returns
+/// 
+/// Returns `Ok(())` if the version is valid, or a description of the problem.
+pub fn validate_version(version: &str) -> std::result::Result<(), String> {
+    let is_numeric = |part: &str| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()) && (part == "0" || !part.starts_with('0'));
+    let is_identifier = |part: &str| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+    
+    let (version, build) = match version.split_once('+') {
+        Some((version, build)) => (version, Some(build)),
+        None => (version, None),
+    };
+    let (core, prerelease) = match version.split_once('-') {
+        Some((core, prerelease)) => (core, Some(prerelease)),
+        None => (version, None),
+    };
+    
+    let core_parts: Vec<&str> = core.split('.').collect();
+    if core_parts.len() != 3 || !core_parts.iter().all(|part| is_numeric(part)) {
+        return Err("must be a semantic version like 1.2.3 or 1.2.3-beta.1".to_string());
+    }
+    if let Some(prerelease) = prerelease {
+        let valid = prerelease.split('.').all(|part| {
+            is_identifier(part) && (!part.chars().all(|c| c.is_ascii_digit()) || is_numeric(part))
+        });
+        if !valid {
+            return Err(format!("invalid pre-release '{}'", prerelease));
+        }
+    }
+    if let Some(build) = build
+        && !build.split('.').all(is_identifier)
+    {
+        return Err(format!("invalid build metadata '{}'", build));
+    }
+    
+    Ok(())
+}
+
+/// Normalizes a repository URL to the https form sent to the registry.
+/// 
+/// GitHub and GitLab SSH remotes (`git@github.com:owner/repo.git`) are
+/// converted to https, and a trailing `.git` or `/` is removed. GitHub and
+/// GitLab URLs must include both an owner and a repository name.
+/// 
+/// # Arguments
+/// 
+/// * `repository` - The trimmed repository value from SolanaPrograms.toml
+/// 
+/// # Returns
+/// 
+/// Returns the normalized URL, or a description of why it is invalid.
+pub fn normalize_repository_url(repository: &str) -> std::result::Result<String, String> {
+    if repository.is_empty() {
+        return Err("required".to_string());
+    }
+    
+    let candidate = match repository.strip_prefix("git@") {
+        Some(ssh_remote) => match ssh_remote.split_once(':') {
+            Some((host @ ("github.com" | "gitlab.com"), path)) => format!("https://{}/{}", host, path),
+            _ => return Err(format!("'{}' is not an https URL", repository)),
+        },
+        None => repository.to_string(),
+    };
+    
+    let parsed = url::Url::parse(&candidate)
+        .map_err(|e| format!("'{}' is not a valid URL ({})", repository, e))?;
+    
+    if parsed.scheme() != "https" {
+        return Err(format!("'{}' must use https", repository));
+    }
+    
+    let Some(host) = parsed.host_str() else {
+        return Err(format!("'{}' has no host", repository));
+    };
+    
+    let path = parsed.path().trim_end_matches('/').trim_end_matches(".git");
+    if matches!(host, "github.com" | "www.github.com" | "gitlab.com")
+        && path.trim_start_matches('/').split('/').filter(|segment| !segment.is_empty()).count() < 2
+    {
+        return Err(format!("'{}' must point to a repository (https://{}/<owner>/<repo>)", repository, host));
+    }
+    
+    let port = parsed.port().map(|port| format!(":{}", port)).unwrap_or_default();
+    Ok(format!("https://{}{}{}", host, port, path))
+}
+
+/// Searches for an IDL file in standard Solana project directories.
+/// 
+/// This function looks for `.json` IDL files in the following directories (in order):
+/// - `target/idl` - Standard Anchor build output
+/// - `idl` - Custom IDL directory
+/// - `target/deploy` - Alternative build output location
+/// 
+/// Files are discovered and filtered like `solpm init` does (keypairs and
+/// other non-IDL JSON files are skipped). The IDL whose `metadata.name`
+/// matches the program name (treating `-` and `_` alike) is used; another
+/// program's IDL is never picked in its place.
+/// 
+/// For a program in a combined IDL file, the combined file's path is
+/// returned; the caller takes the program out with [`select_combined_idl`].
+/// 
+/// # Arguments
+/// 
+/// * `program_name` - The program name from SolanaPrograms.toml
+/// 
+/// # Returns
+/// 
+/// Returns the path to the matching IDL file, or an error if no IDL matches
+/// the program.
+/// 
+/// # Errors
+/// 
+/// Returns `SolanaPmError::InvalidPath` if no IDL file is found, none of the
+/// IDLs found is named after the program (listing the ones that were), or
+/// directory reading fails, and `SolanaPmError::InvalidIdl` if only non-IDL
+/// JSON files are found.
+fn find_idl_file(program_name: &str) -> Result<String> {
+    let normalized_name = program_name.replace('-', "_");
+    let mut candidates = find_idl_candidates()?;
+    
+    let Some(index) = candidates.iter()
+        .position(|candidate| candidate.name.replace('-', "_") == normalized_name)
+    else {
+        let found = candidates.iter()
+            .map(|candidate| format!("{} ({})", candidate.name, candidate.path))
+            .collect::<Vec<_>>()
+            .join(", ");
+        return Err(SolanaPmError::InvalidPath(format!(
+            "No IDL named '{}' found. Found: {}. Set `idl` in {} to the IDL path if its metadata.name differs from the program name.",
+            program_name, found, SOLANA_PROGRAMS_TOML
+        )));
+    };
+    Ok(candidates.swap_remove(index).path)
+}
+
+/// Downloads an IDL for `publish --from-idl-url`.
+/// 
+/// # Arguments
+/// 
+/// * `url` - The http(s) URL the IDL is served at
+/// 
+/// # Returns
+/// 
+/// Returns the response body, or an error if the URL is not http(s), the
+/// request fails or returns an error status, or the IDL is larger than the
+/// registry accepts. The body is streamed and abandoned as soon as it passes
+/// the limit, so a server that sends no `Content-Length` cannot exhaust memory.
+async fn download_idl(url: &str) -> Result<String> {
+    if !url.starts_with("https://") && !url.starts_with("http://") {
+        return Err(SolanaPmError::InvalidPath(format!("--from-idl-url must be an http(s) URL, got '{}'", url)));
+    }
+    
+    let response = http_client()?
+        .get(url)
+        .send_logged()
+        .await
+        .map_err(|e| SolanaPmError::InvalidPath(format!("Failed to download IDL from {}: {}", url, describe_request_error(&e))))?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(SolanaPmError::InvalidPath(format!("Failed to download IDL from {}: server returned {}", url, status)));
+    }
+    let too_large = || SolanaPmError::InvalidIdl(format!(
+        "IDL at {} is larger than the registry's upload limit of {}",
+        url, HumanBytes(MAX_UPLOAD_IDL_SIZE)
+    ));
+    if response.content_length().is_some_and(|length| length > MAX_UPLOAD_IDL_SIZE) {
+        return Err(too_large());
+    }
+    
+    // Without a Content-Length, stop reading once the body passes the limit
+    let mut body = Vec::new();
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk
+            .map_err(|e| SolanaPmError::InvalidPath(format!("Failed to download IDL from {}: {}", url, describe_request_error(&e))))?;
+        if body.len() as u64 + chunk.len() as u64 > MAX_UPLOAD_IDL_SIZE {
+            return Err(too_large());
+        }
+        body.extend_from_slice(&chunk);
+    }
+    
+    String::from_utf8(body)
+        .map_err(|e| SolanaPmError::InvalidIdl(format!("IDL at {} is not valid UTF-8: {}", url, e)))
+}
+
+/// Prints a summary of what is about to be published.
+/// 
+/// # Arguments
+/// 
+/// * `program` - The program configuration from SolanaPrograms.toml
+/// * `authority_pubkey` - The public key of the signing authority
+/// * `idl_file_path` - The IDL file that will be uploaded
+/// * `idl_size` - The serialized size of the uploaded IDL in bytes
+/// * `git_revision` - The git revision being published, if in a git repository
+/// * `overrides` - The TOML values replaced by `--name`, `--version`, and `--network`
+/// * `options` - Publish options containing the visibility and output flags
+fn print_publish_summary(program: &ProgramConfig, authority_pubkey: &str, idl_file_path: &str, idl_size: u64, git_revision: Option<&GitRevision>, overrides: &MetadataOverrides, options: &PublishOptions) {
+    let json = is_json_output();
+    let override_note = |flag: &str, toml_value: &Option<String>| match toml_value {
+        Some(toml_value) => format!(" (from {}; {} has {})", flag, SOLANA_PROGRAMS_TOML, toml_value),
+        None => String::new(),
+    };
+    
+    report(json, &format!("\n{}", CliStyle::header("Publish Summary")));
+    report(json, &format!("  Name:       {}{}", CliStyle::package(&program.name), override_note("--name", &overrides.name)));
+    report(json, &format!("  Version:    {}{}", CliStyle::version(&program.version), override_note("--version", &overrides.version)));
+    report(json, &format!("  Network:    {}{}", CliStyle::highlight(&program.network), override_note("--network", &overrides.network)));
+    report(json, &format!("  Program ID: {}", CliStyle::highlight(&program.program_id)));
+    report(json, &format!("  Authority:  {}", CliStyle::highlight(authority_pubkey)));
+    report(json, &format!("  IDL file:   {} ({})", CliStyle::path(idl_file_path), HumanBytes(idl_size)));
+    report(json, &format!("  Repository: {}", CliStyle::path(&program.repository)));
+    if let Some(revision) = git_revision {
+        report(json, &format!(
+            "  Commit:     {}{}",
+            CliStyle::highlight(&revision.commit),
+            if revision.is_dirty() { " (dirty)" } else { "" }
+        ));
+    }
+    report(json, &format!("  Visibility: {}", CliStyle::highlight(options.visibility.as_str())));
+    report(json, "");
+}
+
+/// Runs the `pre_publish` hook commands from SolanaPrograms.toml.
+/// 
+/// Each command runs through the platform shell in order, with its output
+/// streamed to the terminal (to stderr in JSON mode). The first command that
+/// exits unsuccessfully aborts the publish. With `--no-hooks` the hooks are
+/// skipped, and with `--dry-run` they are only listed.
+/// 
+/// # Arguments
+/// 
+/// * `hooks` - The hooks section of SolanaPrograms.toml
+/// * `options` - Publish options containing the hook and output flags
+/// 
+/// # Returns
+/// 
+/// Returns `Ok(())` if all hooks succeeded or were skipped, or an error if a
+/// hook could not be started or exited unsuccessfully.
+fn run_pre_publish_hooks(hooks: &HooksConfig, options: &PublishOptions) -> Result<()> {
+    let _phase = phase("Pre-publish hooks");
+    let commands = &hooks.pre_publish;
+    if commands.is_empty() {
+        return Ok(());
+    }
+    
+    if options.no_hooks {
+        statusln!("{}", CliStyle::warning(&format!(
+            "Skipping {} pre-publish hook{} (--no-hooks)",
+            commands.len(),
+            if commands.len() == 1 { "" } else { "s" }
+        )));
+        return Ok(());
+    }
+    
+    if options.dry_run {
+        report(is_json_output(), &CliStyle::info("Pre-publish hooks (not run in a dry run):"));
+        for command in commands {
+            report(is_json_output(), &format!("  {}", CliStyle::command(command)));
+        }
+        return Ok(());
+    }
+    
+    for (index, command) in commands.iter().enumerate() {
+        statusln!("{}", CliStyle::progress(&format!(
+            "Running pre-publish hook {}/{}: {}",
+            index + 1,
+            commands.len(),
+            CliStyle::command(command)
+        )));
+        
+        let status = shell_command(command).status()
+            .map_err(|e| SolanaPmError::HookFailed(format!("Failed to run '{}': {}", command, e)))?;
+        
+        if !status.success() {
+            let code = status.code().map_or_else(|| "a signal".to_string(), |code| format!("exit code {}", code));
+            return Err(SolanaPmError::HookFailed(format!(
+                "Pre-publish hook '{}' failed with {}. Fix the problem or pass --no-hooks to skip hooks.",
+                command, code
+            )));
+        }
+    }
+    
+    Ok(())
+}
+
+/// Warns about uncommitted changes and asks whether to publish anyway.
+/// 
+/// The uploaded IDL may not match any committed source when the working tree
+/// is dirty. `--allow-dirty` skips the prompt; without it, non-interactive
+/// sessions (including JSON mode) fail.
+/// 
+/// # Arguments
+/// 
+/// * `revision` - The git revision with uncommitted changes
+/// * `options` - Publish options containing the `allow_dirty` flag
+/// 
+/// # Returns
+/// 
+/// Returns `Ok(true)` to continue, `Ok(false)` if the user declined, or an
+/// error if confirmation is required but cannot be requested.
+fn confirm_dirty_tree(revision: &GitRevision, options: &PublishOptions) -> Result<bool> {
+    const MAX_LISTED_FILES: usize = 10;
+    
+    report(is_json_output(), &CliStyle::warning(&format!(
+        "The git working tree has {} uncommitted change{}; the published IDL may not match commit {}",
+        revision.changed_files.len(),
+        if revision.changed_files.len() == 1 { "" } else { "s" },
+        revision.commit
+    )));
+    for path in revision.changed_files.iter().take(MAX_LISTED_FILES) {
+        report(is_json_output(), &format!("  {}", CliStyle::path(path)));
+    }
+    if revision.changed_files.len() > MAX_LISTED_FILES {
+        report(is_json_output(), &format!("  ...and {} more", revision.changed_files.len() - MAX_LISTED_FILES));
+    }
+    
+    if options.allow_dirty {
+        return Ok(true);
+    }
+    
+    if is_json_output() || !std::io::stdin().is_terminal() {
+        return Err(SolanaPmError::DataMissing(
+            "Refusing to publish from a dirty working tree. Commit your changes or pass --allow-dirty.".to_string()
+        ));
+    }
+    
+    confirm_action("Publish with uncommitted changes?")
+}
+
+/// Prints the registry listing and next steps after a successful publish.
+/// 
+/// # Arguments
+/// 
+/// * `published` - The parsed registry response
+/// * `program` - The program configuration that was published
+/// * `visibility` - Who can discover the published version
+fn print_publish_details(published: &PublishResponse, program: &ProgramConfig, visibility: Visibility) {
+    if let Some(url) = &published.url {
+        outln!("Registry URL: {}", CliStyle::path(url));
+    }
+    if let Some(id) = &published.id {
+        outln!("Listing ID: {}", CliStyle::highlight(id));
+    }
+    if let Some(published_at) = &published.published_at {
+        outln!("Published at: {}", published_at);
+    }
+    
+    let version = published.version.as_deref().unwrap_or(&program.version);
+    outln!("\n{}", CliStyle::header("Next Steps"));
+    outln!("Consumers can add this program with:");
+    outln!("  {}", CliStyle::command(&format!(
+        "solpm add {}@{} --network {}",
+        program.name, version, program.network
+    )));
+    
+    if !matches!(visibility, Visibility::Public) {
+        outln!("\nThis version is {} and will not appear in search or resolve as latest.", visibility.as_str());
+        outln!("Make it public with:");
+        outln!("  {}", CliStyle::command(&format!("solpm release {}@{}", program.name, version)));
+    }
+}
+
+/// Locates the compiled program binary to hash for publication.
+/// 
+/// An explicitly provided path must exist. Otherwise the standard Anchor
+/// output location `target/deploy/<name>.so` is used when present.
+/// 
+/// # Arguments
+/// 
+/// * `program_name` - The program name from SolanaPrograms.toml
+/// * `binary_path` - Optional explicit path to the binary
+/// 
+/// # Returns
+/// 
+/// Returns `Some(path)` if a binary was found, `None` if no binary is
+/// available, or an error if an explicit path does not exist.
+fn find_program_binary(program_name: &str, binary_path: Option<&str>) -> Result<Option<String>> {
+    if let Some(path) = binary_path {
+        let expanded_path = expand_path(path);
+        if !std::path::Path::new(&expanded_path).is_file() {
+            return Err(SolanaPmError::InvalidPath(format!("Program binary not found: {}", expanded_path)));
+        }
+        return Ok(Some(expanded_path));
+    }
+    
+    let default_path = format!("target/deploy/{}.so", program_name);
+    if std::path::Path::new(&default_path).is_file() {
+        Ok(Some(default_path))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Compares a local program binary against the bytes deployed on-chain.
+/// 
+/// The loader pads program data accounts with zeros, so trailing zero bytes
+/// are ignored on both sides before hashing. A mismatch only produces a
+/// warning, since the author may be publishing ahead of an upgrade.
+/// 
+/// # Arguments
+/// 
+/// * `program_id` - The base58 program ID
+/// * `network` - The network the program is deployed to
+/// * `local_bytes` - The contents of the local program binary
+/// * `json` - Whether JSON mode is enabled
+/// 
+/// # Returns
+/// 
+/// Returns `Ok(())` after reporting the result, or an error if the deployed
+/// program cannot be fetched.
+async fn verify_deployed_binary(program_id: &str, network: &str, local_bytes: &[u8], json: bool) -> Result<()> {
+    let spinner = new_spinner(json, "Fetching deployed program from RPC...");
+    let deployed_bytes = get_deployed_program_bytes(&rpc_url_for_network(network)?, program_id).await;
+    spinner.finish_and_clear();
+    let deployed_bytes = deployed_bytes?;
+    
+    let trim_padding = |bytes: &[u8]| -> Vec<u8> {
+        let end = bytes.iter().rposition(|b| *b != 0).map_or(0, |i| i + 1);
+        bytes[..end].to_vec()
+    };
+    
+    let local_hash = Sha256::digest(trim_padding(local_bytes));
+    let deployed_hash = Sha256::digest(trim_padding(&deployed_bytes));
+    
+    if local_hash == deployed_hash {
+        report(json, &CliStyle::success(&format!(
+            "Local binary matches the program deployed on {}",
+            CliStyle::highlight(network)
+        )));
+    } else {
+        statusln!("{}", CliStyle::warning(&format!(
+            "Local binary does not match the program deployed on {} (deployed sha256 {:x})",
+            network, deployed_hash
+        )));
+    }
+    
+    Ok(())
+}
+
+/// Resolves the release notes to attach to a publish request.
+/// 
+/// Notes are taken from the first available source:
+/// 1. The `--notes` flag
+/// 2. The file given by `--notes-file`
+/// 3. The section of CHANGELOG.md matching the version being published
+/// 
+/// Explicit notes exceeding the size cap are rejected, while notes extracted
+/// from the changelog are truncated with a warning.
+/// 
+/// # Arguments
+/// 
+/// * `options` - Publish options containing the notes flags
+/// * `version` - The version being published
+/// 
+/// # Returns
+/// 
+/// Returns `Some(notes)` if release notes were found, `None` otherwise, or an
+/// error if the notes file cannot be read or explicit notes are too large.
+fn resolve_release_notes(options: &PublishOptions, version: &str) -> Result<Option<String>> {
+    let explicit_notes = if let Some(notes) = &options.notes {
+        Some(notes.clone())
+    } else if let Some(path) = &options.notes_file {
+        Some(fs::read_to_string(expand_path(path))
+            .map_err(|e| SolanaPmError::InvalidPath(format!("Failed to read notes file '{}': {}", path, e)))?)
+    } else {
+        None
+    };
+    
+    if let Some(notes) = explicit_notes {
+        let notes = notes.trim().to_string();
+        if notes.len() > MAX_RELEASE_NOTES_BYTES {
+            return Err(SolanaPmError::DataMissing(format!(
+                "Release notes are {} bytes, exceeding the {} byte limit",
+                notes.len(), MAX_RELEASE_NOTES_BYTES
+            )));
+        }
+        return Ok(if notes.is_empty() { None } else { Some(notes) });
+    }
+    
+    let Ok(changelog) = fs::read_to_string(CHANGELOG_FILE) else {
+        return Ok(None);
+    };
+    
+    let Some(mut notes) = extract_changelog_section(&changelog, version) else {
+        return Ok(None);
+    };
+    
+    if notes.len() > MAX_RELEASE_NOTES_BYTES {
+        let mut end = MAX_RELEASE_NOTES_BYTES;
+        while !notes.is_char_boundary(end) {
+            end -= 1;
+        }
+        notes.truncate(end);
+        statusln!("{}", CliStyle::warning(&format!(
+            "Release notes from {} truncated to {} bytes",
+            CHANGELOG_FILE, MAX_RELEASE_NOTES_BYTES
+        )));
+    }
+    
+    statusln!("{}", CliStyle::info(&format!(
+        "Using release notes for {} from {}",
+        CliStyle::version(version),
+        CliStyle::path(CHANGELOG_FILE)
+    )));
+    
+    Ok(Some(notes))
+}
+
+/// Extracts the section for a version from a Keep a Changelog formatted file.
+/// 
+/// Matches level-two headings such as `## [1.2.0] - 2024-05-01`, `## 1.2.0`,
+/// or `## v1.2.0`, and returns everything up to the next level-two heading.
+/// 
+/// # Arguments
+/// 
+/// * `changelog` - The contents of the changelog
+/// * `version` - The version to look up
+/// 
+/// # Returns
+/// 
+/// Returns the trimmed section body, or `None` if the version has no entry.
+fn extract_changelog_section(changelog: &str, version: &str) -> Option<String> {
+    let mut section = Vec::new();
+    let mut in_section = false;
+    
+    for line in changelog.lines() {
+        if let Some(heading) = line.strip_prefix("## ") {
+            if in_section {
+                break;
+            }
+            let heading_version = heading
+                .split_whitespace()
+                .next()
+                .unwrap_or("")
+                .trim_matches(|c| c == '[' || c == ']')
+                .trim_start_matches('v');
+            in_section = heading_version == version;
+            continue;
+        }
+        if in_section {
+            section.push(line);
+        }
+    }
+    
+    let notes = section.join("\n").trim().to_string();
+    if notes.is_empty() { None } else { Some(notes) }
+}
+
+/// Verifies that the signing keypair is the program's on-chain upgrade authority.
+/// 
+/// A signature only proves ownership if it comes from the key that controls
+/// the program. Mismatches fail unless explicitly allowed, which covers
+/// programs whose authority was burned or moved to a multisig.
+/// 
+/// # Arguments
+/// 
+/// * `program_id` - The base58 program ID
+/// * `network` - The network the program is deployed to
+/// * `authority_pubkey` - The public key of the signing keypair
+/// * `allow_mismatch` - Whether to warn instead of failing on a mismatch
+/// * `json` - Whether JSON mode is enabled
+/// 
+/// # Returns
+/// 
+/// Returns `Ok(())` if the authority matches or a mismatch is allowed,
+/// `SolanaPmError::DataMissing` (a validation failure) if the authority
+/// differs, or an error if it cannot be fetched.
+async fn verify_upgrade_authority(program_id: &str, network: &str, authority_pubkey: &Pubkey, allow_mismatch: bool, json: bool) -> Result<()> {
+    let spinner = new_spinner(json, "Checking on-chain upgrade authority...");
+    let upgrade_authority = get_program_upgrade_authority(&rpc_url_for_network(network)?, program_id).await;
+    spinner.finish_and_clear();
+    
+    let on_chain = match upgrade_authority? {
+        Some(authority) if authority == *authority_pubkey => {
+            report(json, &CliStyle::success(&format!(
+                "Signing keypair is the upgrade authority of {}",
+                CliStyle::highlight(program_id)
+            )));
+            return Ok(());
+        }
+        Some(authority) => authority.to_string(),
+        None => "none (program is immutable)".to_string(),
+    };
+    
+    let message = format!(
+        "Signing keypair {} is not the upgrade authority of {} (on-chain authority: {})",
+        authority_pubkey, program_id, on_chain
+    );
+    
+    if allow_mismatch {
+        statusln!("{}", CliStyle::warning(&format!(
+            "{}. Continuing because --allow-authority-mismatch was passed; the registry may reject this publish.",
+            message
+        )));
+        Ok(())
+    } else {
+        Err(SolanaPmError::DataMissing(format!(
+            "{}. Use the upgrade authority keypair, or pass --allow-authority-mismatch if the authority was burned or transferred.",
+            message
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    /// A fixed keypair, so every format encodes the same key.
+    fn test_keypair() -> Keypair {
+        keypair_from_seed(&[7u8; 32]).unwrap()
+    }
+    
+    #[test]
+    fn parses_json_byte_array() {
+        let keypair = test_keypair();
+        let data = serde_json::to_vec(&keypair.to_bytes().to_vec()).unwrap();
+        assert_eq!(parse_keypair_bytes(&data).unwrap().pubkey(), keypair.pubkey());
+    }
+    
+    #[test]
+    fn parses_json_seed_array() {
+        let data = serde_json::to_vec(&[7u8; 32].to_vec()).unwrap();
+        assert_eq!(parse_keypair_bytes(&data).unwrap().pubkey(), test_keypair().pubkey());
+    }
+    
+    #[test]
+    fn parses_secret_key_object() {
+        let keypair = test_keypair();
+        let as_array = json!({ "secretKey": keypair.to_bytes().to_vec() }).to_string();
+        let as_base58 = json!({ "secretKey": keypair.to_base58_string() }).to_string();
+        
+        assert_eq!(parse_keypair_bytes(as_array.as_bytes()).unwrap().pubkey(), keypair.pubkey());
+        assert_eq!(parse_keypair_bytes(as_base58.as_bytes()).unwrap().pubkey(), keypair.pubkey());
+    }
+    
+    #[test]
+    fn parses_base58_string() {
+        let keypair = test_keypair();
+        let data = format!("{}\n", keypair.to_base58_string());
+        assert_eq!(parse_keypair_bytes(data.as_bytes()).unwrap().pubkey(), keypair.pubkey());
+    }
+    
+    #[test]
+    fn parses_raw_bytes() {
+        let keypair = test_keypair();
+        assert_eq!(parse_keypair_bytes(&keypair.to_bytes()).unwrap().pubkey(), keypair.pubkey());
+        assert_eq!(parse_keypair_bytes(&[7u8; 32]).unwrap().pubkey(), keypair.pubkey());
+    }
+    
+    #[test]
+    fn rejects_invalid_input() {
+        let error = parse_keypair_bytes(b"not a keypair").unwrap_err();
+        assert!(matches!(&error, SolanaPmError::InvalidPath(msg) if msg.contains("Tried:")), "{}", error);
+        
+        let short_array = serde_json::to_vec(&[1u8; 10].to_vec()).unwrap();
+        let error = parse_keypair_bytes(&short_array).unwrap_err();
+        assert!(matches!(&error, SolanaPmError::InvalidPath(msg) if msg.contains("found 10 bytes")), "{}", error);
+        
+        let bad_secret_key = json!({ "secretKey": "not base58!" }).to_string();
+        assert!(parse_keypair_bytes(bad_secret_key.as_bytes()).is_err());
+    }
+    
+    #[test]
+    fn load_keypair_from_file_names_the_file() {
+        let keypair = test_keypair();
+        let dir = tempfile::tempdir().unwrap();
+        let valid = dir.path().join("id.json");
+        let corrupt = dir.path().join("corrupt.json");
+        fs::write(&valid, serde_json::to_vec(&keypair.to_bytes().to_vec()).unwrap()).unwrap();
+        fs::write(&corrupt, b"{ not json").unwrap();
+        
+        let loaded = load_keypair_from_file(valid.to_str().unwrap()).unwrap();
+        assert_eq!(loaded.pubkey(), keypair.pubkey());
+        
+        let error = load_keypair_from_file(corrupt.to_str().unwrap()).unwrap_err();
+        assert!(error.to_string().contains("corrupt.json"), "{}", error);
+        
+        let missing = dir.path().join("missing.json");
+        assert!(load_keypair_from_file(missing.to_str().unwrap()).is_err());
+    }
+    
+    /// Serves one HTTP response on a local port, without a `Content-Length`,
+    /// and returns the URL it is served at.
+    async fn serve_once(status: &'static str, body_size: usize) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = [0u8; 4096];
+            let _ = socket.read(&mut request).await;
+            let head = format!("HTTP/1.1 {}\r\nContent-Type: application/json\r\nConnection: close\r\n\r\n", status);
+            let _ = socket.write_all(head.as_bytes()).await;
+            // The client may stop reading early, so write errors are expected
+            let chunk = vec![b' '; 64 * 1024];
+            let mut sent = 0;
+            while sent < body_size {
+                let len = chunk.len().min(body_size - sent);
+                if socket.write_all(&chunk[..len]).await.is_err() {
+                    return;
+                }
+                sent += len;
+            }
+        });
+        format!("http://{}/idl.json", address)
+    }
+    
+    #[tokio::test]
+    async fn download_idl_reads_a_body_without_content_length() {
+        let url = serve_once("200 OK", 1024).await;
+        assert_eq!(download_idl(&url).await.unwrap().len(), 1024);
+    }
+    
+    #[tokio::test]
+    async fn download_idl_stops_once_the_body_passes_the_limit() {
+        let url = serve_once("200 OK", MAX_UPLOAD_IDL_SIZE as usize + 1024 * 1024).await;
+        let error = download_idl(&url).await.err().unwrap();
+        assert!(matches!(error, SolanaPmError::InvalidIdl(_)), "{:?}", error);
+        assert!(error.to_string().contains("larger than the registry's upload limit"), "{}", error);
+    }
+    
+    #[tokio::test]
+    async fn download_idl_reports_error_statuses() {
+        let url = serve_once("404 Not Found", 0).await;
+        let error = download_idl(&url).await.err().unwrap();
+        assert!(error.to_string().contains("server returned 404"), "{}", error);
+        
+        let error = download_idl("ftp://example.com/idl.json").await.err().unwrap();
+        assert!(error.to_string().contains("must be an http(s) URL"), "{}", error);
+    }
+}
@@ -0,0 +1,929 @@
+//! # Registry Module
+//!
+//! This module resolves which registry solpm talks to.
+//!
+//! The base URL is taken from the first of:
+//! 1. The `--registry` flag
+//! 2. The `SOLPM_REGISTRY_URL` environment variable
+//! 3. `registry` in the project `[solpm]` settings or `.solpmrc`
+//! 4. `registry` in the global `~/.solpm/config.toml`
+//! 5. The public registry
+//!
+//! Every endpoint is built by joining a path onto the resolved base URL, so
+//! self-hosted and staging registries work without changing the binary. The
+//! result is resolved once per process and logged with `-v`.
+//!
+//! Reads (install downloads, version lookups, and search) fall over to the
+//! `mirrors` listed in `~/.solpm/config.toml`, in order, when the registry
+//! cannot be reached, times out, or answers with a server error. Writes
+//! (publish, release, and login) always go to the registry itself.
+//!
+//! Commands talk to the registry through the [`RegistryApi`] trait, which
+//! [`RegistryClient`] implements over HTTP with the base URL, mirrors, and API
+//! token it was created with. The token, such as a one-off `--registry-token`,
+//! is only sent to the registry itself, never to a mirror. Commands take an
+//! `impl RegistryApi`, so a client pointed at a local mock server can stand in
+//! for the real registry.
+//!
+//! Failed registry responses become `SolanaPmError::Registry` through
+//! [`registry_error`], which keeps the status and parses the registry's
+//! `{"error", "message", "code"}` error body when there is one.
+
+use crate::commands::config::{ConfigOrigin, global_config, project_settings};
+use crate::commands::constants::{AUTH_VERIFY_PATH, DEFAULT_REGISTRY_URL, PROGRAMS_PATH, REGISTRY_ENV_VAR, SEARCH_PROGRAMS_PATH, TIMEOUT_ENV_VAR};
+use crate::commands::download::download_install_response;
+use crate::commands::hooks::load_solpmrc;
+use crate::commands::http::{SendLogged, http_client, request_error};
+use crate::commands::types::{AuthVerifyResponse, IdlValidators, ProgramResponse, RegistryErrorResponse, SearchResponse, SearchResult};
+use crate::error::{Context, Result, SolanaPmError};
+use crate::output::{is_quiet, phase};
+use crate::utils::{CliProgress, CliStyle};
+use futures_util::StreamExt;
+use indicatif::{HumanBytes, ProgressBar};
+use reqwest::{RequestBuilder, StatusCode};
+use reqwest::header::{AUTHORIZATION, CONTENT_LENGTH, CONTENT_TYPE};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// The most bytes of a failed response's body read into its error.
+const MAX_ERROR_BODY_BYTES: usize = 64 * 1024;
+
+/// The most characters of an unstructured error body shown in its error.
+const ERROR_EXCERPT_CHARS: usize = 200;
+
+/// Size of the chunks a publish upload is streamed in.
+const UPLOAD_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Slowest upload speed the publish timeout allows for.
+const UPLOAD_MIN_BYTES_PER_SEC: u64 = 32 * 1024;
+
+/// The `--registry` flag, set once by `main` before any command runs.
+static REGISTRY_FLAG: OnceLock<String> = OnceLock::new();
+
+/// The resolved base URL and where it came from.
+static RESOLVED_REGISTRY: OnceLock<(String, ConfigOrigin)> = OnceLock::new();
+
+/// Mirror base URLs from the global config.
+static RESOLVED_MIRRORS: OnceLock<Vec<String>> = OnceLock::new();
+
+/// Records the `--registry` flag so it takes precedence over every other source.
+/// 
+/// # Arguments
+/// 
+/// * `url` - The URL passed with `--registry`, if any
+pub fn set_registry_flag(url: Option<String>) {
+    if let Some(url) = url {
+        let _ = REGISTRY_FLAG.set(url);
+    }
+}
+
+/// Returns the base URL of the active registry, without a trailing slash.
+/// 
+/// # Returns
+/// 
+/// Returns the resolved URL, or an error if the chosen value is not an
+/// http(s) URL or a config file cannot be read.
+/// 
+/// # Examples
+/// 
+/// ```no_run
+/// use solpm::commands::registry::registry_base_url;
+/// 
+/// # fn example() -> solpm::error::Result<()> {
+/// let base_url = registry_base_url()?;
+/// println!("Publishing to {}", base_url);
+/// # Ok(())
+/// # }
+/// ```
+pub fn registry_base_url() -> Result<String> {
+    Ok(registry_setting()?.0)
+}
+
+/// Returns the base URL of the active registry and where it was configured.
+/// 
+/// # Returns
+/// 
+/// Returns the resolved URL and its origin, or an error if the chosen value
+/// is not an http(s) URL or a config file cannot be read.
+pub fn registry_setting() -> Result<(String, ConfigOrigin)> {
+    if let Some(setting) = RESOLVED_REGISTRY.get() {
+        return Ok(setting.clone());
+    }
+    
+    let (url, origin) = resolve_registry()?;
+    let source = match origin {
+        ConfigOrigin::Flag => "--registry",
+        ConfigOrigin::Env => REGISTRY_ENV_VAR,
+        ConfigOrigin::Project => "the project settings",
+        ConfigOrigin::Global => "~/.solpm/config.toml",
+        ConfigOrigin::Default => "the default",
+    };
+    let url = normalize_registry_url(&url)
+        .map_err(|problem| SolanaPmError::InvalidPath(format!("Invalid registry URL from {}: {}", source, problem)))?;
+    
+    tracing::debug!("Registry: {} (from {})", url, source);
+    
+    Ok(RESOLVED_REGISTRY.get_or_init(|| (url, origin)).clone())
+}
+
+/// Joins an endpoint path onto the active registry's base URL.
+/// 
+/// # Arguments
+/// 
+/// * `path` - The endpoint path, e.g. `programs` or `auth/verify`
+/// 
+/// # Returns
+/// 
+/// Returns the full endpoint URL, or an error if the registry cannot be resolved.
+/// 
+/// # Examples
+/// 
+/// ```no_run
+/// use solpm::commands::constants::PROGRAMS_PATH;
+/// use solpm::commands::registry::registry_url;
+/// 
+/// # fn example() -> solpm::error::Result<()> {
+/// # let name = "token-vesting";
+/// let url = registry_url(&format!("{}/{}/latest/install", PROGRAMS_PATH, name))?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn registry_url(path: &str) -> Result<String> {
+    Ok(format!("{}/{}", registry_base_url()?, path.trim_start_matches('/')))
+}
+
+/// Returns the base URLs of the configured mirrors, in the order they are tried.
+/// 
+/// Mirrors that repeat the active registry are left out.
+/// 
+/// # Returns
+/// 
+/// Returns the mirror URLs without trailing slashes, or an error if a mirror
+/// is not an http(s) URL or a config file cannot be read.
+pub fn mirror_base_urls() -> Result<Vec<String>> {
+    if let Some(mirrors) = RESOLVED_MIRRORS.get() {
+        return Ok(mirrors.clone());
+    }
+    
+    let registry = registry_base_url()?;
+    let mut mirrors: Vec<String> = Vec::new();
+    for mirror in global_config()?.mirrors.iter().flatten() {
+        let mirror = normalize_registry_url(mirror)
+            .map_err(|problem| SolanaPmError::InvalidPath(format!("Invalid mirror URL in ~/.solpm/config.toml: {}", problem)))?;
+        if mirror != registry && !mirrors.contains(&mirror) {
+            mirrors.push(mirror);
+        }
+    }
+    
+    if !mirrors.is_empty() {
+        tracing::debug!("Mirrors: {}", mirrors.join(", "));
+    }
+    
+    Ok(RESOLVED_MIRRORS.get_or_init(|| mirrors).clone())
+}
+
+/// The registry requests solpm makes, implemented over HTTP by [`RegistryClient`].
+/// 
+/// Commands take an `impl RegistryApi` rather than building requests
+/// themselves, so they can run against any implementation, such as a
+/// `RegistryClient` pointed at a mock server.
+/// 
+/// The futures are awaited on the calling task and never spawned, so they
+/// carry no `Send` bound.
+#[allow(async_fn_in_trait)]
+pub trait RegistryApi {
+    /// Returns the registry base URL, recorded as the source of installed programs.
+    fn base_url(&self) -> &str;
+    
+    /// Starts a POST request to an endpoint URL on the registry or a mirror,
+    /// with the API token if it goes to the registry itself.
+    fn post(&self, url: &str) -> RequestBuilder;
+    
+    /// Looks a published version up without counting a download.
+    /// 
+    /// `version` is an exact version or `latest`. Fails with
+    /// `SolanaPmError::ProgramNotFound` if the program or version does not exist.
+    async fn get_program(&self, name: &str, version: &str, network: &str) -> Result<ProgramResponse>;
+    
+    /// Downloads a version's install response into the IDL's temporary file,
+    /// counting a download, and returns the parsed response.
+    /// 
+    /// With `request.validators`, returns `None` when the registry answers
+    /// `304 Not Modified`.
+    async fn install_program(&self, request: &InstallRequest<'_>) -> Result<Option<ProgramResponse>>;
+    
+    /// Uploads a serialized publish request.
+    /// 
+    /// A response with an error status is returned as
+    /// `PublishUploadResponse::Rejected` so the caller can report its body.
+    async fn publish_program(&self, upload: PublishUpload) -> Result<PublishUploadResponse>;
+    
+    /// Returns whether an API token is valid and what it may do.
+    async fn verify_token(&self, token: &str) -> Result<AuthVerifyResponse>;
+    
+    /// Searches the programs published on a network.
+    async fn search(&self, query: &str, network: &str) -> Result<Vec<SearchResult>>;
+    
+    /// Lists the published versions of a program on a network.
+    async fn versions(&self, name: &str, network: &str) -> Result<Vec<String>>;
+}
+
+/// A download of a version's install response; see [`RegistryApi::install_program`].
+pub struct InstallRequest<'a> {
+    /// The program name
+    pub name: &'a str,
+    /// The version to install, or `None` for the latest
+    pub version: Option<&'a str>,
+    /// The network to install from
+    pub network: &'a str,
+    /// The anonymous project hash the registry counts downloads by
+    pub project_hash: &'a str,
+    /// Where the IDL file goes; the download is streamed into its `.tmp` file
+    pub idl_file_path: &'a str,
+    /// The maximum response size in bytes
+    pub max_idl_size: u64,
+    /// A spinner to show download progress on, if any
+    pub progress: Option<&'a ProgressBar>,
+    /// The validators of the IDL already installed, if any; the download is
+    /// then conditional and yields nothing when the IDL has not changed
+    pub validators: Option<&'a IdlValidators>,
+}
+
+/// A serialized publish request; see [`RegistryApi::publish_program`].
+pub struct PublishUpload {
+    /// The serialized `UploadProgramRequest`
+    pub body: Vec<u8>,
+    /// The request timeout for an empty body; it grows with the body size
+    pub base_timeout: Duration,
+    /// Whether to show an upload progress bar
+    pub show_progress: bool,
+}
+
+/// How the registry answered a publish upload.
+pub enum PublishUploadResponse {
+    /// The version was published; `body` is the registry's response
+    Accepted { body: String },
+    /// The registry refused the upload
+    Rejected { status: StatusCode, content_type: Option<String>, body: String },
+}
+
+/// The registry API over HTTP.
+/// 
+/// Reads fall over to the client's mirrors as described in the module
+/// documentation; uploads only go to the registry itself. The API token, if
+/// any, is sent with lookups and uploads.
+pub struct RegistryClient {
+    client: reqwest::Client,
+    base_url: String,
+    mirrors: Vec<String>,
+    token: Option<String>,
+}
+
+impl RegistryClient {
+    /// Creates a client for the active registry and its configured mirrors.
+    /// 
+    /// # Returns
+    /// 
+    /// Returns the client, or an error if the registry, a mirror, or the HTTP
+    /// settings are misconfigured.
+    /// 
+    /// # Examples
+    /// 
+    /// ```no_run
+    /// use solpm::commands::registry::{RegistryApi, RegistryClient};
+    /// 
+    /// # async fn example() -> solpm::error::Result<()> {
+    /// let registry = RegistryClient::new()?;
+    /// let results = registry.search("vesting", "devnet").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            client: http_client()?,
+            base_url: registry_base_url()?,
+            mirrors: mirror_base_urls()?,
+            token: None,
+        })
+    }
+    
+    /// Creates a client for the given registry, or for the active registry
+    /// and its mirrors when none is given.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `registry` - A registry base URL chosen by the caller, if any; it is
+    ///   used without mirrors
+    /// 
+    /// # Returns
+    /// 
+    /// Returns the client, or an error if the URL is not an http(s) URL or
+    /// the active registry or HTTP settings are misconfigured.
+    pub fn for_registry(registry: Option<&str>) -> Result<Self> {
+        let Some(registry) = registry else {
+            return Self::new();
+        };
+        let base_url = normalize_registry_url(registry)
+            .map_err(|problem| SolanaPmError::InvalidPath(format!("Invalid registry URL: {}", problem)))?;
+        Ok(Self::with_base_url(http_client()?, base_url))
+    }
+    
+    /// Creates a client for a single registry, without mirrors.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `client` - The HTTP client to send requests with
+    /// * `base_url` - The registry base URL, without a trailing slash
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use solpm::commands::registry::{RegistryApi, RegistryClient};
+    /// 
+    /// // Talk to a mock registry on a local port
+    /// let registry = RegistryClient::with_base_url(reqwest::Client::new(), "http://127.0.0.1:8765");
+    /// assert_eq!(registry.base_url(), "http://127.0.0.1:8765");
+    /// ```
+    pub fn with_base_url(client: reqwest::Client, base_url: impl Into<String>) -> Self {
+        Self { client, base_url: base_url.into(), mirrors: Vec::new(), token: None }
+    }
+    
+    /// Sends an API token with lookups and uploads.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `token` - The registry API token
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+    
+    /// Sends an API token, if one was given, with lookups and downloads.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `token` - The `--registry-token` value, if any
+    pub fn with_optional_token(mut self, token: Option<&str>) -> Self {
+        self.token = token.map(str::to_string);
+        self
+    }
+    
+    /// Joins an endpoint path onto the registry base URL.
+    fn url(&self, path: &str) -> String {
+        format!("{}/{}", self.base_url, path.trim_start_matches('/'))
+    }
+    
+    /// Adds the API token, if any, to a request for the registry itself.
+    /// 
+    /// Mirrors never see the token. It is sent as a sensitive header, so it
+    /// is left out of debug output.
+    fn authorized(&self, request: RequestBuilder, url: &str) -> RequestBuilder {
+        let on_registry = url.strip_prefix(&self.base_url).is_some_and(|rest| rest.starts_with('/'));
+        match &self.token {
+            Some(token) if on_registry => request.bearer_auth(token),
+            _ => request,
+        }
+    }
+    
+    /// Sends a read request to the registry, falling over to each mirror in turn.
+    /// 
+    /// The request is retried on the next mirror when it fails with a connection
+    /// error, a timeout, or a server error; any other error, such as a missing
+    /// program, is returned straight away. Use this only for requests that do not
+    /// change registry state.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `path` - The endpoint path, e.g. `programs/my-program/latest`
+    /// * `request` - Sends the request to the full endpoint URL it is given
+    /// 
+    /// # Returns
+    /// 
+    /// Returns the first successful result, or the error from the last registry
+    /// tried.
+    async fn read_with_mirrors<T>(&self, path: &str, mut request: impl AsyncFnMut(&str) -> Result<T>) -> Result<T> {
+        let bases: Vec<&str> = std::iter::once(self.base_url.as_str())
+            .chain(self.mirrors.iter().map(String::as_str))
+            .collect();
+        
+        let mut index = 0;
+        loop {
+            let url = format!("{}/{}", bases[index], path.trim_start_matches('/'));
+            match request(&url).await {
+                Ok(value) => {
+                    if index > 0 && !is_quiet() {
+                        eprintln!("{}", CliStyle::info(&format!("Served by mirror {}", bases[index])));
+                    }
+                    return Ok(value);
+                }
+                Err(e) if index + 1 < bases.len() && should_fail_over(&e) => {
+                    if !is_quiet() {
+                        eprintln!("{}", CliStyle::warning(&format!(
+                            "{} failed ({}); trying mirror {}", bases[index], e, bases[index + 1]
+                        )));
+                    }
+                    index += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+impl RegistryApi for RegistryClient {
+    fn base_url(&self) -> &str {
+        &self.base_url
+    }
+    
+    fn post(&self, url: &str) -> RequestBuilder {
+        self.authorized(self.client.post(url), url)
+    }
+    
+    async fn get_program(&self, name: &str, version: &str, network: &str) -> Result<ProgramResponse> {
+        let path = format!("{}/{}/{}", PROGRAMS_PATH, name, version);
+        self.read_with_mirrors(&path, async |url| {
+            let response = self.authorized(self.client.get(url), url)
+                .query(&[("network", network)])
+                .send_logged()
+                .await
+                .map_err(|e| request_error(&format!("Looking up {}", name), e))?;
+            
+            let status = response.status();
+            if status == StatusCode::NOT_FOUND {
+                let spec = if version == "latest" { name.to_string() } else { format!("{}@{}", name, version) };
+                return Err(SolanaPmError::ProgramNotFound(spec));
+            }
+            if !status.is_success() {
+                return Err(registry_error(response, format!("Looking up {} at {}", name, url)).await);
+            }
+            
+            Ok(response.json().await?)
+        }).await
+    }
+    
+    async fn install_program(&self, request: &InstallRequest<'_>) -> Result<Option<ProgramResponse>> {
+        let _phase = phase(format!("Download {}", request.name));
+        let path = format!("{}/{}/{}/install", PROGRAMS_PATH, request.name, request.version.unwrap_or("latest"));
+        let request_body = serde_json::json!({
+            "network": request.network,
+            "project_hash": request.project_hash,
+        });
+        self.read_with_mirrors(&path, async |url| {
+            download_install_response(self, url, &request_body, request).await
+        }).await
+    }
+    
+    async fn publish_program(&self, upload: PublishUpload) -> Result<PublishUploadResponse> {
+        let _phase = phase("Upload");
+        let PublishUpload { body, base_timeout, show_progress } = upload;
+        let body_size = body.len() as u64;
+        let timeout = base_timeout + Duration::from_secs(body_size / UPLOAD_MIN_BYTES_PER_SEC);
+        
+        let progress = if show_progress {
+            CliProgress::new_bytes_progress_bar(body_size, "Publishing to registry")
+        } else {
+            ProgressBar::hidden()
+        };
+        
+        let chunks: Vec<Vec<u8>> = body.chunks(UPLOAD_CHUNK_SIZE).map(<[u8]>::to_vec).collect();
+        drop(body);
+        let chunk_progress = progress.clone();
+        let stream = futures_util::stream::iter(chunks).map(move |chunk| {
+            CliProgress::inc_bytes(&chunk_progress, chunk.len() as u64);
+            Ok::<_, std::io::Error>(chunk)
+        });
+        
+        let url = self.url(PROGRAMS_PATH);
+        let response = self.authorized(self.client.post(&url), &url)
+            .header(CONTENT_TYPE, "application/json")
+            .header(CONTENT_LENGTH, body_size)
+            .timeout(timeout)
+            .body(reqwest::Body::wrap_stream(stream))
+            .send_logged()
+            .await;
+        
+        progress.finish_and_clear();
+        
+        let response = response.map_err(|e| {
+            if e.is_timeout() && !e.is_connect() {
+                SolanaPmError::Timeout(format!(
+                    "Publishing: upload of {} to {} did not finish within {} seconds; raise the limit with --timeout or {}",
+                    HumanBytes(body_size), e.url().map(|url| url.as_str()).unwrap_or(PROGRAMS_PATH), timeout.as_secs(), TIMEOUT_ENV_VAR
+                ))
+            } else {
+                request_error("Publishing", e)
+            }
+        })?;
+        
+        let status = response.status();
+        if !status.is_success() {
+            let content_type = response_content_type(&response);
+            let body = read_error_body(response).await;
+            return Ok(PublishUploadResponse::Rejected { status, content_type, body });
+        }
+        
+        let body = response.text().await
+            .with_context(|| format!("Failed to read the publish response from {}", self.base_url))?;
+        Ok(PublishUploadResponse::Accepted { body })
+    }
+    
+    async fn verify_token(&self, token: &str) -> Result<AuthVerifyResponse> {
+        let url = self.url(AUTH_VERIFY_PATH);
+        let response = self.client
+            .get(&url)
+            .header(AUTHORIZATION, format!("Bearer {}", token))
+            .send_logged()
+            .await
+            .map_err(|e| request_error("Validating the API token", e))?;
+        
+        if !response.status().is_success() {
+            return Err(registry_error(response, format!("Validating the API token at {}", url)).await);
+        }
+        
+        response.json().await
+            .with_context(|| format!("Invalid token verification response from {}", url))
+    }
+    
+    async fn search(&self, query: &str, network: &str) -> Result<Vec<SearchResult>> {
+        self.read_with_mirrors(SEARCH_PROGRAMS_PATH, async |url| {
+            let response = self.authorized(self.client.get(url), url)
+                .query(&[("q", query), ("network", network)])
+                .send_logged()
+                .await
+                .map_err(|e| request_error("Searching the registry", e))?;
+            
+            if !response.status().is_success() {
+                return Err(registry_error(response, format!("Searching {}", url)).await);
+            }
+            
+            let search_response: SearchResponse = response.json().await
+                .with_context(|| format!("Invalid search response from {}", url))?;
+            Ok(search_response.programs)
+        }).await
+    }
+    
+    async fn versions(&self, name: &str, network: &str) -> Result<Vec<String>> {
+        // The registry has no versions endpoint; search results list them
+        let program = self.search(name, network).await?
+            .into_iter()
+            .find(|program| program.name == name)
+            .ok_or_else(|| SolanaPmError::ProgramNotFound(name.to_string()))?;
+        
+        if program.versions.is_empty() {
+            return Ok(vec![program.version]);
+        }
+        Ok(program.versions)
+    }
+}
+
+/// Returns whether a failed read should be retried on a mirror.
+fn should_fail_over(error: &SolanaPmError) -> bool {
+    match error {
+        SolanaPmError::Http(e) => e.is_connect() || e.is_timeout() || e.is_request(),
+        SolanaPmError::Timeout(_) => true,
+        _ => error.is_registry_fault(),
+    }
+}
+
+/// Converts a failed registry response into `SolanaPmError::Registry`.
+/// 
+/// At most 64 KiB of the body is read; see [`registry_error_from_body`] for
+/// how it becomes the error message.
+/// 
+/// # Arguments
+/// 
+/// * `response` - The response with an error status
+/// * `operation` - What was being done, e.g. `Releasing vesting@1.0.0`
+/// 
+/// # Returns
+/// 
+/// Returns the error describing the response.
+/// 
+/// # Examples
+/// 
+/// ```no_run
+/// use solpm::commands::http::{SendLogged, http_client};
+/// use solpm::commands::registry::registry_error;
+/// 
+/// # async fn example() -> solpm::error::Result<()> {
+/// # let url = "https://registry.example.com/programs/search";
+/// # let response = http_client()?.get(url).send_logged().await?;
+/// if !response.status().is_success() {
+///     return Err(registry_error(response, format!("Searching {}", url)).await);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub async fn registry_error(response: reqwest::Response, operation: impl Into<String>) -> SolanaPmError {
+    let status = response.status();
+    let content_type = response_content_type(&response);
+    let body = read_error_body(response).await;
+    registry_error_from_body(status, content_type.as_deref(), &body, operation)
+}
+
+/// Returns the `Content-Type` header of a response, if any.
+pub fn response_content_type(response: &reqwest::Response) -> Option<String> {
+    response.headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
+
+/// Reads the body of a failed response, stopping after 64 KiB.
+/// 
+/// A body that cannot be read, or is cut off mid-character, is returned as
+/// far as it could be decoded.
+pub async fn read_error_body(mut response: reqwest::Response) -> String {
+    let mut body = Vec::new();
+    while body.len() < MAX_ERROR_BODY_BYTES
+        && let Ok(Some(chunk)) = response.chunk().await
+    {
+        let take = chunk.len().min(MAX_ERROR_BODY_BYTES - body.len());
+        body.extend_from_slice(&chunk[..take]);
+    }
+    String::from_utf8_lossy(&body).into_owned()
+}
+
+/// Builds `SolanaPmError::Registry` from a failed response's status and body.
+/// 
+/// Bodies in the registry's structured error format give the message (from
+/// `message`, or `error` without it), the code, and one line per field
+/// error. Other bodies fall back to a whitespace-collapsed excerpt of at
+/// most 200 characters, noting the content type.
+/// 
+/// # Arguments
+/// 
+/// * `status` - The response status
+/// * `content_type` - The `Content-Type` header of the response, if any
+/// * `body` - The response body
+/// * `operation` - What was being done, e.g. `Publishing to https://registry.example.com`
+/// 
+/// # Returns
+/// 
+/// Returns the error describing the response.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use reqwest::StatusCode;
+/// use solpm::commands::registry::registry_error_from_body;
+/// 
+/// let body = r#"{"code":"validation_failed","message":"Invalid metadata","field_errors":[{"field":"description","message":"too long (max 500)"}]}"#;
+/// let error = registry_error_from_body(StatusCode::UNPROCESSABLE_ENTITY, Some("application/json"), body, "Publishing");
+/// assert_eq!(
+///     error.to_string(),
+///     "Publishing was rejected by the registry (422 Unprocessable Entity, validation_failed): Invalid metadata\n  - description: too long (max 500)",
+/// );
+/// ```
+pub fn registry_error_from_body(status: StatusCode, content_type: Option<&str>, body: &str, operation: impl Into<String>) -> SolanaPmError {
+    let (code, message) = match serde_json::from_str::<RegistryErrorResponse>(body) {
+        Ok(registry_error) if registry_error.message.is_some()
+            || registry_error.error.is_some()
+            || registry_error.code.is_some()
+            || !registry_error.field_errors.is_empty() =>
+        {
+            let mut message = registry_error.message
+                .or(registry_error.error)
+                .unwrap_or_else(|| if registry_error.field_errors.is_empty() {
+                    status.canonical_reason().unwrap_or("No message").to_string()
+                } else {
+                    "Validation failed".to_string()
+                });
+            for field_error in registry_error.field_errors {
+                message.push_str(&format!("\n  - {}: {}", field_error.field, field_error.message));
+            }
+            (registry_error.code, message)
+        }
+        _ => (None, describe_unstructured_body(content_type, body)),
+    };
+    
+    SolanaPmError::Registry { status: status.as_u16(), code, message, operation: operation.into() }
+}
+
+/// Renders an error body that is not in the registry's error format.
+fn describe_unstructured_body(content_type: Option<&str>, body: &str) -> String {
+    let collapsed = body.split_whitespace().collect::<Vec<_>>().join(" ");
+    if collapsed.is_empty() {
+        return format!("empty {} response", content_type.unwrap_or("untyped"));
+    }
+    
+    let excerpt = if collapsed.chars().count() > ERROR_EXCERPT_CHARS {
+        format!("{}...", collapsed.chars().take(ERROR_EXCERPT_CHARS).collect::<String>())
+    } else {
+        collapsed
+    };
+    format!("{} response: {}", content_type.unwrap_or("untyped"), excerpt)
+}
+
+/// Picks the registry URL from the first source that sets one.
+fn resolve_registry() -> Result<(String, ConfigOrigin)> {
+    if let Some(url) = REGISTRY_FLAG.get() {
+        return Ok((url.clone(), ConfigOrigin::Flag));
+    }
+    
+    if let Ok(url) = std::env::var(REGISTRY_ENV_VAR)
+        && !url.trim().is_empty()
+    {
+        return Ok((url, ConfigOrigin::Env));
+    }
+    
+    if let Some(url) = &project_settings().registry {
+        return Ok((url.clone(), ConfigOrigin::Project));
+    }
+    
+    if let Some(url) = load_solpmrc()?.registry {
+        return Ok((url, ConfigOrigin::Project));
+    }
+    
+    if let Some(url) = &global_config()?.registry {
+        return Ok((url.clone(), ConfigOrigin::Global));
+    }
+    
+    Ok((DEFAULT_REGISTRY_URL.to_string(), ConfigOrigin::Default))
+}
+
+/// Checks that a registry URL is an http(s) URL and strips trailing slashes.
+fn normalize_registry_url(url: &str) -> std::result::Result<String, String> {
+    let url = url.trim().trim_end_matches('/');
+    let Some((scheme, rest)) = url.split_once("://") else {
+        return Err(format!("'{}' must start with https:// or http://", url));
+    };
+    if scheme != "https" && scheme != "http" {
+        return Err(format!("'{}' must use https or http", url));
+    }
+    if rest.is_empty() {
+        return Err(format!("'{}' has no host", url));
+    }
+    
+    Ok(url.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::mock_server::{MockResponse, serve};
+    
+    const PROGRAM_RESPONSE: &str = r#"{
+        "version": "1.0.0",
+        "program_id": "Vest1ngZbdCMbGVTwfM8fBNBfWqBqTBBHwCbUHmBxjr",
+        "idl": { "metadata": { "name": "vesting", "version": "1.0.0" }, "instructions": [] }
+    }"#;
+    
+    /// A client for the mock registry at `base_url`.
+    fn registry(base_url: String) -> RegistryClient {
+        RegistryClient::with_base_url(reqwest::Client::new(), base_url)
+    }
+    
+    /// A publish upload of a small JSON body, without a progress bar.
+    fn upload() -> PublishUpload {
+        PublishUpload { body: br#"{"name": "vesting"}"#.to_vec(), base_timeout: Duration::from_secs(10), show_progress: false }
+    }
+    
+    #[tokio::test]
+    async fn accepted_publish_returns_the_response_body() {
+        let (base_url, requests) = serve(|_| MockResponse::json("201 Created", r#"{"id": 42}"#)).await;
+        let registry = registry(base_url).with_token("spr_test");
+        
+        let PublishUploadResponse::Accepted { body } = registry.publish_program(upload()).await.unwrap() else {
+            panic!("the upload was rejected");
+        };
+        assert_eq!(body, r#"{"id": 42}"#);
+        
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests[0].method, "POST");
+        assert_eq!(requests[0].path, "/programs");
+        assert_eq!(requests[0].header("authorization"), Some("Bearer spr_test"));
+        assert_eq!(requests[0].header("content-type"), Some("application/json"));
+        assert_eq!(requests[0].body, br#"{"name": "vesting"}"#);
+    }
+    
+    #[tokio::test]
+    async fn rejected_publish_keeps_the_structured_error() {
+        let error_body = r#"{"code": "validation_failed", "message": "Invalid metadata", "field_errors": [{"field": "description", "message": "too long (max 500)"}]}"#;
+        let (base_url, _) = serve(move |_| MockResponse::json("422 Unprocessable Entity", error_body).with_header("Content-Type", "application/json")).await;
+        
+        let PublishUploadResponse::Rejected { status, content_type, body } = registry(base_url).publish_program(upload()).await.unwrap() else {
+            panic!("the upload was accepted");
+        };
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(body, error_body);
+        
+        let error = registry_error_from_body(status, content_type.as_deref(), &body, "Publishing");
+        assert!(matches!(&error, SolanaPmError::Registry { status: 422, code: Some(code), .. } if code == "validation_failed"), "{}", error);
+        assert!(error.to_string().ends_with("Invalid metadata\n  - description: too long (max 500)"), "{}", error);
+        assert_eq!(error.exit_code(), 7);
+    }
+    
+    #[tokio::test]
+    async fn verify_token_reads_permissions_and_rejects_a_bad_token() {
+        let (base_url, requests) = serve(|request| match request.header("authorization") {
+            Some("Bearer spr_good") => MockResponse::json("200 OK", r#"{"valid": true, "permissions": ["publish"]}"#),
+            _ => MockResponse::json("401 Unauthorized", r#"{"error": "Invalid token"}"#),
+        }).await;
+        let registry = registry(base_url);
+        
+        let verified = registry.verify_token("spr_good").await.unwrap();
+        assert!(verified.valid);
+        assert_eq!(verified.permissions, vec!["publish"]);
+        
+        let Err(error) = registry.verify_token("spr_bad").await else {
+            panic!("the bad token was accepted");
+        };
+        assert!(matches!(error, SolanaPmError::Registry { status: 401, .. }), "{}", error);
+        assert_eq!(error.exit_code(), 4);
+        assert_eq!(error.kind(), "auth");
+        assert_eq!(requests.lock().unwrap()[0].path, "/auth/verify");
+    }
+    
+    #[tokio::test]
+    async fn missing_program_or_version_is_not_found() {
+        let (base_url, requests) = serve(|_| MockResponse::json("404 Not Found", r#"{"error": "not found"}"#)).await;
+        let registry = registry(base_url);
+        
+        let Err(error) = registry.get_program("vesting", "2.0.0", "devnet").await else {
+            panic!("the lookup succeeded");
+        };
+        assert!(matches!(&error, SolanaPmError::ProgramNotFound(spec) if spec == "vesting@2.0.0"), "{}", error);
+        
+        let Err(error) = registry.get_program("vesting", "latest", "devnet").await else {
+            panic!("the lookup succeeded");
+        };
+        assert!(matches!(&error, SolanaPmError::ProgramNotFound(spec) if spec == "vesting"), "{}", error);
+        assert_eq!(requests.lock().unwrap()[0].path, "/programs/vesting/2.0.0?network=devnet");
+    }
+    
+    #[tokio::test]
+    async fn empty_search_results_leave_no_versions() {
+        let (base_url, requests) = serve(|_| MockResponse::json("200 OK", r#"{"programs": []}"#)).await;
+        let registry = registry(base_url);
+        
+        assert!(registry.search("vesting", "devnet").await.unwrap().is_empty());
+        assert!(matches!(registry.versions("vesting", "devnet").await, Err(SolanaPmError::ProgramNotFound(_))));
+        assert_eq!(requests.lock().unwrap()[0].path, "/programs/search?q=vesting&network=devnet");
+    }
+    
+    #[tokio::test]
+    async fn versions_come_from_the_matching_search_result() {
+        let (base_url, _) = serve(|_| MockResponse::json("200 OK", r#"{"programs": [
+            {"name": "vesting-v2", "version": "2.0.0"},
+            {"name": "vesting", "version": "1.1.0", "versions": ["1.0.0", "1.1.0"]}
+        ]}"#)).await;
+        
+        assert_eq!(registry(base_url).versions("vesting", "devnet").await.unwrap(), vec!["1.0.0", "1.1.0"]);
+    }
+    
+    #[tokio::test]
+    async fn failed_search_is_a_registry_error() {
+        let (base_url, _) = serve(|_| MockResponse::json("400 Bad Request", r#"{"message": "query too short"}"#)).await;
+        let registry = registry(base_url);
+        
+        let Err(error) = registry.search("v", "devnet").await else {
+            panic!("the search succeeded");
+        };
+        assert!(matches!(&error, SolanaPmError::Registry { status: 400, message, .. } if message == "query too short"), "{}", error);
+        assert!(matches!(registry.versions("v", "devnet").await, Err(SolanaPmError::Registry { status: 400, .. })));
+    }
+    
+    #[tokio::test]
+    async fn reads_fail_over_to_a_mirror_without_the_token() {
+        let (registry_url, registry_requests) = serve(|_| MockResponse::json("503 Service Unavailable", "down for maintenance")).await;
+        let (mirror_url, mirror_requests) = serve(|_| MockResponse::json("200 OK", PROGRAM_RESPONSE)).await;
+        let registry = RegistryClient {
+            mirrors: vec![mirror_url],
+            ..registry(registry_url).with_token("spr_test")
+        };
+        
+        let program = registry.get_program("vesting", "1.0.0", "devnet").await.unwrap();
+        assert_eq!(program.program_id, "Vest1ngZbdCMbGVTwfM8fBNBfWqBqTBBHwCbUHmBxjr");
+        
+        let registry_requests = registry_requests.lock().unwrap();
+        let mirror_requests = mirror_requests.lock().unwrap();
+        assert_eq!(registry_requests.len(), 1);
+        assert_eq!(registry_requests[0].header("authorization"), Some("Bearer spr_test"));
+        assert_eq!(mirror_requests.len(), 1);
+        assert_eq!(mirror_requests[0].path, "/programs/vesting/1.0.0?network=devnet");
+        assert_eq!(mirror_requests[0].header("authorization"), None);
+    }
+    
+    #[tokio::test]
+    async fn client_errors_are_not_retried_on_a_mirror() {
+        let (registry_url, _) = serve(|_| MockResponse::json("404 Not Found", "")).await;
+        let (mirror_url, mirror_requests) = serve(|_| MockResponse::json("200 OK", PROGRAM_RESPONSE)).await;
+        let registry = RegistryClient { mirrors: vec![mirror_url], ..registry(registry_url) };
+        
+        assert!(matches!(registry.get_program("vesting", "1.0.0", "devnet").await, Err(SolanaPmError::ProgramNotFound(_))));
+        assert!(mirror_requests.lock().unwrap().is_empty());
+    }
+    
+    #[tokio::test]
+    async fn unreachable_registry_fails_over_to_a_mirror() {
+        // Bind a port and close it again, so nothing is listening on it
+        let closed = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let registry_url = format!("http://{}", closed.local_addr().unwrap());
+        drop(closed);
+        let (mirror_url, _) = serve(|_| MockResponse::json("200 OK", r#"{"programs": [{"name": "vesting", "version": "1.0.0"}]}"#)).await;
+        let registry = RegistryClient { mirrors: vec![mirror_url], ..registry(registry_url) };
+        
+        assert_eq!(registry.versions("vesting", "devnet").await.unwrap(), vec!["1.0.0"]);
+    }
+}
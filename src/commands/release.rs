@@ -0,0 +1,226 @@
+//! # Release Command Implementation
+//!
+//! This module implements the `release` command which makes an unlisted or
+//! draft version public.
+//!
+//! Features:
+//! - Authenticated requests using the stored or environment API token
+//! - Ownership proof signed by the program's authority keypair or Ledger
+//! - Single-use nonces, as in publish challenges
+//! - Successful releases are recorded in the local publish history
+//!
+//! ## Challenge Format
+//!
+//! The authority signs a single-line ASCII challenge (sent with
+//! `challenge_version: 1`):
+//!
+//! ```text
+//! solpm-release-v1 registry=<base_url> name=<name> version=<version> visibility=public timestamp=<unix_timestamp> nonce=<nonce>
+//! ```
+//!
+//! The nonce is 32 random bytes in lowercase hex and is also sent as the
+//! `nonce` request field. Ledger signatures cover the challenge wrapped in a
+//! Solana off-chain message and set `signature_format: "offchain"`.
+
+use crate::commands::auth::ensure_authenticated;
+use crate::commands::constants::{AUTHORITY_KEY_ENV_VAR, PROGRAMS_PATH};
+use crate::commands::registry::{registry_base_url, registry_error};
+use crate::commands::history::append_publish_log;
+use crate::commands::http::{SendLogged, http_client, request_error};
+use crate::commands::publish::{AuthoritySigner, PublishOptions, generate_challenge_nonce, load_authority_signer};
+use crate::commands::types::{PublishLogEntry, ReleaseProgramRequest, SolanaProgramsConfig};
+use crate::error::{Result, SolanaPmError};
+use crate::outln;
+use crate::statusln;
+use crate::utils::{CliProgress, CliStyle, current_timestamp, parse_package_spec};
+use reqwest::StatusCode;
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const SOLANA_PROGRAMS_TOML: &str = "SolanaPrograms.toml";
+const RELEASE_CHALLENGE_VERSION: u8 = 1;
+
+/// Options controlling how the release request is signed.
+#[derive(Debug, Default)]
+pub struct ReleaseOptions {
+    /// Path to the authority keypair, overriding the environment and TOML
+    pub authority_keypair: Option<String>,
+    /// Environment variable holding the authority secret key
+    pub authority_env: Option<String>,
+    /// Whether to sign the request with a connected Ledger device
+    pub sign_with_ledger: bool,
+}
+
+/// Makes an unlisted or draft version public.
+/// 
+/// The authority is resolved the same way as for `publish`: the
+/// `--sign-with-ledger` and `--authority-keypair` flags, then the authority
+/// environment variable, then `authority_keypair` in SolanaPrograms.toml.
+/// 
+/// # Arguments
+/// 
+/// * `package_spec` - The version to release in `name@version` form
+/// * `options` - Options selecting the authority key
+/// 
+/// # Returns
+/// 
+/// Returns `Ok(())` once the registry confirms the release, or an error if
+/// the spec has no version, no authority is configured, authentication
+/// fails, or the registry rejects the request.
+/// 
+/// # Examples
+/// 
+/// ```no_run
+/// use solpm::commands::release::{ReleaseOptions, release_program};
+/// 
+/// # async fn example() -> solpm::error::Result<()> {
+/// release_program("my-program@1.2.0", &ReleaseOptions::default()).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn release_program(package_spec: &str, options: &ReleaseOptions) -> Result<()> {
+    let parsed_spec = parse_package_spec(package_spec);
+    let name = parsed_spec.name;
+    let version = parsed_spec.version.filter(|version| !version.is_empty()).ok_or_else(|| {
+        SolanaPmError::DataMissing(format!(
+            "Specify the version to release, e.g. {}",
+            CliStyle::command(&format!("solpm release {}@1.0.0", name))
+        ))
+    })?;
+    
+    let signer = load_release_authority(&name, options)?;
+    let authority_pubkey = signer.pubkey();
+    
+    let registry = registry_base_url()?;
+    let token = ensure_authenticated().await?;
+    
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| SolanaPmError::InvalidPath(format!("System time error: {}", e)))?
+        .as_secs();
+    let nonce = generate_challenge_nonce();
+    let challenge = format!(
+        "solpm-release-v{} registry={} name={} version={} visibility=public timestamp={} nonce={}",
+        RELEASE_CHALLENGE_VERSION,
+        registry,
+        name,
+        version,
+        timestamp,
+        nonce
+    );
+    
+    if let AuthoritySigner::Ledger(_) = signer {
+        statusln!("{}", CliStyle::info("Confirm the release challenge on your Ledger device..."));
+    }
+    let (signature, signature_format) = signer.sign_challenge(&challenge)?;
+    drop(signer);
+    
+    let release_request = ReleaseProgramRequest {
+        name: name.clone(),
+        version: version.clone(),
+        challenge,
+        challenge_version: RELEASE_CHALLENGE_VERSION,
+        nonce,
+        signature: bs58::encode(signature.as_ref()).into_string(),
+        signature_format,
+        authority_pubkey: authority_pubkey.to_string(),
+    };
+    
+    let spinner = CliProgress::new_spinner(&format!(
+        "Releasing {} {}...",
+        CliStyle::package(&name),
+        CliStyle::version(&version)
+    ));
+    
+    let client = http_client()?;
+    let response = client
+        .post(format!("{}/{}/{}/{}/release", registry, PROGRAMS_PATH, name, version))
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&release_request)
+        .send_logged()
+        .await;
+    
+    spinner.finish_and_clear();
+    let response = response.map_err(|e| request_error(&format!("Releasing {}@{}", name, version), e))?;
+    
+    let status = response.status();
+    if status == StatusCode::NOT_FOUND {
+        return Err(SolanaPmError::ProgramNotFound(format!("{}@{}", name, version)));
+    }
+    if !status.is_success() {
+        return Err(registry_error(response, format!("Releasing {}@{} at {}", name, version, registry)).await);
+    }
+    
+    outln!("{}", CliStyle::success(&format!(
+        "Released {} {}; it is now public and eligible as the latest version",
+        CliStyle::package(&name),
+        CliStyle::version(&version)
+    )));
+    
+    let log_entry = PublishLogEntry {
+        timestamp: current_timestamp(),
+        action: "release".to_string(),
+        registry,
+        name,
+        version,
+        network: None,
+        program_id: None,
+        authority: authority_pubkey.to_string(),
+        idl_hash: None,
+        commit: None,
+    };
+    if let Err(e) = append_publish_log(&log_entry) {
+        statusln!("{}", CliStyle::warning(&format!("Could not record the release in the local history: {}", e)));
+    }
+    
+    Ok(())
+}
+
+/// Loads the authority that signs the release request.
+/// 
+/// The `authority_keypair` of the released program is read from
+/// SolanaPrograms.toml, including from a `[[programs]]` entry with the same
+/// name. A single `[program]` section is used whatever its name.
+/// 
+/// # Arguments
+/// 
+/// * `name` - The name of the program being released
+/// * `options` - Options selecting the authority key
+/// 
+/// # Returns
+/// 
+/// Returns the authority signer, or an error if no authority source is
+/// configured or the selected key cannot be loaded.
+fn load_release_authority(name: &str, options: &ReleaseOptions) -> Result<AuthoritySigner> {
+    let config_path = if Path::new(SOLANA_PROGRAMS_TOML).exists() {
+        let content = fs::read_to_string(SOLANA_PROGRAMS_TOML)?;
+        let config: SolanaProgramsConfig = toml::from_str(&content)
+            .map_err(|e| SolanaPmError::InvalidPath(format!("Failed to parse {}: {}", SOLANA_PROGRAMS_TOML, e)))?;
+        config.find_program(name)
+            .or(config.program.as_ref())
+            .map(|program| program.authority_keypair.clone())
+    } else {
+        None
+    };
+    
+    let has_env_key = std::env::var_os(options.authority_env.as_deref().unwrap_or(AUTHORITY_KEY_ENV_VAR)).is_some();
+    if config_path.is_none() && options.authority_keypair.is_none() && !options.sign_with_ledger && !has_env_key {
+        return Err(SolanaPmError::ConfigNotFound(format!(
+            "No authority configured. Run from the program directory with {}, or pass --authority-keypair or --sign-with-ledger.",
+            SOLANA_PROGRAMS_TOML
+        )));
+    }
+    
+    let publish_options = PublishOptions {
+        authority_keypair: options.authority_keypair.clone(),
+        authority_env: options.authority_env.clone(),
+        sign_with_ledger: options.sign_with_ledger,
+        ..PublishOptions::default()
+    };
+    
+    let spinner = CliProgress::new_spinner("Loading authority keypair...");
+    let signer = load_authority_signer(&publish_options, config_path.as_deref().unwrap_or_default());
+    spinner.finish_and_clear();
+    signer
+}
@@ -0,0 +1,282 @@
+//! # Relink Command Implementation
+//!
+//! This module implements the `relink` command which repairs stale IDL paths
+//! in SolanaPrograms.json after IDL files have been moved.
+//!
+//! Features:
+//! - Scans the default, configured, and user-provided IDL directories
+//! - Matches IDL files to manifest entries by their `metadata.name`, preferring
+//!   the per-network IDL (`{idl_dir}/{network}/`) of the entry's network
+//! - Updates each broken entry's `idl_path` to the discovered location
+//! - Reports entries that need to be reinstalled
+//!
+//! Entries whose IDL file still exists are left untouched.
+
+use crate::commands::config::idl_dir;
+use crate::commands::constants::SOLANA_PROGRAMS_FILE;
+use crate::commands::types::{Program, SolanaPrograms};
+use crate::error::{Result, SolanaPmError};
+use crate::outln;
+use crate::statusln;
+use crate::output::set_result;
+use crate::utils::{CliStyle, installed_idl_path, network_idl_path, sanitize_file_name};
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::Path;
+
+/// Repairs missing or moved IDL paths in SolanaPrograms.json.
+/// 
+/// This function:
+/// 1. Finds manifest entries whose IDL file no longer exists
+/// 2. Scans the default IDL directory, the directories of all configured
+///    IDL paths, and any extra directories for `.json` files
+/// 3. Matches found IDL files to entries by `metadata.name` (or top-level `name`)
+/// 4. Updates the `idl_path` of each matched entry and reports the fix
+/// 5. Reports entries without a matching file as needing reinstall
+/// 
+/// # Arguments
+/// 
+/// * `extra_dirs` - Additional directories to search for IDL files
+/// 
+/// # Returns
+/// 
+/// Returns `Ok(())` on success, or an error if the manifest is missing or
+/// cannot be read or written.
+/// 
+/// # Examples
+/// 
+/// ```no_run
+/// use solpm::commands::relink::relink;
+/// 
+/// # fn example() -> solpm::error::Result<()> {
+/// // Repair using the default and configured IDL directories
+/// relink(&[])?;
+/// 
+/// // Also search a directory the IDLs were moved to
+/// relink(&["./src/idl".to_string()])?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn relink(extra_dirs: &[String]) -> Result<()> {
+    if !Path::new(SOLANA_PROGRAMS_FILE).exists() {
+        return Err(SolanaPmError::ConfigNotFound(format!("{} not found. Run 'solpm add <program>' first.", SOLANA_PROGRAMS_FILE)));
+    }
+    
+    let content = fs::read_to_string(SOLANA_PROGRAMS_FILE)?;
+    let mut solana_programs: SolanaPrograms = serde_json::from_str(&content)?;
+    
+    // Collect directories to scan
+    let mut search_dirs = BTreeSet::new();
+    search_dirs.insert(idl_dir());
+    for program_info in solana_programs.programs.values().chain(solana_programs.dev_programs.values()) {
+        if let Some(parent) = program_info.idl_path.as_deref().and_then(|p| Path::new(p).parent()) {
+            search_dirs.insert(parent.to_string_lossy().to_string());
+        }
+    }
+    search_dirs.extend(extra_dirs.iter().cloned());
+    
+    let discovered = discover_idl_files(&search_dirs)?;
+    
+    let mut fixed_count = 0;
+    let mut missing = Vec::new();
+    
+    for programs in [&mut solana_programs.programs, &mut solana_programs.dev_programs] {
+        for (package_name, program_info) in programs.iter_mut() {
+            match relink_program(package_name, program_info, &discovered) {
+                RelinkOutcome::Intact => {}
+                RelinkOutcome::Fixed(path) => {
+                    outln!("{}", CliStyle::success(&format!(
+                        "Relinked {} to {}",
+                        CliStyle::package(package_name),
+                        CliStyle::path(&path)
+                    )));
+                    fixed_count += 1;
+                }
+                RelinkOutcome::Missing => missing.push(package_name.clone()),
+            }
+        }
+    }
+    
+    if fixed_count > 0 {
+        let json = serde_json::to_string_pretty(&solana_programs)?;
+        fs::write(SOLANA_PROGRAMS_FILE, json)?;
+        tracing::debug!("Wrote {}", SOLANA_PROGRAMS_FILE);
+    }
+    
+    for package_name in &missing {
+        statusln!("{}", CliStyle::warning(&format!(
+            "No IDL file found for {}. Run {} to reinstall it.",
+            CliStyle::package(package_name),
+            CliStyle::command("solpm install")
+        )));
+    }
+    
+    if fixed_count == 0 && missing.is_empty() {
+        statusln!("{}", CliStyle::info("All IDL paths are valid"));
+    } else if fixed_count > 0 {
+        outln!("{}", CliStyle::success(&format!(
+            "Relinked {} program{}",
+            fixed_count,
+            if fixed_count == 1 { "" } else { "s" }
+        )));
+    }
+    set_result(serde_json::json!({ "relinked": fixed_count, "missing": missing }));
+    
+    Ok(())
+}
+
+/// The result of checking a single manifest entry.
+enum RelinkOutcome {
+    /// The configured IDL file exists
+    Intact,
+    /// The entry was pointed at a newly discovered IDL file
+    Fixed(String),
+    /// No IDL file could be found for the entry
+    Missing,
+}
+
+/// Checks a manifest entry and updates its IDL path if it is broken.
+/// 
+/// # Arguments
+/// 
+/// * `package_name` - The name of the program in the manifest
+/// * `program_info` - The manifest entry to check and update
+/// * `discovered` - Discovered IDL files keyed by program name
+/// 
+/// # Returns
+/// 
+/// Returns the outcome of the check.
+fn relink_program(package_name: &str, program_info: &mut Program, discovered: &HashMap<String, String>) -> RelinkOutcome {
+    let current_path = installed_idl_path(package_name, program_info);
+    if Path::new(&current_path).exists() {
+        return RelinkOutcome::Intact;
+    }
+    
+    // The IDL installed for the entry's network wins over one of the same
+    // name that was installed from another network
+    let network_path = network_idl_path(package_name, &program_info.network);
+    let found = Some(&network_path)
+        .filter(|path| Path::new(path).exists())
+        .or_else(|| discovered.get(package_name))
+        .or_else(|| discovered.get(&sanitize_file_name(package_name)));
+    
+    match found {
+        Some(path) => {
+            program_info.idl_path = Some(path.clone());
+            RelinkOutcome::Fixed(path.clone())
+        }
+        None => RelinkOutcome::Missing,
+    }
+}
+
+/// Scans directories for IDL files and indexes them by program name.
+/// 
+/// The program name is read from `metadata.name` (Anchor) or the top-level
+/// `name` field (legacy Anchor and Shank). Files that are not valid JSON or
+/// have no name are skipped. Missing directories are ignored.
+/// 
+/// # Arguments
+/// 
+/// * `dirs` - The directories to scan
+/// 
+/// # Returns
+/// 
+/// Returns a map from program name to IDL file path, or an error if a
+/// directory cannot be read.
+fn discover_idl_files(dirs: &BTreeSet<String>) -> Result<HashMap<String, String>> {
+    let mut discovered = HashMap::new();
+    
+    for dir in dirs {
+        let dir_path = Path::new(dir);
+        if !dir_path.is_dir() {
+            continue;
+        }
+        
+        let entries = fs::read_dir(dir_path)
+            .map_err(|e| SolanaPmError::InvalidPath(format!("Failed to read directory {}: {}", dir, e)))?;
+        
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            
+            let Ok(content) = fs::read_to_string(&path) else {
+                continue;
+            };
+            let Ok(idl_json) = serde_json::from_str::<serde_json::Value>(&content) else {
+                continue;
+            };
+            
+            let name = idl_json["metadata"]["name"].as_str()
+                .or_else(|| idl_json["name"].as_str());
+            
+            if let Some(name) = name {
+                discovered.entry(name.to_string())
+                    .or_insert_with(|| path.to_string_lossy().to_string());
+            }
+        }
+    }
+    
+    Ok(discovered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_project;
+    
+    /// A devnet manifest entry for `vesting`.
+    fn program(idl_path: Option<&str>) -> Program {
+        Program {
+            version: "1.0.0".to_string(),
+            program_id: "Vest1ngZbdCMbGVTwfM8fBNBfWqBqTBBHwCbUHmBxjr".to_string(),
+            network: "devnet".to_string(),
+            idl_path: idl_path.map(str::to_string),
+            installed_at: None,
+            source: None,
+            alias: None,
+            tags: Vec::new(),
+        }
+    }
+    
+    /// Writes an IDL named `vesting` to `path`.
+    fn write_idl(path: &str) {
+        fs::create_dir_all(Path::new(path).parent().unwrap()).unwrap();
+        fs::write(path, r#"{"metadata": {"name": "vesting"}, "instructions": []}"#).unwrap();
+    }
+    
+    #[test]
+    fn per_network_idl_is_installed_and_preferred() {
+        let _project = test_project::enter();
+        let devnet_path = network_idl_path("vesting", "devnet");
+        let mainnet_path = network_idl_path("vesting", "mainnet");
+        write_idl(&devnet_path);
+        write_idl(&mainnet_path);
+        let discovered = HashMap::from([("vesting".to_string(), mainnet_path)]);
+        
+        // Without an idl_path, the network's IDL is the installed one
+        let mut entry = program(None);
+        assert!(matches!(relink_program("vesting", &mut entry, &discovered), RelinkOutcome::Intact));
+        assert_eq!(entry.idl_path, None);
+        
+        // A stale idl_path is relinked to the network's IDL, not another network's
+        let mut entry = program(Some("old/vesting.json"));
+        assert!(matches!(relink_program("vesting", &mut entry, &discovered), RelinkOutcome::Fixed(ref path) if *path == devnet_path));
+        assert_eq!(entry.idl_path.as_deref(), Some(devnet_path.as_str()));
+    }
+    
+    #[test]
+    fn stale_path_falls_back_to_a_discovered_idl() {
+        let _project = test_project::enter();
+        let moved_path = "src/idl/vesting.json".to_string();
+        write_idl(&moved_path);
+        let discovered = HashMap::from([("vesting".to_string(), moved_path.clone())]);
+        
+        let mut entry = program(Some("old/vesting.json"));
+        assert!(matches!(relink_program("vesting", &mut entry, &discovered), RelinkOutcome::Fixed(ref path) if *path == moved_path));
+        
+        let mut missing = program(Some("old/vesting.json"));
+        assert!(matches!(relink_program("vesting", &mut missing, &HashMap::new()), RelinkOutcome::Missing));
+    }
+}
@@ -0,0 +1,173 @@
+//! # Reset Command Implementation
+//!
+//! This module implements the `reset` command which removes every dependency
+//! from a project.
+//!
+//! Features:
+//! - Clears both the `programs` and `devPrograms` sections of SolanaPrograms.json
+//! - Removes each dependency's IDL file, including custom `idl_path` locations
+//!   partial downloads, and saved download validators
+//! - Removes each dependency's generated TypeScript client
+//! - Requires confirmation unless `--yes` is passed
+//!
+//! Only files belonging to entries in SolanaPrograms.json are removed; other
+//! files in the IDL and client directories are left untouched. The manifest is
+//! kept as an empty, valid structure so `solpm add` can be run right away.
+
+use crate::commands::codegen::client_identifier;
+use crate::commands::config::client_dir;
+use crate::commands::constants::SOLANA_PROGRAMS_FILE;
+use crate::commands::download::{temp_path_for, validators_path_for};
+use crate::commands::types::SolanaPrograms;
+use crate::error::{Result, SolanaPmError};
+use crate::outln;
+use crate::statusln;
+use crate::output::set_result;
+use crate::utils::{CliStyle, confirm_action, installed_idl_path};
+use std::collections::HashMap;
+use std::fs;
+use std::io::IsTerminal;
+use std::path::Path;
+
+/// Removes all dependencies, their IDL files, and their generated clients.
+/// 
+/// This function:
+/// 1. Lists the dependencies that will be removed
+/// 2. Asks for confirmation unless `yes` is set
+/// 3. Deletes each dependency's IDL file and generated client
+/// 4. Writes an empty SolanaPrograms.json
+/// 5. Reports how many entries and files were removed
+/// 
+/// # Arguments
+/// 
+/// * `yes` - Whether to skip the confirmation prompt
+/// 
+/// # Returns
+/// 
+/// Returns `Ok(())` on success or when the user cancels, or an error if
+/// SolanaPrograms.json is missing or unreadable, confirmation is needed in a
+/// non-interactive session, or a file cannot be removed.
+/// 
+/// # Examples
+/// 
+/// ```no_run
+/// use solpm::commands::reset::reset_project;
+/// 
+/// # fn example() -> solpm::error::Result<()> {
+/// // Ask before removing everything
+/// reset_project(false)?;
+/// 
+/// // Remove everything without prompting (e.g. in scripts)
+/// reset_project(true)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn reset_project(yes: bool) -> Result<()> {
+    if !Path::new(SOLANA_PROGRAMS_FILE).exists() {
+        return Err(SolanaPmError::ConfigNotFound(format!("{} not found. Nothing to reset.", SOLANA_PROGRAMS_FILE)));
+    }
+    
+    let content = fs::read_to_string(SOLANA_PROGRAMS_FILE)?;
+    let solana_programs: SolanaPrograms = serde_json::from_str(&content)?;
+    
+    let program_count = solana_programs.programs.len();
+    let dev_program_count = solana_programs.dev_programs.len();
+    
+    if program_count + dev_program_count == 0 {
+        statusln!("{}", CliStyle::info(&format!("No dependencies in {}; nothing to reset.", SOLANA_PROGRAMS_FILE)));
+        return Ok(());
+    }
+    
+    statusln!("{}", CliStyle::header("Reset Dependencies"));
+    outln!("This removes every dependency, its IDL file, and its generated client:");
+    let mut package_names: Vec<&String> = solana_programs.programs.keys()
+        .chain(solana_programs.dev_programs.keys())
+        .collect();
+    package_names.sort();
+    for package_name in package_names {
+        outln!("  - {}", CliStyle::package(package_name));
+    }
+    outln!();
+    
+    if !yes {
+        if !std::io::stdin().is_terminal() {
+            return Err(SolanaPmError::DataMissing(
+                "Refusing to reset without confirmation in a non-interactive session. Pass --yes to reset.".to_string()
+            ));
+        }
+        if !confirm_action("Remove all dependencies?")? {
+            statusln!("{}", CliStyle::info("Reset cancelled."));
+            return Ok(());
+        }
+    }
+    
+    let client_dir = client_dir()?;
+    let mut removed_idls = 0;
+    let mut removed_clients = 0;
+    
+    for (package_name, program_info) in solana_programs.programs.iter().chain(solana_programs.dev_programs.iter()) {
+        let idl_file_path = installed_idl_path(package_name, program_info);
+        if remove_if_exists(&idl_file_path)? {
+            removed_idls += 1;
+        }
+        remove_if_exists(&temp_path_for(&idl_file_path))?;
+        remove_if_exists(&validators_path_for(&idl_file_path))?;
+        
+        // Names that cannot become a TypeScript identifier never had a client generated
+        if let Ok(client_identifier) = client_identifier(package_name, program_info) {
+            let client_file_path = format!("{}/{}Client.ts", client_dir, client_identifier);
+            if remove_if_exists(&client_file_path)? {
+                removed_clients += 1;
+            }
+        }
+    }
+    
+    let empty = SolanaPrograms {
+        programs: HashMap::new(),
+        dev_programs: HashMap::new(),
+    };
+    let json = serde_json::to_string_pretty(&empty)?;
+    fs::write(SOLANA_PROGRAMS_FILE, json)?;
+    
+    outln!("{}", CliStyle::success(&format!(
+        "Removed {} dependenc{} and {} dev dependenc{}",
+        program_count,
+        if program_count == 1 { "y" } else { "ies" },
+        dev_program_count,
+        if dev_program_count == 1 { "y" } else { "ies" }
+    )));
+    outln!("{}", CliStyle::success(&format!(
+        "Deleted {} IDL file{} and {} generated client{}",
+        removed_idls,
+        if removed_idls == 1 { "" } else { "s" },
+        removed_clients,
+        if removed_clients == 1 { "" } else { "s" }
+    )));
+    set_result(serde_json::json!({
+        "removed_programs": program_count,
+        "removed_dev_programs": dev_program_count,
+        "deleted_idls": removed_idls,
+        "deleted_clients": removed_clients,
+    }));
+    
+    Ok(())
+}
+
+/// Removes a file if it exists.
+/// 
+/// # Arguments
+/// 
+/// * `path` - The file to remove
+/// 
+/// # Returns
+/// 
+/// Returns whether a file was removed, or an error if removal fails.
+fn remove_if_exists(path: &str) -> Result<bool> {
+    if !Path::new(path).is_file() {
+        return Ok(false);
+    }
+    
+    fs::remove_file(path)
+        .map_err(|e| SolanaPmError::InvalidPath(format!("Failed to remove {}: {}", path, e)))?;
+    Ok(true)
+}
@@ -0,0 +1,165 @@
+//! # Solana RPC Module
+//!
+//! This module provides minimal JSON-RPC helpers for reading on-chain state
+//! from Solana clusters. It is used to cross-check locally built artifacts
+//! against what is actually deployed.
+//!
+//! Features:
+//! - Network name to RPC endpoint resolution, honoring the `rpc` config
+//! - Raw account data fetching via `getAccountInfo`
+//! - Upgradeable loader program data decoding
+
+use crate::commands::config::rpc_url;
+use crate::commands::http::{SendLogged, http_client, request_error};
+use crate::error::{Result, SolanaPmError};
+use crate::utils::parse_network;
+use base64::{Engine as _, engine::general_purpose};
+use serde_json::json;
+use solana_sdk::bpf_loader_upgradeable::{self, UpgradeableLoaderState};
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+
+/// Resolves a network name to its RPC endpoint.
+/// 
+/// # Arguments
+/// 
+/// * `network` - The network name (mainnet, devnet, testnet, or localnet)
+/// 
+/// # Returns
+/// 
+/// Returns the configured `rpc` endpoint for the network or its public
+/// endpoint, or an error for unknown names or an unreadable config.
+pub fn rpc_url_for_network(network: &str) -> Result<String> {
+    rpc_url(&parse_network(network)?)
+}
+
+/// Fetches the raw data of an account using the `getAccountInfo` RPC method.
+/// 
+/// # Arguments
+/// 
+/// * `rpc_url` - The RPC endpoint to query
+/// * `address` - The account address to fetch
+/// 
+/// # Returns
+/// 
+/// Returns `Some(data)` with the decoded account data, `None` if the account
+/// does not exist, or an error if the request fails.
+pub async fn get_account_data(rpc_url: &str, address: &Pubkey) -> Result<Option<Vec<u8>>> {
+    let request_body = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "getAccountInfo",
+        "params": [address.to_string(), { "encoding": "base64" }]
+    });
+    
+    let client = http_client()?;
+    let response: serde_json::Value = client
+        .post(rpc_url)
+        .json(&request_body)
+        .send_logged()
+        .await
+        .map_err(|e| request_error(&format!("Reading account {}", address), e))?
+        .json()
+        .await
+        .map_err(|e| request_error(&format!("Reading account {}", address), e))?;
+    
+    if let Some(error) = response.get("error") {
+        return Err(SolanaPmError::DataMissing(format!("RPC error from {}: {}", rpc_url, error)));
+    }
+    
+    let value = &response["result"]["value"];
+    if value.is_null() {
+        return Ok(None);
+    }
+    
+    let encoded = value["data"][0]
+        .as_str()
+        .ok_or_else(|| SolanaPmError::DataMissing(format!("Malformed account data for {}", address)))?;
+    
+    let data = general_purpose::STANDARD.decode(encoded)
+        .map_err(|e| SolanaPmError::DataMissing(format!("Invalid account data for {}: {}", address, e)))?;
+    
+    Ok(Some(data))
+}
+
+/// Fetches the program data account of an upgradeable program.
+/// 
+/// # Arguments
+/// 
+/// * `rpc_url` - The RPC endpoint to query
+/// * `program_id` - The base58 program ID
+/// 
+/// # Returns
+/// 
+/// Returns the raw program data account contents (metadata header followed by
+/// the ELF bytes), or an error if the program ID is invalid, the account does
+/// not exist, or the request fails.
+async fn get_program_data_account(rpc_url: &str, program_id: &str) -> Result<Vec<u8>> {
+    let program_pubkey = Pubkey::from_str(program_id)
+        .map_err(|e| SolanaPmError::InvalidPath(format!("Invalid program ID '{}': {}", program_id, e)))?;
+    
+    let (program_data_address, _) = Pubkey::find_program_address(
+        &[program_pubkey.as_ref()],
+        &bpf_loader_upgradeable::id(),
+    );
+    
+    let data = get_account_data(rpc_url, &program_data_address).await?
+        .ok_or_else(|| SolanaPmError::DataMissing(format!(
+            "Program data account for {} not found. Is the program deployed with the upgradeable loader?",
+            program_id
+        )))?;
+    
+    if data.len() < UpgradeableLoaderState::size_of_programdata_metadata() {
+        return Err(SolanaPmError::DataMissing(format!("Program data account for {} is truncated", program_id)));
+    }
+    
+    Ok(data)
+}
+
+/// Fetches the deployed executable bytes of an upgradeable program.
+/// 
+/// Reads the program data account derived from the program ID and strips the
+/// loader metadata header, returning only the ELF bytes (including any
+/// trailing zero padding left by the loader).
+/// 
+/// # Arguments
+/// 
+/// * `rpc_url` - The RPC endpoint to query
+/// * `program_id` - The base58 program ID
+/// 
+/// # Returns
+/// 
+/// Returns the deployed program bytes, or an error if the program ID is invalid,
+/// the program data account does not exist, or the request fails.
+pub async fn get_deployed_program_bytes(rpc_url: &str, program_id: &str) -> Result<Vec<u8>> {
+    let data = get_program_data_account(rpc_url, program_id).await?;
+    Ok(data[UpgradeableLoaderState::size_of_programdata_metadata()..].to_vec())
+}
+
+/// Fetches the upgrade authority of an upgradeable program.
+/// 
+/// The program data metadata is laid out as a 4-byte state tag, an 8-byte
+/// deployment slot, a 1-byte option flag, and the 32-byte authority pubkey.
+/// 
+/// # Arguments
+/// 
+/// * `rpc_url` - The RPC endpoint to query
+/// * `program_id` - The base58 program ID
+/// 
+/// # Returns
+/// 
+/// Returns `Some(authority)` if the program is upgradeable, `None` if the
+/// authority has been burned (the program is immutable), or an error if the
+/// program data cannot be fetched.
+pub async fn get_program_upgrade_authority(rpc_url: &str, program_id: &str) -> Result<Option<Pubkey>> {
+    let data = get_program_data_account(rpc_url, program_id).await?;
+    
+    if data[12] == 0 {
+        return Ok(None);
+    }
+    
+    let authority = Pubkey::try_from(&data[13..45])
+        .map_err(|_| SolanaPmError::DataMissing(format!("Malformed upgrade authority for {}", program_id)))?;
+    
+    Ok(Some(authority))
+}
@@ -0,0 +1,57 @@
+//! # Schema Module
+//!
+//! This module implements the `schema` command which prints the JSON Schema
+//! of SolanaPrograms.json or SolanaPrograms.toml.
+//!
+//! Features:
+//! - Schemas derived from the same types solpm reads the files into, so they
+//!   always match what solpm accepts
+//! - Field descriptions taken from the types' doc comments
+//!
+//! The schema is written to stdout so users can save it where their editor
+//! looks for it. With `--json` it is returned as the `schema` field of the
+//! result instead.
+
+use crate::cli::SchemaTarget;
+use crate::commands::types::{SolanaPrograms, SolanaProgramsConfig};
+use crate::error::Result;
+use crate::outln;
+use crate::output::{is_json_output, set_result};
+use schemars::schema_for;
+
+/// Prints the JSON Schema of a manifest file to stdout.
+/// 
+/// # Arguments
+/// 
+/// * `target` - The manifest file to describe
+/// 
+/// # Returns
+/// 
+/// Returns `Ok(())` once the schema has been written.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use solpm::cli::SchemaTarget;
+/// use solpm::commands::schema::print_schema;
+/// 
+/// # fn main() -> solpm::error::Result<()> {
+/// // Print the schema of SolanaPrograms.json
+/// print_schema(SchemaTarget::Json)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn print_schema(target: SchemaTarget) -> Result<()> {
+    let (file, schema) = match target {
+        SchemaTarget::Json => ("SolanaPrograms.json", schema_for!(SolanaPrograms)),
+        SchemaTarget::Toml => ("SolanaPrograms.toml", schema_for!(SolanaProgramsConfig)),
+    };
+    
+    // In JSON mode the schema is part of the result instead
+    if is_json_output() {
+        set_result(serde_json::json!({ "file": file, "schema": schema }));
+    } else {
+        outln!("{}", serde_json::to_string_pretty(&schema)?);
+    }
+    Ok(())
+}
@@ -0,0 +1,378 @@
+//! # Self-Update Command Implementation
+//!
+//! This module implements the `self-update` command which replaces the
+//! running solpm binary with the latest GitHub release.
+//!
+//! Features:
+//! - Compares the latest release tag with the running version
+//! - `--check` only reports whether an update is available
+//! - Picks the release asset built for this OS and architecture
+//! - Verifies the download against a published `.sha256` file when present
+//! - Asks for confirmation unless `--yes` is passed
+//!
+//! Archives (`.tar.gz`, `.tar.xz`, `.zip`) are unpacked with the system `tar`.
+//! The new binary is written next to the running one and moved into place, so
+//! an interrupted update leaves the old binary working.
+
+use crate::commands::constants::{SELF_UPDATE_RELEASES_URL, TIMEOUT_ENV_VAR};
+use crate::commands::http::{SendLogged, client_timeouts, http_client, request_error};
+use crate::commands::types::{GithubAsset, GithubRelease};
+use crate::error::{Result, SolanaPmError};
+use crate::outln;
+use crate::statusln;
+use crate::output::set_result;
+use crate::utils::{CliProgress, CliStyle, confirm_action};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+const BINARY_NAME: &str = "solpm";
+const ARCHIVE_SUFFIXES: &[&str] = &[".tar.gz", ".tgz", ".tar.xz", ".zip"];
+const CHECKSUM_SUFFIX: &str = ".sha256";
+const DOWNLOAD_MIN_BYTES_PER_SEC: u64 = 32 * 1024;
+
+/// Options for the `self-update` command.
+pub struct SelfUpdateOptions {
+    /// Only report whether a newer release exists
+    pub check: bool,
+    /// Install without asking for confirmation
+    pub yes: bool,
+}
+
+/// Updates the running solpm binary to the latest GitHub release.
+/// 
+/// This function:
+/// 1. Fetches the latest release and compares it with `CARGO_PKG_VERSION`
+/// 2. Stops after reporting the result with `--check`, or when up to date
+/// 3. Asks for confirmation unless `yes` is set
+/// 4. Downloads the asset for this platform and verifies its checksum
+/// 5. Replaces the running binary
+/// 
+/// # Arguments
+/// 
+/// * `options` - Whether to only check and whether to skip the prompt
+/// 
+/// # Returns
+/// 
+/// Returns `Ok(())` when solpm is up to date, the update is installed, or the
+/// user cancels, or an error if the release cannot be fetched, no asset
+/// matches this platform, confirmation is needed in a non-interactive
+/// session, or the binary cannot be replaced.
+/// 
+/// # Examples
+/// 
+/// ```no_run
+/// use solpm::commands::self_update::{SelfUpdateOptions, self_update};
+/// 
+/// # async fn example() -> solpm::error::Result<()> {
+/// // Report whether an update is available
+/// self_update(&SelfUpdateOptions { check: true, yes: false }).await?;
+/// 
+/// // Update without prompting (e.g. in provisioning scripts)
+/// self_update(&SelfUpdateOptions { check: false, yes: true }).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn self_update(options: &SelfUpdateOptions) -> Result<()> {
+    let client = http_client()?;
+    
+    let spinner = CliProgress::new_spinner("Checking for updates...");
+    let response = client.get(SELF_UPDATE_RELEASES_URL).send_logged().await;
+    spinner.finish_and_clear();
+    let response = response.map_err(|e| request_error("Checking for updates", e))?;
+    
+    let status = response.status();
+    if !status.is_success() {
+        let error_text = response.text().await.unwrap_or_default();
+        return Err(SolanaPmError::UploadFailed(format!(
+            "Failed to fetch the latest release from {} ({}): {}",
+            SELF_UPDATE_RELEASES_URL, status, error_text
+        )));
+    }
+    let release: GithubRelease = response.json().await?;
+    
+    let latest_version = release.tag_name.trim_start_matches('v');
+    let (Some(latest), Some(current)) = (parse_release_version(latest_version), parse_release_version(CURRENT_VERSION)) else {
+        return Err(SolanaPmError::DataMissing(format!(
+            "Cannot compare release '{}' with the installed version {}", release.tag_name, CURRENT_VERSION
+        )));
+    };
+    
+    let report = |updated: bool| set_result(serde_json::json!({
+        "current_version": CURRENT_VERSION,
+        "latest_version": latest_version,
+        "update_available": latest > current,
+        "updated": updated,
+        "release_url": release.html_url,
+    }));
+    report(false);
+    
+    if latest <= current {
+        outln!("{}", CliStyle::success(&format!("solpm {} is up to date", CliStyle::version(CURRENT_VERSION))));
+        return Ok(());
+    }
+    
+    statusln!("{}", CliStyle::info(&format!(
+        "solpm {} is available (installed: {})",
+        CliStyle::version(latest_version),
+        CliStyle::version(CURRENT_VERSION)
+    )));
+    outln!("  Release notes: {}", release.html_url);
+    if options.check {
+        outln!("  Run {} to install it", CliStyle::command("solpm self-update"));
+        return Ok(());
+    }
+    
+    let asset = select_asset(&release.assets).ok_or_else(|| SolanaPmError::DataMissing(format!(
+        "Release {} has no binary for {}-{}; download it from {}",
+        release.tag_name, std::env::consts::OS, std::env::consts::ARCH, release.html_url
+    )))?;
+    let current_exe = std::env::current_exe()
+        .and_then(fs::canonicalize)
+        .map_err(|e| SolanaPmError::InvalidPath(format!("Could not locate the running solpm binary: {}", e)))?;
+    
+    if !options.yes {
+        if !std::io::stdin().is_terminal() {
+            return Err(SolanaPmError::DataMissing(
+                "Refusing to update without confirmation in a non-interactive session. Pass --yes to update.".to_string()
+            ));
+        }
+        let prompt = format!("Replace {} with solpm {}?", current_exe.display(), latest_version);
+        if !confirm_action(&prompt)? {
+            statusln!("{}", CliStyle::info("Update cancelled."));
+            return Ok(());
+        }
+    }
+    
+    let bytes = download_asset(&client, asset).await?;
+    if let Some(checksum_asset) = release.assets.iter().find(|candidate| candidate.name == format!("{}{}", asset.name, CHECKSUM_SUFFIX)) {
+        verify_checksum(&client, checksum_asset, &bytes).await?;
+    } else {
+        statusln!("{}", CliStyle::warning(&format!("No {}{} published; skipping checksum verification", asset.name, CHECKSUM_SUFFIX)));
+    }
+    
+    let work_dir = std::env::temp_dir().join(format!("solpm-update-{}", std::process::id()));
+    fs::create_dir_all(&work_dir)?;
+    let result = install_asset(asset, &bytes, &work_dir, &current_exe);
+    let _ = fs::remove_dir_all(&work_dir);
+    result?;
+    
+    outln!("{}", CliStyle::success(&format!(
+        "Updated solpm {} → {}",
+        CliStyle::version(CURRENT_VERSION),
+        CliStyle::version(latest_version)
+    )));
+    report(true);
+    Ok(())
+}
+
+/// Parses a release version into comparable major, minor, and patch numbers.
+/// 
+/// Pre-release and build suffixes are ignored.
+/// 
+/// # Arguments
+/// 
+/// * `version` - A version such as `1.4.0` or `1.4.0-rc.1`
+/// 
+/// # Returns
+/// 
+/// Returns the three numbers, or `None` if the version is not `X.Y.Z`.
+fn parse_release_version(version: &str) -> Option<(u64, u64, u64)> {
+    let core = version.split(['-', '+']).next()?;
+    let mut parts = core.split('.').map(|part| part.parse::<u64>().ok());
+    let version = (parts.next()??, parts.next()??, parts.next()??);
+    parts.next().is_none().then_some(version)
+}
+
+/// Picks the release asset built for this OS and architecture.
+/// 
+/// # Arguments
+/// 
+/// * `assets` - The assets attached to the release
+/// 
+/// # Returns
+/// 
+/// Returns the first archive or bare binary whose name mentions both this
+/// architecture and this OS, or `None` if there is none.
+fn select_asset(assets: &[GithubAsset]) -> Option<&GithubAsset> {
+    let os_names: &[&str] = match std::env::consts::OS {
+        "macos" => &["apple-darwin", "macos", "darwin"],
+        "windows" => &["windows"],
+        os => &[os][..],
+    };
+    let arch_names: &[&str] = match std::env::consts::ARCH {
+        "x86_64" => &["x86_64", "amd64"],
+        "aarch64" => &["aarch64", "arm64"],
+        arch => &[arch][..],
+    };
+    
+    assets.iter()
+        .filter(|asset| !asset.name.ends_with(CHECKSUM_SUFFIX))
+        .find(|asset| {
+            let name = asset.name.to_ascii_lowercase();
+            name.starts_with(BINARY_NAME)
+                && os_names.iter().any(|os| name.contains(os))
+                && arch_names.iter().any(|arch| name.contains(arch))
+        })
+}
+
+/// Downloads a release asset with a progress bar.
+/// 
+/// # Arguments
+/// 
+/// * `client` - The HTTP client to use
+/// * `asset` - The asset to download
+/// 
+/// # Returns
+/// 
+/// Returns the asset's bytes, or an error if the download fails or does
+/// not finish within the request timeout plus one second per 32 KiB.
+async fn download_asset(client: &reqwest::Client, asset: &GithubAsset) -> Result<Vec<u8>> {
+    let (_, request_timeout) = client_timeouts()?;
+    let timeout = request_timeout + std::time::Duration::from_secs(asset.size / DOWNLOAD_MIN_BYTES_PER_SEC);
+    let download_error = |e: reqwest::Error| {
+        if e.is_timeout() && !e.is_connect() {
+            SolanaPmError::Timeout(format!(
+                "Downloading {}: {} did not finish within {} seconds; raise the limit with --timeout or {}",
+                asset.name, asset.browser_download_url, timeout.as_secs(), TIMEOUT_ENV_VAR
+            ))
+        } else {
+            request_error(&format!("Downloading {}", asset.name), e)
+        }
+    };
+    
+    let mut response = client.get(&asset.browser_download_url)
+        .timeout(timeout)
+        .send_logged()
+        .await
+        .map_err(download_error)?
+        .error_for_status()?;
+    
+    let progress = CliProgress::new_bytes_progress_bar(asset.size, &format!("Downloading {}", asset.name));
+    let mut bytes = Vec::with_capacity(asset.size as usize);
+    while let Some(chunk) = response.chunk().await.map_err(download_error)? {
+        CliProgress::inc_bytes(&progress, chunk.len() as u64);
+        bytes.extend_from_slice(&chunk);
+    }
+    progress.finish_and_clear();
+    
+    Ok(bytes)
+}
+
+/// Checks downloaded bytes against a published `.sha256` file.
+/// 
+/// # Arguments
+/// 
+/// * `client` - The HTTP client to use
+/// * `checksum_asset` - The `.sha256` asset, holding the hex digest first
+/// * `bytes` - The downloaded asset
+/// 
+/// # Returns
+/// 
+/// Returns `Ok(())` if the digests match, or an error if the checksum cannot
+/// be fetched or does not match.
+async fn verify_checksum(client: &reqwest::Client, checksum_asset: &GithubAsset, bytes: &[u8]) -> Result<()> {
+    let checksum_file = client.get(&checksum_asset.browser_download_url).send_logged().await?
+        .error_for_status()?
+        .text().await?;
+    let expected = checksum_file.split_whitespace().next().unwrap_or_default().to_ascii_lowercase();
+    let actual = format!("{:x}", Sha256::digest(bytes));
+    
+    if expected != actual {
+        return Err(SolanaPmError::InvalidPath(format!(
+            "Checksum mismatch for the downloaded update: expected {}, got {}; the binary was not replaced",
+            expected, actual
+        )));
+    }
+    
+    outln!("{}", CliStyle::success("Checksum verified"));
+    Ok(())
+}
+
+/// Unpacks a downloaded asset if needed and replaces the running binary.
+/// 
+/// # Arguments
+/// 
+/// * `asset` - The downloaded asset, used for its file name
+/// * `bytes` - The asset's contents
+/// * `work_dir` - An empty directory to unpack into
+/// * `current_exe` - The path of the running binary
+/// 
+/// # Returns
+/// 
+/// Returns `Ok(())` once the new binary is in place, or an error if the
+/// archive cannot be unpacked, holds no solpm binary, or the binary cannot be
+/// replaced.
+fn install_asset(asset: &GithubAsset, bytes: &[u8], work_dir: &Path, current_exe: &Path) -> Result<()> {
+    let download_path = work_dir.join(&asset.name);
+    fs::write(&download_path, bytes)?;
+    tracing::debug!("Wrote {}", download_path.display());
+    
+    let new_binary = if ARCHIVE_SUFFIXES.iter().any(|suffix| asset.name.ends_with(suffix)) {
+        let status = Command::new("tar")
+            .arg("-xf")
+            .arg(&download_path)
+            .arg("-C")
+            .arg(work_dir)
+            .status()
+            .map_err(|e| SolanaPmError::InvalidPath(format!("Failed to run tar to unpack {}: {}", asset.name, e)))?;
+        if !status.success() {
+            return Err(SolanaPmError::InvalidPath(format!("tar could not unpack {}", asset.name)));
+        }
+        
+        let binary_name = format!("{}{}", BINARY_NAME, std::env::consts::EXE_SUFFIX);
+        find_file(work_dir, &binary_name)
+            .ok_or_else(|| SolanaPmError::InvalidPath(format!("{} does not contain {}", asset.name, binary_name)))?
+    } else {
+        download_path
+    };
+    
+    replace_binary(&new_binary, current_exe)
+}
+
+/// Finds a file by name anywhere below a directory.
+fn find_file(dir: &Path, file_name: &str) -> Option<PathBuf> {
+    for entry in fs::read_dir(dir).ok()?.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            if let Some(found) = find_file(&path, file_name) {
+                return Some(found);
+            }
+        } else if path.file_name().is_some_and(|name| name == file_name) {
+            return Some(path);
+        }
+    }
+    None
+}
+
+/// Moves a new binary over the running one.
+/// 
+/// The new binary is first copied next to the running one so the final
+/// rename stays on one filesystem. Windows cannot overwrite a running
+/// executable, so there the old binary is renamed to `.old` first.
+fn replace_binary(new_binary: &Path, current_exe: &Path) -> Result<()> {
+    let staged = current_exe.with_extension("new");
+    fs::copy(new_binary, &staged)
+        .map_err(|e| SolanaPmError::InvalidPath(format!("Failed to write {}: {}", staged.display(), e)))?;
+    
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&staged, fs::Permissions::from_mode(0o755))?;
+    }
+    
+    if cfg!(windows) {
+        let old = current_exe.with_extension("old");
+        let _ = fs::remove_file(&old);
+        fs::rename(current_exe, &old)
+            .map_err(|e| SolanaPmError::InvalidPath(format!("Failed to move {} aside: {}", current_exe.display(), e)))?;
+    }
+    
+    fs::rename(&staged, current_exe).map_err(|e| {
+        let _ = fs::remove_file(&staged);
+        SolanaPmError::InvalidPath(format!("Failed to replace {}: {}", current_exe.display(), e))
+    })
+}
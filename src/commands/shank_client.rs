@@ -0,0 +1,416 @@
+//! # Shank Client Module
+//!
+//! This module generates TypeScript clients for native programs described by
+//! Shank IDLs (`solpm codegen --idl-format shank`).
+//!
+//! Native programs dispatch on the 1-byte discriminant Shank records for each
+//! instruction, not on the 8-byte sighash `anchor.Program` encodes, so these
+//! clients do not use Anchor. Each instruction gets a
+//! `create{Name}Instruction(accounts, args, programId)` builder returning a
+//! `TransactionInstruction` whose data is the discriminant followed by the
+//! Borsh-encoded args, and whose keys are the instruction's accounts in IDL
+//! order. Args and defined types are encoded with `@coral-xyz/borsh`.
+//!
+//! A type that has no Borsh layout is an error: a client that encodes it
+//! wrongly would only fail on-chain.
+
+use crate::commands::codegen::{snake_to_camel, snake_to_pascal};
+use crate::commands::types::{Idl, IdlInstruction, Program, pascal_to_snake};
+use crate::error::{Result, SolanaPmError};
+use serde_json::Value;
+use std::collections::HashSet;
+
+/// Primitive IDL types with a `@coral-xyz/borsh` layout of the same name.
+const BORSH_PRIMITIVES: [&str; 13] = ["u8", "i8", "u16", "i16", "u32", "i32", "u64", "i64", "u128", "i128", "f32", "f64", "bool"];
+
+/// Generates the TypeScript client of a program with a Shank IDL.
+/// 
+/// # Arguments
+/// 
+/// * `idl` - The Shank IDL converted to the internal representation
+/// * `program_name` - The name of the program
+/// * `program_info` - Program metadata including the program ID
+/// * `remaining_accounts` - Whether an instruction's builder accepts remaining accounts
+/// 
+/// # Returns
+/// 
+/// Returns the complete TypeScript code, or an error listing every arg or
+/// defined type that cannot be encoded and every instruction without a
+/// discriminant.
+/// 
+/// # Examples
+/// 
+/// ```text
+/// let code = generate_shank_ts_code(&idl, "vesting", &program_info, |_| false)?;
+/// assert!(code.contains("export const createCreateVestingInstruction = (accounts, args, programId = PROGRAM_ID) => {"));
+/// ```
+pub fn generate_shank_ts_code(idl: &Idl, program_name: &str, program_info: &Program, remaining_accounts: impl Fn(&IdlInstruction) -> bool) -> Result<String> {
+    let type_names: HashSet<&str> = idl.types.iter()
+        .flatten()
+        .filter_map(|type_def| type_def["name"].as_str())
+        .collect();
+    
+    let mut code = String::new();
+    let mut problems = Vec::new();
+    
+    // Imports
+    code.push_str("import * as borsh from '@coral-xyz/borsh';\n");
+    code.push_str("import { PublicKey, TransactionInstruction } from '@solana/web3.js';\n\n");
+    
+    // Constants
+    code.push_str("// Your deployed program ID\n");
+    code.push_str(&format!("const PROGRAM_ID = new PublicKey('{}');\n\n", program_info.program_id));
+    
+    // Borsh layouts of the defined types
+    for type_def in idl.types.iter().flatten() {
+        match type_layout_function(type_def, &type_names) {
+            Ok(function) => code.push_str(&function),
+            Err(problem) => problems.push(problem),
+        }
+    }
+    
+    // Instruction builders
+    for instruction in &idl.instructions {
+        match instruction_builder(instruction, &type_names, remaining_accounts(instruction)) {
+            Ok(builder) => code.push_str(&builder),
+            Err(problem) => problems.push(problem),
+        }
+    }
+    
+    if !problems.is_empty() {
+        return Err(SolanaPmError::InvalidIdl(format!(
+            "Cannot generate the {} client from its Shank IDL:\n  - {}",
+            program_name,
+            problems.join("\n  - ")
+        )));
+    }
+    
+    Ok(code)
+}
+
+/// Generates the `create{Name}Instruction` builder of an instruction.
+/// 
+/// The instruction data is built in a buffer of the maximum transaction
+/// size: the discriminant byte first, then the args struct encoded after it.
+/// 
+/// # Arguments
+/// 
+/// * `instruction` - The instruction, carrying its Shank discriminant
+/// * `type_names` - Names of the IDL's defined types
+/// * `remaining_accounts` - Whether the builder appends a `remainingAccounts` list
+/// 
+/// # Returns
+/// 
+/// Returns the builder code, or a description of why it cannot be generated.
+fn instruction_builder(instruction: &IdlInstruction, type_names: &HashSet<&str>, remaining_accounts: bool) -> std::result::Result<String, String> {
+    let pascal_name = snake_to_pascal(&instruction.name);
+    let Some(discriminant) = instruction.discriminant else {
+        return Err(format!("instruction {}: no 1-byte discriminant", pascal_name));
+    };
+    
+    let mut fields = Vec::new();
+    for arg in &instruction.args {
+        let layout = borsh_layout(&arg.arg_type, Some(&arg.name), type_names)
+            .ok_or_else(|| format!("instruction {} arg {}: no Borsh layout for type {}", pascal_name, arg.name, arg.arg_type))?;
+        fields.push(layout);
+    }
+    
+    let mut params = vec!["accounts"];
+    if !fields.is_empty() {
+        params.push("args");
+    }
+    if remaining_accounts {
+        params.push("remainingAccounts = []");
+    }
+    params.push("programId = PROGRAM_ID");
+    
+    let mut code = String::new();
+    code.push_str(&format!("// {} (discriminant {})\n", pascal_name, discriminant));
+    if remaining_accounts {
+        code.push_str("// remainingAccounts: Array<{ pubkey: PublicKey, isSigner: boolean, isWritable: boolean }>\n");
+    }
+    code.push_str(&format!("export const create{}Instruction = ({}) => {{\n", pascal_name, params.join(", ")));
+    
+    if fields.is_empty() {
+        code.push_str(&format!("  const data = Buffer.from([{}]);\n", discriminant));
+    } else {
+        code.push_str("  // Instruction data cannot exceed the 1232-byte transaction size\n");
+        code.push_str("  const buffer = Buffer.alloc(1232);\n");
+        code.push_str(&format!("  buffer.writeUInt8({}, 0);\n", discriminant));
+        code.push_str("  const length = borsh.struct([\n");
+        for field in &fields {
+            code.push_str(&format!("    {},\n", field));
+        }
+        code.push_str("  ]).encode(args, buffer, 1);\n");
+        code.push_str("  const data = buffer.subarray(0, 1 + length);\n");
+    }
+    code.push_str("  \n");
+    
+    code.push_str("  return new TransactionInstruction({\n");
+    code.push_str("    programId,\n");
+    code.push_str("    keys: [\n");
+    for account in &instruction.accounts {
+        code.push_str(&format!(
+            "      {{ pubkey: accounts.{}, isSigner: {}, isWritable: {} }},\n",
+            snake_to_camel(&account.name), account.is_signer_account(), account.is_writable()
+        ));
+    }
+    if remaining_accounts {
+        code.push_str("      ...remainingAccounts,\n");
+    }
+    code.push_str("    ],\n");
+    code.push_str("    data,\n");
+    code.push_str("  });\n");
+    code.push_str("};\n\n");
+    
+    Ok(code)
+}
+
+/// Generates the layout function of a defined type.
+/// 
+/// Structs become `borsh.struct` and enums `borsh.rustEnum`, whose tuple
+/// fields are named `_0`, `_1`, and so on. The layout is a function of the
+/// property name, so a type can be used by any arg or field, and types may
+/// refer to each other regardless of their order in the IDL.
+/// 
+/// # Arguments
+/// 
+/// * `type_def` - The IDL type definition, e.g. `{"name": "Config", "type": {"kind": "struct", ...}}`
+/// * `type_names` - Names of the IDL's defined types
+/// 
+/// # Returns
+/// 
+/// Returns the layout function code, or a description of why it cannot be
+/// generated.
+fn type_layout_function(type_def: &Value, type_names: &HashSet<&str>) -> std::result::Result<String, String> {
+    let name = type_def["name"].as_str().unwrap_or_default();
+    let function_name = layout_function_name(name)
+        .ok_or_else(|| format!("type '{}': not a valid TypeScript identifier", name))?;
+    
+    let layout = match type_def["type"]["kind"].as_str() {
+        Some("struct") => {
+            let fields = field_layouts(&type_def["type"]["fields"], type_names)
+                .ok_or_else(|| format!("type {}: a field has no Borsh layout", name))?;
+            format!("borsh.struct([{}], property)", fields.join(", "))
+        }
+        Some("enum") => {
+            let mut variants = Vec::new();
+            for variant in type_def["type"]["variants"].as_array().into_iter().flatten() {
+                let variant_name = variant["name"].as_str().unwrap_or_default();
+                let fields = field_layouts(&variant["fields"], type_names)
+                    .ok_or_else(|| format!("type {} variant {}: a field has no Borsh layout", name, variant_name))?;
+                variants.push(format!("borsh.struct([{}], '{}')", fields.join(", "), variant_name));
+            }
+            format!("borsh.rustEnum([{}], property)", variants.join(", "))
+        }
+        kind => return Err(format!("type {}: unsupported kind {}", name, kind.unwrap_or("(none)"))),
+    };
+    
+    Ok(format!("// Borsh layout of {}\nconst {} = (property) => {};\n\n", name, function_name, layout))
+}
+
+/// Builds the field layouts of a struct or enum variant.
+/// 
+/// # Arguments
+/// 
+/// * `fields` - Named fields (`[{"name": ..., "type": ...}]`), tuple fields
+///   (`["u8", ...]`), or null for a unit variant
+/// * `type_names` - Names of the IDL's defined types
+/// 
+/// # Returns
+/// 
+/// Returns the field layouts, or `None` if a field has no Borsh layout.
+fn field_layouts(fields: &Value, type_names: &HashSet<&str>) -> Option<Vec<String>> {
+    fields.as_array()
+        .into_iter()
+        .flatten()
+        .enumerate()
+        .map(|(index, field)| match field["name"].as_str() {
+            Some(name) => borsh_layout(&field["type"], Some(name), type_names),
+            None => borsh_layout(field, Some(&format!("_{}", index)), type_names),
+        })
+        .collect()
+}
+
+/// Maps an IDL type to a `@coral-xyz/borsh` layout expression.
+/// 
+/// # Arguments
+/// 
+/// * `idl_type` - The IDL type, e.g. `"u64"` or `{"vec": "publicKey"}`
+/// * `property` - The property the value is read from, or `None` for the
+///   element layout of an option, vec, or array
+/// * `type_names` - Names of the IDL's defined types
+/// 
+/// # Returns
+/// 
+/// Returns the layout expression, or `None` if the type has no layout.
+fn borsh_layout(idl_type: &Value, property: Option<&str>, type_names: &HashSet<&str>) -> Option<String> {
+    let property = property.map(|name| format!("'{}'", name)).unwrap_or_default();
+    let with_property = |args: String| if property.is_empty() { args } else { format!("{}, {}", args, property) };
+    
+    match idl_type {
+        Value::String(name) => {
+            let layout = match name.as_str() {
+                primitive if BORSH_PRIMITIVES.contains(&primitive) => primitive,
+                "string" => "str",
+                "publicKey" | "pubkey" => "publicKey",
+                "bytes" => "vecU8",
+                _ => return None,
+            };
+            Some(format!("borsh.{}({})", layout, property))
+        }
+        Value::Object(obj) => {
+            if let Some(inner) = obj.get("option") {
+                Some(format!("borsh.option({})", with_property(borsh_layout(inner, None, type_names)?)))
+            } else if let Some(inner) = obj.get("vec") {
+                Some(format!("borsh.vec({})", with_property(borsh_layout(inner, None, type_names)?)))
+            } else if let Some(Value::Array(array)) = obj.get("array")
+                && let [inner, Value::Number(len)] = array.as_slice()
+            {
+                Some(format!("borsh.array({})", with_property(format!("{}, {}", borsh_layout(inner, None, type_names)?, len))))
+            } else if let Some(defined) = obj.get("defined") {
+                let name = defined.as_str().or_else(|| defined["name"].as_str())?;
+                if !type_names.contains(name) {
+                    return None;
+                }
+                Some(format!("{}({})", layout_function_name(name)?, property))
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Names the layout function of a defined type, e.g. `configLayout` for `Config`.
+/// 
+/// # Arguments
+/// 
+/// * `type_name` - The PascalCase type name from the IDL
+/// 
+/// # Returns
+/// 
+/// Returns the function name, or `None` if the type name cannot be used in
+/// an identifier.
+fn layout_function_name(type_name: &str) -> Option<String> {
+    let valid = type_name.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+        && type_name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    valid.then(|| format!("{}Layout", snake_to_camel(&pascal_to_snake(type_name))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::types::ShankIdl;
+    use serde_json::json;
+    
+    /// A devnet program with a Shank IDL.
+    fn program() -> Program {
+        Program {
+            version: "1.0.0".to_string(),
+            program_id: "Vest1ngZbdCMbGVTwfM8fBNBfWqBqTBBHwCbUHmBxjr".to_string(),
+            network: "devnet".to_string(),
+            idl_path: None,
+            installed_at: None,
+            source: None,
+            alias: None,
+            tags: Vec::new(),
+        }
+    }
+    
+    /// Parses a Shank IDL from JSON into the internal representation.
+    fn shank_idl(value: Value) -> Idl {
+        Idl::from(serde_json::from_value::<ShankIdl>(value).unwrap())
+    }
+    
+    #[test]
+    fn builders_encode_the_discriminant_args_and_account_metas() {
+        let idl = shank_idl(json!({
+            "instructions": [
+                {
+                    "name": "CreateVesting",
+                    "accounts": [
+                        { "name": "authority", "isMut": true, "isSigner": true },
+                        { "name": "vesting", "isMut": true, "isSigner": false },
+                        { "name": "systemProgram", "isMut": false, "isSigner": false }
+                    ],
+                    "args": [
+                        { "name": "amount", "type": "u64" },
+                        { "name": "schedule", "type": { "defined": "Schedule" } },
+                        { "name": "memo", "type": { "option": "string" } }
+                    ],
+                    "discriminant": { "type": "u8", "value": 3 }
+                },
+                { "name": "Close", "accounts": [{ "name": "authority", "isMut": false, "isSigner": true }], "args": [] }
+            ],
+            "types": [
+                {
+                    "name": "Schedule",
+                    "type": { "kind": "struct", "fields": [{ "name": "start", "type": "i64" }, { "name": "cliffs", "type": { "vec": "u8" } }] }
+                },
+                {
+                    "name": "Kind",
+                    "type": { "kind": "enum", "variants": [{ "name": "Linear" }, { "name": "Stepped", "fields": ["u32"] }] }
+                }
+            ],
+            "metadata": { "origin": "shank" }
+        }));
+        
+        let code = generate_shank_ts_code(&idl, "vesting", &program(), |_| false).unwrap();
+        
+        assert!(code.contains("const PROGRAM_ID = new PublicKey('Vest1ngZbdCMbGVTwfM8fBNBfWqBqTBBHwCbUHmBxjr');\n"), "{}", code);
+        assert!(code.contains("const scheduleLayout = (property) => borsh.struct([borsh.i64('start'), borsh.vec(borsh.u8(), 'cliffs')], property);\n"), "{}", code);
+        assert!(code.contains("const kindLayout = (property) => borsh.rustEnum([borsh.struct([], 'Linear'), borsh.struct([borsh.u32('_0')], 'Stepped')], property);\n"), "{}", code);
+        
+        let create = code.split("// CreateVesting (discriminant 3)\n").nth(1).unwrap();
+        let create = create.split("};\n").next().unwrap();
+        assert!(create.starts_with("export const createCreateVestingInstruction = (accounts, args, programId = PROGRAM_ID) => {\n"), "{}", code);
+        assert!(create.contains("  buffer.writeUInt8(3, 0);\n"), "{}", code);
+        assert!(create.contains("    borsh.u64('amount'),\n    scheduleLayout('schedule'),\n    borsh.option(borsh.str(), 'memo'),\n"), "{}", code);
+        assert!(create.contains("  ]).encode(args, buffer, 1);\n  const data = buffer.subarray(0, 1 + length);\n"), "{}", code);
+        assert!(create.contains(concat!(
+            "      { pubkey: accounts.authority, isSigner: true, isWritable: true },\n",
+            "      { pubkey: accounts.vesting, isSigner: false, isWritable: true },\n",
+            "      { pubkey: accounts.systemProgram, isSigner: false, isWritable: false },\n",
+        )), "{}", code);
+        
+        // Without a recorded discriminant, the instruction's position is used
+        assert!(code.contains("// Close (discriminant 1)\nexport const createCloseInstruction = (accounts, programId = PROGRAM_ID) => {\n  const data = Buffer.from([1]);\n"), "{}", code);
+        
+        assert!(!code.contains("anchor"), "{}", code);
+        assert!(!code.contains("program.methods"), "{}", code);
+    }
+    
+    #[test]
+    fn remaining_accounts_follow_the_idl_accounts() {
+        let idl = shank_idl(json!({
+            "instructions": [{ "name": "Distribute", "accounts": [{ "name": "payer", "isMut": true, "isSigner": true }], "args": [] }]
+        }));
+        
+        let code = generate_shank_ts_code(&idl, "vesting", &program(), |instruction| instruction.name == "distribute").unwrap();
+        assert!(code.contains("export const createDistributeInstruction = (accounts, remainingAccounts = [], programId = PROGRAM_ID) => {\n"), "{}", code);
+        assert!(code.contains("      { pubkey: accounts.payer, isSigner: true, isWritable: true },\n      ...remainingAccounts,\n    ],\n"), "{}", code);
+    }
+    
+    #[test]
+    fn types_without_a_borsh_layout_are_refused() {
+        let idl = shank_idl(json!({
+            "instructions": [{
+                "name": "Configure",
+                "accounts": [],
+                "args": [
+                    { "name": "limits", "type": { "hashMap": ["string", "u64"] } },
+                    { "name": "config", "type": { "defined": "Missing" } }
+                ]
+            }],
+            "types": [{ "name": "Alias", "type": { "kind": "alias", "value": "u64" } }]
+        }));
+        
+        let Err(SolanaPmError::InvalidIdl(message)) = generate_shank_ts_code(&idl, "vesting", &program(), |_| false) else {
+            panic!("expected an InvalidIdl error");
+        };
+        assert!(message.starts_with("Cannot generate the vesting client from its Shank IDL:"), "{}", message);
+        assert!(message.contains("type Alias: unsupported kind alias"), "{}", message);
+        assert!(message.contains("instruction Configure arg limits: no Borsh layout"), "{}", message);
+    }
+}
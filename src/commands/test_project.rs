@@ -0,0 +1,51 @@
+//! # Test Project Module
+//!
+//! This module runs tests inside a temporary project directory, for commands
+//! that read and write SolanaPrograms.json and the IDL directory relative to
+//! the working directory.
+//!
+//! The working directory is shared by the whole test process, so a test
+//! holds a lock from [`enter`] until its [`TempProject`] is dropped. Tests
+//! that only use absolute paths do not need it.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use tempfile::TempDir;
+
+/// Serializes the tests that change the working directory.
+static WORKING_DIR: Mutex<()> = Mutex::new(());
+
+/// A temporary directory the process works in until it is dropped.
+pub struct TempProject {
+    dir: TempDir,
+    previous: PathBuf,
+    _lock: MutexGuard<'static, ()>,
+}
+
+impl TempProject {
+    /// Returns the project directory.
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+}
+
+impl Drop for TempProject {
+    fn drop(&mut self) {
+        let _ = std::env::set_current_dir(&self.previous);
+    }
+}
+
+/// Creates an empty project directory and makes it the working directory.
+/// 
+/// # Returns
+/// 
+/// Returns the project, which restores the previous working directory when
+/// dropped.
+pub fn enter() -> TempProject {
+    // A test that failed while holding the lock still restored the directory
+    let lock = WORKING_DIR.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let previous = std::env::current_dir().unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    std::env::set_current_dir(dir.path()).unwrap();
+    TempProject { dir, previous, _lock: lock }
+}
@@ -12,49 +12,359 @@
 //! These types ensure type safety and consistency across all CLI operations,
 //! from project initialization to program publishing and dependency management.
 
+use crate::cli::Network;
+use crate::error::{Result, SolanaPmError};
+use crate::utils::parse_network;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-#[derive(Serialize, Deserialize, Debug)]
+/// SolanaPrograms.toml, the publishing configuration of a project.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
 pub struct SolanaProgramsConfig {
-    pub program: ProgramConfig,
+    /// The program of a single-program project (`[program]`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub program: Option<ProgramConfig>,
+    /// The programs of a multi-program workspace (`[[programs]]`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub programs: Vec<ProgramConfig>,
+    /// Commands run around publishing (`[hooks]`)
+    #[serde(default, skip_serializing_if = "HooksConfig::is_empty")]
+    pub hooks: HooksConfig,
+    /// Registry timeouts used by publish (`[publish]`)
+    #[serde(default, skip_serializing_if = "PublishConfig::is_empty")]
+    pub publish: PublishConfig,
+    /// Project directories and defaults (`[solpm]`)
+    #[serde(default, skip_serializing_if = "ProjectSettings::is_empty")]
+    pub solpm: ProjectSettings,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+impl SolanaProgramsConfig {
+    /// Returns every configured program, from `[program]` or `[[programs]]`.
+    pub fn all_programs_mut(&mut self) -> Vec<&mut ProgramConfig> {
+        self.program.iter_mut().chain(self.programs.iter_mut()).collect()
+    }
+    
+    /// Returns the configured program with the given name.
+    pub fn find_program(&self, name: &str) -> Option<&ProgramConfig> {
+        self.program.iter().chain(self.programs.iter()).find(|program| program.name == name)
+    }
+    
+    /// Removes and returns the program to operate on.
+    /// 
+    /// A `[program]` section is returned as is (and must match `name` if one
+    /// is given). With `[[programs]]`, `name` selects an entry and may only be
+    /// omitted when there is exactly one.
+    pub fn take_program(&mut self, name: Option<&str>) -> Result<ProgramConfig> {
+        if self.program.is_some() && !self.programs.is_empty() {
+            return Err(SolanaPmError::InvalidPath(
+                "Invalid TOML format: use either [program] or [[programs]], not both".to_string()
+            ));
+        }
+        
+        if let Some(program) = self.program.take() {
+            return match name {
+                Some(name) if name != program.name => Err(SolanaPmError::DataMissing(format!(
+                    "Program '{}' is not configured; SolanaPrograms.toml configures '{}'", name, program.name
+                ))),
+                _ => Ok(program),
+            };
+        }
+        
+        let names: Vec<&str> = self.programs.iter().map(|program| program.name.as_str()).collect();
+        let index = match name {
+            Some(name) => self.programs.iter().position(|program| program.name == name).ok_or_else(|| {
+                SolanaPmError::DataMissing(format!(
+                    "Program '{}' is not configured. Configured programs: {}", name, names.join(", ")
+                ))
+            })?,
+            None if self.programs.len() == 1 => 0,
+            None if self.programs.is_empty() => {
+                return Err(SolanaPmError::ConfigNotFound(
+                    "SolanaPrograms.toml has no [program] section or [[programs]] entries".to_string()
+                ));
+            }
+            None => {
+                return Err(SolanaPmError::DataMissing(format!(
+                    "SolanaPrograms.toml configures {} programs ({}). Pass --program <name> to choose one.",
+                    names.len(), names.join(", ")
+                )));
+            }
+        };
+        
+        Ok(self.programs.remove(index))
+    }
+}
+
+/// Project settings from `.solpmrc`.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct SolpmRc {
+    /// Shell command run after `solpm install` succeeds (requires `--allow-hooks`)
+    #[serde(default)]
+    pub post_install: Option<String>,
+    /// Store newly added IDLs under `./program/idl/{network}/`
+    #[serde(default)]
+    pub per_network_idl: bool,
+    /// Registry base URL for this project
+    #[serde(default)]
+    pub registry: Option<String>,
+}
+
+/// User settings from `~/.solpm/config.toml`.
+/// 
+/// Every field is optional; unset fields fall back to the built-in defaults.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct GlobalConfig {
+    /// Network used when `--network` is not passed
+    #[serde(default)]
+    pub network: Option<String>,
+    /// Registry base URL used when a project does not set one
+    #[serde(default)]
+    pub registry: Option<String>,
+    /// Directory generated clients are written to
+    #[serde(default)]
+    pub client_dir: Option<String>,
+    /// How many times a failed download is retried
+    #[serde(default)]
+    pub retries: Option<usize>,
+    /// Request timeout in seconds
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+    /// Connect timeout in seconds
+    #[serde(default)]
+    pub connect_timeout_secs: Option<u64>,
+    /// HTTP proxy used for every request
+    #[serde(default)]
+    pub proxy: Option<String>,
+    /// Registries tried in order when the primary cannot serve a read
+    #[serde(default)]
+    pub mirrors: Option<Vec<String>>,
+    /// Leave the OS and architecture out of the User-Agent header
+    #[serde(default)]
+    pub anonymous_headers: Option<bool>,
+    /// RPC endpoints used instead of the public ones
+    #[serde(default)]
+    pub rpc: RpcEndpoints,
+}
+
+/// RPC endpoints per network, from the `rpc` table of a config file.
+/// 
+/// Networks without an entry use the public endpoint.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, JsonSchema)]
+pub struct RpcEndpoints {
+    /// RPC URL used for mainnet
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mainnet: Option<String>,
+    /// RPC URL used for devnet
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub devnet: Option<String>,
+    /// RPC URL used for testnet
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub testnet: Option<String>,
+    /// RPC URL used for localnet
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub localnet: Option<String>,
+}
+
+impl RpcEndpoints {
+    pub fn is_empty(&self) -> bool {
+        self.mainnet.is_none() && self.devnet.is_none() && self.testnet.is_none() && self.localnet.is_none()
+    }
+    
+    /// Returns the configured endpoint for a network, if any.
+    pub fn get(&self, network: &Network) -> Option<&String> {
+        match network {
+            Network::Main => self.mainnet.as_ref(),
+            Network::Dev => self.devnet.as_ref(),
+            Network::Test => self.testnet.as_ref(),
+            Network::Local => self.localnet.as_ref(),
+        }
+    }
+    
+    /// Returns every endpoint as `(network, url)` pairs, for validation.
+    pub fn entries(&self) -> [(&'static str, Option<&String>); 4] {
+        [
+            ("mainnet", self.mainnet.as_ref()),
+            ("devnet", self.devnet.as_ref()),
+            ("testnet", self.testnet.as_ref()),
+            ("localnet", self.localnet.as_ref()),
+        ]
+    }
+}
+
+/// Project settings from the `[solpm]` section of SolanaPrograms.toml or
+/// from `.solpm.toml`.
+/// 
+/// Directories are relative to the project root.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, JsonSchema)]
+pub struct ProjectSettings {
+    /// Directory IDL files are stored in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idl_dir: Option<String>,
+    /// Directory generated clients are written to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_dir: Option<String>,
+    /// Network used when `--network` is not passed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_network: Option<String>,
+    /// Registry base URL for this project
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub registry: Option<String>,
+    /// RPC endpoints for this project
+    #[serde(default, skip_serializing_if = "RpcEndpoints::is_empty")]
+    pub rpc: RpcEndpoints,
+}
+
+impl ProjectSettings {
+    pub fn is_empty(&self) -> bool {
+        self.idl_dir.is_none() && self.client_dir.is_none() && self.default_network.is_none() && self.registry.is_none() && self.rpc.is_empty()
+    }
+}
+
+/// Commands from the `[hooks]` section of SolanaPrograms.toml.
+#[derive(Serialize, Deserialize, Debug, Default, JsonSchema)]
+pub struct HooksConfig {
+    /// Shell commands run in order before publishing
+    #[serde(default)]
+    pub pre_publish: Vec<String>,
+}
+
+impl HooksConfig {
+    pub fn is_empty(&self) -> bool {
+        self.pre_publish.is_empty()
+    }
+}
+
+/// Settings from the `[publish]` section of SolanaPrograms.toml.
+#[derive(Serialize, Deserialize, Debug, Default, JsonSchema)]
+pub struct PublishConfig {
+    /// Seconds allowed for establishing a connection to the registry
+    pub connect_timeout_secs: Option<u64>,
+    /// Seconds allowed for each registry request, before size-based extensions for uploads
+    pub timeout_secs: Option<u64>,
+}
+
+impl PublishConfig {
+    pub fn is_empty(&self) -> bool {
+        self.connect_timeout_secs.is_none() && self.timeout_secs.is_none()
+    }
+}
+
+/// A program published from SolanaPrograms.toml.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
 pub struct ProgramConfig {
+    /// Registry name the program is published under
     pub name: String,
+    /// Semantic version to publish
     pub version: String,
+    /// Base58 address of the deployed program
     pub program_id: String,
+    /// Network the program is deployed to
+    #[serde(deserialize_with = "deserialize_network")]
+    #[schemars(schema_with = "network_schema")]
     pub network: String,
+    /// One-line summary shown in the registry
     pub description: String,
+    /// URL of the source repository
     pub repository: String,
+    /// SPDX license expression, e.g. `MIT` or `Apache-2.0`
+    #[serde(default)]
+    pub license: String,
+    /// Path to the upgrade authority keypair, or `ledger://?key=0/0`
     pub authority_keypair: String,
+    /// Search keywords shown in the registry
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keywords: Vec<String>,
+    /// IDL file recorded by `init --from-idl`, used instead of searching
+    /// target/idl, idl, and target/deploy
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idl: Option<String>,
+}
+
+/// Deserializes a manifest `network`, rejecting unknown names and storing
+/// the canonical spelling (see `parse_network`).
+/// 
+/// An empty value is kept so the missing-field checks of `verify` and
+/// `publish` can report it.
+fn deserialize_network<'de, D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<String, D::Error> {
+    let network = String::deserialize(deserializer)?;
+    if network.trim().is_empty() {
+        return Ok(network);
+    }
+    
+    parse_network(&network)
+        .map(|network| network.as_str().to_string())
+        .map_err(|_| serde::de::Error::custom(format!(
+            "unknown network '{}'; expected mainnet, devnet, testnet, or localnet",
+            network
+        )))
+}
+
+/// Describes a manifest `network` in JSON Schema: a known network name,
+/// in any of the spellings `deserialize_network` accepts.
+fn network_schema(_generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+    schemars::json_schema!({
+        "type": "string",
+        "enum": ["mainnet", "devnet", "testnet", "localnet", "mainnet-beta", "localhost"],
+    })
+}
+
+/// Deserializes a list that may be absent or `null` as an empty list.
+fn deserialize_null_as_empty<'de, D, T>(deserializer: D) -> std::result::Result<Vec<T>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Ok(Option::<Vec<T>>::deserialize(deserializer)?.unwrap_or_default())
 }
 
-#[derive(Serialize, Deserialize, Clone)]
+/// A dependency recorded in SolanaPrograms.json.
+#[derive(Serialize, Deserialize, Clone, JsonSchema)]
 pub struct Program {
+    /// Installed version
     pub version: String,
+    /// Base58 address of the program
     pub program_id: String,
+    /// Network the IDL was fetched for
+    #[serde(deserialize_with = "deserialize_network")]
+    #[schemars(schema_with = "network_schema")]
     pub network: String,
+    /// Where the IDL file is stored
     #[serde(skip_serializing_if = "Option::is_none")]
     pub idl_path: Option<String>,
+    /// When the IDL was downloaded (RFC 3339)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub installed_at: Option<String>,
+    /// Registry the IDL was downloaded from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// Name codegen uses for the client file instead of the program name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+    /// Labels such as `frontend` or `tests` that `install --tag` selects by;
+    /// absent in manifests written before tags existed, so it defaults to empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
 }
 
-#[derive(Serialize, Deserialize)]
+impl Program {
+    /// Returns whether the program carries one of the given tags, or `true`
+    /// when no tags are given.
+    pub fn has_any_tag(&self, tags: &[String]) -> bool {
+        tags.is_empty() || self.tags.iter().any(|tag| tags.contains(tag))
+    }
+}
+
+/// SolanaPrograms.json, the dependencies of a project.
+#[derive(Serialize, Deserialize, JsonSchema)]
 pub struct SolanaPrograms {
+    /// Dependencies, by registry name
     pub programs: HashMap<String, Program>,
+    /// Development dependencies, by registry name
     #[serde(rename = "devPrograms")]
     pub dev_programs: HashMap<String, Program>,
 }
 
-#[derive(Serialize, Deserialize)]
-pub struct IdlMetadata {
-    pub name: String,
-    pub version: String,
-    pub spec: String,
-    pub description: String,
-}
-
 #[derive(Serialize, Deserialize)]
 pub struct IdlArg {
     pub name: String,
@@ -64,20 +374,38 @@ pub struct IdlArg {
 
 impl IdlArg {
     pub fn get_type_string(&self) -> String {
-        match &self.arg_type {
-            serde_json::Value::String(s) => s.clone(),
-            serde_json::Value::Object(obj) => {
-                // Handle complex types like {"option": "u64"}
-                if let Some(option_type) = obj.get("option") {
-                    format!("option_{}", option_type.as_str().unwrap_or("unknown"))
-                } else if let Some(defined_type) = obj.get("defined") {
-                    format!("defined_{}", defined_type.as_str().unwrap_or("unknown"))
-                } else {
-                    "unknown".to_string()
-                }
+        idl_type_string(&self.arg_type)
+    }
+}
+
+/// Flattens an IDL type into a string such as `u64`, `option_u64`,
+/// `vec_pubkey`, `array_u8_32`, or `defined_Config`.
+/// 
+/// Unrecognized shapes contain `unknown`.
+pub fn idl_type_string(idl_type: &serde_json::Value) -> String {
+    match idl_type {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Object(obj) => {
+            // Handle complex types like {"option": "u64"}
+            if let Some(option_type) = obj.get("option") {
+                format!("option_{}", idl_type_string(option_type))
+            } else if let Some(vec_type) = obj.get("vec") {
+                format!("vec_{}", idl_type_string(vec_type))
+            } else if let Some(serde_json::Value::Array(array)) = obj.get("array")
+                && let [element_type, serde_json::Value::Number(len)] = array.as_slice()
+            {
+                format!("array_{}_{}", idl_type_string(element_type), len)
+            } else if let Some(defined_type) = obj.get("defined") {
+                // Anchor 0.30 wraps the name in an object: {"defined": {"name": "Config"}}
+                let name = defined_type.as_str()
+                    .or_else(|| defined_type["name"].as_str())
+                    .unwrap_or("unknown");
+                format!("defined_{}", name)
+            } else {
+                "unknown".to_string()
             }
-            _ => "unknown".to_string(),
         }
+        _ => "unknown".to_string(),
     }
 }
 
@@ -92,6 +420,10 @@ pub struct IdlSeed {
 #[derive(Serialize, Deserialize)]
 pub struct IdlPda {
     pub seeds: Vec<IdlSeed>,
+    /// Program the PDA is derived against when it is not the IDL's own
+    /// program: a `const` public key, or an `account`/`arg` reference
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub program: Option<IdlSeed>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -122,17 +454,127 @@ impl IdlAccount {
 #[derive(Serialize, Deserialize)]
 pub struct IdlInstruction {
     pub name: String,
+    #[serde(default)]
+    pub docs: Vec<String>,
     pub accounts: Vec<IdlAccount>,
     pub args: Vec<IdlArg>,
+    /// The 1-byte instruction discriminant of a Shank IDL; Anchor IDLs
+    /// dispatch on 8-byte sighashes instead and leave it empty
+    #[serde(skip)]
+    pub discriminant: Option<u8>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct IdlConstant {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub constant_type: serde_json::Value,  // Can be string or complex object
+    pub value: String,  // The Rust expression, e.g. "100", "\"seed\"" or "[1, 2]"
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct Idl {
+    #[serde(default, deserialize_with = "deserialize_null_as_empty")]
     pub instructions: Vec<IdlInstruction>,
     pub accounts: Option<Vec<serde_json::Value>>,
     pub events: Option<Vec<serde_json::Value>>,
     pub errors: Option<Vec<serde_json::Value>>,
     pub types: Option<Vec<serde_json::Value>>,
+    pub constants: Option<Vec<IdlConstant>>,
+}
+
+#[derive(Deserialize)]
+pub struct ShankAccount {
+    pub name: String,
+    #[serde(rename = "isMut", default)]
+    pub is_mut: bool,
+    #[serde(rename = "isSigner", default)]
+    pub is_signer: bool,
+}
+
+/// The discriminant Shank records for an instruction, e.g.
+/// `{"type": "u8", "value": 0}`.
+#[derive(Deserialize)]
+pub struct ShankDiscriminant {
+    pub value: u8,
+}
+
+#[derive(Deserialize)]
+pub struct ShankInstruction {
+    pub name: String,
+    #[serde(default)]
+    pub accounts: Vec<ShankAccount>,
+    #[serde(default)]
+    pub args: Vec<IdlArg>,
+    pub discriminant: Option<ShankDiscriminant>,
+}
+
+#[derive(Deserialize)]
+pub struct ShankIdl {
+    #[serde(default, deserialize_with = "deserialize_null_as_empty")]
+    pub instructions: Vec<ShankInstruction>,
+    pub accounts: Option<Vec<serde_json::Value>>,
+    pub errors: Option<Vec<serde_json::Value>>,
+    pub types: Option<Vec<serde_json::Value>>,
+}
+
+/// Converts a Shank IDL into the Anchor-shaped internal representation.
+/// 
+/// Each instruction keeps its discriminant. Older Shank IDLs omit it, in
+/// which case the instruction's position in the IDL is used, as that is the
+/// variant index of the instruction enum Shank reads.
+impl From<ShankIdl> for Idl {
+    fn from(shank: ShankIdl) -> Self {
+        let instructions = shank.instructions
+            .into_iter()
+            .enumerate()
+            .map(|(index, instruction)| IdlInstruction {
+                name: pascal_to_snake(&instruction.name),
+                docs: Vec::new(),
+                accounts: instruction.accounts
+                    .into_iter()
+                    .map(|account| IdlAccount {
+                        name: account.name,
+                        writable: None,
+                        signer: None,
+                        is_mut: Some(account.is_mut),
+                        is_signer: Some(account.is_signer),
+                        address: None,
+                        pda: None,
+                    })
+                    .collect(),
+                args: instruction.args,
+                discriminant: instruction.discriminant
+                    .map(|discriminant| discriminant.value)
+                    .or_else(|| u8::try_from(index).ok()),
+            })
+            .collect();
+        
+        Idl {
+            instructions,
+            accounts: shank.accounts,
+            events: None,
+            errors: shank.errors,
+            types: shank.types,
+            constants: None,
+        }
+    }
+}
+
+/// Converts PascalCase instruction names (as emitted by Shank) to snake_case.
+pub fn pascal_to_snake(s: &str) -> String {
+    let mut result = String::new();
+    for (i, c) in s.chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 {
+                result.push('_');
+            }
+            result.extend(c.to_lowercase());
+        } else {
+            result.push(c);
+        }
+    }
+    result
 }
 
 #[derive(Serialize)]
@@ -141,19 +583,219 @@ pub struct UploadProgramRequest {
     pub version: String,
     pub program_id: String,
     pub network: String,
-    pub idl: serde_json::Value,
+    /// The IDL, serialized once so its size can be checked before upload
+    pub idl: Box<serde_json::value::RawValue>,
     pub description: String,
     pub repository: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub keywords: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub binary_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub release_notes: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dirty: Option<bool>,
+    pub visibility: String,
+    pub idl_flavor: String,
+    pub idl_adapted: bool,
+    // Cryptographic verification fields
+    pub idl_hash: String,
+    pub challenge: String,
+    pub challenge_version: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
+    pub signature: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature_format: Option<String>,
+    pub authority_pubkey: String,
+}
+
+#[derive(Serialize)]
+pub struct ReleaseProgramRequest {
+    pub name: String,
+    pub version: String,
     // Cryptographic verification fields
     pub challenge: String,
+    pub challenge_version: u8,
+    pub nonce: String,
     pub signature: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature_format: Option<String>,
     pub authority_pubkey: String,
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct PublishLogEntry {
+    pub timestamp: String,
+    /// `publish` or `release`
+    pub action: String,
+    pub registry: String,
+    pub name: String,
+    pub version: String,
+    pub network: Option<String>,
+    pub program_id: Option<String>,
+    pub authority: String,
+    pub idl_hash: Option<String>,
+    pub commit: Option<String>,
+}
+
+/// Proof of a successful publish, written by `publish --output-receipt`.
+#[derive(Serialize)]
+pub struct PublishReceipt {
+    pub registry: String,
+    pub name: String,
+    /// The version the registry published
+    pub version: String,
+    pub network: String,
+    pub program_id: String,
+    pub idl_hash: String,
+    /// Unix timestamp included in the signed challenge
+    pub timestamp: u64,
+    pub challenge: String,
+    pub challenge_version: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
+    /// Base58 signature of the challenge by the authority
+    pub signature: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature_format: Option<String>,
+    pub authority_pubkey: String,
+    pub publication_id: Option<String>,
+    pub url: Option<String>,
+    pub published_at: Option<String>,
+    /// The registry's response body, parsed as JSON when possible
+    pub registry_response: serde_json::Value,
+}
+
 #[derive(Deserialize)]
-pub struct ProgramResponse {
+pub struct PublishResponse {
+    #[serde(default, deserialize_with = "deserialize_publication_id")]
+    pub id: Option<String>,
+    #[serde(alias = "listing_url")]
+    pub url: Option<String>,
+    pub published_at: Option<String>,
+    pub version: Option<String>,
+}
+
+/// Deserializes a publication `id`, which registries send as a string or a
+/// number. Any other value is treated as missing.
+fn deserialize_publication_id<'de, D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Option<String>, D::Error> {
+    Ok(match Option::<serde_json::Value>::deserialize(deserializer)? {
+        Some(serde_json::Value::String(id)) => Some(id),
+        Some(serde_json::Value::Number(id)) => Some(id.to_string()),
+        _ => None,
+    })
+}
+
+#[derive(Deserialize)]
+pub struct RegistryErrorResponse {
+    pub code: Option<String>,
+    pub message: Option<String>,
+    pub error: Option<String>,
+    #[serde(default, alias = "errors")]
+    pub field_errors: Vec<RegistryFieldError>,
+}
+
+#[derive(Deserialize)]
+pub struct RegistryFieldError {
+    pub field: String,
+    pub message: String,
+}
+
+#[derive(Deserialize)]
+pub struct AuthVerifyResponse {
+    pub valid: bool,
+    pub permissions: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub struct SearchResponse {
+    pub programs: Vec<SearchResult>,
+}
+
+#[derive(Deserialize)]
+pub struct SearchResult {
     pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub versions: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ProgramResponse {
     pub version: String,
     pub program_id: String,
     pub idl: serde_json::Value,
-}
\ No newline at end of file
+    #[serde(default)]
+    pub visibility: Option<String>,
+    #[serde(default)]
+    pub yanked: bool,
+    #[serde(default)]
+    pub deprecation_message: Option<String>,
+    /// The validators the install response was served with; not part of the body
+    #[serde(skip)]
+    pub validators: IdlValidators,
+}
+
+/// The `ETag` and `Last-Modified` an IDL was served with, saved next to the
+/// installed IDL so the next install can ask the registry whether it changed.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+pub struct IdlValidators {
+    /// The `ETag` response header, sent back as `If-None-Match`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+    /// The `Last-Modified` response header, sent back as `If-Modified-Since`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+}
+
+impl IdlValidators {
+    /// Returns whether the response carried neither validator.
+    pub fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+}
+
+/// A GitHub release, as returned by the releases API.
+#[derive(Deserialize, Debug)]
+pub struct GithubRelease {
+    /// The release tag, e.g. `v0.3.0`
+    pub tag_name: String,
+    /// The release page
+    pub html_url: String,
+    #[serde(default)]
+    pub assets: Vec<GithubAsset>,
+}
+
+/// A file attached to a GitHub release.
+#[derive(Deserialize, Debug)]
+pub struct GithubAsset {
+    pub name: String,
+    pub browser_download_url: String,
+    #[serde(default)]
+    pub size: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn publication_id_is_read_from_a_string_or_a_number() {
+        let parse = |body: &str| serde_json::from_str::<PublishResponse>(body).unwrap();
+        assert_eq!(parse(r#"{"id": "pub_42", "url": "https://registry.example.com/p/vesting"}"#).id.as_deref(), Some("pub_42"));
+        
+        let numeric = parse(r#"{"id": 42, "listing_url": "https://registry.example.com/p/vesting"}"#);
+        assert_eq!(numeric.id.as_deref(), Some("42"));
+        assert_eq!(numeric.url.as_deref(), Some("https://registry.example.com/p/vesting"));
+        
+        assert_eq!(parse(r#"{"url": null}"#).id, None);
+        assert_eq!(parse(r#"{"id": null}"#).id, None);
+    }
+}
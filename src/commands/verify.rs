@@ -0,0 +1,528 @@
+//! # Configuration Verification Module
+//!
+//! This module implements the `verify` command which checks SolanaPrograms.toml
+//! for problems that would otherwise only surface at publish time.
+//!
+//! Features:
+//! - Lists every invalid field at once, using the same rules as `solpm publish`
+//! - Detects placeholder or malformed program IDs
+//! - Checks that the `authority_keypair` file exists and holds a keypair
+//! - `--fix` fills missing or invalid fields and rewrites SolanaPrograms.toml
+//! - Fails when two SolanaPrograms.json entries share a program ID on a network
+//!
+//! ## Fixing
+//!
+//! With `--fix`, each invalid field is filled from, in order:
+//! 1. Its command line flag (`--description`, `--repository`, `--program-id`,
+//!    `--authority-keypair`), which also replaces a valid value
+//! 2. An interactive prompt, offering the detected value as the default
+//! 3. The detected value, when the session is not interactive
+//!
+//! Values are detected from the `description` field of Cargo.toml, the git
+//! `origin` remote, the `address` of the project IDL, and the default Solana
+//! CLI keypair (or the one `solana config get keypair` reports). Fields that cannot be detected are left for the user to fill
+//! in. Rewriting the file restores the standard field comments written by
+//! `solpm init` and removes any other comments.
+//!
+//! ## Workspaces
+//!
+//! Every `[[programs]]` entry is verified unless `--program` selects one.
+//! `--description` and `--program-id` differ between programs, so they need
+//! `--program` when several programs are configured; `--repository` and
+//! `--authority-keypair` apply to every selected program.
+
+use crate::commands::config_template::render_config_toml;
+use crate::commands::constants::SOLANA_PROGRAMS_FILE;
+use crate::commands::init::{find_idl_candidates, get_github_repository_url, read_idl_candidates};
+use crate::commands::install::check_duplicate_program_ids;
+use crate::commands::ledger::is_ledger_uri;
+use crate::commands::publish::{expand_path, normalize_repository_url, parse_keypair_bytes, validate_description, validate_program_name, validate_version};
+use crate::commands::types::{ProgramConfig, SolanaPrograms, SolanaProgramsConfig};
+use crate::error::{Context, Result, SolanaPmError};
+use crate::outln;
+use crate::statusln;
+use crate::output::is_json_output;
+use crate::utils::{CliStyle, is_placeholder_program_id, parse_network, prompt_input};
+use solana_sdk::pubkey::Pubkey;
+use std::fs;
+use std::io::IsTerminal;
+use std::path::Path;
+use std::str::FromStr;
+
+const SOLANA_PROGRAMS_TOML: &str = "SolanaPrograms.toml";
+const DEFAULT_AUTHORITY_KEYPAIR: &str = "~/.config/solana/id.json";
+const NETWORKS: &[&str] = &["mainnet", "devnet", "testnet", "localnet"];
+
+/// Options for the `verify` command.
+pub struct VerifyOptions {
+    /// Only verify the `[[programs]]` entry with this name
+    pub program: Option<String>,
+    /// Fill invalid fields and rewrite SolanaPrograms.toml
+    pub fix: bool,
+    /// Description to set with `--fix`
+    pub description: Option<String>,
+    /// Repository URL to set with `--fix`
+    pub repository: Option<String>,
+    /// Program ID to set with `--fix`
+    pub program_id: Option<String>,
+    /// Authority keypair path to set with `--fix`
+    pub authority_keypair: Option<String>,
+}
+
+/// Checks a field value and returns its normalized form.
+pub type FieldValidator = fn(&str) -> std::result::Result<String, String>;
+
+/// Verifies SolanaPrograms.toml and optionally repairs it.
+/// 
+/// This function:
+/// 1. Reads SolanaPrograms.toml and selects the programs to verify
+/// 2. With `--fix`, fills invalid fields from flags, prompts, or detection
+///    and rewrites the file if anything changed
+/// 3. Reports every remaining invalid field
+/// 
+/// # Arguments
+/// 
+/// * `options` - Whether to fix the file and the values to fill in
+/// 
+/// # Returns
+/// 
+/// Returns `Ok(())` if the configuration is ready to publish, or an error
+/// listing the invalid fields of every selected program.
+/// 
+/// # Examples
+/// 
+/// ```no_run
+/// use solpm::commands::verify::{VerifyOptions, verify_config};
+/// 
+/// # fn example() -> solpm::error::Result<()> {
+/// // Report problems without changing anything
+/// verify_config(&VerifyOptions { program: None, fix: false, description: None, repository: None, program_id: None, authority_keypair: None })?;
+/// 
+/// // Fill the description and detect everything else
+/// verify_config(&VerifyOptions {
+///     program: None,
+///     fix: true,
+///     description: Some("Token vesting with cliffs".to_string()),
+///     repository: None,
+///     program_id: None,
+///     authority_keypair: None,
+/// })?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn verify_config(options: &VerifyOptions) -> Result<()> {
+    check_manifest(Path::new(SOLANA_PROGRAMS_FILE))?;
+    
+    if !Path::new(SOLANA_PROGRAMS_TOML).exists() {
+        return Err(SolanaPmError::ConfigNotFound(
+            "SolanaPrograms.toml not found. Run 'solpm init' first.".to_string()
+        ));
+    }
+    
+    let toml_content = fs::read_to_string(SOLANA_PROGRAMS_TOML)
+        .map_err(|e| SolanaPmError::InvalidPath(format!("Failed to read SolanaPrograms.toml: {}", e)))?;
+    
+    let mut config: SolanaProgramsConfig = toml::from_str(&toml_content)
+        .map_err(|e| SolanaPmError::InvalidPath(format!("Invalid TOML format: {}", e)))?;
+    
+    if config.program.is_some() && !config.programs.is_empty() {
+        return Err(SolanaPmError::InvalidPath(
+            "Invalid TOML format: use either [program] or [[programs]], not both".to_string()
+        ));
+    }
+    
+    let mut programs = config.all_programs_mut();
+    if programs.is_empty() {
+        return Err(SolanaPmError::ConfigNotFound(
+            "SolanaPrograms.toml has no [program] section or [[programs]] entries".to_string()
+        ));
+    }
+    if let Some(name) = &options.program {
+        let names: Vec<String> = programs.iter().map(|program| program.name.clone()).collect();
+        programs.retain(|program| program.name == *name);
+        if programs.is_empty() {
+            return Err(SolanaPmError::DataMissing(format!(
+                "Program '{}' is not configured. Configured programs: {}", name, names.join(", ")
+            )));
+        }
+    }
+    
+    let multiple = programs.len() > 1;
+    if multiple && (options.description.is_some() || options.program_id.is_some()) {
+        return Err(SolanaPmError::DataMissing(
+            "--description and --program-id need --program <name> when several programs are configured".to_string()
+        ));
+    }
+    
+    let mut problems = Vec::new();
+    let mut changed = false;
+    for program in programs {
+        if multiple {
+            outln!("{}", CliStyle::header(&format!("Program {}", program.name)));
+        }
+        
+        if options.fix {
+            let fixed = fix_program(program, options)?;
+            if !fixed.is_empty() {
+                changed = true;
+                outln!("{}", CliStyle::success(&format!(
+                    "Updated {}{}",
+                    if multiple { format!("{}: ", program.name) } else { String::new() },
+                    fixed.join(", ")
+                )));
+            }
+        }
+        
+        let prefix = if multiple { format!("{}.", program.name) } else { String::new() };
+        problems.extend(find_problems(program).into_iter().map(|problem| format!("{}{}", prefix, problem)));
+    }
+    
+    if changed {
+        fs::write(SOLANA_PROGRAMS_TOML, render_config_toml(&config)?)?;
+        
+        outln!("{}", CliStyle::success(&format!("Rewrote {}", SOLANA_PROGRAMS_TOML)));
+    }
+    
+    if !problems.is_empty() {
+        return Err(SolanaPmError::DataMissing(format!(
+            "Invalid fields in {}:\n  - {}{}",
+            SOLANA_PROGRAMS_TOML,
+            problems.join("\n  - "),
+            if options.fix { "" } else { "\nRun 'solpm verify --fix' to repair them." }
+        )));
+    }
+    
+    outln!("{}", CliStyle::success(&format!("{} is ready to publish", SOLANA_PROGRAMS_TOML)));
+    Ok(())
+}
+
+/// Checks SolanaPrograms.json, if the project has one, for entries that
+/// share a program ID.
+/// 
+/// Unlike `install`, which only warns, verifying treats duplicates as an error.
+/// 
+/// # Arguments
+/// 
+/// * `manifest_path` - The path of SolanaPrograms.json
+/// 
+/// # Returns
+/// 
+/// Returns `Ok(())` if the file is absent or has no duplicates, or an error
+/// if it cannot be read or two entries share a program ID.
+fn check_manifest(manifest_path: &Path) -> Result<()> {
+    if !manifest_path.exists() {
+        return Ok(());
+    }
+    
+    let content = fs::read_to_string(manifest_path)
+        .with_context(|| format!("Failed to read {}", manifest_path.display()))?;
+    let solana_programs: SolanaPrograms = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse {}", manifest_path.display()))?;
+    check_duplicate_program_ids(&solana_programs, true)
+}
+
+/// Fills the invalid fields of one program.
+/// 
+/// # Arguments
+/// 
+/// * `program` - The program configuration to update
+/// * `options` - The values passed on the command line
+/// 
+/// # Returns
+/// 
+/// Returns the names of the fields that changed, or an error if a flag value
+/// is invalid.
+fn fix_program(program: &mut ProgramConfig, options: &VerifyOptions) -> Result<Vec<&'static str>> {
+    let interactive = !is_json_output() && std::io::stdin().is_terminal();
+    let name = program.name.clone();
+    let mut fixed = Vec::new();
+    
+    if fix_field("description", &mut program.description, options.description.as_deref(), check_description, &detect_cargo_description, interactive)? {
+        fixed.push("description");
+    }
+    if fix_field("repository", &mut program.repository, options.repository.as_deref(), normalize_repository_url, &get_github_repository_url, interactive)? {
+        fixed.push("repository");
+    }
+    let idl = program.idl.clone();
+    if fix_field("program_id", &mut program.program_id, options.program_id.as_deref(), check_program_id, &|| detect_idl_program_id(&name, idl.as_deref()), interactive)? {
+        fixed.push("program_id");
+    }
+    if fix_field("authority_keypair", &mut program.authority_keypair, options.authority_keypair.as_deref(), check_authority_keypair, &detect_authority_keypair, interactive)? {
+        fixed.push("authority_keypair");
+    }
+    
+    Ok(fixed)
+}
+
+/// Lists every invalid field of a program configuration.
+/// 
+/// # Arguments
+/// 
+/// * `program` - A `[program]` section or `[[programs]]` entry of SolanaPrograms.toml
+/// 
+/// # Returns
+/// 
+/// Returns one `field: problem` entry per invalid field.
+fn find_problems(program: &ProgramConfig) -> Vec<String> {
+    let checks: [(&str, &str, FieldValidator); 7] = [
+        ("name", &program.name, |name| validate_program_name(name).map(|_| name.to_string())),
+        ("version", &program.version, |version| validate_version(version).map(|_| version.to_string())),
+        ("description", &program.description, check_description),
+        ("repository", &program.repository, normalize_repository_url),
+        ("program_id", &program.program_id, check_program_id),
+        ("network", &program.network, check_network),
+        ("authority_keypair", &program.authority_keypair, check_authority_keypair),
+    ];
+    
+    let mut problems: Vec<String> = checks.iter()
+        .filter_map(|(field, value, validate)| validate(value.trim()).err().map(|problem| format!("{}: {}", field, problem)))
+        .collect();
+    if let Some(idl) = &program.idl
+        && read_idl_candidates(Path::new(&expand_path(idl))).is_empty()
+    {
+        problems.push(format!("idl: {} is missing or not an IDL; rebuild it or run 'solpm init --from-idl <path>'", idl));
+    }
+    problems
+}
+
+/// Fills one field from its flag, a prompt, or a detected value.
+/// 
+/// A flag value always replaces the field. Otherwise the field is only
+/// changed when it is invalid: interactive sessions are prompted (with the
+/// detected value as the default) until a valid value is entered, and other
+/// sessions use the detected value if there is one.
+/// 
+/// # Arguments
+/// 
+/// * `field` - The TOML field name
+/// * `value` - The field to update
+/// * `flag` - The value passed on the command line, if any
+/// * `validate` - Checks and normalizes a candidate value
+/// * `detect` - Detects a value from the project
+/// * `interactive` - Whether prompting is possible
+/// 
+/// # Returns
+/// 
+/// Returns whether the field changed, or an error if the flag value is invalid.
+fn fix_field(
+    field: &str,
+    value: &mut String,
+    flag: Option<&str>,
+    validate: FieldValidator,
+    detect: &dyn Fn() -> Option<String>,
+    interactive: bool,
+) -> Result<bool> {
+    if let Some(flag) = flag {
+        let fixed = validate(flag.trim())
+            .map_err(|problem| SolanaPmError::DataMissing(format!("--{}: {}", field.replace('_', "-"), problem)))?;
+        let changed = fixed != *value;
+        *value = fixed;
+        return Ok(changed);
+    }
+    
+    let Err(problem) = validate(value.trim()) else {
+        return Ok(false);
+    };
+    
+    let detected = detect().and_then(|detected| validate(&detected).ok());
+    
+    if !interactive {
+        let Some(detected) = detected else {
+            return Ok(false);
+        };
+        statusln!("{}", CliStyle::info(&format!("Detected {}: {}", field, CliStyle::highlight(&detected))));
+        *value = detected;
+        return Ok(true);
+    }
+    
+    outln!("{}", CliStyle::warning(&format!("{}: {}", field, problem)));
+    match prompt_valid(field, detected.as_deref(), validate)? {
+        Some(fixed) => {
+            *value = fixed;
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+/// Prompts for a field until a valid value is entered.
+/// 
+/// # Arguments
+/// 
+/// * `field` - The prompt label
+/// * `default` - The value accepted by pressing enter, if any
+/// * `validate` - Checks and normalizes the entered value
+/// 
+/// # Returns
+/// 
+/// Returns the normalized value, `None` if the prompt was aborted, or
+/// `SolanaPmError::InputRequired` in JSON mode.
+pub fn prompt_valid(field: &str, default: Option<&str>, validate: FieldValidator) -> Result<Option<String>> {
+    loop {
+        let Some(input) = prompt_input(field, default)? else {
+            return Ok(None);
+        };
+        match validate(input.trim()) {
+            Ok(value) => return Ok(Some(value)),
+            Err(problem) => outln!("{}", CliStyle::warning(&format!("{}: {}", field, problem))),
+        }
+    }
+}
+
+/// Checks a description and returns it trimmed.
+pub fn check_description(description: &str) -> std::result::Result<String, String> {
+    let description = description.trim();
+    validate_description(description).map(|_| description.to_string())
+}
+
+/// Checks that a program ID is a base58 public key.
+pub fn check_program_id(program_id: &str) -> std::result::Result<String, String> {
+    if program_id.is_empty() {
+        return Err("required".to_string());
+    }
+    if is_placeholder_program_id(program_id) {
+        return Err(format!("'{}' is a placeholder; set the deployed program ID", program_id));
+    }
+    Pubkey::from_str(program_id)
+        .map(|_| program_id.to_string())
+        .map_err(|_| format!("'{}' is not a valid base58 public key", program_id))
+}
+
+/// Checks that a network is supported by the registry.
+fn check_network(network: &str) -> std::result::Result<String, String> {
+    parse_network(network)
+        .map(|network| network.as_str().to_string())
+        .map_err(|_| format!("'{}' must be one of: {}", network, NETWORKS.join(", ")))
+}
+
+/// Checks that an authority keypair file exists and holds a keypair.
+/// 
+/// The file must parse in one of the formats publish accepts. Ledger URIs
+/// and `prompt://` seed phrase sources are accepted without a file check.
+pub fn check_authority_keypair(path: &str) -> std::result::Result<String, String> {
+    if path.is_empty() {
+        return Err("required".to_string());
+    }
+    if is_ledger_uri(path) || path.starts_with("prompt://") {
+        return Ok(path.to_string());
+    }
+    
+    let expanded_path = expand_path(path);
+    if !Path::new(&expanded_path).is_file() {
+        return Err(format!("keypair file {} does not exist", expanded_path));
+    }
+    let data = zeroize::Zeroizing::new(fs::read(&expanded_path).map_err(|e| format!("cannot read {}: {}", expanded_path, e))?);
+    parse_keypair_bytes(&data)
+        .map(|_| path.to_string())
+        .map_err(|_| format!("{} is not a keypair file", expanded_path))
+}
+
+/// Detects a description from the `description` field of Cargo.toml.
+/// 
+/// Checks the root manifest (package or workspace package) and then each
+/// `programs/*/Cargo.toml`.
+pub fn detect_cargo_description() -> Option<String> {
+    let read_description = |path: &Path| -> Option<String> {
+        let manifest: toml::Value = toml::from_str(&fs::read_to_string(path).ok()?).ok()?;
+        manifest.get("package")
+            .and_then(|package| package.get("description"))
+            .or_else(|| manifest.get("workspace")
+                .and_then(|workspace| workspace.get("package"))
+                .and_then(|package| package.get("description")))
+            .and_then(|description| description.as_str())
+            .map(|description| description.to_string())
+    };
+    
+    if let Some(description) = read_description(Path::new("Cargo.toml")) {
+        return Some(description);
+    }
+    
+    fs::read_dir("programs").ok()?
+        .flatten()
+        .find_map(|entry| read_description(&entry.path().join("Cargo.toml")))
+}
+
+/// Detects the program ID from the `address` field of the project IDL.
+/// 
+/// Searches the IDL recorded in the `idl` key, or else the project IDLs, for
+/// the IDL whose metadata name matches the program, or the only IDL found.
+fn detect_idl_program_id(name: &str, idl: Option<&str>) -> Option<String> {
+    let candidates = match idl {
+        Some(idl) => read_idl_candidates(Path::new(&expand_path(idl))),
+        None => find_idl_candidates().ok()?,
+    };
+    let candidate = match candidates.iter().find(|candidate| candidate.name == name) {
+        Some(candidate) => candidate,
+        None if candidates.len() == 1 => &candidates[0],
+        None => return None,
+    };
+    candidate.program_id.clone()
+}
+
+/// Detects a usable authority keypair.
+/// 
+/// Uses the default Solana CLI keypair (`~/.config/solana/id.json`), or else
+/// the keypair `solana config get keypair` reports, if it holds a keypair.
+pub fn detect_authority_keypair() -> Option<String> {
+    std::iter::once(DEFAULT_AUTHORITY_KEYPAIR.to_string())
+        .chain(solana_config_keypair())
+        .find(|path| check_authority_keypair(path).is_ok())
+}
+
+/// Reads the keypair path from `solana config get keypair`.
+/// 
+/// # Returns
+/// 
+/// Returns the `Key Path` the Solana CLI reports, or `None` if the CLI is
+/// not installed or reports no path.
+fn solana_config_keypair() -> Option<String> {
+    let output = std::process::Command::new("solana")
+        .args(["config", "get", "keypair"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("Key Path:"))
+        .map(|path| path.trim().to_string())
+        .filter(|path| !path.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    /// Writes a SolanaPrograms.json into a temporary directory.
+    fn manifest(contents: serde_json::Value) -> (tempfile::TempDir, std::path::PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(SOLANA_PROGRAMS_FILE);
+        fs::write(&path, contents.to_string()).unwrap();
+        (dir, path)
+    }
+    
+    #[test]
+    fn entries_sharing_a_program_id_fail_verification() {
+        let program_id = "Vest1ngZbdCMbGVTwfM8fBNBfWqBqTBBHwCbUHmBxjr";
+        let (_dir, path) = manifest(serde_json::json!({
+            "programs": { "vesting": { "version": "1.0.0", "program_id": program_id, "network": "devnet" } },
+            "devPrograms": { "vesting-copy": { "version": "1.0.0", "program_id": program_id, "network": "devnet" } },
+        }));
+        
+        let error = check_manifest(&path).unwrap_err();
+        assert!(matches!(&error, SolanaPmError::DataMissing(msg) if msg.contains("vesting, vesting-copy")), "{}", error);
+        assert_eq!(error.exit_code(), 6);
+    }
+    
+    #[test]
+    fn distinct_program_ids_and_a_missing_manifest_pass() {
+        let (dir, path) = manifest(serde_json::json!({
+            "programs": {
+                "vesting": { "version": "1.0.0", "program_id": "Vest1ngZbdCMbGVTwfM8fBNBfWqBqTBBHwCbUHmBxjr", "network": "devnet" },
+                "vesting-main": { "version": "1.0.0", "program_id": "Vest1ngZbdCMbGVTwfM8fBNBfWqBqTBBHwCbUHmBxjr", "network": "mainnet" },
+            },
+            "devPrograms": {},
+        }));
+        check_manifest(&path).unwrap();
+        check_manifest(&dir.path().join("missing.json")).unwrap();
+    }
+}
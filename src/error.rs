@@ -4,15 +4,27 @@
 //! It provides a comprehensive error system that covers:
 //!
 //! - File I/O errors
-//! - Network and HTTP request failures
+//! - Network and HTTP request failures, including timeouts
 //! - Configuration and validation errors
-//! - Authentication and authorization issues
+//! - Authentication and authorization issues, including corrupt credential
+//!   files and incorrect passwords
 //! - IDL parsing and validation errors
-//! - Registry communication errors
+//! - Registry communication errors, including the status and error code of
+//!   a failed registry response
+//! - User-defined hook command failures
+//! - Input that a prompt would ask for in non-interactive `--json` mode
 //!
 //! All errors implement standard Rust error traits and provide meaningful
-//! error messages to help users diagnose and resolve issues.
+//! error messages to help users diagnose and resolve issues. Errors wrapping
+//! an I/O, JSON, or HTTP error return it from `source()`, so error chains
+//! keep the original cause. The [`Context`] trait wraps an error with the
+//! operation that failed, such as the file being read, and `main` prints
+//! each cause on its own `caused by:` line. Each variant
+//! also maps to a stable process exit code (see [`SolanaPmError::exit_code`])
+//! so scripts can tell failures apart, and to a kind name (see
+//! [`SolanaPmError::kind`]) reported in `--json` output.
 
+use crate::commands::http::describe_request_error;
 use std::fmt;
 
 /// Error types for Solana Package Manager operations.
@@ -25,12 +37,22 @@ pub enum SolanaPmError {
     Io(std::io::Error),
     Json(serde_json::Error),
     Http(reqwest::Error),
+    Timeout(String),
+    /// The registry answered an operation with an error status
+    Registry { status: u16, code: Option<String>, message: String, operation: String },
     ConfigNotFound(String),
     ProgramNotFound(String),
     InvalidIdl(String),
     UploadFailed(String),
     InvalidPath(String),
     DataMissing(String),
+    HookFailed(String),
+    AuthRequired(String),
+    InputRequired(String),
+    CorruptCredentials(String),
+    IncorrectPassword,
+    /// An error wrapped with the operation that failed, e.g. the file being read
+    WithContext { context: String, source: Box<SolanaPmError> },
 }
 
 /// Implements Display for SolanaPmError to provide human-readable error messages.
@@ -51,22 +73,165 @@ impl fmt::Display for SolanaPmError {
         match self {
             SolanaPmError::Io(err) => write!(f, "IO error: {}", err),
             SolanaPmError::Json(err) => write!(f, "JSON parsing error: {}", err),
-            SolanaPmError::Http(err) => write!(f, "HTTP request error: {}", err),
+            SolanaPmError::Http(err) => write!(f, "HTTP request error: {}", describe_request_error(err)),
+            SolanaPmError::Timeout(msg) => write!(f, "Timed out: {}", msg),
+            SolanaPmError::Registry { status, code, message, operation } => {
+                let mut status = match reqwest::StatusCode::from_u16(*status) {
+                    Ok(known) => known.to_string(),
+                    Err(_) => status.to_string(),
+                };
+                if let Some(code) = code {
+                    status.push_str(&format!(", {}", code));
+                }
+                if self.is_registry_fault() {
+                    write!(f, "{} failed because of a registry problem ({}): {}. Try again later", operation, status, message)
+                } else {
+                    write!(f, "{} was rejected by the registry ({}): {}", operation, status, message)
+                }
+            }
             SolanaPmError::ConfigNotFound(msg) => write!(f, "Configuration error: {}", msg),
             SolanaPmError::ProgramNotFound(name) => write!(f, "Program '{}' not found in registry", name),
             SolanaPmError::InvalidIdl(msg) => write!(f, "Invalid IDL: {}", msg),
             SolanaPmError::UploadFailed(msg) => write!(f, "Upload failed: {}", msg),
             SolanaPmError::InvalidPath(msg) => write!(f, "Invalid path: {}", msg),
             SolanaPmError::DataMissing(msg) => write!(f, "Data missing: {}", msg),
+            SolanaPmError::HookFailed(msg) => write!(f, "Hook failed: {}", msg),
+            SolanaPmError::AuthRequired(msg) => write!(f, "Authentication required: {}", msg),
+            SolanaPmError::InputRequired(msg) => write!(f, "Input required: {}", msg),
+            SolanaPmError::CorruptCredentials(msg) => write!(f, "Credentials file appears corrupt ({}); run 'solpm login' again", msg),
+            SolanaPmError::IncorrectPassword => write!(f, "Decryption failed. Incorrect password?"),
+            SolanaPmError::WithContext { context, .. } => write!(f, "{}", context),
         }
     }
 }
 
+impl SolanaPmError {
+    /// Returns the process exit code for this error.
+    /// 
+    /// The codes are part of the CLI's interface and must not change:
+    /// 
+    /// | Code | Meaning |
+    /// |------|---------|
+    /// | 1 | Other failure (file I/O, JSON, hook) |
+    /// | 2 | Configuration or usage error |
+    /// | 3 | Program or version not found |
+    /// | 4 | Authentication required or invalid |
+    /// | 5 | Network error or registry unreachable |
+    /// | 6 | Validation failed |
+    /// | 7 | Registry rejected the request |
+    /// 
+    /// Registry error responses are classified by status: 401 and 403 give
+    /// 4, 404 gives 3, 5xx give 5, and any other status gives 7.
+    /// 
+    /// clap exits with 2 for invalid arguments, matching code 2.
+    /// 
+    /// # Returns
+    /// 
+    /// Returns the exit code `main` exits with.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            SolanaPmError::Io(_) | SolanaPmError::Json(_) | SolanaPmError::HookFailed(_) => 1,
+            SolanaPmError::ConfigNotFound(_) | SolanaPmError::InvalidPath(_) | SolanaPmError::InputRequired(_) => 2,
+            SolanaPmError::ProgramNotFound(_) => 3,
+            SolanaPmError::AuthRequired(_) | SolanaPmError::CorruptCredentials(_) | SolanaPmError::IncorrectPassword => 4,
+            SolanaPmError::Http(_) | SolanaPmError::Timeout(_) => 5,
+            SolanaPmError::Registry { status: 401 | 403, .. } => 4,
+            SolanaPmError::Registry { status: 404, .. } => 3,
+            SolanaPmError::Registry { .. } if self.is_registry_fault() => 5,
+            SolanaPmError::InvalidIdl(_) | SolanaPmError::DataMissing(_) => 6,
+            SolanaPmError::UploadFailed(_) | SolanaPmError::Registry { .. } => 7,
+            SolanaPmError::WithContext { source, .. } => source.exit_code(),
+        }
+    }
+    
+    /// Returns the error kind reported in `--json` output.
+    /// 
+    /// Like the exit codes, the names are stable so tools can match on them.
+    /// 
+    /// # Returns
+    /// 
+    /// Returns a stable, machine-readable kind name such as `network` or `auth`.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            SolanaPmError::Io(_) | SolanaPmError::InvalidPath(_) => "io",
+            SolanaPmError::Json(_) | SolanaPmError::InvalidIdl(_) => "idl",
+            SolanaPmError::Http(_) | SolanaPmError::Timeout(_) => "network",
+            SolanaPmError::Registry { status: 401 | 403, .. } => "auth",
+            SolanaPmError::Registry { .. } if self.is_registry_fault() => "network",
+            SolanaPmError::ConfigNotFound(_) => "config",
+            SolanaPmError::DataMissing(_) => "validation",
+            SolanaPmError::HookFailed(_) => "hook",
+            SolanaPmError::InputRequired(_) => "input",
+            SolanaPmError::AuthRequired(_) | SolanaPmError::CorruptCredentials(_) | SolanaPmError::IncorrectPassword => "auth",
+            SolanaPmError::ProgramNotFound(_) | SolanaPmError::UploadFailed(_) | SolanaPmError::Registry { .. } => "registry",
+            SolanaPmError::WithContext { source, .. } => source.kind(),
+        }
+    }
+    
+    /// Returns whether this is a registry response with a server error
+    /// (5xx) status, which the user can only retry, rather than a 4xx they
+    /// can act on.
+    pub fn is_registry_fault(&self) -> bool {
+        matches!(self, SolanaPmError::Registry { status, .. } if *status >= 500)
+    }
+    
+    /// Returns the messages of this error and each of its causes.
+    /// 
+    /// A cause whose message is already part of the previous message, such
+    /// as the `serde_json` error inside `Json`, is left out.
+    /// 
+    /// # Returns
+    /// 
+    /// Returns the messages, outermost first.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use solpm::error::Context;
+    /// 
+    /// let error = serde_json::from_str::<serde_json::Value>("x").context("Failed to parse SolanaPrograms.json").unwrap_err();
+    /// assert_eq!(error.chain(), [
+    ///     "Failed to parse SolanaPrograms.json",
+    ///     "JSON parsing error: expected value at line 1 column 1",
+    /// ]);
+    /// ```
+    pub fn chain(&self) -> Vec<String> {
+        let mut messages = vec![self.to_string()];
+        let mut cause = std::error::Error::source(self);
+        while let Some(error) = cause {
+            let message = error.to_string();
+            if !messages.last().is_some_and(|previous| previous.contains(&message)) {
+                messages.push(message);
+            }
+            cause = error.source();
+        }
+        messages
+    }
+}
+
 /// Implements the standard Error trait for SolanaPmError.
 /// 
 /// This allows SolanaPmError to be used with the standard error handling
 /// infrastructure and error propagation mechanisms.
-impl std::error::Error for SolanaPmError {}
+impl std::error::Error for SolanaPmError {
+    /// Returns the wrapped error, so error-chain reporters such as `anyhow`
+    /// can show the underlying cause.
+    /// 
+    /// # Returns
+    /// 
+    /// Returns the original error for the `Io`, `Json`, and `Http` variants,
+    /// the wrapped error for `WithContext`, and `None` for variants that only
+    /// carry a message.
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SolanaPmError::Io(err) => Some(err),
+            SolanaPmError::Json(err) => Some(err),
+            SolanaPmError::Http(err) => Some(err),
+            SolanaPmError::WithContext { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
 
 /// Converts std::io::Error to SolanaPmError.
 /// 
@@ -129,10 +294,170 @@ impl From<reqwest::Error> for SolanaPmError {
 /// 
 /// # Examples
 /// 
-/// ```rust
+/// ```
+/// use solpm::error::Result;
+/// 
 /// fn my_function() -> Result<String> {
 ///     // Returns Result<String, SolanaPmError>
 ///     Ok("success".to_string())
 /// }
+/// # assert!(my_function().is_ok());
+/// ```
+pub type Result<T> = std::result::Result<T, SolanaPmError>;
+
+/// Wraps errors with the operation that failed.
+/// 
+/// Implemented for any result whose error converts into `SolanaPmError`, so
+/// I/O, JSON, and HTTP results can be given context directly. The original
+/// error stays reachable through `source()`, and keeps its exit code and kind.
+/// 
+/// # Examples
+/// 
+/// ```no_run
+/// use solpm::error::{Context, Result};
+/// use std::fs;
+/// 
+/// # fn example(idl_file_path: &str) -> Result<()> {
+/// let content = fs::read_to_string(idl_file_path)
+///     .with_context(|| format!("Failed to read {}", idl_file_path))?;
+/// let idl: serde_json::Value = serde_json::from_str(&content).context("Failed to parse the IDL")?;
+/// # Ok(())
+/// # }
 /// ```
-pub type Result<T> = std::result::Result<T, SolanaPmError>;
\ No newline at end of file
+pub trait Context<T> {
+    /// Wraps the error, if any, with a fixed description.
+    fn context(self, context: impl Into<String>) -> Result<T>;
+    
+    /// Wraps the error, if any, with a description built only on failure.
+    fn with_context(self, context: impl FnOnce() -> String) -> Result<T>;
+}
+
+impl<T, E: Into<SolanaPmError>> Context<T> for std::result::Result<T, E> {
+    fn context(self, context: impl Into<String>) -> Result<T> {
+        self.map_err(|e| SolanaPmError::WithContext { context: context.into(), source: Box::new(e.into()) })
+    }
+    
+    fn with_context(self, context: impl FnOnce() -> String) -> Result<T> {
+        self.map_err(|e| SolanaPmError::WithContext { context: context(), source: Box::new(e.into()) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    /// An HTTP error, built without sending a request.
+    fn http_error() -> reqwest::Error {
+        reqwest::Client::new().get("not a url").build().unwrap_err()
+    }
+    
+    /// A JSON parsing error.
+    fn json_error() -> serde_json::Error {
+        serde_json::from_str::<serde_json::Value>("{").unwrap_err()
+    }
+    
+    /// A registry error response with the given status.
+    fn registry_error(status: u16) -> SolanaPmError {
+        SolanaPmError::Registry { status, code: None, message: "rejected".to_string(), operation: "Publishing".to_string() }
+    }
+    
+    /// Names the variant of an error. The match is exhaustive, so a new
+    /// variant fails to compile until it is added here and to the table.
+    fn variant(error: &SolanaPmError) -> &'static str {
+        match error {
+            SolanaPmError::Io(_) => "Io",
+            SolanaPmError::Json(_) => "Json",
+            SolanaPmError::Http(_) => "Http",
+            SolanaPmError::Timeout(_) => "Timeout",
+            SolanaPmError::Registry { .. } => "Registry",
+            SolanaPmError::ConfigNotFound(_) => "ConfigNotFound",
+            SolanaPmError::ProgramNotFound(_) => "ProgramNotFound",
+            SolanaPmError::InvalidIdl(_) => "InvalidIdl",
+            SolanaPmError::UploadFailed(_) => "UploadFailed",
+            SolanaPmError::InvalidPath(_) => "InvalidPath",
+            SolanaPmError::DataMissing(_) => "DataMissing",
+            SolanaPmError::HookFailed(_) => "HookFailed",
+            SolanaPmError::AuthRequired(_) => "AuthRequired",
+            SolanaPmError::InputRequired(_) => "InputRequired",
+            SolanaPmError::CorruptCredentials(_) => "CorruptCredentials",
+            SolanaPmError::IncorrectPassword => "IncorrectPassword",
+            SolanaPmError::WithContext { .. } => "WithContext",
+        }
+    }
+    
+    #[test]
+    fn exit_codes_and_kinds_are_stable() {
+        let message = || "message".to_string();
+        let table = [
+            (SolanaPmError::Io(std::io::Error::other("disk")), 1, "io"),
+            (SolanaPmError::Json(json_error()), 1, "idl"),
+            (SolanaPmError::Http(http_error()), 5, "network"),
+            (SolanaPmError::Timeout(message()), 5, "network"),
+            (registry_error(503), 5, "network"),
+            (registry_error(409), 7, "registry"),
+            (registry_error(401), 4, "auth"),
+            (registry_error(403), 4, "auth"),
+            (registry_error(404), 3, "registry"),
+            (SolanaPmError::ConfigNotFound(message()), 2, "config"),
+            (SolanaPmError::ProgramNotFound(message()), 3, "registry"),
+            (SolanaPmError::InvalidIdl(message()), 6, "idl"),
+            (SolanaPmError::UploadFailed(message()), 7, "registry"),
+            (SolanaPmError::InvalidPath(message()), 2, "io"),
+            (SolanaPmError::DataMissing(message()), 6, "validation"),
+            (SolanaPmError::HookFailed(message()), 1, "hook"),
+            (SolanaPmError::AuthRequired(message()), 4, "auth"),
+            (SolanaPmError::InputRequired(message()), 2, "input"),
+            (SolanaPmError::CorruptCredentials(message()), 4, "auth"),
+            (SolanaPmError::IncorrectPassword, 4, "auth"),
+            (SolanaPmError::WithContext { context: message(), source: Box::new(SolanaPmError::ProgramNotFound(message())) }, 3, "registry"),
+        ];
+        
+        for (error, exit_code, kind) in &table {
+            assert_eq!(error.exit_code(), *exit_code, "exit code of {}", variant(error));
+            assert_eq!(error.kind(), *kind, "kind of {}", variant(error));
+        }
+        
+        let mut covered: Vec<&str> = table.iter().map(|(error, _, _)| variant(error)).collect();
+        covered.dedup();
+        assert_eq!(covered.len(), 17, "every variant has an entry: {:?}", covered);
+    }
+    
+    #[test]
+    fn source_returns_the_wrapped_error() {
+        let io = SolanaPmError::Io(std::io::Error::other("disk full"));
+        assert_eq!(std::error::Error::source(&io).map(|e| e.to_string()).as_deref(), Some("disk full"));
+        
+        let json = SolanaPmError::Json(json_error());
+        assert_eq!(std::error::Error::source(&json).map(|e| e.to_string()), Some(json_error().to_string()));
+        
+        let http = SolanaPmError::Http(http_error());
+        assert!(std::error::Error::source(&http).is_some());
+        
+        let wrapped = Err::<(), _>(SolanaPmError::ProgramNotFound("token-vesting".to_string())).context("Failed to add").unwrap_err();
+        let source = std::error::Error::source(&wrapped).unwrap();
+        assert_eq!(source.to_string(), SolanaPmError::ProgramNotFound("token-vesting".to_string()).to_string());
+        
+        assert!(std::error::Error::source(&SolanaPmError::InvalidIdl("no instructions".to_string())).is_none());
+        assert!(std::error::Error::source(&SolanaPmError::IncorrectPassword).is_none());
+    }
+    
+    #[test]
+    fn chain_lists_each_cause_outermost_first() {
+        let error = Err::<(), _>(std::io::Error::other("disk full"))
+            .with_context(|| "Failed to write SolanaPrograms.json".to_string())
+            .unwrap_err();
+        assert_eq!(error.chain(), vec!["Failed to write SolanaPrograms.json", "IO error: disk full"]);
+        
+        let nested = Err::<(), _>(error).context("Failed to add token-vesting").unwrap_err();
+        assert_eq!(nested.chain(), vec!["Failed to add token-vesting", "Failed to write SolanaPrograms.json", "IO error: disk full"]);
+    }
+    
+    #[test]
+    fn chain_leaves_out_causes_already_in_the_message() {
+        let error = Err::<(), _>(json_error()).context("Failed to parse SolanaPrograms.json").unwrap_err();
+        assert_eq!(error.chain(), vec!["Failed to parse SolanaPrograms.json".to_string(), format!("JSON parsing error: {}", json_error())]);
+        
+        let message_only = SolanaPmError::InvalidIdl("no instructions".to_string());
+        assert_eq!(message_only.chain(), vec![message_only.to_string()]);
+    }
+}
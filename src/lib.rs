@@ -0,0 +1,56 @@
+//! # Solana Program Manager (solpm)
+//!
+//! A library for managing Solana program dependencies, generating TypeScript
+//! clients, and publishing programs to a registry. The `solpm` binary is a
+//! thin command-line wrapper over this crate.
+//!
+//! Most programs only need the [`api`] module: it runs a command against the
+//! project in the current directory without prompting and returns what the
+//! command did as a typed value. The command modules under [`commands`] hold
+//! the implementations and their options structs.
+//!
+//! ## Adding a program
+//!
+//! ```no_run
+//! use solpm::api::{self, AddOutcome};
+//! use solpm::commands::add::AddOptions;
+//! use solpm::Network;
+//!
+//! # async fn example() -> solpm::error::Result<()> {
+//! let options = AddOptions { network: Some(Network::Dev), ..Default::default() };
+//! match api::add("token-vesting", &options).await? {
+//!     AddOutcome::Added(program) => println!("Added {} v{}", program.name, program.version),
+//!     AddOutcome::Skipped { name, .. } => println!("{} is already a dependency", name),
+//!     _ => {}
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! ## Generating a client
+//!
+//! ```no_run
+//! use solpm::api;
+//! use solpm::commands::codegen::CodegenOptions;
+//!
+//! # fn example() -> solpm::error::Result<()> {
+//! let outcome = api::codegen(&CodegenOptions::default())?;
+//! for client in outcome.clients {
+//!     println!("Generated {} for {}", client.path, client.name);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Errors are `error::SolanaPmError`; [`error::SolanaPmError::exit_code`]
+//! and [`error::SolanaPmError::kind`] classify them the same way the CLI does.
+
+pub mod api;
+pub mod cli;
+pub mod commands;
+pub mod error;
+pub mod output;
+pub mod utils;
+
+pub use cli::Network;
+pub use error::{Result, SolanaPmError};
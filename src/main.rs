@@ -2,16 +2,16 @@
 //!
 //! A command-line interface for managing Solana program dependencies and publishing
 //! programs to a registry.
+//!
+//! This binary is a thin wrapper over the `solpm` library: it parses the
+//! arguments, applies the global flags, and maps each subcommand to its
+//! command function. Prompts and interactive flows only run from here.
 
 use clap::Parser;
 
-mod cli;
-mod commands;
-mod error;
-mod utils;
-
-use cli::{Cli, Commands};
-use utils::{CliStyle, print_banner};
+use solpm::{commands, error, output};
+use solpm::cli::{Cli, Commands, ConfigAction};
+use solpm::utils::{CliStyle, print_banner};
 
 /// Main entry point for the Solana Program Manager CLI application.
 /// 
@@ -28,34 +28,222 @@ async fn main() {
     }
     
     let cli = Cli::parse();
+    output::set_json_output(cli.json);
+    output::set_verbosity(cli.quiet, cli.verbose);
+    output::init_logging();
+    commands::registry::set_registry_flag(cli.registry.clone());
+    commands::http::set_proxy_flag(cli.proxy.clone());
+    commands::http::set_command_name(cli.command.name());
+    commands::config::set_timeout_flag(cli.timeout);
+    if let Err(e) = commands::config::load_project_settings() {
+        finish(cli.command.name(), Err(e));
+    }
     
 
     let result = match &cli.command {
-        Commands::Init { network } => {
-            commands::init::init_project(network)
+        Commands::Init { network, no_interactive, interactive, program, all, from_idl, name, program_version, program_id, description, repository, authority_keypair, yes, fresh, allow_placeholder, require_idl, print, with_deps } => {
+            let options = commands::init::InitOptions {
+                network: network.clone(),
+                no_interactive: *no_interactive,
+                interactive: *interactive,
+                program: program.clone(),
+                all: *all,
+                from_idl: from_idl.clone(),
+                name: name.clone(),
+                program_version: program_version.clone(),
+                program_id: program_id.clone(),
+                description: description.clone(),
+                repository: repository.clone(),
+                authority_keypair: authority_keypair.clone(),
+                yes: *yes,
+                fresh: *fresh,
+                allow_placeholder: *allow_placeholder,
+                require_idl: *require_idl,
+                print: *print,
+                with_deps: *with_deps,
+            };
+            commands::init::init_project(&options)
         }
-        Commands::Add { package, dev, path, network, codegen } => {
-            commands::add::add_program(&package, *dev, path.as_deref(), network, *codegen).await
+        Commands::Add { package, dev, path, network, codegen, max_idl_size, allow_yanked, per_network_idl, check_only, alias, tags, prefer_cached, registry_token, registry_token_stdin } => {
+            let registry_token = match commands::auth::read_registry_token(registry_token.clone(), *registry_token_stdin) {
+                Ok(token) => token,
+                Err(e) => return finish(cli.command.name(), Err(e)),
+            };
+            let options = commands::add::AddOptions {
+                dev: *dev,
+                path: path.clone(),
+                network: network.clone(),
+                codegen: *codegen,
+                max_idl_size: *max_idl_size,
+                allow_yanked: *allow_yanked,
+                per_network_idl: *per_network_idl,
+                check_only: *check_only,
+                alias: alias.clone(),
+                tags: tags.clone(),
+                prefer_cached: *prefer_cached,
+                registry_token,
+                registry: None,
+            };
+            match package {
+                Some(package) => commands::add::add_program(package, &options).await.and_then(output::record_result),
+                None => commands::add::add_program_interactive(&options).await.and_then(output::record_result),
+            }
         }
-        Commands::Install { codegen } => {
-            commands::install::install_dependencies(*codegen).await
+        Commands::Install { codegen, strict, max_idl_size, allow_hooks, per_network_idl, tags, registry_token, registry_token_stdin } => {
+            let registry_token = match commands::auth::read_registry_token(registry_token.clone(), *registry_token_stdin) {
+                Ok(token) => token,
+                Err(e) => return finish(cli.command.name(), Err(e)),
+            };
+            let options = commands::install::InstallOptions {
+                codegen: *codegen,
+                strict: *strict,
+                max_idl_size: *max_idl_size,
+                allow_hooks: *allow_hooks,
+                per_network_idl: *per_network_idl,
+                tags: tags.clone(),
+                registry_token,
+                registry: None,
+            };
+            commands::install::install_dependencies(&options).await.and_then(output::record_result)
         }
-        Commands::Codegen => {
-            commands::codegen::generate_typescript_client()
+        Commands::Codegen { idl_format, accounts_as_remaining, ensure_helpers, jobs, strict_types, anchor_version, emit_idl_types, tags } => {
+            let options = commands::codegen::CodegenOptions {
+                idl_format: idl_format.clone(),
+                accounts_as_remaining: accounts_as_remaining.clone(),
+                ensure_helpers: *ensure_helpers,
+                jobs: jobs.map(|jobs| jobs as usize),
+                strict_types: *strict_types,
+                anchor_version: *anchor_version,
+                emit_idl_types: *emit_idl_types,
+                tags: tags.clone(),
+            };
+            commands::codegen::generate_typescript_client(&options).and_then(output::record_result)
         }
-        Commands::Login { token } => {
-            commands::auth::login(token.as_deref()).await
+        Commands::Login { token, refresh, sso, export, env_file, i_understand, test_connection } => {
+            if *test_connection {
+                commands::auth::test_connection().await
+            } else if *export {
+                commands::auth::export_token(env_file.as_deref(), *i_understand).await
+            } else if *refresh {
+                commands::auth::refresh_credentials().await
+            } else if *sso {
+                commands::auth::login_with_sso().await
+            } else {
+                commands::auth::login(token.as_deref()).await
+            }
         }
         Commands::Logout => {
             commands::auth::logout()
         }
-        Commands::Publish => {
-            commands::publish::publish_program().await
+        Commands::Publish { binary, verify_onchain, strict, notes, notes_file, yes, canonical_idl, authority_keypair, authority_env, sign_with_ledger, legacy_challenge, allow_authority_mismatch, allow_dirty, no_hooks, dry_run, visibility, name, version, network, warn_idl_size, no_verify_readback, any_idl, adapt_idl, program, output_receipt, from_idl_url } => {
+            let options = commands::publish::PublishOptions {
+                binary: binary.clone(),
+                verify_onchain: *verify_onchain,
+                strict: *strict,
+                notes: notes.clone(),
+                notes_file: notes_file.clone(),
+                yes: *yes,
+                canonical_idl: *canonical_idl,
+                authority_keypair: authority_keypair.clone(),
+                authority_env: authority_env.clone(),
+                sign_with_ledger: *sign_with_ledger,
+                legacy_challenge: *legacy_challenge,
+                allow_authority_mismatch: *allow_authority_mismatch,
+                allow_dirty: *allow_dirty,
+                no_hooks: *no_hooks,
+                dry_run: *dry_run,
+                visibility: *visibility,
+                name: name.clone(),
+                version: version.clone(),
+                network: network.clone(),
+                warn_idl_size: *warn_idl_size,
+                no_verify_readback: *no_verify_readback,
+                any_idl: *any_idl,
+                adapt_idl: *adapt_idl,
+                program: program.clone(),
+                output_receipt: output_receipt.clone(),
+                from_idl_url: from_idl_url.clone(),
+            };
+            commands::publish::publish_program(&options).await
+        }
+        Commands::Release { package, authority_keypair, authority_env, sign_with_ledger } => {
+            let options = commands::release::ReleaseOptions {
+                authority_keypair: authority_keypair.clone(),
+                authority_env: authority_env.clone(),
+                sign_with_ledger: *sign_with_ledger,
+            };
+            commands::release::release_program(package, &options).await
+        }
+        Commands::History { package } => {
+            commands::history::print_history(package.as_deref())
+        }
+        Commands::Relink { dir } => {
+            commands::relink::relink(dir)
+        }
+        Commands::Reset { yes } => {
+            commands::reset::reset_project(*yes)
+        }
+        Commands::Verify { program, fix, description, repository, program_id, authority_keypair } => {
+            let options = commands::verify::VerifyOptions {
+                program: program.clone(),
+                fix: *fix,
+                description: description.clone(),
+                repository: repository.clone(),
+                program_id: program_id.clone(),
+                authority_keypair: authority_keypair.clone(),
+            };
+            commands::verify::verify_config(&options)
+        }
+        Commands::Graph { dot } => {
+            commands::graph::print_graph(*dot)
+        }
+        Commands::Diff { program } => {
+            commands::diff::diff(program).await
+        }
+        Commands::SelfUpdate { check, yes } => {
+            let options = commands::self_update::SelfUpdateOptions {
+                check: *check,
+                yes: *yes,
+            };
+            commands::self_update::self_update(&options).await
+        }
+        Commands::Config { action: ConfigAction::List } => {
+            commands::config::list_config()
+        }
+        Commands::Schema { target } => {
+            commands::schema::print_schema(*target)
+        }
+        Commands::Completions { shell } => {
+            commands::completions::print_completions(*shell)
         }
     };
 
+    finish(cli.command.name(), result);
+}
+
+/// Reports how the command finished and exits with the matching code on failure.
+/// 
+/// Errors are printed to stderr. In JSON mode the result document is also
+/// printed on stdout.
+/// 
+/// # Arguments
+/// 
+/// * `command` - The subcommand name
+/// * `result` - The command's outcome
+fn finish(command: &str, result: error::Result<()>) {
+    if let Err(e) = &result {
+        let mut messages = e.chain().into_iter();
+        if let Some(message) = messages.next() {
+            eprintln!("{}", CliStyle::error(&message));
+        }
+        for cause in messages {
+            eprintln!("  caused by: {}", cause);
+        }
+    }
+    if output::is_json_output() {
+        output::print_document(command, &result);
+    }
     if let Err(e) = result {
-            eprintln!("{}", CliStyle::error(&format!("{}", e)));
-            std::process::exit(1);
+        std::process::exit(e.exit_code());
     }
 }
\ No newline at end of file
@@ -0,0 +1,348 @@
+//! # Output Module
+//!
+//! This module decides where command output goes and how much of it is
+//! printed. By default commands print colored, human-oriented text to stdout.
+//!
+//! Output is split into three kinds:
+//!
+//! - Results, printed with [`outln!`]: what the command did or found
+//! - Status, printed with [`statusln!`]: progress messages, notes, and
+//!   warnings, hidden with `--quiet`
+//! - Diagnostics, logged with `tracing` to stderr: requests, file writes,
+//!   skipped downloads, and retries at debug level (`-v`), and phase timings
+//!   at trace level (`-vv`)
+//!
+//! Errors are always printed. In JSON mode (`--json` or `SOLPM_OUTPUT=json`):
+//!
+//! - Lines printed with [`outln!`] and [`statusln!`] go to stderr
+//! - Spinners and progress bars are hidden
+//! - Prompts fail with `SolanaPmError::InputRequired` instead of waiting for input
+//! - `main` prints a single JSON document on stdout once the command finishes
+//!
+//! The document looks like this:
+//!
+//! ```text
+//! {"ok": true, "command": "install", "result": {...}}
+//! {"ok": false, "command": "install", "error": {"kind": ..., "message": ..., "exit_code": ...}}
+//! ```
+//!
+//! `result` is what the command recorded with [`set_result`], or `null` for
+//! commands without a structured result. A failed command that recorded a
+//! result, such as `publish` after the registry rejected an upload, carries
+//! it next to `error`. `kind` and `exit_code` come from
+//! `SolanaPmError::kind` and `SolanaPmError::exit_code`.
+//!
+//! Commands with a typed outcome, such as `add`, `install`, and `codegen`,
+//! return it and `main` records it with [`record_result`].
+//!
+//! When solpm is used as a library, [`embed`] routes output the same way as
+//! JSON mode, but no document is printed: the `solpm::api` functions return
+//! the commands' outcomes directly.
+
+use crate::commands::constants::OUTPUT_ENV_VAR;
+use crate::error::{Result, SolanaPmError};
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::io::IsTerminal;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU8, AtomicUsize, Ordering};
+use std::time::Instant;
+use tracing::level_filters::LevelFilter;
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+
+/// Whether JSON mode is enabled.
+static JSON_OUTPUT: AtomicBool = AtomicBool::new(false);
+
+/// The number of `solpm::api` calls running; solpm is embedded while any is.
+static EMBEDDED_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+/// The result recorded by the running command.
+static RESULT: Mutex<Option<Value>> = Mutex::new(None);
+
+/// The verbosity, stored as a `Verbosity` discriminant.
+static VERBOSITY: AtomicU8 = AtomicU8::new(Verbosity::Normal as u8);
+
+/// How much output to print, from `--quiet` and `-v`/`-vv`.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    /// Errors and results only
+    Quiet,
+    /// Results and status lines
+    Normal,
+    /// Also debug diagnostics
+    Verbose,
+    /// Also trace diagnostics such as phase timings
+    Trace,
+}
+
+/// Prints a line of human-oriented output.
+/// 
+/// Takes the same arguments as `println!`. The line goes to stdout normally
+/// and to stderr in JSON mode, so stdout only carries the result document.
+#[macro_export]
+macro_rules! outln {
+    ($($arg:tt)*) => {
+        if $crate::output::is_json_output() {
+            eprintln!($($arg)*);
+        } else {
+            println!($($arg)*);
+        }
+    };
+}
+
+/// Prints a status line, such as a progress message or a warning.
+/// 
+/// Takes the same arguments as `println!` and prints like [`outln!`], unless
+/// `--quiet` was passed.
+#[macro_export]
+macro_rules! statusln {
+    ($($arg:tt)*) => {
+        if !$crate::output::is_quiet() {
+            $crate::outln!($($arg)*);
+        }
+    };
+}
+
+/// Turns JSON mode on or off for the rest of the process.
+/// 
+/// JSON mode is also enabled when `SOLPM_OUTPUT` is set to `json`.
+/// 
+/// # Arguments
+/// 
+/// * `json` - Whether `--json` was passed
+pub fn set_json_output(json: bool) {
+    let from_env = std::env::var(OUTPUT_ENV_VAR)
+        .is_ok_and(|value| value.trim().eq_ignore_ascii_case("json"));
+    JSON_OUTPUT.store(json || from_env, Ordering::Relaxed);
+}
+
+/// Returns whether JSON mode is enabled.
+/// 
+/// Embedded use counts as JSON mode, so stdout stays free of text and
+/// spinners stay hidden.
+pub fn is_json_output() -> bool {
+    JSON_OUTPUT.load(Ordering::Relaxed) || is_embedded()
+}
+
+/// Keeps solpm in embedded mode until dropped; see [`embed`].
+pub struct Embedded(());
+
+impl Drop for Embedded {
+    fn drop(&mut self) {
+        EMBEDDED_CALLS.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Marks solpm as running inside another program while a `solpm::api` call runs.
+/// 
+/// Output is routed as in JSON mode, and prompts fail with
+/// `SolanaPmError::InputRequired` so every answer has to come from the
+/// caller's options. Calls are counted, so concurrent calls stay embedded
+/// until the last one ends.
+/// 
+/// # Returns
+/// 
+/// Returns a guard that ends embedded mode when dropped.
+pub fn embed() -> Embedded {
+    EMBEDDED_CALLS.fetch_add(1, Ordering::Relaxed);
+    Embedded(())
+}
+
+/// Returns whether solpm is running inside another program.
+pub fn is_embedded() -> bool {
+    EMBEDDED_CALLS.load(Ordering::Relaxed) > 0
+}
+
+/// Sets the verbosity for the rest of the process.
+/// 
+/// # Arguments
+/// 
+/// * `quiet` - Whether `--quiet` was passed
+/// * `verbose` - How many times `-v` was passed
+pub fn set_verbosity(quiet: bool, verbose: u8) {
+    let verbosity = match (quiet, verbose) {
+        (true, _) => Verbosity::Quiet,
+        (false, 0) => Verbosity::Normal,
+        (false, 1) => Verbosity::Verbose,
+        (false, _) => Verbosity::Trace,
+    };
+    VERBOSITY.store(verbosity as u8, Ordering::Relaxed);
+}
+
+/// Returns the verbosity set with `--quiet` or `-v`.
+pub fn verbosity() -> Verbosity {
+    match VERBOSITY.load(Ordering::Relaxed) {
+        0 => Verbosity::Quiet,
+        1 => Verbosity::Normal,
+        2 => Verbosity::Verbose,
+        _ => Verbosity::Trace,
+    }
+}
+
+/// Returns whether `--quiet` was passed.
+pub fn is_quiet() -> bool {
+    verbosity() == Verbosity::Quiet
+}
+
+/// Installs the `tracing` subscriber that writes diagnostics to stderr.
+/// 
+/// Only solpm's own events are shown, at debug level with `-v` and at trace
+/// level with `-vv`; events from dependencies such as the HTTP client are
+/// dropped. Call after [`set_verbosity`].
+pub fn init_logging() {
+    let level = match verbosity() {
+        Verbosity::Quiet => LevelFilter::ERROR,
+        Verbosity::Normal => LevelFilter::WARN,
+        Verbosity::Verbose => LevelFilter::DEBUG,
+        Verbosity::Trace => LevelFilter::TRACE,
+    };
+    tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .with_ansi(std::io::stderr().is_terminal())
+        .without_time()
+        .with_target(false)
+        .with_max_level(LevelFilter::TRACE)
+        .finish()
+        .with(Targets::new().with_target(env!("CARGO_PKG_NAME"), level))
+        .init();
+}
+
+/// Logs how long a phase of a command took when dropped.
+pub struct Phase {
+    name: String,
+    started: Instant,
+}
+
+impl Drop for Phase {
+    fn drop(&mut self) {
+        tracing::trace!("{} took {} ms", self.name, self.started.elapsed().as_millis());
+    }
+}
+
+/// Starts timing a phase, logged at trace level (`-vv`) when it ends.
+/// 
+/// # Arguments
+/// 
+/// * `name` - The phase, e.g. `"Download token-vesting"`
+/// 
+/// # Returns
+/// 
+/// Returns a guard that logs the elapsed time when dropped.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use solpm::output::phase;
+/// 
+/// let _phase = phase("Generate clients");
+/// // ... the phase ends when `_phase` goes out of scope
+/// ```
+pub fn phase(name: impl Into<String>) -> Phase {
+    Phase { name: name.into(), started: Instant::now() }
+}
+
+/// Records the structured result of the running command.
+/// 
+/// Only the last recorded result is printed. Outside JSON mode the result is
+/// ignored.
+/// 
+/// # Arguments
+/// 
+/// * `result` - The command's result, printed as `result` in the JSON document
+pub fn set_result(result: Value) {
+    if let Ok(mut recorded) = RESULT.lock() {
+        *recorded = Some(result);
+    }
+}
+
+/// Records what a command returned as its structured result.
+/// 
+/// Commands with a typed outcome return it instead of recording it, and
+/// `main` records it with this function.
+/// 
+/// # Arguments
+/// 
+/// * `outcome` - The command's outcome, serialized as `result`
+/// 
+/// # Returns
+/// 
+/// Returns `Ok(())`, or an error if the outcome cannot be serialized.
+pub fn record_result(outcome: impl Serialize) -> Result<()> {
+    set_result(serde_json::to_value(outcome)?);
+    Ok(())
+}
+
+/// Takes the result recorded by the last command, leaving none behind.
+/// 
+/// # Returns
+/// 
+/// Returns the recorded result, or `None` if the command recorded none.
+pub fn take_result() -> Option<Value> {
+    RESULT.lock().ok().and_then(|mut recorded| recorded.take())
+}
+
+/// Fails if the command would have to prompt for input in JSON mode or when
+/// embedded.
+/// 
+/// # Arguments
+/// 
+/// * `input` - What the prompt would ask for, used in the error message
+/// 
+/// # Returns
+/// 
+/// Returns `Ok(())` outside JSON mode and embedded use, or
+/// `SolanaPmError::InputRequired` naming the missing input.
+/// 
+/// # Examples
+/// 
+/// ```no_run
+/// use solpm::output::require_interactive;
+/// 
+/// # fn example() -> solpm::error::Result<()> {
+/// require_interactive("The encryption password")?;
+/// let password = rpassword::prompt_password("Enter your encryption password: ")?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn require_interactive(input: &str) -> Result<()> {
+    if is_embedded() {
+        return Err(SolanaPmError::InputRequired(format!(
+            "{} cannot be prompted for when solpm is used as a library; pass it in the options",
+            input
+        )));
+    }
+    if is_json_output() {
+        return Err(SolanaPmError::InputRequired(format!(
+            "{} cannot be prompted for with --json",
+            input
+        )));
+    }
+    Ok(())
+}
+
+/// Prints the JSON document describing how the command finished.
+/// 
+/// # Arguments
+/// 
+/// * `command` - The subcommand name, e.g. `install`
+/// * `outcome` - The command's outcome
+pub fn print_document(command: &str, outcome: &Result<()>) {
+    let recorded = take_result();
+    let document = match outcome {
+        Ok(()) => json!({ "ok": true, "command": command, "result": recorded }),
+        Err(e) => {
+            let mut document = json!({
+                "ok": false,
+                "command": command,
+                "error": { "kind": e.kind(), "message": e.chain().join(": "), "exit_code": e.exit_code() },
+            });
+            if let Some(recorded) = recorded {
+                document["result"] = recorded;
+            }
+            document
+        }
+    };
+    println!("{}", document);
+}
@@ -4,18 +4,30 @@
 //! Solana Program Manager application. It includes:
 //!
 //! - CLI styling and formatting utilities
-//! - Progress indicators and spinners
-//! - User input and confirmation prompts
+//! - Progress indicators and spinners, hidden in `--json` mode and reduced to
+//!   plain status lines when stderr is not an interactive terminal
+//! - User input and confirmation prompts, refused in `--json` mode
 //! - Project identification and hashing
+//! - Git revision and working tree status
+//! - Timestamp formatting
+//! - Canonical JSON serialization for hashing
 //! - Package specification parsing
+//! - Network name validation
+//! - Filesystem-safe package names
+//! - Placeholder program ID detection
 //! - ASCII art banner display
 //!
 //! The utilities are designed to provide a consistent user experience across
 //! all commands with proper error handling and user feedback.
 
+use crate::cli::Network;
+use crate::commands::config::idl_dir;
+use crate::commands::types::Program;
+use crate::error::{Result, SolanaPmError};
+use crate::output::{is_json_output, is_quiet, require_interactive};
 use colored::*;
 use console::Term;
-use indicatif::{ProgressBar, ProgressStyle};
+use indicatif::{HumanBytes, ProgressBar, ProgressDrawTarget, ProgressStyle};
 use std::time::Duration;
 use sha2::{Sha256, Digest};
 
@@ -31,7 +43,9 @@ use sha2::{Sha256, Digest};
 /// 
 /// # Examples
 /// 
-/// ```rust
+/// ```
+/// use solpm::utils::PackageSpec;
+/// 
 /// let spec = PackageSpec {
 ///     name: "feedana".to_string(),
 ///     version: Some("0.1.0".to_string()),
@@ -231,9 +245,15 @@ impl CliProgress {
     /// 
     /// # Returns
     /// 
-    /// Returns a configured ProgressBar with spinner animation.
+    /// Returns a configured ProgressBar with spinner animation, hidden in
+    /// JSON mode. Without an interactive terminal the message is printed
+    /// once as a plain line instead of animating.
     pub fn new_spinner(msg: &str) -> ProgressBar {
         let pb = ProgressBar::new_spinner();
+        hide_when_silenced(&pb);
+        if prints_progress_lines() {
+            eprintln!("{}", msg);
+        }
         pb.set_style(
             ProgressStyle::default_spinner()
                 .tick_strings(&["⠁", "⠂", "⠄", "⡀", "⢀", "⠠", "⠐", "⠈"])
@@ -257,9 +277,12 @@ impl CliProgress {
     /// 
     /// # Returns
     /// 
-    /// Returns a configured ProgressBar for tracking progress.
+    /// Returns a configured ProgressBar for tracking progress, hidden in
+    /// JSON mode. Without an interactive terminal, advancing it with
+    /// [`CliProgress::inc`] prints a line per item instead.
     pub fn new_progress_bar(len: u64, msg: &str) -> ProgressBar {
         let pb = ProgressBar::new(len);
+        hide_when_silenced(&pb);
         pb.set_style(
             ProgressStyle::default_bar()
                 .template("{msg} [{bar:40.cyan/blue}] {pos}/{len} {percent}%")
@@ -270,24 +293,191 @@ impl CliProgress {
         pb
     }
 
+    /// Creates a new progress bar that tracks a transfer in bytes.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `len` - The total number of bytes to transfer
+    /// * `msg` - The message to display with the progress bar
+    /// 
+    /// # Returns
+    /// 
+    /// Returns a configured ProgressBar showing transferred and total size,
+    /// hidden in JSON mode. Without an interactive terminal, advancing it
+    /// with [`CliProgress::inc_bytes`] prints a line at every quarter instead.
+    pub fn new_bytes_progress_bar(len: u64, msg: &str) -> ProgressBar {
+        let pb = ProgressBar::new(len);
+        hide_when_silenced(&pb);
+        if prints_progress_lines() {
+            eprintln!("{} ({})", msg, HumanBytes(len));
+        }
+        pb.set_style(
+            ProgressStyle::default_bar()
+                .template("{msg} [{bar:40.cyan/blue}] {bytes}/{total_bytes} {percent}%")
+                .unwrap()
+                .progress_chars("█▉▊▋▌▍▎▏  "),
+        );
+        pb.set_message(msg.to_string());
+        pb
+    }
+
+    /// Turns a spinner into a download progress bar once the size is known.
+    /// 
+    /// The bar keeps the spinner's message and shows the downloaded and total
+    /// size and the transfer speed.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `pb` - The spinner to convert
+    /// * `downloaded` - Bytes already downloaded (e.g. by a resumed attempt)
+    /// * `total` - The total number of bytes
+    pub fn start_download_bar(pb: &ProgressBar, downloaded: u64, total: u64) {
+        pb.set_style(
+            ProgressStyle::default_bar()
+                .template("{msg} [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({bytes_per_sec})")
+                .unwrap()
+                .progress_chars("█▉▊▋▌▍▎▏  "),
+        );
+        pb.set_length(total);
+        pb.set_position(downloaded);
+    }
+    
+    /// Advances an item progress bar.
+    /// 
+    /// Without an interactive terminal the bar is hidden, so the new position
+    /// is printed as a plain line (e.g. "Installing dependencies 3/7").
+    /// 
+    /// # Arguments
+    /// 
+    /// * `pb` - The progress bar to advance
+    /// * `delta` - The number of items completed
+    pub fn inc(pb: &ProgressBar, delta: u64) {
+        pb.inc(delta);
+        if prints_progress_lines() {
+            let len = pb.length().unwrap_or(0);
+            eprintln!("{} {}/{}", pb.message(), pb.position().min(len), len);
+        }
+    }
+    
+    /// Advances a byte progress bar by a transferred chunk.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `pb` - The progress bar to advance
+    /// * `delta` - The number of bytes transferred
+    pub fn inc_bytes(pb: &ProgressBar, delta: u64) {
+        Self::set_bytes_position(pb, pb.position() + delta);
+    }
+    
+    /// Moves a byte progress bar to an absolute position.
+    /// 
+    /// Without an interactive terminal the bar is hidden, so a plain line is
+    /// printed each time the transfer crosses another quarter of its length
+    /// rather than on every chunk.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `pb` - The progress bar to move
+    /// * `position` - The number of bytes transferred so far
+    pub fn set_bytes_position(pb: &ProgressBar, position: u64) {
+        let previous = pb.position();
+        pb.set_position(position);
+        if !prints_progress_lines() {
+            return;
+        }
+        let Some(len) = pb.length().filter(|len| *len > 0) else {
+            return;
+        };
+        let quarter = |pos: u64| pos.min(len) * 4 / len;
+        if quarter(position) > quarter(previous) {
+            eprintln!(
+                "{} {}/{} ({}%)",
+                pb.message(),
+                HumanBytes(position.min(len)),
+                HumanBytes(len),
+                quarter(position) * 25
+            );
+        }
+    }
+
     /// Finishes a progress bar with a success message.
     /// 
+    /// In JSON mode the bar is hidden, so the message is printed to stderr.
+    /// With `--quiet` the message is not printed.
+    /// 
     /// # Arguments
     /// 
     /// * `pb` - The progress bar to finish
     /// * `msg` - The success message to display
     pub fn finish_with_message(pb: ProgressBar, msg: &str) {
-        pb.finish_with_message(CliStyle::success(msg));
+        if is_quiet() {
+            pb.finish_and_clear();
+            return;
+        }
+        finish_visibly(pb, CliStyle::success(msg));
     }
 
     /// Finishes a progress bar with an error message.
     /// 
+    /// When the bar is hidden (JSON mode or `--quiet`), the message is printed
+    /// to stderr.
+    /// 
     /// # Arguments
     /// 
     /// * `pb` - The progress bar to finish
     /// * `msg` - The error message to display
     pub fn finish_with_error(pb: ProgressBar, msg: &str) {
-        pb.finish_with_message(CliStyle::error(msg));
+        finish_visibly(pb, CliStyle::error(msg));
+    }
+}
+
+/// Stops a progress bar from drawing in JSON mode, with `--quiet`, or when
+/// stderr is not an interactive terminal.
+/// 
+/// # Arguments
+/// 
+/// * `pb` - The progress bar to hide
+fn hide_when_silenced(pb: &ProgressBar) {
+    if is_json_output() || is_quiet() || !is_interactive_terminal() {
+        pb.set_draw_target(ProgressDrawTarget::hidden());
+    }
+}
+
+/// Checks whether stderr can show animated progress bars.
+/// 
+/// CI logs and pipes are not terminals, and some CI runners attach a
+/// terminal that reports no size; redrawing a bar in either of them leaves
+/// a line per frame in the log.
+/// 
+/// # Returns
+/// 
+/// Returns true if stderr is a terminal with a known size.
+fn is_interactive_terminal() -> bool {
+    let term = Term::stderr();
+    term.is_term() && term.size_checked().is_some_and(|(_, width)| width > 0)
+}
+
+/// Checks whether progress should be reported as plain lines.
+/// 
+/// # Returns
+/// 
+/// Returns true if progress is not silenced but stderr cannot show bars.
+fn prints_progress_lines() -> bool {
+    !is_json_output() && !is_quiet() && !is_interactive_terminal()
+}
+
+/// Finishes a progress bar, keeping its final message visible.
+/// 
+/// # Arguments
+/// 
+/// * `pb` - The progress bar to finish
+/// * `msg` - The styled final message
+fn finish_visibly(pb: ProgressBar, msg: String) {
+    if pb.is_hidden() {
+        pb.finish_and_clear();
+        eprintln!("{}", msg);
+    } else {
+        pb.finish_with_message(msg);
     }
 }
 
@@ -321,15 +511,18 @@ pub fn print_banner() {
 /// 
 /// # Returns
 /// 
-/// Returns `true` if user confirms, `false` if they decline or on error.
-pub fn confirm_action(msg: &str) -> bool {
+/// Returns `true` if user confirms, `false` if they decline or on error, or
+/// `SolanaPmError::InputRequired` in JSON mode.
+pub fn confirm_action(msg: &str) -> Result<bool> {
     use dialoguer::Confirm;
     
-    Confirm::new()
+    require_interactive(&format!("An answer to '{}'", msg))?;
+    
+    Ok(Confirm::new()
         .with_prompt(msg)
         .default(false)
         .interact()
-        .unwrap_or(false)
+        .unwrap_or(false))
 }
 
 /// Prompts the user for text input.
@@ -343,17 +536,77 @@ pub fn confirm_action(msg: &str) -> bool {
 /// 
 /// # Returns
 /// 
-/// Returns `Some(String)` with the user's input, or `None` if input fails.
-pub fn prompt_input(msg: &str, default: Option<&str>) -> Option<String> {
+/// Returns `Some(String)` with the user's input, `None` if input fails, or
+/// `SolanaPmError::InputRequired` in JSON mode.
+pub fn prompt_input(msg: &str, default: Option<&str>) -> Result<Option<String>> {
     use dialoguer::Input;
     
+    require_interactive(&format!("A value for '{}'", msg))?;
+    
     let mut input = Input::<String>::new().with_prompt(msg);
     
     if let Some(def) = default {
         input = input.default(def.to_string());
     }
     
-    input.interact().ok()
+    Ok(input.interact().ok())
+}
+
+/// Returns the current UTC time formatted as an RFC 3339 timestamp.
+/// 
+/// Used to record when programs were installed into the project manifest.
+/// 
+/// # Returns
+/// 
+/// Returns a timestamp string such as `2024-05-01T12:34:56Z`.
+pub fn current_timestamp() -> String {
+    chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+}
+
+/// Serializes an IDL (or any JSON value) in canonical form.
+/// 
+/// Object keys are sorted lexicographically by their UTF-8 bytes, no
+/// insignificant whitespace is emitted, and strings and numbers use
+/// serde_json's standard escaping. The output is independent of the key
+/// order in the source file, so hashes computed from it are reproducible
+/// across machines and tools.
+/// 
+/// # Arguments
+/// 
+/// * `value` - The JSON value to serialize
+/// 
+/// # Returns
+/// 
+/// Returns the canonical JSON string.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use solpm::utils::canonicalize_idl;
+/// 
+/// # fn main() -> solpm::error::Result<()> {
+/// let a: serde_json::Value = serde_json::from_str(r#"{"b":1,"a":[{"d":2,"c":3}]}"#)?;
+/// assert_eq!(canonicalize_idl(&a), r#"{"a":[{"c":3,"d":2}],"b":1}"#);
+/// # Ok(())
+/// # }
+/// ```
+pub fn canonicalize_idl(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            let entries: Vec<String> = keys
+                .into_iter()
+                .map(|key| format!("{}:{}", serde_json::Value::String(key.clone()), canonicalize_idl(&map[key])))
+                .collect();
+            format!("{{{}}}", entries.join(","))
+        }
+        serde_json::Value::Array(items) => {
+            let entries: Vec<String> = items.iter().map(canonicalize_idl).collect();
+            format!("[{}]", entries.join(","))
+        }
+        other => other.to_string(),
+    }
 }
 
 /// Generates a unique project hash for download tracking.
@@ -372,7 +625,9 @@ pub fn prompt_input(msg: &str, default: Option<&str>) -> Option<String> {
 /// 
 /// # Examples
 /// 
-/// ```rust
+/// ```
+/// use solpm::utils::generate_project_hash;
+/// 
 /// let project_hash = generate_project_hash();
 /// println!("Project hash: {}", project_hash);
 /// ```
@@ -403,7 +658,7 @@ fn get_github_repository_url() -> Option<String> {
     
     // Try to get the git remote origin URL
     let output = Command::new("git")
-        .args(&["config", "--get", "remote.origin.url"])
+        .args(["config", "--get", "remote.origin.url"])
         .output()
         .ok()?;
     
@@ -421,6 +676,59 @@ fn get_github_repository_url() -> Option<String> {
     }
 }
 
+/// The git revision a project is built from.
+pub struct GitRevision {
+    /// The full hash of the HEAD commit
+    pub commit: String,
+    /// Paths with uncommitted changes, as reported by `git status --porcelain`
+    pub changed_files: Vec<String>,
+}
+
+impl GitRevision {
+    /// Returns whether the working tree has uncommitted changes.
+    pub fn is_dirty(&self) -> bool {
+        !self.changed_files.is_empty()
+    }
+}
+
+/// Reads the HEAD commit and working tree status of the current git repository.
+/// 
+/// # Returns
+/// 
+/// Returns `Some(GitRevision)` when run inside a git repository with at least
+/// one commit, or `None` if git is unavailable or this is not a git repository.
+pub fn get_git_revision() -> Option<GitRevision> {
+    use std::process::Command;
+    
+    let output = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .ok()?;
+    
+    if !output.status.success() {
+        return None;
+    }
+    
+    let commit = String::from_utf8(output.stdout).ok()?.trim().to_string();
+    
+    let output = Command::new("git")
+        .args(["status", "--porcelain"])
+        .output()
+        .ok()?;
+    
+    if !output.status.success() {
+        return None;
+    }
+    
+    let changed_files = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.get(3..))
+        .map(|path| path.to_string())
+        .collect();
+    
+    Some(GitRevision { commit, changed_files })
+}
+
 /// Normalizes GitHub URLs to a consistent format for hashing.
 /// 
 /// Converts both SSH and HTTPS GitHub URLs to a consistent format.
@@ -460,7 +768,9 @@ fn normalize_github_url(url: String) -> String {
 /// 
 /// # Examples
 /// 
-/// ```rust
+/// ```
+/// use solpm::utils::parse_package_spec;
+/// 
 /// let spec = parse_package_spec("feedana@0.1.0");
 /// assert_eq!(spec.name, "feedana");
 /// assert_eq!(spec.version, Some("0.1.0".to_string()));
@@ -483,4 +793,223 @@ pub fn parse_package_spec(package_spec: &str) -> PackageSpec {
             version: None,
         }
     }
-}
\ No newline at end of file
+}
+
+/// Parses a network name from a manifest or config file.
+/// 
+/// Names are matched case-insensitively after trimming, and the cluster
+/// names used by the Solana CLI (`mainnet-beta`, `localhost`) are accepted
+/// as aliases, so `Network::as_str` gives the canonical spelling.
+/// 
+/// # Arguments
+/// 
+/// * `network` - The network name to parse
+/// 
+/// # Returns
+/// 
+/// Returns the network, or an error naming the supported networks.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use solpm::utils::parse_network;
+/// 
+/// # fn main() -> solpm::error::Result<()> {
+/// assert_eq!(parse_network("Mainnet-Beta")?.as_str(), "mainnet");
+/// assert!(parse_network("devent").is_err());
+/// # Ok(())
+/// # }
+/// ```
+pub fn parse_network(network: &str) -> Result<Network> {
+    match network.trim().to_ascii_lowercase().as_str() {
+        "mainnet" | "mainnet-beta" => Ok(Network::Main),
+        "devnet" => Ok(Network::Dev),
+        "testnet" => Ok(Network::Test),
+        "localnet" | "localhost" => Ok(Network::Local),
+        _ => Err(SolanaPmError::DataMissing(format!(
+            "Unknown network '{}'; expected mainnet, devnet, testnet, or localnet",
+            network
+        ))),
+    }
+}
+
+/// Sanitizes a package name for use as a file name.
+/// 
+/// Replaces path separators, whitespace, and any other characters outside
+/// `[A-Za-z0-9._-]` with underscores, and guards against names that would
+/// resolve to special directory entries. The original name should still be
+/// used for registry lookups.
+/// 
+/// # Arguments
+/// 
+/// * `name` - The package name to sanitize
+/// 
+/// # Returns
+/// 
+/// Returns a string that is safe to use as a single path component.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use solpm::utils::sanitize_file_name;
+/// 
+/// assert_eq!(sanitize_file_name("org/my program"), "org_my_program");
+/// assert_eq!(sanitize_file_name("feedana"), "feedana");
+/// ```
+pub fn sanitize_file_name(name: &str) -> String {
+    let sanitized: String = name
+        .trim()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.' { c } else { '_' })
+        .collect();
+    
+    if sanitized.is_empty() || sanitized.chars().all(|c| c == '.') {
+        format!("_{}", sanitized)
+    } else {
+        sanitized
+    }
+}
+
+/// Returns whether a program ID is a placeholder rather than a deployed address.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use solpm::utils::is_placeholder_program_id;
+/// 
+/// assert!(is_placeholder_program_id("PLACEHOLDER_PROGRAM_ID"));
+/// assert!(!is_placeholder_program_id("11111111111111111111111111111111"));
+/// ```
+pub fn is_placeholder_program_id(program_id: &str) -> bool {
+    program_id.starts_with("PLACEHOLDER") || program_id == "YOUR_PROGRAM_ID_HERE"
+}
+
+/// Returns the default IDL file path for a package.
+/// 
+/// # Arguments
+/// 
+/// * `package_name` - The package name as known to the registry
+/// 
+/// # Returns
+/// 
+/// Returns `{idl_dir}/{sanitized_name}.json`, where the IDL directory
+/// defaults to `./program/idl`.
+pub fn default_idl_path(package_name: &str) -> String {
+    format!("{}/{}.json", idl_dir(), sanitize_file_name(package_name))
+}
+
+/// Returns the per-network IDL file path for a package.
+/// 
+/// Keeps the IDLs of one program installed from several networks apart.
+/// 
+/// # Arguments
+/// 
+/// * `package_name` - The package name as known to the registry
+/// * `network` - The network the package is installed from
+/// 
+/// # Returns
+/// 
+/// Returns `{idl_dir}/{network}/{sanitized_name}.json`.
+pub fn network_idl_path(package_name: &str, network: &str) -> String {
+    format!("{}/{}/{}.json", idl_dir(), sanitize_file_name(network), sanitize_file_name(package_name))
+}
+
+/// Returns where an installed program's IDL file is.
+/// 
+/// # Arguments
+/// 
+/// * `package_name` - The package name as known to the registry
+/// * `program` - The program's entry in SolanaPrograms.json
+/// 
+/// # Returns
+/// 
+/// Returns the entry's `idl_path`, or for entries without one the
+/// per-network path if that file exists, or the default path.
+pub fn installed_idl_path(package_name: &str, program: &Program) -> String {
+    if let Some(idl_path) = &program.idl_path {
+        return idl_path.clone();
+    }
+    
+    let network_path = network_idl_path(package_name, &program.network);
+    if std::path::Path::new(&network_path).exists() {
+        network_path
+    } else {
+        default_idl_path(package_name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn sanitize_file_name_replaces_slashes_and_spaces() {
+        assert_eq!(sanitize_file_name("org/my program"), "org_my_program");
+        assert_eq!(sanitize_file_name("a\\b c"), "a_b_c");
+        assert_eq!(sanitize_file_name("../../etc/passwd"), ".._.._etc_passwd");
+        assert_eq!(sanitize_file_name("  spaced name  "), "spaced_name");
+    }
+    
+    #[test]
+    fn canonicalize_idl_ignores_key_order_and_whitespace() {
+        let compact = r#"{"version":"0.1.0","name":"vesting","instructions":[{"name":"init","args":[{"type":"u64","name":"amount"}]}]}"#;
+        let pretty = r#"{
+            "name": "vesting",
+            "instructions": [
+                { "args": [ { "name": "amount", "type": "u64" } ], "name": "init" }
+            ],
+            "version": "0.1.0"
+        }"#;
+        let compact: serde_json::Value = serde_json::from_str(compact).unwrap();
+        let pretty: serde_json::Value = serde_json::from_str(pretty).unwrap();
+        
+        let canonical = canonicalize_idl(&compact);
+        assert_eq!(canonical, canonicalize_idl(&pretty));
+        assert_eq!(canonical, r#"{"instructions":[{"args":[{"name":"amount","type":"u64"}],"name":"init"}],"name":"vesting","version":"0.1.0"}"#);
+        assert_eq!(Sha256::digest(canonical.as_bytes()), Sha256::digest(canonicalize_idl(&pretty).as_bytes()));
+    }
+    
+    #[test]
+    fn canonicalize_idl_keeps_array_order() {
+        let value = serde_json::json!({ "seeds": ["b", "a"], "n": 1.5, "s": "quote\"d" });
+        assert_eq!(canonicalize_idl(&value), r#"{"n":1.5,"s":"quote\"d","seeds":["b","a"]}"#);
+    }
+    
+    #[test]
+    fn sanitize_file_name_keeps_safe_names() {
+        assert_eq!(sanitize_file_name("feedana"), "feedana");
+        assert_eq!(sanitize_file_name("token-vesting_v1.2"), "token-vesting_v1.2");
+    }
+    
+    #[test]
+    fn sanitize_file_name_guards_special_entries() {
+        assert_eq!(sanitize_file_name(""), "_");
+        assert_eq!(sanitize_file_name("."), "_.");
+        assert_eq!(sanitize_file_name(".."), "_..");
+    }
+    
+    #[test]
+    fn parse_network_accepts_names_and_aliases() {
+        let cases = [
+            ("mainnet", "mainnet"),
+            ("mainnet-beta", "mainnet"),
+            ("devnet", "devnet"),
+            ("testnet", "testnet"),
+            ("localnet", "localnet"),
+            ("localhost", "localnet"),
+            ("  TestNet ", "testnet"),
+            ("LOCALHOST", "localnet"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(parse_network(input).unwrap().as_str(), expected, "{:?}", input);
+        }
+    }
+    
+    #[test]
+    fn parse_network_rejects_unknown_names() {
+        for input in ["devent", "test", "local", "main", ""] {
+            let error = parse_network(input).err().unwrap();
+            assert!(error.to_string().contains(&format!("Unknown network '{}'", input)), "{}", error);
+        }
+    }
+}